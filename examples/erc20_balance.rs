@@ -1,9 +1,10 @@
 use starknet::{
-    core::types::{BlockId, BlockTag, Felt, FunctionCall},
-    macros::{felt, selector},
+    contract::Erc20,
+    core::types::Felt,
+    macros::felt,
     providers::{
         jsonrpc::{HttpTransport, JsonRpcClient},
-        Provider, Url,
+        Url,
     },
 };
 
@@ -13,23 +14,16 @@ async fn main() {
         Url::parse("https://starknet-sepolia.public.blastapi.io/rpc/v0_9").unwrap(),
     ));
 
-    let tst_token_address =
-        felt!("0x07394cbe418daa16e42b87ba67372d4ab4a5df0b05c6e554d158458ce245bc10");
+    let tst_token = Erc20::new(
+        felt!("0x07394cbe418daa16e42b87ba67372d4ab4a5df0b05c6e554d158458ce245bc10"),
+        provider,
+    );
 
-    let call_result =
-        provider
-            .call(
-                FunctionCall {
-                    contract_address: tst_token_address,
-                    entry_point_selector: selector!("balanceOf"),
-                    calldata: vec![
-                        Felt::from_hex("YOUR_ACCOUNT_CONTRACT_ADDRESS_IN_HEX_HERE").unwrap()
-                    ],
-                },
-                BlockId::Tag(BlockTag::Latest),
-            )
-            .await
-            .expect("failed to call contract");
+    let balance = tst_token
+        .balance_of(Felt::from_hex("YOUR_ACCOUNT_CONTRACT_ADDRESS_IN_HEX_HERE").unwrap())
+        .await
+        .expect("failed to call contract");
+    let symbol = tst_token.symbol().await.expect("failed to call contract");
 
-    dbg!(call_result);
+    println!("Balance: {balance} {symbol}");
 }
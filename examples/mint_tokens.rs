@@ -1,9 +1,9 @@
 use starknet::{
     accounts::{Account, ExecutionEncoding, SingleOwnerAccount},
+    contract::Erc20,
     core::{
         chain_id,
-        types::{Call, Felt},
-        utils::get_selector_from_name,
+        types::{Felt, U256},
     },
     providers::{
         jsonrpc::{HttpTransport, JsonRpcClient},
@@ -22,30 +22,22 @@ async fn main() {
         Felt::from_hex("YOUR_PRIVATE_KEY_IN_HEX_HERE").unwrap(),
     ));
     let address = Felt::from_hex("YOUR_ACCOUNT_CONTRACT_ADDRESS_IN_HEX_HERE").unwrap();
-    let tst_token_address =
-        Felt::from_hex("07394cbe418daa16e42b87ba67372d4ab4a5df0b05c6e554d158458ce245bc10").unwrap();
+    let tst_token = Erc20::new(
+        Felt::from_hex("07394cbe418daa16e42b87ba67372d4ab4a5df0b05c6e554d158458ce245bc10").unwrap(),
+        &provider,
+    );
 
     let account = SingleOwnerAccount::new(
-        provider,
+        &provider,
         signer,
         address,
         chain_id::SEPOLIA,
         ExecutionEncoding::New,
     );
 
-    let result = account
-        .execute_v3(vec![Call {
-            to: tst_token_address,
-            selector: get_selector_from_name("mint").unwrap(),
-            calldata: vec![
-                address,
-                Felt::from_dec_str("1000000000000000000000").unwrap(),
-                Felt::ZERO,
-            ],
-        }])
-        .send()
-        .await
-        .unwrap();
+    let mint_call = tst_token.mint(address, U256::from(1000000000000000000000u128));
+
+    let result = account.execute_v3(vec![mint_call]).send().await.unwrap();
 
     println!("Transaction hash: {:#064x}", result.transaction_hash);
 }
@@ -1,11 +1,15 @@
 use std::time::Duration;
 
 use starknet::core::types::ConfirmedBlockId;
-use starknet_tokio_tungstenite::{NewHeadsUpdate, TungsteniteStream};
+use starknet_tokio_tungstenite::{NewHeadsUpdate, TungsteniteStreamBuilder};
 
 #[tokio::main]
 async fn main() {
-    let stream = TungsteniteStream::connect("ws://localhost:9545/rpc/v0_9", Duration::from_secs(5))
+    let stream = TungsteniteStreamBuilder::new()
+        .timeout(Duration::from_secs(5))
+        // Transparently re-establishes the connection and re-subscribes when it's lost
+        .reconnect(true)
+        .connect("ws://localhost:9545/rpc/v0_9")
         .await
         .expect("WebSocket connection failed");
 
@@ -29,6 +33,12 @@ async fn main() {
                         reorg.starting_block_number, reorg.ending_block_number
                     );
                 }
+                Ok(NewHeadsUpdate::Reconnected(reconnection)) => {
+                    println!(
+                        "Reconnected and resumed from {:?}",
+                        reconnection.resumed_from
+                    );
+                }
                 Err(err) => {
                     eprintln!("Failed to receieve update: {err}");
                 }
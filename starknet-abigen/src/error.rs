@@ -0,0 +1,68 @@
+use std::fmt::{Display, Formatter};
+
+/// Errors generating bindings from a contract ABI.
+#[derive(Debug)]
+pub enum AbigenError {
+    /// A type name in the ABI cannot be parsed.
+    InvalidTypeName(
+        /// Type name.
+        String,
+    ),
+    /// A type is referenced but not defined in the ABI.
+    UnknownType(
+        /// Type name.
+        String,
+    ),
+    /// A type has no Rust counterpart that serializes the same way.
+    UnsupportedType(
+        /// Type name.
+        String,
+    ),
+    /// A contract or function name cannot be used in generated code.
+    InvalidName(
+        /// The offending name.
+        String,
+    ),
+    /// The contract artifact cannot be parsed.
+    InvalidArtifact(serde_json::Error),
+    /// An error is encountered reading the contract artifact or writing the bindings.
+    Io(std::io::Error),
+    /// Bindings are written to `OUT_DIR` outside of a build script.
+    OutDirNotSet,
+}
+
+impl From<serde_json::Error> for AbigenError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::InvalidArtifact(value)
+    }
+}
+
+impl From<std::io::Error> for AbigenError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl std::error::Error for AbigenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidArtifact(err) => Some(err),
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AbigenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidTypeName(type_name) => write!(f, "invalid type name: {type_name}"),
+            Self::UnknownType(type_name) => write!(f, "type `{type_name}` not defined"),
+            Self::UnsupportedType(type_name) => write!(f, "unsupported type: {type_name}"),
+            Self::InvalidName(name) => write!(f, "invalid name: {name}"),
+            Self::InvalidArtifact(err) => write!(f, "invalid contract artifact: {err}"),
+            Self::Io(err) => write!(f, "{err}"),
+            Self::OutDirNotSet => write!(f, "`OUT_DIR` is not set; not running in a build script?"),
+        }
+    }
+}
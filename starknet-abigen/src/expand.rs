@@ -0,0 +1,625 @@
+use std::collections::BTreeMap;
+
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote};
+use starknet_core::{
+    abi::AbiType,
+    types::{contract::EventFieldKind, Felt},
+    utils::{get_selector_from_name, starknet_keccak},
+};
+
+use crate::{
+    model::{ContractModel, TypeDefinition, TypeKind},
+    names::{assign_type_names, to_ident, to_upper_camel_case},
+    AbigenError,
+};
+
+/// Path to the `starknet` crate root in generated code.
+const STARKNET_PATH: &str = "::starknet";
+
+/// Expands a contract ABI into Rust bindings.
+pub(crate) struct Expander {
+    contract_name: Ident,
+    model: ContractModel,
+    type_names: BTreeMap<String, String>,
+    starknet: TokenStream,
+}
+
+impl Expander {
+    pub(crate) fn new(contract_name: &str, model: ContractModel) -> Result<Self, AbigenError> {
+        let contract_name = syn::parse_str::<Ident>(contract_name)
+            .map_err(|_| AbigenError::InvalidName(contract_name.to_owned()))?;
+
+        let type_names = assign_type_names(model.definitions.keys().map(String::as_str));
+
+        Ok(Self {
+            contract_name,
+            model,
+            type_names,
+            starknet: STARKNET_PATH
+                .parse()
+                .expect("starknet crate path must be valid"),
+        })
+    }
+
+    pub(crate) fn expand(&self) -> Result<TokenStream, AbigenError> {
+        let types = self
+            .model
+            .definitions
+            .iter()
+            .map(|(type_name, definition)| self.expand_type(type_name, definition))
+            .collect::<Result<Vec<_>, _>>()?;
+        let contract = self.expand_contract()?;
+
+        Ok(quote! {
+            #(#types)*
+
+            #contract
+        })
+    }
+
+    fn expand_type(
+        &self,
+        type_name: &str,
+        definition: &TypeDefinition,
+    ) -> Result<TokenStream, AbigenError> {
+        let starknet = &self.starknet;
+        let core_path = format!("{STARKNET_PATH}::core");
+        let ident = format_ident!("{}", self.type_names[type_name]);
+        let doc = match definition.kind {
+            TypeKind::Struct | TypeKind::Enum => format!("Cairo type `{type_name}`."),
+            TypeKind::EventStruct | TypeKind::EventEnum | TypeKind::UntypedEvent => format!(
+                "Cairo event `{type_name}`. Use `decode_event` on the top-level event type to \
+                decode emitted events, as `Decode` expects the plain serialization of the type \
+                instead of event keys and data."
+            ),
+        };
+
+        let body = match definition.kind {
+            TypeKind::Struct | TypeKind::EventStruct | TypeKind::UntypedEvent => {
+                let fields = definition
+                    .members
+                    .iter()
+                    .map(|(name, member_type)| {
+                        let name = to_ident(name);
+                        let member_type = self.rust_type(member_type)?;
+                        Ok(quote! { pub #name: #member_type })
+                    })
+                    .collect::<Result<Vec<_>, AbigenError>>()?;
+
+                quote! {
+                    #[allow(non_snake_case)]
+                    pub struct #ident {
+                        #(#fields),*
+                    }
+                }
+            }
+            TypeKind::Enum | TypeKind::EventEnum => {
+                let variants = definition
+                    .members
+                    .iter()
+                    .map(|(name, variant_type)| {
+                        let name = to_ident(&to_upper_camel_case(name));
+
+                        // Unit variants are serialized as the variant index only.
+                        Ok(match variant_type {
+                            AbiType::Tuple(elements) if elements.is_empty() => quote! { #name },
+                            variant_type => {
+                                let variant_type = self.rust_type(variant_type)?;
+                                quote! { #name(#variant_type) }
+                            }
+                        })
+                    })
+                    .collect::<Result<Vec<_>, AbigenError>>()?;
+
+                quote! {
+                    pub enum #ident {
+                        #(#variants),*
+                    }
+                }
+            }
+        };
+
+        let event_impl = self.expand_event_impl(type_name, definition, &ident)?;
+
+        Ok(quote! {
+            #[doc = #doc]
+            #[derive(
+                Debug,
+                Clone,
+                PartialEq,
+                Eq,
+                #starknet::core::codec::Encode,
+                #starknet::core::codec::Decode,
+            )]
+            #[starknet(core = #core_path)]
+            #body
+
+            #event_impl
+        })
+    }
+
+    /// Generates functions for decoding an event type from the keys and data of emitted events,
+    /// following the same rules as `starknet_core::abi::EventDecoder`.
+    fn expand_event_impl(
+        &self,
+        type_name: &str,
+        definition: &TypeDefinition,
+        ident: &Ident,
+    ) -> Result<TokenStream, AbigenError> {
+        let starknet = &self.starknet;
+        let codec = quote! { #starknet::core::codec };
+        let felt = quote! { #starknet::core::types::Felt };
+
+        let (decode_fields, matches_selector) = match definition.kind {
+            TypeKind::Struct | TypeKind::Enum => return Ok(quote! {}),
+            TypeKind::EventStruct | TypeKind::UntypedEvent => {
+                let fields = definition
+                    .members
+                    .iter()
+                    .zip(definition.event_kinds.iter())
+                    .map(|((name, member_type), kind)| {
+                        let name = to_ident(name);
+                        let value = match (kind, self.event_type(member_type)) {
+                            (EventFieldKind::Key, _) => quote! { __keys.decode()? },
+                            (EventFieldKind::Nested | EventFieldKind::Flat, Some(_)) => {
+                                let member_type = self.rust_type(member_type)?;
+                                quote! { <#member_type>::decode_fields(__keys, __data)? }
+                            }
+                            _ => quote! { __data.decode()? },
+                        };
+                        Ok(quote! { #name: #value })
+                    })
+                    .collect::<Result<Vec<_>, AbigenError>>()?;
+
+                (
+                    quote! {
+                        ::core::result::Result::Ok(Self { #(#fields),* })
+                    },
+                    None,
+                )
+            }
+            TypeKind::EventEnum => {
+                let mut variants = vec![];
+                let mut matchers = vec![];
+
+                for ((name, variant_type), kind) in
+                    definition.members.iter().zip(definition.event_kinds.iter())
+                {
+                    let variant_name = to_ident(&to_upper_camel_case(name));
+                    let event_type = self.event_type(variant_type);
+
+                    // Flattened event enums are looked into without consuming a selector key.
+                    if matches!(
+                        (kind, event_type),
+                        (EventFieldKind::Flat, Some(TypeKind::EventEnum))
+                    ) {
+                        let variant_type = self.rust_type(variant_type)?;
+                        variants.push(quote! {
+                            if <#variant_type>::matches_selector(&__selector) {
+                                return ::core::result::Result::Ok(Self::#variant_name(
+                                    <#variant_type>::decode_fields(__keys, __data)?,
+                                ));
+                            }
+                        });
+                        matchers.push(quote! { <#variant_type>::matches_selector(selector) });
+                        continue;
+                    }
+
+                    let selector = felt_tokens(starknet_keccak(name.as_bytes()), starknet);
+                    let value = match (variant_type, event_type) {
+                        (AbiType::Tuple(elements), _) if elements.is_empty() => quote! {},
+                        (variant_type, Some(_)) => {
+                            let variant_type = self.rust_type(variant_type)?;
+                            quote! { (<#variant_type>::decode_fields(__keys, __data)?) }
+                        }
+                        // Variants carrying non-event types are serialized into data
+                        _ => quote! { (__data.decode()?) },
+                    };
+                    variants.push(quote! {
+                        if __selector == #selector {
+                            __keys.decode::<#felt>()?;
+                            return ::core::result::Result::Ok(Self::#variant_name #value);
+                        }
+                    });
+                    matchers.push(quote! { *selector == #selector });
+                }
+
+                let matchers = if matchers.is_empty() {
+                    quote! { false }
+                } else {
+                    quote! { #(#matchers)||* }
+                };
+
+                (
+                    quote! {
+                        let __selector = __keys.clone().decode::<#felt>()?;
+                        #(#variants)*
+                        ::core::result::Result::Err(#codec::Error::custom(::core::format_args!(
+                            "unknown event selector {:#x}",
+                            __selector
+                        )))
+                    },
+                    Some(quote! {
+                        /// Whether `selector` identifies a variant of this event enum, including
+                        /// the variants of flattened event enums.
+                        pub fn matches_selector(selector: &#felt) -> bool {
+                            #matchers
+                        }
+                    }),
+                )
+            }
+        };
+
+        // Only top-level event types are emitted on their own.
+        let decode_event = match definition.kind {
+            TypeKind::EventEnum => Some(quote! {}),
+            TypeKind::UntypedEvent => {
+                let selector = felt_tokens(
+                    starknet_keccak(
+                        type_name
+                            .rsplit("::")
+                            .next()
+                            .unwrap_or(type_name)
+                            .as_bytes(),
+                    ),
+                    starknet,
+                );
+                Some(quote! {
+                    if __keys.decode::<#felt>()? != #selector {
+                        return ::core::result::Result::Err(#codec::Error::custom(
+                            "unexpected event selector",
+                        ));
+                    }
+                })
+            }
+            _ => None,
+        }
+        .map(|check_selector| {
+            quote! {
+                /// Decodes the event from the keys and data of an emitted event, such as an
+                /// `EmittedEvent` returned by a provider.
+                pub fn decode_event(
+                    keys: &[#felt],
+                    data: &[#felt],
+                ) -> ::core::result::Result<Self, #codec::Error> {
+                    let mut __keys = #codec::Decoder::new(keys);
+                    let mut __data = #codec::Decoder::new(data);
+
+                    #check_selector
+                    let event = Self::decode_fields(&mut __keys, &mut __data)?;
+
+                    __keys.finish()?;
+                    __data.finish()?;
+                    ::core::result::Result::Ok(event)
+                }
+            }
+        });
+
+        Ok(quote! {
+            #[allow(non_snake_case)]
+            impl #ident {
+                #decode_event
+
+                /// Decodes the event from the keys and data of an emitted event, with the selectors
+                /// of enclosing event enums already consumed.
+                #[allow(unused_variables, clippy::needless_pass_by_ref_mut)]
+                pub fn decode_fields(
+                    __keys: &mut #codec::Decoder<'_>,
+                    __data: &mut #codec::Decoder<'_>,
+                ) -> ::core::result::Result<Self, #codec::Error> {
+                    #decode_fields
+                }
+
+                #matches_selector
+            }
+        })
+    }
+
+    /// Gets the kind of the event type generated for `abi_type`, if any.
+    fn event_type(&self, abi_type: &AbiType) -> Option<TypeKind> {
+        match abi_type {
+            AbiType::Named(type_name) => self
+                .model
+                .definitions
+                .get(type_name)
+                .map(|definition| definition.kind)
+                .filter(|kind| matches!(kind, TypeKind::EventStruct | TypeKind::EventEnum)),
+            _ => None,
+        }
+    }
+
+    fn expand_contract(&self) -> Result<TokenStream, AbigenError> {
+        let starknet = &self.starknet;
+        let contract_name = &self.contract_name;
+        let reader_name = format_ident!("{}Reader", contract_name);
+
+        let contract_doc = format!(
+            "Bindings for the `{contract_name}` contract, sending transactions through an account."
+        );
+        let reader_doc = format!("Read-only bindings for the `{contract_name}` contract.");
+
+        let mut call_builders = vec![];
+        let mut externals = vec![];
+        let mut contract_views = vec![];
+        let mut reader_views = vec![];
+
+        for function in &self.model.functions {
+            let name = to_ident(&function.name);
+            let call_name = format_ident!("{}_call", function.name);
+            let selector = felt_tokens(
+                get_selector_from_name(&function.name)
+                    .map_err(|_| AbigenError::InvalidName(function.name.clone()))?,
+                starknet,
+            );
+
+            let arg_names = function
+                .inputs
+                .iter()
+                .map(|(name, _)| to_ident(name))
+                .collect::<Vec<_>>();
+            let args = function
+                .inputs
+                .iter()
+                .zip(arg_names.iter())
+                .map(|((_, input_type), arg_name)| {
+                    let arg_type = self.rust_type(input_type)?;
+                    Ok(quote! { #arg_name: &#arg_type })
+                })
+                .collect::<Result<Vec<_>, AbigenError>>()?;
+            let calldata = if arg_names.is_empty() {
+                quote! { ::std::vec::Vec::new() }
+            } else {
+                quote! {{
+                    let mut __calldata = ::std::vec::Vec::new();
+                    #(
+                        #starknet::core::codec::Encode::encode(#arg_names, &mut __calldata)
+                            .expect("generated types always encode");
+                    )*
+                    __calldata
+                }}
+            };
+
+            let call_doc = format!("Builds the call to the `{}` function.", function.name);
+            call_builders.push(quote! {
+                #[doc = #call_doc]
+                pub fn #call_name(&self, #(#args),*) -> #starknet::core::types::Call {
+                    #starknet::core::types::Call {
+                        to: self.address,
+                        selector: #selector,
+                        calldata: #calldata,
+                    }
+                }
+            });
+
+            if !function.view {
+                let doc = format!("Invokes the `{}` function.", function.name);
+                externals.push(quote! {
+                    #[doc = #doc]
+                    pub fn #name(&self, #(#args),*) -> #starknet::accounts::ExecutionV3<'_, A> {
+                        #starknet::accounts::Account::execute_v3(
+                            &self.account,
+                            ::std::vec![self.#call_name(#(#arg_names),*)],
+                        )
+                    }
+                });
+            } else {
+                let doc = format!("Calls the `{}` view function.", function.name);
+                let output_types = function
+                    .outputs
+                    .iter()
+                    .map(|output| self.rust_type(output))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let (output_type, decode) = match output_types.as_slice() {
+                    [] => (quote! { () }, quote! { Ok(()) }),
+                    [output_type] => (
+                        output_type.clone(),
+                        quote! {
+                            <#output_type as #starknet::core::codec::Decode>::decode(&__result)
+                                .map_err(#starknet::contract::CallError::Decode)
+                        },
+                    ),
+                    output_types => {
+                        let output_type = quote! { (#(#output_types),*) };
+                        (
+                            output_type.clone(),
+                            quote! {
+                                <#output_type as #starknet::core::codec::Decode>::decode(
+                                    &__result,
+                                )
+                                .map_err(#starknet::contract::CallError::Decode)
+                            },
+                        )
+                    }
+                };
+
+                let view = |provider: TokenStream, block_id: TokenStream| {
+                    quote! {
+                        #[doc = #doc]
+                        pub async fn #name(
+                            &self,
+                            #(#args),*
+                        ) -> ::core::result::Result<#output_type, #starknet::contract::CallError>
+                        {
+                            let __result = #starknet::providers::Provider::call(
+                                #provider,
+                                #starknet::core::types::FunctionCall {
+                                    contract_address: self.address,
+                                    entry_point_selector: #selector,
+                                    calldata: #calldata,
+                                },
+                                #block_id,
+                            )
+                            .await
+                            .map_err(#starknet::contract::CallError::Provider)?;
+
+                            #decode
+                        }
+                    }
+                };
+
+                contract_views.push(view(
+                    quote! { #starknet::accounts::ConnectedAccount::provider(&self.account) },
+                    quote! { #starknet::accounts::ConnectedAccount::block_id(&self.account) },
+                ));
+                reader_views.push(view(quote! { &self.provider }, quote! { self.block_id }));
+            }
+        }
+
+        Ok(quote! {
+            #[doc = #contract_doc]
+            #[derive(Debug, Clone)]
+            pub struct #contract_name<A> {
+                address: #starknet::core::types::Felt,
+                account: A,
+            }
+
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            impl<A> #contract_name<A> {
+                /// Creates bindings for the contract deployed at `address`.
+                pub const fn new(address: #starknet::core::types::Felt, account: A) -> Self {
+                    Self { address, account }
+                }
+
+                /// Gets the contract address.
+                pub const fn address(&self) -> #starknet::core::types::Felt {
+                    self.address
+                }
+
+                /// Gets a reference to the account used for sending transactions.
+                pub const fn account(&self) -> &A {
+                    &self.account
+                }
+
+                #(#call_builders)*
+            }
+
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            impl<A: #starknet::accounts::Account> #contract_name<A> {
+                #(#externals)*
+            }
+
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            impl<A: #starknet::accounts::ConnectedAccount> #contract_name<A> {
+                #(#contract_views)*
+            }
+
+            #[doc = #reader_doc]
+            #[derive(Debug, Clone)]
+            pub struct #reader_name<P> {
+                address: #starknet::core::types::Felt,
+                provider: P,
+                block_id: #starknet::core::types::BlockId,
+            }
+
+            impl<P> #reader_name<P> {
+                /// Creates read-only bindings for the contract deployed at `address`, querying the
+                /// latest block.
+                pub const fn new(address: #starknet::core::types::Felt, provider: P) -> Self {
+                    Self {
+                        address,
+                        provider,
+                        block_id: #starknet::core::types::BlockId::Tag(
+                            #starknet::core::types::BlockTag::Latest,
+                        ),
+                    }
+                }
+
+                /// Sets the block to query against.
+                pub fn with_block_id(self, block_id: #starknet::core::types::BlockId) -> Self {
+                    Self { block_id, ..self }
+                }
+
+                /// Gets the contract address.
+                pub const fn address(&self) -> #starknet::core::types::Felt {
+                    self.address
+                }
+
+                /// Gets a reference to the provider used for queries.
+                pub const fn provider(&self) -> &P {
+                    &self.provider
+                }
+            }
+
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            impl<P: #starknet::providers::Provider> #reader_name<P> {
+                #(#reader_views)*
+            }
+        })
+    }
+
+    /// Maps a generic argument of a Cairo type to the Rust type used in generated code.
+    fn rust_type_of(&self, type_name: &str) -> Result<TokenStream, AbigenError> {
+        let abi_type = AbiType::parse(type_name)
+            .map_err(|_| AbigenError::InvalidTypeName(type_name.to_owned()))?;
+        self.rust_type(&abi_type)
+    }
+
+    /// Maps a Cairo type to the Rust type used in generated code.
+    fn rust_type(&self, abi_type: &AbiType) -> Result<TokenStream, AbigenError> {
+        let starknet = &self.starknet;
+
+        Ok(match abi_type {
+            AbiType::Felt => quote! { #starknet::core::types::Felt },
+            AbiType::Bool => quote! { bool },
+            AbiType::Unsigned(256) => quote! { #starknet::core::types::U256 },
+            AbiType::Unsigned(bits) => {
+                let ident = format_ident!("u{}", bits);
+                quote! { #ident }
+            }
+            AbiType::Signed(bits) => {
+                let ident = format_ident!("i{}", bits);
+                quote! { #ident }
+            }
+            AbiType::ByteArray => quote! { #starknet::core::types::ByteArray },
+            AbiType::Array(element) => {
+                let element = self.rust_type(element)?;
+                quote! { ::std::vec::Vec<#element> }
+            }
+            // `[T; N]` in the codec carries a length prefix, unlike Cairo fixed-size arrays.
+            AbiType::FixedArray(_, _) => {
+                return Err(AbigenError::UnsupportedType(abi_type.to_string()))
+            }
+            AbiType::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.rust_type(element))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if elements.len() == 1 {
+                    quote! { (#(#elements),*,) }
+                } else {
+                    quote! { (#(#elements),*) }
+                }
+            }
+            AbiType::Named(type_name) => {
+                let (path, args) = AbiType::split_generic_args(type_name)
+                    .map_err(|_| AbigenError::InvalidTypeName(type_name.clone()))?;
+                match (path, args.as_slice()) {
+                    ("core::option::Option", [inner]) => {
+                        let inner = self.rust_type_of(inner)?;
+                        quote! { ::core::option::Option<#inner> }
+                    }
+                    ("core::result::Result", [ok, err]) => {
+                        let ok = self.rust_type_of(ok)?;
+                        let err = self.rust_type_of(err)?;
+                        quote! { ::core::result::Result<#ok, #err> }
+                    }
+                    _ => {
+                        let ident = self
+                            .type_names
+                            .get(type_name)
+                            .ok_or_else(|| AbigenError::UnknownType(type_name.clone()))?;
+                        let ident = format_ident!("{}", ident);
+                        quote! { #ident }
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Turns a [`Felt`] into tokens for a constant expression.
+fn felt_tokens(felt: Felt, starknet: &TokenStream) -> TokenStream {
+    let raw = felt.to_raw().map(Literal::u64_unsuffixed);
+    quote! { #starknet::core::types::Felt::from_raw([#(#raw),*]) }
+}
@@ -0,0 +1,360 @@
+//! Generator of type-safe Rust bindings for Starknet contracts from their ABIs.
+//!
+//! This crate powers the `abigen!` macro from `starknet-macros`, and can also be used from build
+//! scripts to write bindings into source files, making the generated code reviewable in diffs and
+//! visible to IDEs:
+//!
+//! ```no_run
+//! // build.rs
+//! use starknet_abigen::Abigen;
+//!
+//! Abigen::from_file("Erc20", "contracts/erc20.contract_class.json")
+//!     .unwrap()
+//!     .write_to_out_dir("erc20.rs")
+//!     .unwrap();
+//! ```
+//!
+//! The bindings can then be included with `include!(concat!(env!("OUT_DIR"), "/erc20.rs"))`.
+//!
+//! For each struct, enum and event defined in the ABI, a Rust type deriving `Encode` and `Decode`
+//! is generated, using the same Cairo type mapping as the `starknet_core::abi` module. As emitted
+//! events spread their fields over keys and data, top-level event types additionally get a
+//! `decode_event` function for decoding them from the keys and data of emitted events. The contract
+//! itself is represented by two types:
+//!
+//! - `Name<A>`, which sends transactions through an account. External functions return an
+//!   `ExecutionV3`, while view functions are called through the account's provider.
+//! - `NameReader<P>`, which only calls view functions through a provider.
+//!
+//! Generated code refers to items through the `starknet` crate, which must be a dependency of the
+//! crate using the bindings.
+
+#![deny(missing_docs)]
+
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use serde_json::Value as JsonValue;
+use starknet_core::types::contract::{
+    legacy::{LegacyContractClass, RawLegacyAbiEntry},
+    AbiEntry, SierraClass,
+};
+
+mod error;
+pub use error::AbigenError;
+
+mod expand;
+mod model;
+mod names;
+
+/// Header prepended to generated source files.
+const GENERATED_HEADER: &str =
+    "// This file is generated by `starknet-abigen`. Do not edit manually.\n\n";
+
+/// Generator of Rust bindings for a single contract.
+#[derive(Debug, Clone)]
+pub struct Abigen {
+    contract_name: String,
+    abi: Abi,
+}
+
+#[derive(Debug, Clone)]
+enum Abi {
+    Sierra(Vec<AbiEntry>),
+    Legacy(Vec<RawLegacyAbiEntry>),
+}
+
+impl Abigen {
+    /// Creates a generator from a Sierra (Cairo 1) contract ABI. The contract name is used as the
+    /// name of the generated contract type and must be a valid Rust identifier.
+    pub fn new<N>(contract_name: N, abi: Vec<AbiEntry>) -> Self
+    where
+        N: Into<String>,
+    {
+        Self {
+            contract_name: contract_name.into(),
+            abi: Abi::Sierra(abi),
+        }
+    }
+
+    /// Creates a generator from a legacy (Cairo 0) contract ABI.
+    pub fn new_legacy<N>(contract_name: N, abi: Vec<RawLegacyAbiEntry>) -> Self
+    where
+        N: Into<String>,
+    {
+        Self {
+            contract_name: contract_name.into(),
+            abi: Abi::Legacy(abi),
+        }
+    }
+
+    /// Creates a generator from the ABI of a Sierra contract class.
+    pub fn from_sierra_class<N>(contract_name: N, class: &SierraClass) -> Self
+    where
+        N: Into<String>,
+    {
+        Self::new(contract_name, class.abi.clone())
+    }
+
+    /// Creates a generator from the ABI of a legacy contract class.
+    pub fn from_legacy_class<N>(contract_name: N, class: &LegacyContractClass) -> Self
+    where
+        N: Into<String>,
+    {
+        Self::new_legacy(contract_name, class.abi.clone())
+    }
+
+    /// Creates a generator from a JSON file containing a Sierra contract class, a legacy contract
+    /// class, or just the ABI of either.
+    pub fn from_file<N, P>(contract_name: N, path: P) -> Result<Self, AbigenError>
+    where
+        N: Into<String>,
+        P: AsRef<Path>,
+    {
+        let json = serde_json::from_str::<JsonValue>(&std::fs::read_to_string(path)?)?;
+
+        let abi = if json.get("sierra_program").is_some() {
+            Abi::Sierra(serde_json::from_value::<SierraClass>(json)?.abi)
+        } else if json.get("program").is_some() {
+            Abi::Legacy(serde_json::from_value::<LegacyContractClass>(json)?.abi)
+        } else {
+            match serde_json::from_value::<Vec<AbiEntry>>(json.clone()) {
+                Ok(abi) => Abi::Sierra(abi),
+                Err(err) => Abi::Legacy(
+                    serde_json::from_value::<Vec<RawLegacyAbiEntry>>(json).map_err(|_| err)?,
+                ),
+            }
+        };
+
+        Ok(Self {
+            contract_name: contract_name.into(),
+            abi,
+        })
+    }
+
+    /// Generates the bindings as a token stream.
+    pub fn generate(&self) -> Result<TokenStream, AbigenError> {
+        let model = match &self.abi {
+            Abi::Sierra(abi) => model::ContractModel::from_sierra(abi)?,
+            Abi::Legacy(abi) => model::ContractModel::from_legacy(abi)?,
+        };
+
+        expand::Expander::new(&self.contract_name, model)?.expand()
+    }
+
+    /// Generates the bindings as formatted Rust source code.
+    pub fn generate_source(&self) -> Result<String, AbigenError> {
+        let file = syn::parse2::<syn::File>(self.generate()?)
+            .expect("generated bindings must be valid Rust code");
+
+        Ok(format!(
+            "{GENERATED_HEADER}{}",
+            prettyplease::unparse(&file)
+        ))
+    }
+
+    /// Writes the formatted bindings to a file. The file is left untouched if its content is
+    /// already up to date, avoiding unnecessary rebuilds.
+    pub fn write_to_file<P>(&self, path: P) -> Result<(), AbigenError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let source = self.generate_source()?;
+
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == source) {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, source)?;
+
+        Ok(())
+    }
+
+    /// Writes the formatted bindings to a file in `OUT_DIR`, returning the path written to. This
+    /// is intended to be called from build scripts.
+    pub fn write_to_out_dir<P>(&self, file_name: P) -> Result<PathBuf, AbigenError>
+    where
+        P: AsRef<Path>,
+    {
+        let out_dir = std::env::var_os("OUT_DIR").ok_or(AbigenError::OutDirNotSet)?;
+        let path = Path::new(&out_dir).join(file_name);
+
+        self.write_to_file(&path)?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate(abi: &str) -> Result<String, AbigenError> {
+        Abigen::new("Contract", serde_json::from_str(abi).unwrap())
+            .generate()
+            .map(|tokens| tokens.to_string())
+    }
+
+    #[test]
+    fn test_generate_sierra_class() {
+        let class = serde_json::from_str::<SierraClass>(include_str!(
+            "../../starknet-core/test-data/contracts/cairo2/artifacts/abi_types_sierra.txt"
+        ))
+        .unwrap();
+
+        let bindings = Abigen::from_sierra_class("AbiTypes", &class)
+            .generate()
+            .unwrap()
+            .to_string();
+
+        for item in [
+            "pub struct AbiTypes < A >",
+            "pub struct AbiTypesReader < P >",
+            "pub enum ExampleEnum { VariantA (:: starknet :: core :: types :: Felt) , VariantB (:: starknet :: core :: types :: U256) }",
+            "pub struct StaticEvent { }",
+            "pub enum Event { ExampleEvent (ExampleEvent) , FlatEvent (FlatEvent) }",
+            "pub fn example_external_function",
+        ] {
+            assert!(bindings.contains(item), "missing `{item}`");
+        }
+
+        // Core types defined in the ABI map to existing Rust types.
+        assert!(!bindings.contains("pub struct U256"));
+        // L1 handlers cannot be invoked through transactions.
+        assert!(!bindings.contains("example_l1_handler"));
+    }
+
+    #[test]
+    fn test_generate_unit_variants() {
+        let bindings = generate(
+            r#"[
+                {
+                    "type": "enum",
+                    "name": "test::Status",
+                    "variants": [
+                        { "name": "active", "type": "()" },
+                        { "name": "paused", "type": "core::option::Option::<core::integer::u64>" }
+                    ]
+                },
+                {
+                    "type": "enum",
+                    "name": "core::option::Option::<core::integer::u64>",
+                    "variants": [
+                        { "name": "Some", "type": "core::integer::u64" },
+                        { "name": "None", "type": "()" }
+                    ]
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert!(bindings
+            .contains("pub enum Status { Active , Paused (:: core :: option :: Option < u64 >) }"));
+        assert!(!bindings.contains("pub enum Option"));
+    }
+
+    #[test]
+    fn test_generate_unknown_type() {
+        let err = generate(
+            r#"[
+                {
+                    "type": "function",
+                    "name": "get",
+                    "inputs": [],
+                    "outputs": [{ "type": "test::Missing" }],
+                    "state_mutability": "view"
+                }
+            ]"#,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, AbigenError::UnknownType(name) if name == "test::Missing"));
+    }
+
+    #[test]
+    fn test_generate_untyped_event() {
+        let bindings = generate(
+            r#"[
+                {
+                    "type": "event",
+                    "name": "Transfer",
+                    "inputs": [{ "name": "amount", "type": "core::felt252" }]
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        // Cairo 1.x events are identified by the selector of their name.
+        let selector = starknet_core::utils::starknet_keccak(b"Transfer")
+            .to_raw()
+            .map(|limb| limb.to_string())
+            .join(" , ");
+        assert!(bindings.contains("pub fn decode_event"));
+        assert!(bindings.contains(&format!("from_raw ([{selector}])")));
+    }
+
+    #[test]
+    fn test_generate_legacy_class() {
+        let bindings = Abigen::from_file(
+            "OzAccount",
+            "../starknet-core/test-data/contracts/cairo0/artifacts/oz_account.txt",
+        )
+        .unwrap()
+        .generate()
+        .unwrap()
+        .to_string();
+
+        for item in [
+            "pub struct AccountCallArray",
+            "pub async fn getPublicKey",
+            "pub fn setPublicKey",
+            "signature : & :: std :: vec :: Vec < :: starknet :: core :: types :: Felt >",
+        ] {
+            assert!(bindings.contains(item), "missing `{item}`");
+        }
+        // Length parameters are merged into arrays.
+        assert!(!bindings.contains("signature_len"));
+    }
+
+    #[test]
+    fn test_write_to_file() {
+        let path = std::env::temp_dir()
+            .join(format!("starknet-abigen-{}", std::process::id()))
+            .join("erc20.rs");
+        let abigen = Abigen::from_file(
+            "Erc20",
+            "../starknet-core/test-data/contracts/cairo2/artifacts/erc20_sierra.txt",
+        )
+        .unwrap();
+
+        abigen.write_to_file(&path).unwrap();
+        let source = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert!(source.starts_with(GENERATED_HEADER));
+        assert!(source.contains("\npub struct Erc20<A> {\n"));
+        assert_eq!(source, abigen.generate_source().unwrap());
+    }
+
+    #[test]
+    fn test_from_file_invalid_artifact() {
+        let path =
+            std::env::temp_dir().join(format!("starknet-abigen-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"abi": 1}"#).unwrap();
+
+        let err = Abigen::from_file("Contract", &path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(err, AbigenError::InvalidArtifact(_)));
+    }
+
+    #[test]
+    fn test_generate_invalid_contract_name() {
+        let err = Abigen::new("not a name", vec![]).generate().unwrap_err();
+
+        assert!(matches!(err, AbigenError::InvalidName(_)));
+    }
+}
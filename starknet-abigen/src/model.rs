@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+
+use starknet_core::{
+    abi::AbiType,
+    types::{
+        contract::{
+            legacy::RawLegacyAbiEntry, AbiEntry, AbiEvent, AbiNamedMember, EventField,
+            EventFieldKind, StateMutability, TypedAbiEvent,
+        },
+        FunctionStateMutability, LegacyTypedParameter,
+    },
+};
+
+use crate::AbigenError;
+
+/// Contract ABI normalized from either Sierra or legacy ABI entries, with all types parsed.
+pub(crate) struct ContractModel {
+    pub functions: Vec<Function>,
+    pub definitions: BTreeMap<String, TypeDefinition>,
+}
+
+pub(crate) struct Function {
+    pub name: String,
+    pub inputs: Vec<(String, AbiType)>,
+    pub outputs: Vec<AbiType>,
+    pub view: bool,
+}
+
+/// A struct or enum to be generated, with its members or variants as name and type pairs.
+pub(crate) struct TypeDefinition {
+    pub kind: TypeKind,
+    pub members: Vec<(String, AbiType)>,
+    /// For event types, where each member is emitted.
+    pub event_kinds: Vec<EventFieldKind>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TypeKind {
+    Struct,
+    Enum,
+    /// Cairo 2.x event struct.
+    EventStruct,
+    /// Cairo 2.x event enum.
+    EventEnum,
+    /// Cairo 0 or Cairo 1.x event, identified by the selector of its name.
+    UntypedEvent,
+}
+
+impl ContractModel {
+    pub fn from_sierra(abi: &[AbiEntry]) -> Result<Self, AbigenError> {
+        let mut model = Self {
+            functions: vec![],
+            definitions: BTreeMap::new(),
+        };
+        model.add_sierra_entries(abi)?;
+        Ok(model)
+    }
+
+    pub fn from_legacy(abi: &[RawLegacyAbiEntry]) -> Result<Self, AbigenError> {
+        let mut model = Self {
+            functions: vec![],
+            definitions: BTreeMap::new(),
+        };
+
+        for entry in abi {
+            match entry {
+                RawLegacyAbiEntry::Function(function) => model.add_function(Function {
+                    name: function.name.clone(),
+                    inputs: parse_legacy_parameters(&function.inputs)?,
+                    outputs: parse_legacy_parameters(&function.outputs)?
+                        .into_iter()
+                        .map(|(_, output_type)| output_type)
+                        .collect(),
+                    view: matches!(
+                        function.state_mutability,
+                        Some(FunctionStateMutability::View)
+                    ),
+                }),
+                RawLegacyAbiEntry::Struct(item) => model.add_definition(
+                    &item.name,
+                    TypeDefinition {
+                        kind: TypeKind::Struct,
+                        members: item
+                            .members
+                            .iter()
+                            .map(|member| Ok((member.name.clone(), parse_legacy(&member.r#type)?)))
+                            .collect::<Result<_, AbigenError>>()?,
+                        event_kinds: vec![],
+                    },
+                ),
+                // Keys come before data, matching how events are emitted.
+                RawLegacyAbiEntry::Event(event) => {
+                    let keys = parse_legacy_parameters(&event.keys)?;
+                    let data = parse_legacy_parameters(&event.data)?;
+
+                    model.add_definition(
+                        &event.name,
+                        TypeDefinition {
+                            kind: TypeKind::UntypedEvent,
+                            event_kinds: keys
+                                .iter()
+                                .map(|_| EventFieldKind::Key)
+                                .chain(data.iter().map(|_| EventFieldKind::Data))
+                                .collect(),
+                            members: keys.into_iter().chain(data).collect(),
+                        },
+                    )
+                }
+                RawLegacyAbiEntry::Constructor(_) | RawLegacyAbiEntry::L1Handler(_) => {}
+            }
+        }
+
+        Ok(model)
+    }
+
+    fn add_sierra_entries(&mut self, entries: &[AbiEntry]) -> Result<(), AbigenError> {
+        for entry in entries {
+            match entry {
+                AbiEntry::Function(function) => self.add_function(Function {
+                    name: function.name.clone(),
+                    inputs: parse_members(&function.inputs)?,
+                    outputs: function
+                        .outputs
+                        .iter()
+                        .map(|output| parse(&output.r#type))
+                        .collect::<Result<_, _>>()?,
+                    view: matches!(function.state_mutability, StateMutability::View),
+                }),
+                AbiEntry::Interface(interface) => self.add_sierra_entries(&interface.items)?,
+                AbiEntry::Struct(item) => self.add_definition(
+                    &item.name,
+                    TypeDefinition {
+                        kind: TypeKind::Struct,
+                        members: parse_members(&item.members)?,
+                        event_kinds: vec![],
+                    },
+                ),
+                AbiEntry::Enum(item) => self.add_definition(
+                    &item.name,
+                    TypeDefinition {
+                        kind: TypeKind::Enum,
+                        members: parse_members(&item.variants)?,
+                        event_kinds: vec![],
+                    },
+                ),
+                AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Struct(event))) => self
+                    .add_definition(
+                        &event.name,
+                        TypeDefinition {
+                            kind: TypeKind::EventStruct,
+                            members: parse_event_fields(&event.members)?,
+                            event_kinds: event_kinds(&event.members),
+                        },
+                    ),
+                AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Enum(event))) => self
+                    .add_definition(
+                        &event.name,
+                        TypeDefinition {
+                            kind: TypeKind::EventEnum,
+                            members: parse_event_fields(&event.variants)?,
+                            event_kinds: event_kinds(&event.variants),
+                        },
+                    ),
+                AbiEntry::Event(AbiEvent::Untyped(event)) => self.add_definition(
+                    &event.name,
+                    TypeDefinition {
+                        kind: TypeKind::UntypedEvent,
+                        members: parse_members(&event.inputs)?,
+                        event_kinds: event.inputs.iter().map(|_| EventFieldKind::Data).collect(),
+                    },
+                ),
+                AbiEntry::Constructor(_) | AbiEntry::Impl(_) | AbiEntry::L1Handler(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Functions with duplicate names, such as those implemented in multiple interfaces, are only
+    /// kept once.
+    fn add_function(&mut self, function: Function) {
+        if !self.functions.iter().any(|item| item.name == function.name) {
+            self.functions.push(function);
+        }
+    }
+
+    /// Core types like `u256` and `Option<T>` are defined in the ABI but map to existing Rust
+    /// types, so no definitions are added for them.
+    fn add_definition(&mut self, type_name: &str, definition: TypeDefinition) {
+        if !matches!(AbiType::parse(type_name), Ok(AbiType::Named(_))) {
+            return;
+        }
+        if let Ok(("core::option::Option" | "core::result::Result", _)) =
+            AbiType::split_generic_args(type_name)
+        {
+            return;
+        }
+
+        self.definitions
+            .entry(type_name.to_owned())
+            .or_insert(definition);
+    }
+}
+
+fn parse(type_name: &str) -> Result<AbiType, AbigenError> {
+    AbiType::parse(type_name).map_err(|_| AbigenError::InvalidTypeName(type_name.to_owned()))
+}
+
+fn parse_legacy(type_name: &str) -> Result<AbiType, AbigenError> {
+    AbiType::parse_legacy(type_name).map_err(|_| AbigenError::InvalidTypeName(type_name.to_owned()))
+}
+
+fn parse_members(members: &[AbiNamedMember]) -> Result<Vec<(String, AbiType)>, AbigenError> {
+    members
+        .iter()
+        .map(|member| Ok((member.name.clone(), parse(&member.r#type)?)))
+        .collect()
+}
+
+fn parse_event_fields(fields: &[EventField]) -> Result<Vec<(String, AbiType)>, AbigenError> {
+    fields
+        .iter()
+        .map(|field| Ok((field.name.clone(), parse(&field.r#type)?)))
+        .collect()
+}
+
+fn event_kinds(fields: &[EventField]) -> Vec<EventFieldKind> {
+    fields.iter().map(|field| field.kind.clone()).collect()
+}
+
+/// Parses legacy parameters, merging each pointer parameter `x` and its preceding `x_len` parameter
+/// into a single array.
+fn parse_legacy_parameters(
+    parameters: &[LegacyTypedParameter],
+) -> Result<Vec<(String, AbiType)>, AbigenError> {
+    let mut parsed: Vec<(String, AbiType)> = vec![];
+
+    for parameter in parameters {
+        match parameter.r#type.strip_suffix('*') {
+            Some(element_type) => {
+                let length_name = format!("{}_len", parameter.name);
+                match parsed.last() {
+                    Some((name, AbiType::Felt)) if name == &length_name => {
+                        parsed.pop();
+                    }
+                    _ => return Err(AbigenError::InvalidTypeName(parameter.r#type.clone())),
+                }
+
+                parsed.push((
+                    parameter.name.clone(),
+                    AbiType::Array(Box::new(parse_legacy(element_type)?)),
+                ));
+            }
+            None => parsed.push((parameter.name.clone(), parse_legacy(&parameter.r#type)?)),
+        }
+    }
+
+    Ok(parsed)
+}
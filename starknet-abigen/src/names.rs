@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use proc_macro2::{Ident, Span};
+use starknet_core::abi::AbiType;
+
+/// Converts a Cairo identifier into `UpperCamelCase`, keeping existing capitalization, e.g.
+/// `variant_a` into `VariantA` and `ERC20Component` as is.
+pub(crate) fn to_upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// Turns a Cairo identifier into a Rust identifier, escaping Rust keywords.
+pub(crate) fn to_ident(name: &str) -> Ident {
+    match name {
+        // These keywords cannot be used as raw identifiers.
+        "self" | "Self" | "super" | "crate" | "_" => {
+            Ident::new(&format!("{name}_"), Span::call_site())
+        }
+        _ if syn::parse_str::<Ident>(name).is_err() => Ident::new_raw(name, Span::call_site()),
+        _ => Ident::new(name, Span::call_site()),
+    }
+}
+
+/// Assigns a unique Rust type name to each Cairo type name.
+///
+/// The last path segment is used whenever possible. Paths sharing the same last segment are
+/// prefixed with as many parent segments as necessary, e.g. `ERC20Component::Event` becomes
+/// `ERC20ComponentEvent`. Generic instantiations of the same type are suffixed with their generic
+/// arguments, e.g. `Pair::<core::integer::u8>` becomes `PairU8`.
+pub(crate) fn assign_type_names<'a, I>(type_names: I) -> BTreeMap<String, String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    struct Entry<'a> {
+        type_name: &'a str,
+        segments: Vec<String>,
+        suffix: String,
+        depth: usize,
+    }
+
+    let mut entries = type_names
+        .into_iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|type_name| {
+            let (path, args) =
+                AbiType::split_generic_args(type_name).unwrap_or_else(|_| (type_name, vec![]));
+
+            Entry {
+                type_name,
+                segments: path.split("::").map(to_upper_camel_case).collect(),
+                suffix: args.into_iter().map(generic_arg_name).collect(),
+                depth: 1,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut path_counts = BTreeMap::<&[String], usize>::new();
+    for entry in &entries {
+        *path_counts.entry(&entry.segments).or_default() += 1;
+    }
+    let generic_paths = path_counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(path, _)| path.to_vec())
+        .collect::<BTreeSet<_>>();
+
+    let candidate = |entry: &Entry<'_>| {
+        let mut name = entry.segments[(entry.segments.len() - entry.depth)..].concat();
+        if generic_paths.contains(&entry.segments) {
+            name.push_str(&entry.suffix);
+        }
+        name
+    };
+
+    loop {
+        let mut candidate_counts = BTreeMap::<String, usize>::new();
+        for entry in &entries {
+            *candidate_counts.entry(candidate(entry)).or_default() += 1;
+        }
+
+        let mut extended = false;
+        for entry in &mut entries {
+            if candidate_counts[&candidate(entry)] > 1 && entry.depth < entry.segments.len() {
+                entry.depth += 1;
+                extended = true;
+            }
+        }
+
+        if !extended {
+            break;
+        }
+    }
+
+    // Names can still collide when different paths turn into the same `UpperCamelCase` form.
+    let mut taken = BTreeSet::new();
+    entries
+        .iter()
+        .map(|entry| {
+            let base = candidate(entry);
+            let mut name = base.clone();
+            let mut index = 1usize;
+            while !taken.insert(name.clone()) {
+                index += 1;
+                name = format!("{base}{index}");
+            }
+            (entry.type_name.to_owned(), name)
+        })
+        .collect()
+}
+
+/// Turns a generic argument into a name suitable as part of a type name, e.g.
+/// `core::integer::u8` into `U8`.
+fn generic_arg_name(arg: &str) -> String {
+    let (path, _) = AbiType::split_generic_args(arg).unwrap_or((arg, vec![]));
+    to_upper_camel_case(
+        &path
+            .rsplit("::")
+            .next()
+            .unwrap_or(path)
+            .chars()
+            .filter(|char| char.is_ascii_alphanumeric() || *char == '_')
+            .collect::<String>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_upper_camel_case() {
+        assert_eq!(to_upper_camel_case("variant_a"), "VariantA");
+        assert_eq!(to_upper_camel_case("ERC20Component"), "ERC20Component");
+        assert_eq!(to_upper_camel_case("abi_types"), "AbiTypes");
+    }
+
+    #[test]
+    fn test_to_ident() {
+        assert_eq!(to_ident("amount").to_string(), "amount");
+        assert_eq!(to_ident("type").to_string(), "r#type");
+        assert_eq!(to_ident("self").to_string(), "self_");
+    }
+
+    #[test]
+    fn test_assign_type_names() {
+        let names = assign_type_names([
+            "erc20::Transfer",
+            "erc20::Event",
+            "openzeppelin::token::erc20::ERC20Component::Event",
+            "pair::Pair::<core::integer::u8>",
+            "pair::Pair::<core::felt252>",
+            "single::Wrapper::<core::felt252>",
+        ]);
+
+        assert_eq!(names["erc20::Transfer"], "Transfer");
+        assert_eq!(names["erc20::Event"], "Erc20Event");
+        assert_eq!(
+            names["openzeppelin::token::erc20::ERC20Component::Event"],
+            "ERC20ComponentEvent"
+        );
+        assert_eq!(names["pair::Pair::<core::integer::u8>"], "PairU8");
+        assert_eq!(names["pair::Pair::<core::felt252>"], "PairFelt252");
+        assert_eq!(names["single::Wrapper::<core::felt252>"], "Wrapper");
+    }
+}
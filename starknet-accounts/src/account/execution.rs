@@ -9,7 +9,7 @@ use starknet_core::types::{
     FeeEstimate, Felt, InvokeTransactionResult, ResourceBounds, ResourceBoundsMapping,
     SimulatedTransaction, SimulationFlag, SimulationFlagForEstimateFee,
 };
-use starknet_crypto::PoseidonHasher;
+use starknet_crypto::{poseidon_hash_many, PoseidonHasher};
 use starknet_providers::Provider;
 use starknet_signers::SignerInteractivityContext;
 
@@ -48,6 +48,10 @@ impl<'a, A> ExecutionV3<'a, A> {
             gas_estimate_multiplier: 1.5,
             gas_price_estimate_multiplier: 1.5,
             tip: None,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
         }
     }
 
@@ -135,6 +139,48 @@ impl<'a, A> ExecutionV3<'a, A> {
         }
     }
 
+    /// Returns a new [`ExecutionV3`] with the `paymaster_data`. Defaults to empty, meaning that the
+    /// account pays for the transaction itself.
+    pub fn paymaster_data(self, paymaster_data: Vec<Felt>) -> Self {
+        Self {
+            paymaster_data,
+            ..self
+        }
+    }
+
+    /// Returns a new [`ExecutionV3`] with the `account_deployment_data`. Defaults to empty, meaning
+    /// that the account is already deployed.
+    pub fn account_deployment_data(self, account_deployment_data: Vec<Felt>) -> Self {
+        Self {
+            account_deployment_data,
+            ..self
+        }
+    }
+
+    /// Returns a new [`ExecutionV3`] with the `nonce_data_availability_mode`. Defaults to
+    /// [`DataAvailabilityMode::L1`].
+    pub fn nonce_data_availability_mode(
+        self,
+        nonce_data_availability_mode: DataAvailabilityMode,
+    ) -> Self {
+        Self {
+            nonce_data_availability_mode,
+            ..self
+        }
+    }
+
+    /// Returns a new [`ExecutionV3`] with the `fee_data_availability_mode`. Defaults to
+    /// [`DataAvailabilityMode::L1`].
+    pub fn fee_data_availability_mode(
+        self,
+        fee_data_availability_mode: DataAvailabilityMode,
+    ) -> Self {
+        Self {
+            fee_data_availability_mode,
+            ..self
+        }
+    }
+
     /// Calling this function after manually specifying `nonce`, `gas` and `gas_price` turns
     /// [`ExecutionV3`] into [`PreparedExecutionV3`]. Returns `Err` if any field is `None`.
     pub fn prepared(self) -> Result<PreparedExecutionV3<'a, A>, NotPreparedError> {
@@ -159,6 +205,10 @@ impl<'a, A> ExecutionV3<'a, A> {
                 l1_data_gas,
                 l1_data_gas_price,
                 tip,
+                paymaster_data: self.paymaster_data,
+                account_deployment_data: self.account_deployment_data,
+                nonce_data_availability_mode: self.nonce_data_availability_mode,
+                fee_data_availability_mode: self.fee_data_availability_mode,
             },
         })
     }
@@ -209,7 +259,12 @@ where
         self.prepare().await?.send().await
     }
 
-    async fn prepare(&self) -> Result<PreparedExecutionV3<'a, A>, AccountError<A::SignError>> {
+    /// Resolves `nonce` and transaction fee options that are not manually specified from a
+    /// [`Provider`], turning [`ExecutionV3`] into [`PreparedExecutionV3`] without signing it.
+    ///
+    /// This is useful when the transaction hash has to be known before signing, such as for
+    /// collecting signatures from multiple parties.
+    pub async fn prepare(&self) -> Result<PreparedExecutionV3<'a, A>, AccountError<A::SignError>> {
         // Resolves nonce
         let nonce = match self.nonce {
             Some(value) => value,
@@ -366,6 +421,10 @@ where
                 l1_data_gas,
                 l1_data_gas_price,
                 tip,
+                paymaster_data: self.paymaster_data.clone(),
+                account_deployment_data: self.account_deployment_data.clone(),
+                nonce_data_availability_mode: self.nonce_data_availability_mode,
+                fee_data_availability_mode: self.fee_data_availability_mode,
             },
         })
     }
@@ -390,6 +449,10 @@ where
                 l1_data_gas: 0,
                 l1_data_gas_price: 0,
                 tip: 0,
+                paymaster_data: self.paymaster_data.clone(),
+                account_deployment_data: self.account_deployment_data.clone(),
+                nonce_data_availability_mode: self.nonce_data_availability_mode,
+                fee_data_availability_mode: self.fee_data_availability_mode,
             },
         };
         let invoke = prepared
@@ -445,6 +508,10 @@ where
                 l1_data_gas: self.l1_data_gas.unwrap_or_default(),
                 l1_data_gas_price: self.l1_data_gas_price.unwrap_or_default(),
                 tip: self.tip.unwrap_or_default(),
+                paymaster_data: self.paymaster_data.clone(),
+                account_deployment_data: self.account_deployment_data.clone(),
+                nonce_data_availability_mode: self.nonce_data_availability_mode,
+                fee_data_availability_mode: self.fee_data_availability_mode,
             },
         };
         let invoke = prepared
@@ -527,17 +594,18 @@ impl RawExecutionV3 {
             fee_hasher.finalize()
         });
 
-        // Hard-coded empty `paymaster_data`
-        hasher.update(PoseidonHasher::new().finalize());
+        hasher.update(poseidon_hash_many(&self.paymaster_data));
 
         hasher.update(chain_id);
         hasher.update(self.nonce);
 
-        // Hard-coded L1 DA mode for nonce and fee
-        hasher.update(Felt::ZERO);
+        // Nonce DA mode in the upper 32 bits, followed by fee DA mode in the lower 32 bits
+        hasher.update(Felt::from(
+            (data_availability_mode_value(self.nonce_data_availability_mode) << 32)
+                | data_availability_mode_value(self.fee_data_availability_mode),
+        ));
 
-        // Hard-coded empty `account_deployment_data`
-        hasher.update(PoseidonHasher::new().finalize());
+        hasher.update(poseidon_hash_many(&self.account_deployment_data));
 
         hasher.update({
             let mut calldata_hasher = PoseidonHasher::new();
@@ -597,6 +665,43 @@ impl RawExecutionV3 {
     pub const fn tip(&self) -> u64 {
         self.tip
     }
+
+    /// Gets the `paymaster_data` of the execution request.
+    pub fn paymaster_data(&self) -> &[Felt] {
+        &self.paymaster_data
+    }
+
+    /// Gets the `account_deployment_data` of the execution request.
+    pub fn account_deployment_data(&self) -> &[Felt] {
+        &self.account_deployment_data
+    }
+
+    /// Gets the `nonce_data_availability_mode` of the execution request.
+    pub const fn nonce_data_availability_mode(&self) -> DataAvailabilityMode {
+        self.nonce_data_availability_mode
+    }
+
+    /// Gets the `fee_data_availability_mode` of the execution request.
+    pub const fn fee_data_availability_mode(&self) -> DataAvailabilityMode {
+        self.fee_data_availability_mode
+    }
+}
+
+impl<'a, A> PreparedExecutionV3<'a, A> {
+    /// Constructs [`PreparedExecutionV3`] by attaching an account to a [`RawExecutionV3`], which
+    /// could have been prepared elsewhere.
+    pub const fn from_raw(raw_execution: RawExecutionV3, account: &'a A) -> Self {
+        Self {
+            account,
+            inner: raw_execution,
+        }
+    }
+
+    /// Gets a reference to the underlying [`RawExecutionV3`], which contains everything needed
+    /// for computing the transaction hash.
+    pub const fn raw_execution(&self) -> &RawExecutionV3 {
+        &self.inner
+    }
 }
 
 impl<A> PreparedExecutionV3<'_, A>
@@ -667,14 +772,124 @@ where
                 },
             },
             tip: self.inner.tip,
-            // Hard-coded empty `paymaster_data`
+            paymaster_data: self.inner.paymaster_data.clone(),
+            account_deployment_data: self.inner.account_deployment_data.clone(),
+            nonce_data_availability_mode: self.inner.nonce_data_availability_mode,
+            fee_data_availability_mode: self.inner.fee_data_availability_mode,
+            is_query: query_only,
+        })
+    }
+}
+
+const fn data_availability_mode_value(mode: DataAvailabilityMode) -> u64 {
+    match mode {
+        DataAvailabilityMode::L1 => 0,
+        DataAvailabilityMode::L2 => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_core::chain_id;
+
+    use super::*;
+    use crate::ExecutionEncoding;
+
+    /// Transaction `0x053a60988dcb176eec0c6a8740030e30848de7ce49d91b76b7e5204dd872f817` on Starknet
+    /// Sepolia.
+    fn sepolia_execution() -> RawExecutionV3 {
+        RawExecutionV3 {
+            calls: vec![
+                Call {
+                    to: Felt::from_hex_unchecked(
+                        "0x4138fd51f90d171df37e9d4419c8cdb67d525840c58f8a5c347be93a1c5277d",
+                    ),
+                    selector: Felt::from_hex_unchecked(
+                        "0xb17d8a2731ba7ca1816631e6be14f0fc1b8390422d649fa27f0fbb0c91eea8",
+                    ),
+                    calldata: vec![],
+                },
+                Call {
+                    to: Felt::from_hex_unchecked(
+                        "0x28c62efb55444e72ba017fd975177c3960fc62a1c213713691dff28c0a81424",
+                    ),
+                    selector: Felt::from_hex_unchecked(
+                        "0x27a4a7332e590dd789019a6d125ff2aacd358e453090978cbf81f0d85e4c045",
+                    ),
+                    calldata: vec![
+                        Felt::from_hex_unchecked("0x344"),
+                        Felt::from_hex_unchecked(
+                            "0x388dfafef49d69f534cd3cca247e589049657d08b6442725053da1d992635ba",
+                        ),
+                    ],
+                },
+            ],
+            nonce: Felt::from_hex_unchecked("0x28de"),
+            l1_gas: 0x186a0,
+            l1_gas_price: 0x2d79883d20000,
+            l2_gas: 0x5f5e100,
+            l2_gas_price: 0xba43b7400,
+            l1_data_gas: 0x186a0,
+            l1_data_gas_price: 0x2d79883d20000,
+            tip: 0,
             paymaster_data: vec![],
-            // Hard-coded empty `account_deployment_data`
             account_deployment_data: vec![],
-            // Hard-coded L1 DA mode for nonce and fee
             nonce_data_availability_mode: DataAvailabilityMode::L1,
             fee_data_availability_mode: DataAvailabilityMode::L1,
-            is_query: query_only,
-        })
+        }
+    }
+
+    const SENDER_ADDRESS: Felt = Felt::from_hex_unchecked(
+        "0x745d525a3582e91299d8d7c71730ffc4b1f191f5b219d800334bc0edad0983b",
+    );
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_transaction_hash() {
+        assert_eq!(
+            sepolia_execution().transaction_hash(
+                chain_id::SEPOLIA,
+                SENDER_ADDRESS,
+                false,
+                ExecutionEncoding::New
+            ),
+            Felt::from_hex_unchecked(
+                "0x53a60988dcb176eec0c6a8740030e30848de7ce49d91b76b7e5204dd872f817"
+            )
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_transaction_hash_with_paymaster_and_deployment_data() {
+        let execution = RawExecutionV3 {
+            tip: 5,
+            paymaster_data: vec![
+                Felt::from_hex_unchecked("0x1234"),
+                Felt::from_hex_unchecked("0x5678"),
+            ],
+            account_deployment_data: vec![
+                Felt::from_hex_unchecked("0xabcd"),
+                Felt::from_hex_unchecked("0xef01"),
+                Felt::from_hex_unchecked("0x2345"),
+            ],
+            nonce_data_availability_mode: DataAvailabilityMode::L2,
+            fee_data_availability_mode: DataAvailabilityMode::L2,
+            ..sepolia_execution()
+        };
+
+        // Computed with the `starknet_api` transaction hash implementation, extended with the
+        // `L1_DATA` resource bound, which reproduces the Sepolia transaction hash above
+        assert_eq!(
+            execution.transaction_hash(
+                chain_id::SEPOLIA,
+                SENDER_ADDRESS,
+                false,
+                ExecutionEncoding::New
+            ),
+            Felt::from_hex_unchecked(
+                "0x7aa183196670cb543a95428e1b0fb5f33f3f00574a66a5b5f034bb7c8651859"
+            )
+        );
     }
 }
@@ -1,14 +1,21 @@
 use async_trait::async_trait;
 use auto_impl::auto_impl;
 use starknet_core::types::{
-    contract::ComputeClassHashError, BlockId, BlockTag, Call, Felt, FlattenedSierraClass,
+    contract::ComputeClassHashError, BlockId, BlockTag, Call, DataAvailabilityMode, Felt,
+    FlattenedSierraClass,
+};
+use starknet_providers::{
+    paymaster::{FeeMode, TimeBounds},
+    PaymasterClient, Provider, ProviderError,
 };
-use starknet_providers::{Provider, ProviderError};
 use starknet_signers::SignerInteractivityContext;
 use std::{error::Error, sync::Arc};
 
+use crate::ParseOutsideExecutionError;
+
 mod declaration;
 mod execution;
+mod paymaster;
 
 /// The standard Starknet account contract interface. It makes no assumption about the underlying
 /// signer or provider. Account implementations that come with an active connection to the network
@@ -153,6 +160,31 @@ pub trait ConnectedAccount: Account {
     }
 }
 
+/// An [`Account`] implementation that can also sign arbitrary message hashes, such as the SNIP-12
+/// hashes of outside executions.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait MessageSigner: Account {
+    /// Signs a message hash on behalf of the account. The signature must be accepted by the
+    /// `is_valid_signature` entrypoint of the account contract.
+    async fn sign_message_hash(&self, message_hash: &Felt) -> Result<Vec<Felt>, Self::SignError>;
+}
+
+/// Extension trait for executing calls through a SNIP-29 paymaster from any [`MessageSigner`].
+pub trait PaymasterAccount: MessageSigner {
+    /// Generates an instance of [`PaymasterExecution`] for executing calls through a SNIP-29
+    /// paymaster, which submits the transaction on behalf of the account and collects fees
+    /// according to `fee_mode`.
+    fn execute_paymaster<'a, T>(
+        &'a self,
+        calls: Vec<Call>,
+        paymaster: &'a PaymasterClient<T>,
+        fee_mode: FeeMode,
+    ) -> PaymasterExecution<'a, Self, T> {
+        PaymasterExecution::new(calls, self, paymaster, fee_mode)
+    }
+}
+
 /// Abstraction over `INVOKE` transactions from accounts for invoking contracts. This struct uses
 /// v3 `INVOKE` transactions under the hood, and hence pays transaction fees in STRK.
 ///
@@ -173,6 +205,10 @@ pub struct ExecutionV3<'a, A> {
     gas_estimate_multiplier: f64,
     gas_price_estimate_multiplier: f64,
     tip: Option<u64>,
+    paymaster_data: Vec<Felt>,
+    account_deployment_data: Vec<Felt>,
+    nonce_data_availability_mode: DataAvailabilityMode,
+    fee_data_availability_mode: DataAvailabilityMode,
 }
 
 /// Abstraction over `DECLARE` transactions from accounts for invoking contracts. This struct uses
@@ -199,7 +235,7 @@ pub struct DeclarationV3<'a, A> {
 }
 
 /// [`ExecutionV3`] but with `nonce` and other transaction fee options already determined.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RawExecutionV3 {
     calls: Vec<Call>,
     nonce: Felt,
@@ -210,6 +246,10 @@ pub struct RawExecutionV3 {
     l1_data_gas: u64,
     l1_data_gas_price: u128,
     tip: u64,
+    paymaster_data: Vec<Felt>,
+    account_deployment_data: Vec<Felt>,
+    nonce_data_availability_mode: DataAvailabilityMode,
+    fee_data_availability_mode: DataAvailabilityMode,
 }
 
 /// [`DeclarationV3`] but with `nonce` and other transaction fee options already determined.
@@ -227,6 +267,23 @@ pub struct RawDeclarationV3 {
     tip: u64,
 }
 
+/// Abstraction over executing calls through a SNIP-29 paymaster. Calls are wrapped in a SNIP-9
+/// outside execution built by the paymaster and signed by the account, and the paymaster sends the
+/// actual transaction, collecting fees in a token of choice or sponsoring them.
+///
+/// This is an intermediate type allowing users to specify time bounds and a fee limit. The fee
+/// limit is required for sending transactions with [`FeeMode::Default`].
+#[must_use]
+#[derive(Debug)]
+pub struct PaymasterExecution<'a, A, T> {
+    account: &'a A,
+    paymaster: &'a PaymasterClient<T>,
+    calls: Vec<Call>,
+    fee_mode: FeeMode,
+    time_bounds: Option<TimeBounds>,
+    max_fee_in_gas_token: Option<Felt>,
+}
+
 /// [`RawExecutionV3`] but with an account associated.
 #[derive(Debug)]
 pub struct PreparedExecutionV3<'a, A> {
@@ -258,6 +315,38 @@ pub enum AccountError<S> {
     FeeOutOfRange,
 }
 
+/// Errors executing calls through a paymaster.
+#[derive(Debug, thiserror::Error)]
+pub enum PaymasterExecutionError<S> {
+    /// An error is encountered when signing the outside execution.
+    #[error(transparent)]
+    Signing(S),
+    /// An error is encountered with communicating with the paymaster.
+    #[error(transparent)]
+    Paymaster(ProviderError),
+    /// The typed data returned by the paymaster is not a valid outside execution.
+    #[error(transparent)]
+    OutsideExecution(ParseOutsideExecutionError),
+    /// The paymaster returned a transaction of an unexpected type.
+    #[error("unexpected paymaster transaction type")]
+    UnexpectedTransactionType,
+    /// The outside execution built by the paymaster does not run the requested calls, or pays more
+    /// than the configured maximum fee.
+    #[error("paymaster outside execution does not match the requested calls")]
+    UnexpectedCalls,
+    /// No maximum fee is configured while fees are to be paid in a gas token.
+    #[error("a maximum fee in the gas token is required for paying paymaster fees")]
+    MissingMaxFee,
+    /// The maximum fee suggested by the paymaster exceeds the configured limit.
+    #[error("paymaster fee {fee:#x} exceeds the maximum of {max_fee:#x}")]
+    FeeTooHigh {
+        /// Maximum fee suggested by the paymaster in the gas token.
+        fee: Felt,
+        /// Configured fee limit in the gas token.
+        max_fee: Felt,
+    },
+}
+
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl<A> Account for &A
@@ -373,6 +462,41 @@ where
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<A> MessageSigner for &A
+where
+    A: MessageSigner + Sync,
+{
+    async fn sign_message_hash(&self, message_hash: &Felt) -> Result<Vec<Felt>, Self::SignError> {
+        (*self).sign_message_hash(message_hash).await
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<A> MessageSigner for Box<A>
+where
+    A: MessageSigner + Sync + Send,
+{
+    async fn sign_message_hash(&self, message_hash: &Felt) -> Result<Vec<Felt>, Self::SignError> {
+        self.as_ref().sign_message_hash(message_hash).await
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<A> MessageSigner for Arc<A>
+where
+    A: MessageSigner + Sync + Send,
+{
+    async fn sign_message_hash(&self, message_hash: &Felt) -> Result<Vec<Felt>, Self::SignError> {
+        self.as_ref().sign_message_hash(message_hash).await
+    }
+}
+
+impl<A> PaymasterAccount for A where A: MessageSigner {}
+
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl<A> ConnectedAccount for &A
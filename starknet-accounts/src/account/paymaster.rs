@@ -0,0 +1,198 @@
+use super::{MessageSigner, PaymasterExecution, PaymasterExecutionError};
+use crate::OutsideExecution;
+
+use starknet_core::types::{typed_data::TypedData, Call, Felt};
+use starknet_providers::{
+    paymaster::{
+        BuildTransactionResponse, ExecutableUserInvoke, ExecutableUserTransaction,
+        ExecuteTransactionResponse, ExecutionParameters, ExecutionParametersVersion, FeeMode,
+        PaymasterFeeEstimate, PaymasterTransport, TimeBounds, UserInvoke, UserTransaction,
+    },
+    PaymasterClient,
+};
+
+/// Selector for entrypoint `transfer`.
+const SELECTOR_TRANSFER: Felt = Felt::from_raw([
+    437381113334062809,
+    2507318034922653180,
+    16370534037708042650,
+    5927927059297104468,
+]);
+
+impl<'a, A, T> PaymasterExecution<'a, A, T> {
+    /// Constructs a new [`PaymasterExecution`].
+    ///
+    /// Users would typically use [`execute_paymaster`](fn.execute_paymaster) on a
+    /// [`PaymasterAccount`](crate::PaymasterAccount) instead of directly calling this method.
+    pub const fn new(
+        calls: Vec<Call>,
+        account: &'a A,
+        paymaster: &'a PaymasterClient<T>,
+        fee_mode: FeeMode,
+    ) -> Self {
+        Self {
+            account,
+            paymaster,
+            calls,
+            fee_mode,
+            time_bounds: None,
+            max_fee_in_gas_token: None,
+        }
+    }
+
+    /// Returns a new [`PaymasterExecution`] with the `time_bounds`.
+    pub fn time_bounds(self, time_bounds: TimeBounds) -> Self {
+        Self {
+            time_bounds: Some(time_bounds),
+            ..self
+        }
+    }
+
+    /// Returns a new [`PaymasterExecution`] with the `max_fee_in_gas_token`, which is required for
+    /// sending with [`FeeMode::Default`]. Sending fails without signing anything if the maximum
+    /// fee suggested by the paymaster is higher.
+    pub fn max_fee_in_gas_token(self, max_fee_in_gas_token: Felt) -> Self {
+        Self {
+            max_fee_in_gas_token: Some(max_fee_in_gas_token),
+            ..self
+        }
+    }
+
+    fn parameters(&self) -> ExecutionParameters {
+        ExecutionParameters {
+            version: ExecutionParametersVersion::V1,
+            fee_mode: self.fee_mode,
+            time_bounds: self.time_bounds,
+        }
+    }
+}
+
+impl<A, T> PaymasterExecution<'_, A, T>
+where
+    A: MessageSigner + Sync,
+    T: 'static + PaymasterTransport + Send + Sync,
+{
+    /// Estimates transaction fees by having the paymaster build the transaction.
+    pub async fn estimate_fee(
+        &self,
+    ) -> Result<PaymasterFeeEstimate, PaymasterExecutionError<A::SignError>> {
+        let (_, fee) = self.build(&self.parameters()).await?;
+        Ok(fee)
+    }
+
+    /// Signs the outside execution built by the paymaster and has the paymaster send the
+    /// transaction.
+    ///
+    /// Nothing is signed unless the outside execution runs exactly the requested calls. With
+    /// [`FeeMode::Default`], it may be followed by one `transfer` of the gas token, whose amount
+    /// must not exceed the [`max_fee_in_gas_token`](Self::max_fee_in_gas_token) set by the
+    /// caller, as the fee estimate comes from the paymaster and cannot be trusted on its own.
+    pub async fn send(
+        &self,
+    ) -> Result<ExecuteTransactionResponse, PaymasterExecutionError<A::SignError>> {
+        let max_fee = match self.fee_mode {
+            FeeMode::Default { .. } => Some(
+                self.max_fee_in_gas_token
+                    .ok_or(PaymasterExecutionError::MissingMaxFee)?,
+            ),
+            FeeMode::Sponsored => None,
+        };
+
+        let parameters = self.parameters();
+        let (typed_data, fee) = self.build(&parameters).await?;
+
+        if let Some(max_fee) = max_fee {
+            if fee.suggested_max_fee_in_gas_token > max_fee {
+                return Err(PaymasterExecutionError::FeeTooHigh {
+                    fee: fee.suggested_max_fee_in_gas_token,
+                    max_fee,
+                });
+            }
+        }
+
+        let (version, outside_execution) = OutsideExecution::from_typed_data(&typed_data)
+            .map_err(PaymasterExecutionError::OutsideExecution)?;
+        if !self.is_expected_execution(&outside_execution, max_fee) {
+            return Err(PaymasterExecutionError::UnexpectedCalls);
+        }
+        let message_hash = outside_execution.message_hash(
+            version,
+            self.account.chain_id(),
+            self.account.address(),
+        );
+        let signature = self
+            .account
+            .sign_message_hash(&message_hash)
+            .await
+            .map_err(PaymasterExecutionError::Signing)?;
+
+        self.paymaster
+            .execute_transaction(
+                &ExecutableUserTransaction::Invoke {
+                    invoke: ExecutableUserInvoke {
+                        user_address: self.account.address(),
+                        typed_data,
+                        signature,
+                    },
+                },
+                &parameters,
+            )
+            .await
+            .map_err(PaymasterExecutionError::Paymaster)
+    }
+
+    fn is_expected_execution(
+        &self,
+        outside_execution: &OutsideExecution,
+        max_fee: Option<Felt>,
+    ) -> bool {
+        let Some(extra_calls) = outside_execution.calls.strip_prefix(self.calls.as_slice()) else {
+            return false;
+        };
+
+        match (extra_calls, self.fee_mode, max_fee) {
+            ([], _, _) => true,
+            ([fee_transfer], FeeMode::Default { gas_token }, Some(max_fee)) => {
+                // `transfer(recipient, amount)` with `amount` as `u256`
+                fee_transfer.to == gas_token
+                    && fee_transfer.selector == SELECTOR_TRANSFER
+                    && matches!(
+                        fee_transfer.calldata.as_slice(),
+                        [_, amount_low, amount_high]
+                            if *amount_high == Felt::ZERO
+                                && *amount_low <= max_fee
+                    )
+            }
+            _ => false,
+        }
+    }
+
+    async fn build(
+        &self,
+        parameters: &ExecutionParameters,
+    ) -> Result<(TypedData, PaymasterFeeEstimate), PaymasterExecutionError<A::SignError>> {
+        let response = self
+            .paymaster
+            .build_transaction(
+                &UserTransaction::Invoke {
+                    invoke: UserInvoke {
+                        user_address: self.account.address(),
+                        calls: self.calls.clone(),
+                    },
+                },
+                parameters,
+            )
+            .await
+            .map_err(PaymasterExecutionError::Paymaster)?;
+
+        match response {
+            BuildTransactionResponse::Invoke {
+                typed_data, fee, ..
+            } => Ok((typed_data, fee)),
+            BuildTransactionResponse::Deploy { .. }
+            | BuildTransactionResponse::DeployAndInvoke { .. } => {
+                Err(PaymasterExecutionError::UnexpectedTransactionType)
+            }
+        }
+    }
+}
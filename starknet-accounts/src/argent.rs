@@ -0,0 +1,308 @@
+use crate::{
+    factory::argent::ArgentSigner, Account, ConnectedAccount, ExecutionEncoder, ExecutionEncoding,
+    MessageSigner, RawDeclarationV3, RawExecutionV3,
+};
+
+use async_trait::async_trait;
+use starknet_core::{
+    codec::Encode,
+    types::{BlockId, BlockTag, Call, Felt},
+};
+use starknet_providers::Provider;
+use starknet_signers::{Signer, SignerInteractivityContext};
+
+/// Selector for entrypoint `trigger_escape_guardian`.
+const SELECTOR_TRIGGER_ESCAPE_GUARDIAN: Felt = Felt::from_raw([
+    38987901156690881,
+    1802104375587603901,
+    515735650760354150,
+    4771767727248285268,
+]);
+
+/// Selector for entrypoint `escape_guardian`.
+const SELECTOR_ESCAPE_GUARDIAN: Felt = Felt::from_raw([
+    560481306804492787,
+    5513970924402304728,
+    17313635812358474203,
+    4736402847786676516,
+]);
+
+/// An [`Account`] implementation for controlling `Argent X` account contracts (v0.4.0) with an
+/// owner and an optional guardian, using ECDSA on the STARK curve.
+///
+/// Transactions are co-signed by the owner and the guardian if one is set. The guardian can be any
+/// [`Signer`] implementation, whether it holds a key locally or delegates signing to a remote
+/// guardian service.
+///
+/// Escaping the guardian only requires the owner signature, which allows recovering the account
+/// when the guardian is unavailable: executions made of a single call built from
+/// [`trigger_escape_guardian`](Self::trigger_escape_guardian) or
+/// [`escape_guardian`](Self::escape_guardian) are signed by the owner alone.
+#[derive(Debug)]
+pub struct ArgentAccount<P, S, G>
+where
+    P: Provider + Send,
+    S: Signer + Send,
+    G: Signer + Send,
+{
+    provider: P,
+    owner: S,
+    owner_public_key: Felt,
+    guardian: Option<(Felt, G)>,
+    address: Felt,
+    chain_id: Felt,
+    block_id: BlockId,
+}
+
+/// Errors from the owner or the guardian signer implementation.
+#[derive(Debug, thiserror::Error)]
+pub enum SignError<S, G> {
+    /// An error encountered by the owner signer implementation.
+    #[error(transparent)]
+    Owner(S),
+    /// An error encountered by the guardian signer implementation.
+    #[error(transparent)]
+    Guardian(G),
+}
+
+/// A simplified version of `argent::signer::signer_signature::SignerSignature` that only supports
+/// the simple Starknet signer.
+#[derive(Encode)]
+#[starknet(core = "starknet_core")]
+enum ArgentSignerSignature {
+    Starknet(StarknetSignerSignature),
+}
+
+/// Signature of `argent::signer::signer_signature::StarknetSigner`.
+#[derive(Encode)]
+#[starknet(core = "starknet_core")]
+struct StarknetSignerSignature {
+    pubkey: Felt,
+    r: Felt,
+    s: Felt,
+}
+
+impl<P, S, G> ArgentAccount<P, S, G>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+    G: Signer + Sync + Send,
+{
+    /// Create a new account controlled by an owner and an optional guardian. Public keys of the
+    /// signers are fetched upfront for encoding signatures.
+    ///
+    /// ### Parameters
+    ///
+    /// - `provider`: A `Provider` implementation that provides access to the Starknet network.
+    /// - `owner`: A `Signer` implementation for the owner of the account.
+    /// - `guardian`: A `Signer` implementation for the guardian of the account, if it has one.
+    /// - `address`: Account contract address.
+    /// - `chain_id`: Network chain ID.
+    pub async fn new(
+        provider: P,
+        owner: S,
+        guardian: Option<G>,
+        address: Felt,
+        chain_id: Felt,
+    ) -> Result<Self, SignError<S::GetPublicKeyError, G::GetPublicKeyError>> {
+        let owner_public_key = owner
+            .get_public_key()
+            .await
+            .map_err(SignError::Owner)?
+            .scalar();
+        let guardian = match guardian {
+            Some(guardian) => Some((
+                guardian
+                    .get_public_key()
+                    .await
+                    .map_err(SignError::Guardian)?
+                    .scalar(),
+                guardian,
+            )),
+            None => None,
+        };
+
+        Ok(Self {
+            provider,
+            owner,
+            owner_public_key,
+            guardian,
+            address,
+            chain_id,
+            block_id: BlockId::Tag(BlockTag::PreConfirmed),
+        })
+    }
+
+    /// Sets a new block ID to run queries against.
+    pub fn set_block_id(&mut self, block_id: BlockId) -> &Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Builds a [`Call`] starting the escape of the guardian, after which it can be replaced by
+    /// `new_guardian` (or removed) with [`escape_guardian`](Self::escape_guardian) once the escape
+    /// security period has passed.
+    pub fn trigger_escape_guardian(&self, new_guardian: Option<Felt>) -> Call {
+        let mut calldata = vec![];
+
+        // Encoding this value never fails
+        new_guardian
+            .map(ArgentSigner::Starknet)
+            .encode(&mut calldata)
+            .unwrap();
+
+        Call {
+            to: self.address,
+            selector: SELECTOR_TRIGGER_ESCAPE_GUARDIAN,
+            calldata,
+        }
+    }
+
+    /// Builds a [`Call`] completing the escape of the guardian started with
+    /// [`trigger_escape_guardian`](Self::trigger_escape_guardian).
+    pub const fn escape_guardian(&self) -> Call {
+        Call {
+            to: self.address,
+            selector: SELECTOR_ESCAPE_GUARDIAN,
+            calldata: vec![],
+        }
+    }
+
+    /// Signs `hash` with the owner and, unless `owner_only` is `true`, the guardian.
+    async fn sign_hash(
+        &self,
+        hash: &Felt,
+        owner_only: bool,
+    ) -> Result<Vec<Felt>, SignError<S::SignError, G::SignError>> {
+        let owner_signature = self.owner.sign_hash(hash).await.map_err(SignError::Owner)?;
+        let mut signatures = vec![ArgentSignerSignature::Starknet(StarknetSignerSignature {
+            pubkey: self.owner_public_key,
+            r: owner_signature.r,
+            s: owner_signature.s,
+        })];
+
+        if let (Some((guardian_public_key, guardian)), false) = (&self.guardian, owner_only) {
+            let guardian_signature = guardian
+                .sign_hash(hash)
+                .await
+                .map_err(SignError::Guardian)?;
+            signatures.push(ArgentSignerSignature::Starknet(StarknetSignerSignature {
+                pubkey: *guardian_public_key,
+                r: guardian_signature.r,
+                s: guardian_signature.s,
+            }));
+        }
+
+        let mut encoded = vec![];
+
+        // Encoding signatures never fails
+        signatures.encode(&mut encoded).unwrap();
+
+        Ok(encoded)
+    }
+
+    /// Whether `calls` escape the guardian, which must be signed by the owner alone.
+    fn is_guardian_escape(&self, calls: &[Call]) -> bool {
+        match calls {
+            [call] => {
+                call.to == self.address
+                    && (call.selector == SELECTOR_TRIGGER_ESCAPE_GUARDIAN
+                        || call.selector == SELECTOR_ESCAPE_GUARDIAN)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P, S, G> Account for ArgentAccount<P, S, G>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+    G: Signer + Sync + Send,
+{
+    type SignError = SignError<S::SignError, G::SignError>;
+
+    fn address(&self) -> Felt {
+        self.address
+    }
+
+    fn chain_id(&self) -> Felt {
+        self.chain_id
+    }
+
+    async fn sign_execution_v3(
+        &self,
+        execution: &RawExecutionV3,
+        query_only: bool,
+    ) -> Result<Vec<Felt>, Self::SignError> {
+        let tx_hash = execution.transaction_hash(self.chain_id, self.address, query_only, self);
+        self.sign_hash(&tx_hash, self.is_guardian_escape(execution.calls()))
+            .await
+    }
+
+    async fn sign_declaration_v3(
+        &self,
+        declaration: &RawDeclarationV3,
+        query_only: bool,
+    ) -> Result<Vec<Felt>, Self::SignError> {
+        let tx_hash = declaration.transaction_hash(self.chain_id, self.address, query_only);
+        self.sign_hash(&tx_hash, false).await
+    }
+
+    fn is_signer_interactive(&self, context: SignerInteractivityContext<'_>) -> bool {
+        let guardian_signs = match context {
+            SignerInteractivityContext::Execution { calls } => !self.is_guardian_escape(calls),
+            SignerInteractivityContext::Other => true,
+        };
+
+        self.owner.is_interactive(context)
+            || (guardian_signs
+                && self
+                    .guardian
+                    .as_ref()
+                    .is_some_and(|(_, guardian)| guardian.is_interactive(context)))
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P, S, G> MessageSigner for ArgentAccount<P, S, G>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+    G: Signer + Sync + Send,
+{
+    async fn sign_message_hash(&self, message_hash: &Felt) -> Result<Vec<Felt>, Self::SignError> {
+        self.sign_hash(message_hash, false).await
+    }
+}
+
+impl<P, S, G> ExecutionEncoder for ArgentAccount<P, S, G>
+where
+    P: Provider + Send,
+    S: Signer + Send,
+    G: Signer + Send,
+{
+    fn encode_calls(&self, calls: &[Call]) -> Vec<Felt> {
+        ExecutionEncoding::New.encode_calls(calls)
+    }
+}
+
+impl<P, S, G> ConnectedAccount for ArgentAccount<P, S, G>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+    G: Signer + Sync + Send,
+{
+    type Provider = P;
+
+    fn provider(&self) -> &Self::Provider {
+        &self.provider
+    }
+
+    fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+}
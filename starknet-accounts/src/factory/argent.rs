@@ -32,7 +32,7 @@ struct ArgentAccountConstructorParams {
 /// Starknet signer.
 #[derive(Encode)]
 #[starknet(core = "starknet_core")]
-enum ArgentSigner {
+pub(crate) enum ArgentSigner {
     Starknet(Felt),
 }
 
@@ -5,6 +5,7 @@
 mod account;
 pub use account::{
     Account, AccountError, ConnectedAccount, DeclarationV3, ExecutionEncoder, ExecutionV3,
+    MessageSigner, PaymasterAccount, PaymasterExecution, PaymasterExecutionError,
     PreparedDeclarationV3, PreparedExecutionV3, RawDeclarationV3, RawExecutionV3,
 };
 
@@ -14,6 +15,20 @@ pub use factory::{
     AccountFactory, AccountFactoryError, PreparedAccountDeploymentV3, RawAccountDeploymentV3,
 };
 
+/// Module containing types for SNIP-9 outside executions (meta-transactions).
+pub mod outside_execution;
+pub use outside_execution::{
+    OutsideExecution, OutsideExecutionVersion, ParseOutsideExecutionError,
+};
+
+/// Module containing types for using an `Argent X` account contract with an optional guardian.
+pub mod argent;
+pub use argent::ArgentAccount;
+
+/// Module containing types for using a multisig account contract.
+pub mod multisig;
+pub use multisig::{MultisigAccount, MultisigSignature, MultisigSignatureEncoding};
+
 /// Module containing types for using an account contract with only one signer.
 pub mod single_owner;
 pub use single_owner::{ExecutionEncoding, SingleOwnerAccount};
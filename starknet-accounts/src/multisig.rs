@@ -0,0 +1,336 @@
+use crate::{
+    Account, ConnectedAccount, ExecutionEncoder, ExecutionEncoding, MessageSigner,
+    RawDeclarationV3, RawExecutionV3,
+};
+
+use async_trait::async_trait;
+use starknet_core::types::{BlockId, BlockTag, Call, Felt};
+use starknet_crypto::{poseidon_hash_many, verify};
+use starknet_providers::Provider;
+use starknet_signers::{Signer, SignerInteractivityContext};
+use std::{collections::HashMap, sync::Mutex};
+
+/// Cairo short string encoding for `Starknet Signer`, used in signer GUIDs.
+const STARKNET_SIGNER: Felt = Felt::from_raw([
+    348686685110234461,
+    18446744073709551615,
+    17695051755396674407,
+    12024458411795420542,
+]);
+
+/// An [`Account`] implementation for controlling multisig account contracts requiring signatures
+/// from `threshold` signers, using ECDSA on the STARK curve.
+///
+/// Signers available locally sign directly. Signatures from remote cosigners can be collected for a
+/// transaction hash in advance with [`add_signatures`](Self::add_signatures), after having them
+/// sign the same transaction with [`sign_hash_partial`](Self::sign_hash_partial) on their side.
+/// Typically, the proposer of a transaction calls [`prepare`](crate::ExecutionV3::prepare) to fix
+/// the nonce and fee options, and shares the resulting
+/// [`RawExecutionV3`](crate::PreparedExecutionV3::raw_execution) with cosigners.
+#[derive(Debug)]
+pub struct MultisigAccount<P, S>
+where
+    P: Provider + Send,
+    S: Signer + Send,
+{
+    provider: P,
+    signers: Vec<(Felt, S)>,
+    threshold: usize,
+    address: Felt,
+    chain_id: Felt,
+    block_id: BlockId,
+    encoding: ExecutionEncoding,
+    signature_encoding: MultisigSignatureEncoding,
+    cosigner_signatures: Mutex<HashMap<Felt, Vec<MultisigSignature>>>,
+}
+
+/// A signature from one of the signers of a multisig account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultisigSignature {
+    /// Public key of the signer.
+    pub signer: Felt,
+    /// The `r` value of the ECDSA signature.
+    pub r: Felt,
+    /// The `s` value of the ECDSA signature.
+    pub s: Felt,
+}
+
+/// How signatures from multiple signers are encoded into a transaction signature.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MultisigSignatureEncoding {
+    /// Encode signatures as concatenated `[signer, r, s]` triples, as expected by Argent multisig
+    /// contracts before v0.2.0.
+    Legacy,
+    /// Encode signatures as a Cairo-serialized `Array<SignerSignature>` of `Starknet` signers, as
+    /// expected by Argent multisig contracts since v0.2.0.
+    New,
+}
+
+/// Errors signing an execution/declaration request.
+#[derive(Debug, thiserror::Error)]
+pub enum SignError<S> {
+    /// An error encountered by the signer implementation.
+    #[error(transparent)]
+    Signer(S),
+    /// Fewer signatures than the threshold are available for the hash being signed.
+    #[error("not enough signatures: {available} available but {required} required")]
+    NotEnoughSignatures {
+        /// Number of signatures required by the account.
+        required: usize,
+        /// Number of signatures available from local signers and cosigners.
+        available: usize,
+    },
+}
+
+/// Error adding a cosigner signature that is not valid for the hash it is added for.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid signature from signer {signer:#x}")]
+pub struct InvalidSignatureError {
+    /// Public key of the signer.
+    pub signer: Felt,
+}
+
+impl<P, S> MultisigAccount<P, S>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+{
+    /// Create a new account controlled by multiple signers. Public keys of `signers` are fetched
+    /// upfront for encoding signatures.
+    ///
+    /// ### Parameters
+    ///
+    /// - `provider`: A `Provider` implementation that provides access to the Starknet network.
+    /// - `signers`: `Signer` implementations available locally. Other signers of the account can
+    ///   provide their signatures through [`add_signatures`](Self::add_signatures).
+    /// - `threshold`: Number of signatures required by the account contract. A threshold of `0` is
+    ///   treated as `1`.
+    /// - `address`: Account contract address.
+    /// - `chain_id`: Network chain ID.
+    /// - `encoding`: How `__execute__` calldata should be encoded.
+    /// - `signature_encoding`: How signatures should be encoded.
+    pub async fn new(
+        provider: P,
+        signers: Vec<S>,
+        threshold: usize,
+        address: Felt,
+        chain_id: Felt,
+        encoding: ExecutionEncoding,
+        signature_encoding: MultisigSignatureEncoding,
+    ) -> Result<Self, S::GetPublicKeyError> {
+        let mut keyed_signers = Vec::with_capacity(signers.len());
+        for signer in signers {
+            keyed_signers.push((signer.get_public_key().await?.scalar(), signer));
+        }
+
+        Ok(Self {
+            provider,
+            signers: keyed_signers,
+            threshold: threshold.max(1),
+            address,
+            chain_id,
+            block_id: BlockId::Tag(BlockTag::PreConfirmed),
+            encoding,
+            signature_encoding,
+            cosigner_signatures: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Sets a new block ID to run queries against.
+    pub fn set_block_id(&mut self, block_id: BlockId) -> &Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Gets the number of signatures required by the account contract.
+    pub const fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Signs `hash` with all local signers, returning signatures to be shared with the party
+    /// sending the transaction.
+    pub async fn sign_hash_partial(
+        &self,
+        hash: &Felt,
+    ) -> Result<Vec<MultisigSignature>, SignError<S::SignError>> {
+        let mut signatures = Vec::with_capacity(self.signers.len());
+
+        for (public_key, signer) in &self.signers {
+            let signature = signer.sign_hash(hash).await.map_err(SignError::Signer)?;
+
+            signatures.push(MultisigSignature {
+                signer: *public_key,
+                r: signature.r,
+                s: signature.s,
+            });
+        }
+
+        Ok(signatures)
+    }
+
+    /// Adds signatures from remote cosigners for `hash`, to be used when the same hash is signed
+    /// by this account. Returns `Err` without adding anything if any signature is invalid.
+    pub fn add_signatures<I>(&self, hash: Felt, signatures: I) -> Result<(), InvalidSignatureError>
+    where
+        I: IntoIterator<Item = MultisigSignature>,
+    {
+        let signatures = signatures.into_iter().collect::<Vec<_>>();
+
+        for signature in &signatures {
+            if !verify(&signature.signer, &hash, &signature.r, &signature.s).unwrap_or(false) {
+                return Err(InvalidSignatureError {
+                    signer: signature.signer,
+                });
+            }
+        }
+
+        self.cosigner_signatures
+            .lock()
+            .unwrap()
+            .entry(hash)
+            .or_default()
+            .extend(signatures);
+
+        Ok(())
+    }
+
+    /// Signs `hash` with local signers and combines the result with cosigner signatures added for
+    /// it, returning the encoded signature of the account.
+    async fn sign_hash_full(&self, hash: &Felt) -> Result<Vec<Felt>, SignError<S::SignError>> {
+        let mut signatures = self.sign_hash_partial(hash).await?;
+        if let Some(cosigner_signatures) = self.cosigner_signatures.lock().unwrap().get(hash) {
+            signatures.extend_from_slice(cosigner_signatures);
+        }
+
+        // Contracts expect signatures sorted without duplicates
+        signatures.sort_by_key(|signature| self.signature_encoding.sort_key(signature.signer));
+        signatures.dedup_by_key(|signature| signature.signer);
+
+        if signatures.len() < self.threshold {
+            return Err(SignError::NotEnoughSignatures {
+                required: self.threshold,
+                available: signatures.len(),
+            });
+        }
+        signatures.truncate(self.threshold);
+
+        Ok(self.signature_encoding.encode(&signatures))
+    }
+}
+
+impl MultisigSignatureEncoding {
+    /// Encodes signatures into a transaction signature. Signatures must already be sorted by
+    /// signer GUID for [`New`](Self::New) and by signer public key for [`Legacy`](Self::Legacy).
+    pub fn encode(&self, signatures: &[MultisigSignature]) -> Vec<Felt> {
+        let mut encoded = vec![];
+
+        if *self == Self::New {
+            encoded.push(signatures.len().into());
+        }
+
+        for signature in signatures {
+            if *self == Self::New {
+                // Variant index of `SignerSignature::Starknet`
+                encoded.push(Felt::ZERO);
+            }
+            encoded.push(signature.signer);
+            encoded.push(signature.r);
+            encoded.push(signature.s);
+        }
+
+        encoded
+    }
+
+    /// Gets the value contracts sort signatures by for `signer`: its GUID
+    /// (`poseidon("Starknet Signer", signer)`) for `New`, and the public key itself for `Legacy`.
+    fn sort_key(&self, signer: Felt) -> Felt {
+        match self {
+            Self::New => poseidon_hash_many(&[STARKNET_SIGNER, signer]),
+            Self::Legacy => signer,
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P, S> Account for MultisigAccount<P, S>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+{
+    type SignError = SignError<S::SignError>;
+
+    fn address(&self) -> Felt {
+        self.address
+    }
+
+    fn chain_id(&self) -> Felt {
+        self.chain_id
+    }
+
+    async fn sign_execution_v3(
+        &self,
+        execution: &RawExecutionV3,
+        query_only: bool,
+    ) -> Result<Vec<Felt>, Self::SignError> {
+        let tx_hash = execution.transaction_hash(self.chain_id, self.address, query_only, self);
+        self.sign_hash_full(&tx_hash).await
+    }
+
+    async fn sign_declaration_v3(
+        &self,
+        declaration: &RawDeclarationV3,
+        query_only: bool,
+    ) -> Result<Vec<Felt>, Self::SignError> {
+        let tx_hash = declaration.transaction_hash(self.chain_id, self.address, query_only);
+        self.sign_hash_full(&tx_hash).await
+    }
+
+    fn is_signer_interactive(&self, context: SignerInteractivityContext<'_>) -> bool {
+        // Without enough local signers, real signatures can only be produced once cosigners have
+        // signed, so signing is avoided for estimation just like with interactive signers.
+        self.signers.len() < self.threshold
+            || self
+                .signers
+                .iter()
+                .any(|(_, signer)| signer.is_interactive(context))
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P, S> MessageSigner for MultisigAccount<P, S>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+{
+    async fn sign_message_hash(&self, message_hash: &Felt) -> Result<Vec<Felt>, Self::SignError> {
+        self.sign_hash_full(message_hash).await
+    }
+}
+
+impl<P, S> ExecutionEncoder for MultisigAccount<P, S>
+where
+    P: Provider + Send,
+    S: Signer + Send,
+{
+    fn encode_calls(&self, calls: &[Call]) -> Vec<Felt> {
+        self.encoding.encode_calls(calls)
+    }
+}
+
+impl<P, S> ConnectedAccount for MultisigAccount<P, S>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+{
+    type Provider = P;
+
+    fn provider(&self) -> &Self::Provider {
+        &self.provider
+    }
+
+    fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+}
@@ -0,0 +1,887 @@
+use starknet_core::{
+    crypto::compute_hash_on_elements,
+    types::{
+        typed_data::{Domain, ObjectValue, Revision, TypedData, Value},
+        Call, Felt,
+    },
+    utils::get_selector_from_name,
+};
+use starknet_crypto::poseidon_hash_many;
+use starknet_signers::Signer;
+
+/// Cairo short string encoding of `ANY_CALLER`.
+///
+/// Using this value as [`OutsideExecution::caller`] allows anyone to submit the outside execution.
+pub const ANY_CALLER: Felt = Felt::from_raw([
+    401125202220711261,
+    18446744073709551615,
+    18446744073709016628,
+    15282850808162428286,
+]);
+
+/// Selector for entrypoint `execute_from_outside`.
+const SELECTOR_EXECUTE_FROM_OUTSIDE: Felt = Felt::from_raw([
+    103404265739581092,
+    7454104187107852239,
+    14422704657280406897,
+    4854481508886030259,
+]);
+
+/// Selector for entrypoint `execute_from_outside_v2`.
+const SELECTOR_EXECUTE_FROM_OUTSIDE_V2: Felt = Felt::from_raw([
+    537717562993026711,
+    14911223287911860702,
+    13246598959679174800,
+    9668673901294984529,
+]);
+
+/// Cairo short string encoding of `Account.execute_from_outside`, the name of the SNIP-12 domain
+/// used for signing outside executions.
+const DOMAIN_NAME: Felt = Felt::from_raw([
+    218199290950915036,
+    5922668442931752642,
+    12117707125106848942,
+    13203613645416923163,
+]);
+
+/// Cairo short string encoding of `StarkNet Message`.
+const STARKNET_MESSAGE_PREFIX: Felt = Felt::from_raw([
+    257012186512350467,
+    18446744073709551605,
+    10480951322775611302,
+    16156019428408348868,
+]);
+
+/// SNIP-12 revision 0 type hash of the `OutsideExecution` struct of SNIP-9 version 1.
+///
+/// Computed as:
+///
+/// ```ignore
+/// starknet_keccak(concat!(
+///     "OutsideExecution(caller:felt,nonce:felt,execute_after:felt,execute_before:felt,",
+///     "calls_len:felt,calls:OutsideCall*)",
+///     "OutsideCall(to:felt,selector:felt,calldata_len:felt,calldata:felt*)",
+/// ))
+/// ```
+const OUTSIDE_EXECUTION_TYPE_HASH_V1: Felt = Felt::from_raw([
+    519076381040955965,
+    10812475380141289304,
+    4649530818564417751,
+    7270185042151943247,
+]);
+
+/// SNIP-12 revision 0 type hash of the `OutsideCall` struct of SNIP-9 version 1.
+///
+/// Computed as:
+///
+/// ```ignore
+/// starknet_keccak("OutsideCall(to:felt,selector:felt,calldata_len:felt,calldata:felt*)")
+/// ```
+const CALL_TYPE_HASH_V1: Felt = Felt::from_raw([
+    515948440445354255,
+    10352631866937147489,
+    5684816691998369206,
+    2361653742213297599,
+]);
+
+/// SNIP-12 revision 1 type hash of the `OutsideExecution` struct of SNIP-9 version 2.
+///
+/// Computed as:
+///
+/// ```ignore
+/// starknet_keccak(concat!(
+///     r#""OutsideExecution"("Caller":"ContractAddress","Nonce":"felt","#,
+///     r#""Execute After":"u128","Execute Before":"u128","Calls":"Call*")"#,
+///     r#""Call"("To":"ContractAddress","Selector":"selector","Calldata":"felt*")"#,
+/// ))
+/// ```
+const OUTSIDE_EXECUTION_TYPE_HASH_V2: Felt = Felt::from_raw([
+    305642079185481034,
+    2051667931325918626,
+    14478441369475314075,
+    5656861162701763622,
+]);
+
+/// SNIP-12 revision 1 type hash of the `Call` struct of SNIP-9 version 2.
+///
+/// Computed as:
+///
+/// ```ignore
+/// starknet_keccak(r#""Call"("To":"ContractAddress","Selector":"selector","Calldata":"felt*")"#)
+/// ```
+const CALL_TYPE_HASH_V2: Felt = Felt::from_raw([
+    30635959383713237,
+    14733449956667422918,
+    14709092989635869533,
+    7243251918453884024,
+]);
+
+/// Version of the SNIP-9 (outside execution) standard.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutsideExecutionVersion {
+    /// Version 1, where the message is hashed as revision 0 of SNIP-12 and submitted through the
+    /// `execute_from_outside` entrypoint.
+    V1,
+    /// Version 2, where the message is hashed as revision 1 of SNIP-12 and submitted through the
+    /// `execute_from_outside_v2` entrypoint.
+    V2,
+}
+
+/// A list of calls to be executed by an account on behalf of its owner, submitted by another
+/// party (e.g. a relayer paying for the transaction), as specified in SNIP-9.
+///
+/// The account owner signs the [`message_hash`](Self::message_hash) of the outside execution, and
+/// the submitter includes the [`Call`] obtained from [`to_call`](Self::to_call) in its own
+/// transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutsideExecution {
+    /// Address allowed to submit the outside execution, or [`ANY_CALLER`] to allow anyone.
+    pub caller: Felt,
+    /// Nonce preventing the outside execution from being replayed. Unlike transaction nonces, it
+    /// does not need to be sequential and only has to be unused.
+    pub nonce: Felt,
+    /// Timestamp (in seconds) after which the outside execution is valid (exclusive).
+    pub execute_after: u64,
+    /// Timestamp (in seconds) before which the outside execution is valid (exclusive).
+    pub execute_before: u64,
+    /// Calls to be executed by the account.
+    pub calls: Vec<Call>,
+}
+
+/// Errors parsing an [`OutsideExecution`] from SNIP-12 typed data.
+#[derive(Debug, thiserror::Error)]
+pub enum ParseOutsideExecutionError {
+    /// The typed data domain is not one used for signing outside executions.
+    #[error("typed data is not an outside execution")]
+    UnexpectedDomain,
+    /// A message field is missing or has an invalid value.
+    #[error("invalid outside execution field: {0}")]
+    InvalidField(&'static str),
+}
+
+impl OutsideExecutionVersion {
+    /// Gets the selector of the account contract entrypoint executing outside executions of this
+    /// version.
+    pub const fn selector(&self) -> Felt {
+        match self {
+            Self::V1 => SELECTOR_EXECUTE_FROM_OUTSIDE,
+            Self::V2 => SELECTOR_EXECUTE_FROM_OUTSIDE_V2,
+        }
+    }
+
+    const fn domain(&self, chain_id: Felt) -> Domain {
+        match self {
+            Self::V1 => Domain {
+                name: DOMAIN_NAME,
+                version: Felt::ONE,
+                chain_id,
+                revision: Revision::V0,
+            },
+            Self::V2 => Domain {
+                name: DOMAIN_NAME,
+                version: Felt::TWO,
+                chain_id,
+                revision: Revision::V1,
+            },
+        }
+    }
+}
+
+impl OutsideExecution {
+    /// Parses an outside execution from SNIP-12 [`TypedData`], typically built by another party
+    /// such as a paymaster, along with the version of SNIP-9 it is meant for.
+    ///
+    /// The type definitions included in `typed_data` are ignored: the message hash to be signed is
+    /// always computed with [`message_hash`](Self::message_hash) from the parsed message fields.
+    pub fn from_typed_data(
+        typed_data: &TypedData,
+    ) -> Result<(OutsideExecutionVersion, Self), ParseOutsideExecutionError> {
+        let domain = typed_data.encoder().domain();
+        let version = match domain.revision {
+            Revision::V0 => OutsideExecutionVersion::V1,
+            Revision::V1 => OutsideExecutionVersion::V2,
+        };
+        if domain != version.domain(domain.chain_id) {
+            return Err(ParseOutsideExecutionError::UnexpectedDomain);
+        }
+
+        let message = as_object(typed_data.message(), "message")?;
+        let outside_execution = match version {
+            OutsideExecutionVersion::V1 => {
+                let calls = as_array(field(message, "calls")?, "calls")?
+                    .iter()
+                    .map(|call| {
+                        let call = as_object(call, "calls")?;
+                        let calldata = as_array(field(call, "calldata")?, "calldata")?
+                            .iter()
+                            .map(|item| as_felt(item, "calldata"))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        check_len(
+                            felt_field(call, "calldata_len")?,
+                            calldata.len(),
+                            "calldata_len",
+                        )?;
+
+                        Ok(Call {
+                            to: felt_field(call, "to")?,
+                            selector: felt_field(call, "selector")?,
+                            calldata,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                check_len(felt_field(message, "calls_len")?, calls.len(), "calls_len")?;
+
+                Self {
+                    caller: felt_field(message, "caller")?,
+                    nonce: felt_field(message, "nonce")?,
+                    execute_after: u64_field(message, "execute_after")?,
+                    execute_before: u64_field(message, "execute_before")?,
+                    calls,
+                }
+            }
+            OutsideExecutionVersion::V2 => Self {
+                caller: felt_field(message, "Caller")?,
+                nonce: felt_field(message, "Nonce")?,
+                execute_after: u64_field(message, "Execute After")?,
+                execute_before: u64_field(message, "Execute Before")?,
+                calls: as_array(field(message, "Calls")?, "Calls")?
+                    .iter()
+                    .map(|call| {
+                        let call = as_object(call, "Calls")?;
+                        Ok(Call {
+                            to: felt_field(call, "To")?,
+                            selector: selector_field(call, "Selector")?,
+                            calldata: as_array(field(call, "Calldata")?, "Calldata")?
+                                .iter()
+                                .map(|item| as_felt(item, "Calldata"))
+                                .collect::<Result<Vec<_>, _>>()?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            },
+        };
+
+        Ok((version, outside_execution))
+    }
+
+    /// Computes the SNIP-12 message hash to be signed by the owner of the account at
+    /// `account_address` on the network identified by `chain_id`.
+    ///
+    /// The result is the same as [`TypedData::message_hash`] on the SNIP-12 typed data of the
+    /// outside execution, but it is computed directly from the message fields instead of building
+    /// that typed data: in version 2, call selectors have the `selector` type, which SNIP-12 only
+    /// defines for function names, whereas [`Call`] only holds the selector itself.
+    pub fn message_hash(
+        &self,
+        version: OutsideExecutionVersion,
+        chain_id: Felt,
+        account_address: Felt,
+    ) -> Felt {
+        match version {
+            OutsideExecutionVersion::V1 => {
+                let call_hashes = self
+                    .calls
+                    .iter()
+                    .map(|call| {
+                        compute_hash_on_elements(&[
+                            CALL_TYPE_HASH_V1,
+                            call.to,
+                            call.selector,
+                            call.calldata.len().into(),
+                            compute_hash_on_elements(&call.calldata),
+                        ])
+                    })
+                    .collect::<Vec<_>>();
+
+                compute_hash_on_elements(&[
+                    STARKNET_MESSAGE_PREFIX,
+                    version.domain(chain_id).encoded_hash(),
+                    account_address,
+                    compute_hash_on_elements(&[
+                        OUTSIDE_EXECUTION_TYPE_HASH_V1,
+                        self.caller,
+                        self.nonce,
+                        self.execute_after.into(),
+                        self.execute_before.into(),
+                        self.calls.len().into(),
+                        compute_hash_on_elements(&call_hashes),
+                    ]),
+                ])
+            }
+            OutsideExecutionVersion::V2 => {
+                let call_hashes = self
+                    .calls
+                    .iter()
+                    .map(|call| {
+                        poseidon_hash_many(&[
+                            CALL_TYPE_HASH_V2,
+                            call.to,
+                            call.selector,
+                            poseidon_hash_many(&call.calldata),
+                        ])
+                    })
+                    .collect::<Vec<_>>();
+
+                poseidon_hash_many(&[
+                    STARKNET_MESSAGE_PREFIX,
+                    version.domain(chain_id).encoded_hash(),
+                    account_address,
+                    poseidon_hash_many(&[
+                        OUTSIDE_EXECUTION_TYPE_HASH_V2,
+                        self.caller,
+                        self.nonce,
+                        self.execute_after.into(),
+                        self.execute_before.into(),
+                        poseidon_hash_many(&call_hashes),
+                    ]),
+                ])
+            }
+        }
+    }
+
+    /// Signs the outside execution with `signer`, returning the signature to be submitted along
+    /// with it for an account contract expecting a single ECDSA signature `[r, s]`.
+    pub async fn sign<S>(
+        &self,
+        version: OutsideExecutionVersion,
+        chain_id: Felt,
+        account_address: Felt,
+        signer: &S,
+    ) -> Result<Vec<Felt>, S::SignError>
+    where
+        S: Signer,
+    {
+        let message_hash = self.message_hash(version, chain_id, account_address);
+        let signature = signer.sign_hash(&message_hash).await?;
+
+        Ok(vec![signature.r, signature.s])
+    }
+
+    /// Builds the [`Call`] submitting the signed outside execution to the account contract at
+    /// `account_address`. The call is meant to be included in a transaction sent by the caller.
+    pub fn to_call(
+        &self,
+        version: OutsideExecutionVersion,
+        account_address: Felt,
+        signature: &[Felt],
+    ) -> Call {
+        let mut calldata = vec![
+            self.caller,
+            self.nonce,
+            self.execute_after.into(),
+            self.execute_before.into(),
+            self.calls.len().into(),
+        ];
+
+        for call in &self.calls {
+            calldata.push(call.to);
+            calldata.push(call.selector);
+            calldata.push(call.calldata.len().into());
+            calldata.extend_from_slice(&call.calldata);
+        }
+
+        calldata.push(signature.len().into());
+        calldata.extend_from_slice(signature);
+
+        Call {
+            to: account_address,
+            selector: version.selector(),
+            calldata,
+        }
+    }
+}
+
+fn field<'a>(
+    object: &'a ObjectValue,
+    name: &'static str,
+) -> Result<&'a Value, ParseOutsideExecutionError> {
+    object
+        .fields
+        .get(name)
+        .ok_or(ParseOutsideExecutionError::InvalidField(name))
+}
+
+fn as_object<'a>(
+    value: &'a Value,
+    name: &'static str,
+) -> Result<&'a ObjectValue, ParseOutsideExecutionError> {
+    match value {
+        Value::Object(object) => Ok(object),
+        _ => Err(ParseOutsideExecutionError::InvalidField(name)),
+    }
+}
+
+fn as_array<'a>(
+    value: &'a Value,
+    name: &'static str,
+) -> Result<&'a [Value], ParseOutsideExecutionError> {
+    match value {
+        Value::Array(array) => Ok(&array.elements),
+        _ => Err(ParseOutsideExecutionError::InvalidField(name)),
+    }
+}
+
+fn as_felt(value: &Value, name: &'static str) -> Result<Felt, ParseOutsideExecutionError> {
+    let felt = match value {
+        Value::String(value) if value.starts_with("0x") => Felt::from_hex(value).ok(),
+        Value::String(value) => Felt::from_dec_str(value).ok(),
+        Value::UnsignedInteger(value) => Some((*value).into()),
+        _ => None,
+    };
+    felt.ok_or(ParseOutsideExecutionError::InvalidField(name))
+}
+
+fn felt_field(
+    object: &ObjectValue,
+    name: &'static str,
+) -> Result<Felt, ParseOutsideExecutionError> {
+    as_felt(field(object, name)?, name)
+}
+
+fn u64_field(object: &ObjectValue, name: &'static str) -> Result<u64, ParseOutsideExecutionError> {
+    felt_field(object, name)?
+        .try_into()
+        .map_err(|_| ParseOutsideExecutionError::InvalidField(name))
+}
+
+/// SNIP-12 selectors are function names, but selectors in hexadecimal are also commonly used.
+fn selector_field(
+    object: &ObjectValue,
+    name: &'static str,
+) -> Result<Felt, ParseOutsideExecutionError> {
+    match field(object, name)? {
+        Value::String(value) if !value.starts_with("0x") => get_selector_from_name(value)
+            .map_err(|_| ParseOutsideExecutionError::InvalidField(name)),
+        value => as_felt(value, name),
+    }
+}
+
+fn check_len(
+    len: Felt,
+    expected: usize,
+    name: &'static str,
+) -> Result<(), ParseOutsideExecutionError> {
+    if len == Felt::from(expected) {
+        Ok(())
+    } else {
+        Err(ParseOutsideExecutionError::InvalidField(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_core::utils::{cairo_short_string_to_felt, starknet_keccak};
+    use starknet_crypto::verify;
+    use starknet_signers::{LocalWallet, SigningKey};
+
+    use super::*;
+
+    fn sample_outside_execution() -> OutsideExecution {
+        OutsideExecution {
+            caller: ANY_CALLER,
+            nonce: Felt::from_hex_unchecked("0x1234"),
+            execute_after: 100,
+            execute_before: 200,
+            calls: vec![
+                Call {
+                    to: Felt::from_hex_unchecked("0x1111"),
+                    selector: get_selector_from_name("transfer").unwrap(),
+                    calldata: vec![Felt::ONE, Felt::TWO, Felt::ZERO],
+                },
+                Call {
+                    to: Felt::from_hex_unchecked("0x2222"),
+                    selector: get_selector_from_name("approve").unwrap(),
+                    calldata: vec![],
+                },
+            ],
+        }
+    }
+
+    /// The SNIP-9 typed data of [`sample_outside_execution`] as built by `starknet.js`, except
+    /// that selectors of version 2 are given as `selectors`.
+    fn sample_typed_data(
+        version: OutsideExecutionVersion,
+        selectors: [&str; 2],
+    ) -> serde_json::Value {
+        let [transfer, approve] = ["transfer", "approve"]
+            .map(|name| format!("{:#x}", get_selector_from_name(name).unwrap()));
+
+        match version {
+            OutsideExecutionVersion::V1 => serde_json::json!({
+                "types": {
+                    "StarkNetDomain": [
+                        { "name": "name", "type": "felt" },
+                        { "name": "version", "type": "felt" },
+                        { "name": "chainId", "type": "felt" }
+                    ],
+                    "OutsideExecution": [
+                        { "name": "caller", "type": "felt" },
+                        { "name": "nonce", "type": "felt" },
+                        { "name": "execute_after", "type": "felt" },
+                        { "name": "execute_before", "type": "felt" },
+                        { "name": "calls_len", "type": "felt" },
+                        { "name": "calls", "type": "OutsideCall*" }
+                    ],
+                    "OutsideCall": [
+                        { "name": "to", "type": "felt" },
+                        { "name": "selector", "type": "felt" },
+                        { "name": "calldata_len", "type": "felt" },
+                        { "name": "calldata", "type": "felt*" }
+                    ]
+                },
+                "primaryType": "OutsideExecution",
+                "domain": {
+                    "name": "Account.execute_from_outside",
+                    "version": "1",
+                    "chainId": "SN_SEPOLIA"
+                },
+                "message": {
+                    "caller": "0x414e595f43414c4c4552",
+                    "nonce": "0x1234",
+                    "execute_after": 100,
+                    "execute_before": "200",
+                    "calls_len": 2,
+                    "calls": [
+                        {
+                            "to": "0x1111",
+                            "selector": transfer,
+                            "calldata_len": 3,
+                            "calldata": ["0x1", "0x2", "0x0"]
+                        },
+                        {
+                            "to": "0x2222",
+                            "selector": approve,
+                            "calldata_len": 0,
+                            "calldata": []
+                        }
+                    ]
+                }
+            }),
+            OutsideExecutionVersion::V2 => serde_json::json!({
+                "types": {
+                    "StarknetDomain": [
+                        { "name": "name", "type": "shortstring" },
+                        { "name": "version", "type": "shortstring" },
+                        { "name": "chainId", "type": "shortstring" },
+                        { "name": "revision", "type": "shortstring" }
+                    ],
+                    "OutsideExecution": [
+                        { "name": "Caller", "type": "ContractAddress" },
+                        { "name": "Nonce", "type": "felt" },
+                        { "name": "Execute After", "type": "u128" },
+                        { "name": "Execute Before", "type": "u128" },
+                        { "name": "Calls", "type": "Call*" }
+                    ],
+                    "Call": [
+                        { "name": "To", "type": "ContractAddress" },
+                        { "name": "Selector", "type": "selector" },
+                        { "name": "Calldata", "type": "felt*" }
+                    ]
+                },
+                "primaryType": "OutsideExecution",
+                "domain": {
+                    "name": "Account.execute_from_outside",
+                    "version": "2",
+                    "chainId": "SN_SEPOLIA",
+                    "revision": "1"
+                },
+                "message": {
+                    "Caller": "0x414e595f43414c4c4552",
+                    "Nonce": "0x1234",
+                    "Execute After": "0x64",
+                    "Execute Before": 200,
+                    "Calls": [
+                        {
+                            "To": "0x1111",
+                            "Selector": selectors[0],
+                            "Calldata": ["0x1", "0x2", "0x0"]
+                        },
+                        {
+                            "To": "0x2222",
+                            "Selector": selectors[1],
+                            "Calldata": []
+                        }
+                    ]
+                }
+            }),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_outside_execution_constants() {
+        assert_eq!(
+            ANY_CALLER,
+            cairo_short_string_to_felt("ANY_CALLER").unwrap()
+        );
+        assert_eq!(
+            DOMAIN_NAME,
+            cairo_short_string_to_felt("Account.execute_from_outside").unwrap()
+        );
+        assert_eq!(
+            STARKNET_MESSAGE_PREFIX,
+            cairo_short_string_to_felt("StarkNet Message").unwrap()
+        );
+        assert_eq!(
+            CALL_TYPE_HASH_V1,
+            starknet_keccak(b"OutsideCall(to:felt,selector:felt,calldata_len:felt,calldata:felt*)")
+        );
+        assert_eq!(
+            CALL_TYPE_HASH_V2,
+            starknet_keccak(
+                br#""Call"("To":"ContractAddress","Selector":"selector","Calldata":"felt*")"#
+            )
+        );
+        assert_eq!(
+            OutsideExecutionVersion::V1.selector(),
+            get_selector_from_name("execute_from_outside").unwrap()
+        );
+        assert_eq!(
+            OutsideExecutionVersion::V2.selector(),
+            get_selector_from_name("execute_from_outside_v2").unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_outside_execution_message_hash_v1() {
+        let outside_execution = sample_outside_execution();
+        let chain_id = cairo_short_string_to_felt("SN_SEPOLIA").unwrap();
+        let account_address = Felt::from_hex_unchecked("0x9999");
+
+        // Type hashes as defined by the SNIP-9 reference implementation
+        let outside_execution_type_hash = Felt::from_hex_unchecked(
+            "0x11ff76fe3f640fa6f3d60bbd94a3b9d47141a2c96f87fdcfbeb2af1d03f7050",
+        );
+        let call_type_hash = Felt::from_hex_unchecked(
+            "0xf00de1fccbb286f9a020ba8821ee936b1deea42a5c485c11ccdc82c8bebb3a",
+        );
+        let domain_type_hash =
+            starknet_keccak(b"StarkNetDomain(name:felt,version:felt,chainId:felt)");
+
+        let call_hashes = outside_execution
+            .calls
+            .iter()
+            .map(|call| {
+                compute_hash_on_elements(&[
+                    call_type_hash,
+                    call.to,
+                    call.selector,
+                    call.calldata.len().into(),
+                    compute_hash_on_elements(&call.calldata),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let struct_hash = compute_hash_on_elements(&[
+            outside_execution_type_hash,
+            outside_execution.caller,
+            outside_execution.nonce,
+            outside_execution.execute_after.into(),
+            outside_execution.execute_before.into(),
+            outside_execution.calls.len().into(),
+            compute_hash_on_elements(&call_hashes),
+        ]);
+        let domain_hash = compute_hash_on_elements(&[
+            domain_type_hash,
+            cairo_short_string_to_felt("Account.execute_from_outside").unwrap(),
+            Felt::ONE,
+            chain_id,
+        ]);
+
+        assert_eq!(
+            outside_execution.message_hash(OutsideExecutionVersion::V1, chain_id, account_address),
+            compute_hash_on_elements(&[
+                cairo_short_string_to_felt("StarkNet Message").unwrap(),
+                domain_hash,
+                account_address,
+                struct_hash,
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_outside_execution_message_hash_v2() {
+        let outside_execution = sample_outside_execution();
+        let chain_id = cairo_short_string_to_felt("SN_SEPOLIA").unwrap();
+        let account_address = Felt::from_hex_unchecked("0x9999");
+
+        // Type hashes as defined by the SNIP-9 reference implementation
+        let outside_execution_type_hash = Felt::from_hex_unchecked(
+            "0x312b56c05a7965066ddbda31c016d8d05afc305071c0ca3cdc2192c3c2f1f0f",
+        );
+        let call_type_hash = Felt::from_hex_unchecked(
+            "0x3635c7f2a7ba93844c0d064e18e487f35ab90f7c39d00f186a781fc3f0c2ca9",
+        );
+        let domain_type_hash = starknet_keccak(
+            concat!(
+                r#""StarknetDomain"("name":"shortstring","version":"shortstring","#,
+                r#""chainId":"shortstring","revision":"shortstring")"#,
+            )
+            .as_bytes(),
+        );
+
+        let call_hashes = outside_execution
+            .calls
+            .iter()
+            .map(|call| {
+                poseidon_hash_many(&[
+                    call_type_hash,
+                    call.to,
+                    call.selector,
+                    poseidon_hash_many(&call.calldata),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let struct_hash = poseidon_hash_many(&[
+            outside_execution_type_hash,
+            outside_execution.caller,
+            outside_execution.nonce,
+            outside_execution.execute_after.into(),
+            outside_execution.execute_before.into(),
+            poseidon_hash_many(&call_hashes),
+        ]);
+        let domain_hash = poseidon_hash_many(&[
+            domain_type_hash,
+            cairo_short_string_to_felt("Account.execute_from_outside").unwrap(),
+            Felt::TWO,
+            chain_id,
+            Felt::ONE,
+        ]);
+
+        assert_eq!(
+            outside_execution.message_hash(OutsideExecutionVersion::V2, chain_id, account_address),
+            poseidon_hash_many(&[
+                cairo_short_string_to_felt("StarkNet Message").unwrap(),
+                domain_hash,
+                account_address,
+                struct_hash,
+            ])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_outside_execution_from_typed_data() {
+        let chain_id = cairo_short_string_to_felt("SN_SEPOLIA").unwrap();
+        let account_address = Felt::from_hex_unchecked("0x9999");
+
+        for version in [OutsideExecutionVersion::V1, OutsideExecutionVersion::V2] {
+            let typed_data = serde_json::from_value::<TypedData>(sample_typed_data(
+                version,
+                ["transfer", "approve"],
+            ))
+            .unwrap();
+
+            assert_eq!(
+                OutsideExecution::from_typed_data(&typed_data).unwrap(),
+                (version, sample_outside_execution())
+            );
+
+            // Same hash as generic SNIP-12 hashing
+            assert_eq!(
+                sample_outside_execution().message_hash(version, chain_id, account_address),
+                typed_data.message_hash(account_address).unwrap()
+            );
+        }
+
+        // Selectors in hexadecimal are accepted as well
+        let typed_data = serde_json::from_value::<TypedData>(sample_typed_data(
+            OutsideExecutionVersion::V2,
+            [
+                "0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e",
+                "0x219209e083275171774dab1df80982e9df2096516f06319c5c6d71ae0a8480c",
+            ],
+        ))
+        .unwrap();
+        assert_eq!(
+            OutsideExecution::from_typed_data(&typed_data).unwrap(),
+            (OutsideExecutionVersion::V2, sample_outside_execution())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_outside_execution_from_invalid_typed_data() {
+        let mut raw = sample_typed_data(OutsideExecutionVersion::V2, ["transfer", "approve"]);
+        raw["domain"]["name"] = "Other".into();
+        assert!(matches!(
+            OutsideExecution::from_typed_data(&serde_json::from_value(raw).unwrap()),
+            Err(ParseOutsideExecutionError::UnexpectedDomain)
+        ));
+
+        let mut raw = sample_typed_data(OutsideExecutionVersion::V1, ["transfer", "approve"]);
+        raw["message"]["calls_len"] = 1.into();
+        assert!(matches!(
+            OutsideExecution::from_typed_data(&serde_json::from_value(raw).unwrap()),
+            Err(ParseOutsideExecutionError::InvalidField("calls_len"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_outside_execution_sign() {
+        let signer = LocalWallet::from(SigningKey::from_secret_scalar(Felt::from_hex_unchecked(
+            "0x00ae7f6a3ec2a4bf0d4fb6ee9f3fa7e0e1f42bba5e0cb5b3bba1e1fb68f39d37",
+        )));
+        let public_key = signer.get_public_key().await.unwrap();
+
+        let outside_execution = sample_outside_execution();
+        let chain_id = cairo_short_string_to_felt("SN_SEPOLIA").unwrap();
+        let account_address = Felt::from_hex_unchecked("0x9999");
+
+        let signature = outside_execution
+            .sign(
+                OutsideExecutionVersion::V2,
+                chain_id,
+                account_address,
+                &signer,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(signature.len(), 2);
+        assert!(verify(
+            &public_key.scalar(),
+            &outside_execution.message_hash(OutsideExecutionVersion::V2, chain_id, account_address),
+            &signature[0],
+            &signature[1],
+        )
+        .unwrap());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_outside_execution_to_call() {
+        let outside_execution = sample_outside_execution();
+        let account_address = Felt::from_hex_unchecked("0x9999");
+        let signature = [Felt::from(7), Felt::from(8)];
+
+        let call =
+            outside_execution.to_call(OutsideExecutionVersion::V2, account_address, &signature);
+
+        assert_eq!(call.to, account_address);
+        assert_eq!(call.selector, OutsideExecutionVersion::V2.selector());
+        assert_eq!(
+            call.calldata,
+            vec![
+                ANY_CALLER,
+                Felt::from_hex_unchecked("0x1234"),
+                Felt::from(100),
+                Felt::from(200),
+                Felt::TWO,
+                Felt::from_hex_unchecked("0x1111"),
+                get_selector_from_name("transfer").unwrap(),
+                Felt::THREE,
+                Felt::ONE,
+                Felt::TWO,
+                Felt::ZERO,
+                Felt::from_hex_unchecked("0x2222"),
+                get_selector_from_name("approve").unwrap(),
+                Felt::ZERO,
+                Felt::TWO,
+                Felt::from(7),
+                Felt::from(8),
+            ]
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{Account, ConnectedAccount, ExecutionEncoder, RawDeclarationV3, RawExecutionV3};
+use crate::{
+    Account, ConnectedAccount, ExecutionEncoder, MessageSigner, RawDeclarationV3, RawExecutionV3,
+};
 
 use async_trait::async_trait;
 use starknet_core::types::{contract::ComputeClassHashError, BlockId, BlockTag, Call, Felt};
@@ -132,16 +134,40 @@ where
     }
 }
 
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P, S> MessageSigner for SingleOwnerAccount<P, S>
+where
+    P: Provider + Sync + Send,
+    S: Signer + Sync + Send,
+{
+    async fn sign_message_hash(&self, message_hash: &Felt) -> Result<Vec<Felt>, Self::SignError> {
+        let signature = self
+            .signer
+            .sign_hash(message_hash)
+            .await
+            .map_err(SignError::Signer)?;
+
+        Ok(vec![signature.r, signature.s])
+    }
+}
+
 impl<P, S> ExecutionEncoder for SingleOwnerAccount<P, S>
 where
     P: Provider + Send,
     S: Signer + Send,
 {
+    fn encode_calls(&self, calls: &[Call]) -> Vec<Felt> {
+        self.encoding.encode_calls(calls)
+    }
+}
+
+impl ExecutionEncoder for ExecutionEncoding {
     fn encode_calls(&self, calls: &[Call]) -> Vec<Felt> {
         let mut execute_calldata: Vec<Felt> = vec![calls.len().into()];
 
-        match self.encoding {
-            ExecutionEncoding::Legacy => {
+        match self {
+            Self::Legacy => {
                 let mut concated_calldata: Vec<Felt> = vec![];
                 for call in calls {
                     execute_calldata.push(call.to); // to
@@ -157,7 +183,7 @@ where
                 execute_calldata.push(concated_calldata.len().into()); // calldata_len
                 execute_calldata.extend_from_slice(&concated_calldata);
             }
-            ExecutionEncoding::New => {
+            Self::New => {
                 for call in calls {
                     execute_calldata.push(call.to); // to
                     execute_calldata.push(call.selector); // selector
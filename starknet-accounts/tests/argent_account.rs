@@ -0,0 +1,170 @@
+use starknet_accounts::{Account, ArgentAccount, MessageSigner};
+use starknet_core::{
+    types::{Call, Felt},
+    utils::get_selector_from_name,
+};
+use starknet_crypto::verify;
+use starknet_providers::{
+    jsonrpc::{HttpTransport, JsonRpcClient},
+    Url,
+};
+use starknet_signers::{LocalWallet, Signer, SignerInteractivityContext, SigningKey};
+
+/// Cairo short string encoding for `SN_SEPOLIA`.
+const CHAIN_ID: Felt = Felt::from_raw([
+    507980251676163170,
+    18446744073709551615,
+    18446744073708869172,
+    1555806712078248243,
+]);
+
+const ACCOUNT_ADDRESS: Felt = Felt::from_hex_unchecked("0x9999");
+
+fn create_signer(private_key: &str) -> LocalWallet {
+    LocalWallet::from(SigningKey::from_secret_scalar(Felt::from_hex_unchecked(
+        private_key,
+    )))
+}
+
+async fn create_account(
+    guardian: Option<LocalWallet>,
+) -> ArgentAccount<JsonRpcClient<HttpTransport>, LocalWallet, LocalWallet> {
+    ArgentAccount::new(
+        JsonRpcClient::new(HttpTransport::new(
+            Url::parse("http://localhost:5050").unwrap(),
+        )),
+        create_signer("0x1"),
+        guardian,
+        ACCOUNT_ADDRESS,
+        CHAIN_ID,
+    )
+    .await
+    .unwrap()
+}
+
+fn sample_calls() -> Vec<Call> {
+    vec![Call {
+        to: Felt::from_hex_unchecked("0x1111"),
+        selector: get_selector_from_name("transfer").unwrap(),
+        calldata: vec![Felt::ONE, Felt::TWO, Felt::ZERO],
+    }]
+}
+
+async fn sign_execution(
+    account: &ArgentAccount<JsonRpcClient<HttpTransport>, LocalWallet, LocalWallet>,
+    calls: Vec<Call>,
+) -> (Felt, Vec<Felt>) {
+    let prepared = account
+        .execute_v3(calls)
+        .nonce(Felt::ONE)
+        .l1_gas(1)
+        .l1_gas_price(2)
+        .l2_gas(3)
+        .l2_gas_price(4)
+        .l1_data_gas(5)
+        .l1_data_gas_price(6)
+        .tip(0)
+        .prepared()
+        .unwrap();
+    let signature = account
+        .sign_execution_v3(prepared.raw_execution(), false)
+        .await
+        .unwrap();
+
+    (prepared.transaction_hash(false), signature)
+}
+
+#[tokio::test]
+async fn can_sign_with_owner_and_guardian() {
+    let owner_public_key = create_signer("0x1").get_public_key().await.unwrap();
+    let guardian_public_key = create_signer("0x2").get_public_key().await.unwrap();
+    let account = create_account(Some(create_signer("0x2"))).await;
+
+    let (tx_hash, signature) = sign_execution(&account, sample_calls()).await;
+
+    // Encoded as `Array<SignerSignature>` of `Starknet` signers
+    assert_eq!(signature.len(), 1 + 2 * 4);
+    assert_eq!(signature[0], Felt::TWO);
+    assert_eq!(signature[1], Felt::ZERO);
+    assert_eq!(signature[2], owner_public_key.scalar());
+    assert_eq!(signature[5], Felt::ZERO);
+    assert_eq!(signature[6], guardian_public_key.scalar());
+    for signer_signature in signature[1..].chunks(4) {
+        assert!(verify(
+            &signer_signature[1],
+            &tx_hash,
+            &signer_signature[2],
+            &signer_signature[3]
+        )
+        .unwrap());
+    }
+}
+
+#[tokio::test]
+async fn can_sign_without_guardian() {
+    let account = create_account(None).await;
+
+    let signature = account.sign_message_hash(&Felt::ONE).await.unwrap();
+
+    assert_eq!(signature.len(), 1 + 4);
+    assert_eq!(signature[0], Felt::ONE);
+    assert!(verify(&signature[2], &Felt::ONE, &signature[3], &signature[4]).unwrap());
+}
+
+#[tokio::test]
+async fn can_escape_guardian_with_owner_only() {
+    let owner_public_key = create_signer("0x1").get_public_key().await.unwrap();
+    let account = create_account(Some(create_signer("0x2"))).await;
+
+    for call in [
+        account.trigger_escape_guardian(Some(Felt::from_hex_unchecked("0x3"))),
+        account.escape_guardian(),
+    ] {
+        let calls = vec![call];
+        assert!(
+            !account.is_signer_interactive(SignerInteractivityContext::Execution { calls: &calls })
+        );
+
+        let (tx_hash, signature) = sign_execution(&account, calls).await;
+
+        assert_eq!(signature.len(), 1 + 4);
+        assert_eq!(signature[0], Felt::ONE);
+        assert_eq!(signature[2], owner_public_key.scalar());
+        assert!(verify(&signature[2], &tx_hash, &signature[3], &signature[4]).unwrap());
+    }
+
+    // Escaping along with other calls still requires the guardian
+    let mut calls = sample_calls();
+    calls.push(account.escape_guardian());
+    let (_, signature) = sign_execution(&account, calls).await;
+    assert_eq!(signature[0], Felt::TWO);
+}
+
+#[tokio::test]
+async fn can_build_escape_guardian_calls() {
+    let account = create_account(None).await;
+
+    let trigger_call = account.trigger_escape_guardian(Some(Felt::from_hex_unchecked("0x3")));
+    assert_eq!(trigger_call.to, ACCOUNT_ADDRESS);
+    assert_eq!(
+        trigger_call.selector,
+        get_selector_from_name("trigger_escape_guardian").unwrap()
+    );
+    assert_eq!(
+        trigger_call.calldata,
+        vec![Felt::ZERO, Felt::ZERO, Felt::THREE]
+    );
+
+    // `Option::None` for removing the guardian
+    assert_eq!(
+        account.trigger_escape_guardian(None).calldata,
+        vec![Felt::ONE]
+    );
+
+    let escape_call = account.escape_guardian();
+    assert_eq!(
+        escape_call.selector,
+        get_selector_from_name("escape_guardian").unwrap()
+    );
+    assert!(escape_call.calldata.is_empty());
+}
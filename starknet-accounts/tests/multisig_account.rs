@@ -0,0 +1,213 @@
+use starknet_accounts::{
+    multisig::SignError, Account, ExecutionEncoding, MessageSigner, MultisigAccount,
+    MultisigSignature, MultisigSignatureEncoding, PreparedExecutionV3,
+};
+use starknet_core::{
+    types::{Call, Felt},
+    utils::{cairo_short_string_to_felt, get_selector_from_name},
+};
+use starknet_crypto::poseidon_hash_many;
+use starknet_providers::{
+    jsonrpc::{HttpTransport, JsonRpcClient},
+    Url,
+};
+use starknet_signers::{LocalWallet, Signer, SignerInteractivityContext, SigningKey};
+
+/// Cairo short string encoding for `SN_SEPOLIA`.
+const CHAIN_ID: Felt = Felt::from_raw([
+    507980251676163170,
+    18446744073709551615,
+    18446744073708869172,
+    1555806712078248243,
+]);
+
+const ACCOUNT_ADDRESS: Felt = Felt::from_hex_unchecked("0x9999");
+
+fn create_signer(private_key: &str) -> LocalWallet {
+    LocalWallet::from(SigningKey::from_secret_scalar(Felt::from_hex_unchecked(
+        private_key,
+    )))
+}
+
+async fn create_account(
+    signers: Vec<LocalWallet>,
+    signature_encoding: MultisigSignatureEncoding,
+) -> MultisigAccount<JsonRpcClient<HttpTransport>, LocalWallet> {
+    MultisigAccount::new(
+        JsonRpcClient::new(HttpTransport::new(
+            Url::parse("http://localhost:5050").unwrap(),
+        )),
+        signers,
+        2,
+        ACCOUNT_ADDRESS,
+        CHAIN_ID,
+        ExecutionEncoding::New,
+        signature_encoding,
+    )
+    .await
+    .unwrap()
+}
+
+fn sample_calls() -> Vec<Call> {
+    vec![Call {
+        to: Felt::from_hex_unchecked("0x1111"),
+        selector: get_selector_from_name("transfer").unwrap(),
+        calldata: vec![Felt::ONE, Felt::TWO, Felt::ZERO],
+    }]
+}
+
+#[tokio::test]
+async fn can_collect_cosigner_signatures() {
+    let proposer_signer = create_signer("0x1");
+    let cosigner_signer = create_signer("0x2");
+    let mut expected_signers = [
+        proposer_signer.get_public_key().await.unwrap().scalar(),
+        cosigner_signer.get_public_key().await.unwrap().scalar(),
+    ];
+    expected_signers.sort();
+
+    let proposer = create_account(vec![proposer_signer], MultisigSignatureEncoding::Legacy).await;
+    let cosigner = create_account(vec![cosigner_signer], MultisigSignatureEncoding::Legacy).await;
+
+    let prepared = proposer
+        .execute_v3(sample_calls())
+        .nonce(Felt::ONE)
+        .l1_gas(1)
+        .l1_gas_price(2)
+        .l2_gas(3)
+        .l2_gas_price(4)
+        .l1_data_gas(5)
+        .l1_data_gas_price(6)
+        .tip(0)
+        .prepared()
+        .unwrap();
+    let tx_hash = prepared.transaction_hash(false);
+
+    // Not enough local signers to reach the threshold
+    assert!(
+        proposer.is_signer_interactive(SignerInteractivityContext::Execution {
+            calls: &sample_calls()
+        })
+    );
+    match proposer
+        .sign_execution_v3(prepared.raw_execution(), false)
+        .await
+    {
+        Err(SignError::NotEnoughSignatures {
+            required: 2,
+            available: 1,
+        }) => {}
+        result => panic!("unexpected result: {result:?}"),
+    }
+
+    // The cosigner computes the same hash from the exported execution
+    let cosigner_prepared =
+        PreparedExecutionV3::from_raw(prepared.raw_execution().clone(), &cosigner);
+    assert_eq!(cosigner_prepared.transaction_hash(false), tx_hash);
+
+    let cosigner_signatures = cosigner.sign_hash_partial(&tx_hash).await.unwrap();
+    assert_eq!(cosigner_signatures.len(), 1);
+    proposer
+        .add_signatures(tx_hash, cosigner_signatures)
+        .unwrap();
+
+    let signature = proposer
+        .sign_execution_v3(prepared.raw_execution(), false)
+        .await
+        .unwrap();
+    assert_eq!(signature.len(), 6);
+    assert_eq!(signature[0], expected_signers[0]);
+    assert_eq!(signature[3], expected_signers[1]);
+    for triple in signature.chunks(3) {
+        assert!(starknet_crypto::verify(&triple[0], &tx_hash, &triple[1], &triple[2]).unwrap());
+    }
+}
+
+#[tokio::test]
+async fn can_reject_invalid_cosigner_signatures() {
+    let account = create_account(
+        vec![create_signer("0x1")],
+        MultisigSignatureEncoding::Legacy,
+    )
+    .await;
+    let cosigner = create_account(
+        vec![create_signer("0x2")],
+        MultisigSignatureEncoding::Legacy,
+    )
+    .await;
+
+    let signatures = cosigner.sign_hash_partial(&Felt::ONE).await.unwrap();
+    assert_eq!(
+        account
+            .add_signatures(Felt::TWO, signatures.clone())
+            .unwrap_err()
+            .signer,
+        signatures[0].signer
+    );
+
+    // Nothing is added for the hash after a failure
+    assert!(matches!(
+        account.sign_message_hash(&Felt::TWO).await,
+        Err(SignError::NotEnoughSignatures { .. })
+    ));
+}
+
+#[tokio::test]
+async fn can_sign_with_local_signers_only() {
+    let account = create_account(
+        vec![
+            create_signer("0x1"),
+            create_signer("0x2"),
+            create_signer("0x3"),
+        ],
+        MultisigSignatureEncoding::New,
+    )
+    .await;
+
+    assert!(
+        !account.is_signer_interactive(SignerInteractivityContext::Execution {
+            calls: &sample_calls()
+        })
+    );
+
+    let signature = account.sign_message_hash(&Felt::ONE).await.unwrap();
+
+    // Only as many signatures as the threshold, encoded as `Array<SignerSignature>`
+    assert_eq!(signature.len(), 1 + 2 * 4);
+    assert_eq!(signature[0], Felt::TWO);
+    assert_eq!(signature[1], Felt::ZERO);
+    assert_eq!(signature[5], Felt::ZERO);
+    // Sorted by signer GUID instead of public key
+    let guid = |signer| {
+        poseidon_hash_many(&[
+            cairo_short_string_to_felt("Starknet Signer").unwrap(),
+            signer,
+        ])
+    };
+    assert!(guid(signature[2]) < guid(signature[6]));
+}
+
+#[test]
+fn can_encode_multisig_signatures() {
+    let signatures = [
+        MultisigSignature {
+            signer: Felt::ONE,
+            r: Felt::TWO,
+            s: Felt::THREE,
+        },
+        MultisigSignature {
+            signer: Felt::from(4),
+            r: Felt::from(5),
+            s: Felt::from(6),
+        },
+    ];
+
+    assert_eq!(
+        MultisigSignatureEncoding::Legacy.encode(&signatures),
+        [1, 2, 3, 4, 5, 6].map(Felt::from).to_vec()
+    );
+    assert_eq!(
+        MultisigSignatureEncoding::New.encode(&signatures),
+        [2, 0, 1, 2, 3, 0, 4, 5, 6].map(Felt::from).to_vec()
+    );
+}
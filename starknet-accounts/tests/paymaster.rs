@@ -0,0 +1,369 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use starknet_accounts::{
+    Account, ExecutionEncoding, OutsideExecution, OutsideExecutionVersion, PaymasterAccount,
+    PaymasterExecutionError, SingleOwnerAccount,
+};
+use starknet_core::{
+    types::{typed_data::TypedData, Call, Felt},
+    utils::get_selector_from_name,
+};
+use starknet_crypto::verify;
+use starknet_providers::{
+    jsonrpc::{HttpTransport, JsonRpcClient, JsonRpcResponse},
+    paymaster::{FeeMode, PaymasterMethod, PaymasterTransport},
+    PaymasterClient,
+};
+use starknet_signers::{LocalWallet, Signer, SigningKey};
+
+/// Cairo short string encoding for `SN_SEPOLIA`.
+const CHAIN_ID: Felt = Felt::from_raw([
+    507980251676163170,
+    18446744073709551615,
+    18446744073708869172,
+    1555806712078248243,
+]);
+
+/// A paymaster transport building outside executions with a fee transfer appended, and recording
+/// executed transactions.
+#[derive(Debug, Default)]
+struct MockPaymaster {
+    tampering: Tampering,
+    executed: Mutex<Vec<serde_json::Value>>,
+}
+
+/// How a malicious paymaster changes the calls of the outside executions it builds.
+#[derive(Debug, Default, Clone, Copy)]
+enum Tampering {
+    #[default]
+    None,
+    /// Changes the calldata of a requested call.
+    ChangeCall,
+    /// Inserts an extra call before the fee transfer.
+    InsertCall,
+    /// Transfers more than the maximum fee.
+    OverchargeFee,
+    /// Transfers a token other than the gas token.
+    ChangeFeeToken,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unsupported method")]
+struct MockError;
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl PaymasterTransport for MockPaymaster {
+    type Error = MockError;
+
+    async fn send_paymaster_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let params = serde_json::to_value(params).unwrap();
+
+        let result = match method {
+            PaymasterMethod::BuildTransaction => {
+                let invoke = &params["transaction"]["invoke"];
+                let mut calls = invoke["calls"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|call| Call {
+                        to: Felt::from_hex(call["to"].as_str().unwrap()).unwrap(),
+                        selector: Felt::from_hex(call["selector"].as_str().unwrap()).unwrap(),
+                        calldata: call["calldata"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|item| Felt::from_hex(item.as_str().unwrap()).unwrap())
+                            .collect(),
+                    })
+                    .collect::<Vec<_>>();
+                let mut fee_transfer = Call {
+                    to: Felt::from_hex_unchecked("0x5555"),
+                    selector: get_selector_from_name("transfer").unwrap(),
+                    calldata: vec![
+                        Felt::from_hex_unchecked("0x7777"),
+                        Felt::from(100),
+                        Felt::ZERO,
+                    ],
+                };
+                match self.tampering {
+                    Tampering::None => {}
+                    Tampering::ChangeCall => {
+                        calls[0].calldata[0] = Felt::from_hex_unchecked("0x7777")
+                    }
+                    Tampering::InsertCall => calls.push(fee_transfer.clone()),
+                    Tampering::OverchargeFee => fee_transfer.calldata[1] = Felt::from(101),
+                    Tampering::ChangeFeeToken => {
+                        fee_transfer.to = Felt::from_hex_unchecked("0x6666")
+                    }
+                }
+                calls.push(fee_transfer);
+
+                let typed_data = outside_execution_typed_data(&calls);
+
+                serde_json::json!({
+                    "type": "invoke",
+                    "typed_data": typed_data,
+                    "parameters": params["parameters"],
+                    "fee": {
+                        "gas_token_price_in_strk": "0x1",
+                        "estimated_fee_in_strk": "0x50",
+                        "estimated_fee_in_gas_token": "0x50",
+                        "suggested_max_fee_in_strk": "0x64",
+                        "suggested_max_fee_in_gas_token": "0x64"
+                    }
+                })
+            }
+            PaymasterMethod::ExecuteTransaction => {
+                self.executed.lock().unwrap().push(params);
+                serde_json::json!({ "tracking_id": "0x1", "transaction_hash": "0x2" })
+            }
+            _ => return Err(MockError),
+        };
+
+        Ok(serde_json::from_value(serde_json::json!({ "id": 1, "result": result })).unwrap())
+    }
+}
+
+/// Builds SNIP-9 version 2 typed data the way paymasters do, with selectors in hexadecimal.
+fn outside_execution_typed_data(calls: &[Call]) -> serde_json::Value {
+    serde_json::json!({
+        "types": {
+            "StarknetDomain": [
+                { "name": "name", "type": "shortstring" },
+                { "name": "version", "type": "shortstring" },
+                { "name": "chainId", "type": "shortstring" },
+                { "name": "revision", "type": "shortstring" }
+            ],
+            "OutsideExecution": [
+                { "name": "Caller", "type": "ContractAddress" },
+                { "name": "Nonce", "type": "felt" },
+                { "name": "Execute After", "type": "u128" },
+                { "name": "Execute Before", "type": "u128" },
+                { "name": "Calls", "type": "Call*" }
+            ],
+            "Call": [
+                { "name": "To", "type": "ContractAddress" },
+                { "name": "Selector", "type": "selector" },
+                { "name": "Calldata", "type": "felt*" }
+            ]
+        },
+        "primaryType": "OutsideExecution",
+        "domain": {
+            "name": "Account.execute_from_outside",
+            "version": "2",
+            "chainId": format!("{CHAIN_ID:#x}"),
+            "revision": "1"
+        },
+        "message": {
+            "Caller": "0x7777",
+            "Nonce": "0x1234",
+            "Execute After": "0x1",
+            "Execute Before": "0x2",
+            "Calls": calls
+                .iter()
+                .map(|call| serde_json::json!({
+                    "To": format!("{:#x}", call.to),
+                    "Selector": format!("{:#x}", call.selector),
+                    "Calldata": call
+                        .calldata
+                        .iter()
+                        .map(|item| format!("{item:#x}"))
+                        .collect::<Vec<_>>()
+                }))
+                .collect::<Vec<_>>()
+        }
+    })
+}
+
+fn create_account() -> SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> {
+    SingleOwnerAccount::new(
+        JsonRpcClient::new(HttpTransport::new(
+            url::Url::parse("http://localhost:5050").unwrap(),
+        )),
+        LocalWallet::from(SigningKey::from_secret_scalar(Felt::from_hex_unchecked(
+            "0x00ae7f6a3ec2a4bf0d4fb6ee9f3fa7e0e1f42bba5e0cb5b3bba1e1fb68f39d37",
+        ))),
+        Felt::from_hex_unchecked("0x9999"),
+        CHAIN_ID,
+        ExecutionEncoding::New,
+    )
+}
+
+fn sample_calls() -> Vec<Call> {
+    vec![Call {
+        to: Felt::from_hex_unchecked("0x1111"),
+        selector: get_selector_from_name("approve").unwrap(),
+        calldata: vec![Felt::ONE, Felt::TWO, Felt::ZERO],
+    }]
+}
+
+#[tokio::test]
+async fn can_execute_through_paymaster() {
+    let account = create_account();
+    let transport = Arc::new(MockPaymaster::default());
+    let paymaster = PaymasterClient::new(transport.clone());
+    let gas_token = Felt::from_hex_unchecked("0x5555");
+
+    let execution = account
+        .execute_paymaster(sample_calls(), &paymaster, FeeMode::Default { gas_token })
+        .max_fee_in_gas_token(Felt::from(100));
+
+    let fee = execution.estimate_fee().await.unwrap();
+    assert_eq!(fee.suggested_max_fee_in_gas_token, Felt::from(100));
+
+    let result = execution.send().await.unwrap();
+    assert_eq!(result.transaction_hash, Felt::TWO);
+
+    let executed = transport.executed.lock().unwrap().clone();
+    assert_eq!(executed.len(), 1);
+
+    let invoke = &executed[0]["transaction"]["invoke"];
+    assert_eq!(invoke["user_address"], "0x9999");
+    assert_eq!(
+        executed[0]["parameters"]["fee_mode"],
+        serde_json::json!({ "mode": "default", "gas_token": "0x5555" })
+    );
+
+    let typed_data = serde_json::from_value::<TypedData>(invoke["typed_data"].clone()).unwrap();
+    let signature = invoke["signature"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| Felt::from_hex(item.as_str().unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    let public_key = LocalWallet::from(SigningKey::from_secret_scalar(Felt::from_hex_unchecked(
+        "0x00ae7f6a3ec2a4bf0d4fb6ee9f3fa7e0e1f42bba5e0cb5b3bba1e1fb68f39d37",
+    )))
+    .get_public_key()
+    .await
+    .unwrap();
+
+    let (version, outside_execution) = OutsideExecution::from_typed_data(&typed_data).unwrap();
+    assert_eq!(version, OutsideExecutionVersion::V2);
+    assert_eq!(outside_execution.calls.len(), 2);
+
+    assert!(verify(
+        &public_key.scalar(),
+        &outside_execution.message_hash(version, CHAIN_ID, account.address()),
+        &signature[0],
+        &signature[1],
+    )
+    .unwrap());
+}
+
+#[tokio::test]
+async fn can_reject_paymaster_fee_above_max() {
+    let account = create_account();
+    let transport = Arc::new(MockPaymaster::default());
+    let paymaster = PaymasterClient::new(transport.clone());
+
+    let result = account
+        .execute_paymaster(
+            sample_calls(),
+            &paymaster,
+            FeeMode::Default {
+                gas_token: Felt::from_hex_unchecked("0x5555"),
+            },
+        )
+        .max_fee_in_gas_token(Felt::from(99))
+        .send()
+        .await;
+
+    match result {
+        Err(PaymasterExecutionError::FeeTooHigh { fee, max_fee }) => {
+            assert_eq!(fee, Felt::from(100));
+            assert_eq!(max_fee, Felt::from(99));
+        }
+        _ => panic!("unexpected result: {result:?}"),
+    }
+    assert!(transport.executed.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn can_require_max_fee_for_paying_in_gas_token() {
+    let account = create_account();
+    let transport = Arc::new(MockPaymaster::default());
+    let paymaster = PaymasterClient::new(transport.clone());
+
+    let result = account
+        .execute_paymaster(
+            sample_calls(),
+            &paymaster,
+            FeeMode::Default {
+                gas_token: Felt::from_hex_unchecked("0x5555"),
+            },
+        )
+        .send()
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(PaymasterExecutionError::MissingMaxFee)
+    ));
+    assert!(transport.executed.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn can_reject_tampered_paymaster_calls() {
+    let account = create_account();
+
+    for tampering in [
+        Tampering::ChangeCall,
+        Tampering::InsertCall,
+        Tampering::OverchargeFee,
+        Tampering::ChangeFeeToken,
+    ] {
+        let transport = Arc::new(MockPaymaster {
+            tampering,
+            ..Default::default()
+        });
+        let paymaster = PaymasterClient::new(transport.clone());
+
+        let result = account
+            .execute_paymaster(
+                sample_calls(),
+                &paymaster,
+                FeeMode::Default {
+                    gas_token: Felt::from_hex_unchecked("0x5555"),
+                },
+            )
+            .max_fee_in_gas_token(Felt::from(100))
+            .send()
+            .await;
+
+        assert!(
+            matches!(result, Err(PaymasterExecutionError::UnexpectedCalls)),
+            "unexpected result for {tampering:?}: {result:?}"
+        );
+        assert!(transport.executed.lock().unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn can_reject_fee_transfer_when_sponsored() {
+    let account = create_account();
+    let transport = Arc::new(MockPaymaster::default());
+    let paymaster = PaymasterClient::new(transport.clone());
+
+    let result = account
+        .execute_paymaster(sample_calls(), &paymaster, FeeMode::Sponsored)
+        .send()
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(PaymasterExecutionError::UnexpectedCalls)
+    ));
+    assert!(transport.executed.lock().unwrap().is_empty());
+}
@@ -0,0 +1,499 @@
+use starknet_accounts::{Account, ConnectedAccount, ExecutionV3};
+use starknet_core::{
+    codec::Decode,
+    types::{BlockId, BlockTag, Call, ContractExecutionError, Felt, FunctionCall, StarknetError},
+    utils::{get_selector_from_name, NonAsciiNameError},
+};
+use starknet_providers::{Provider, ProviderError};
+
+#[cfg(not(target_arch = "wasm32"))]
+use starknet_core::types::EventFilter;
+#[cfg(not(target_arch = "wasm32"))]
+use starknet_providers::EventPaginator;
+
+use crate::CallError;
+
+/// Selector for entrypoint `supports_interface`.
+const SELECTOR_SUPPORTS_INTERFACE: Felt = Felt::from_raw([
+    491583210683321117,
+    11012281078312324323,
+    15018431991248068039,
+    4029439699179956302,
+]);
+
+/// Selector for entrypoint `supportsInterface`.
+const SELECTOR_SUPPORTSINTERFACE: Felt = Felt::from_raw([
+    528306269168197809,
+    15300974161839687783,
+    15926960749644257622,
+    17781685874325179807,
+]);
+
+/// A deployed contract paired with an account for sending transactions to it.
+///
+/// Functions are referenced by name and take raw calldata. For type-safe bindings generated from
+/// the contract ABI, use the `abigen!` macro instead.
+#[derive(Debug, Clone)]
+pub struct Contract<A> {
+    address: Felt,
+    account: A,
+}
+
+/// A deployed contract paired with a provider for calling its view functions and fetching its
+/// events.
+#[derive(Debug, Clone)]
+pub struct ContractReader<P> {
+    address: Felt,
+    provider: P,
+    block_id: BlockId,
+}
+
+impl<A> Contract<A> {
+    /// Constructs a new [`Contract`] from the contract address and an account.
+    pub const fn new(address: Felt, account: A) -> Self {
+        Self { address, account }
+    }
+
+    /// Gets the contract address.
+    pub const fn address(&self) -> Felt {
+        self.address
+    }
+
+    /// Gets a reference to the account used for sending transactions.
+    pub const fn account(&self) -> &A {
+        &self.account
+    }
+
+    /// Builds a [`Call`] to a function of the contract.
+    pub fn build_call(
+        &self,
+        function_name: &str,
+        calldata: Vec<Felt>,
+    ) -> Result<Call, NonAsciiNameError> {
+        Ok(Call {
+            to: self.address,
+            selector: get_selector_from_name(function_name)?,
+            calldata,
+        })
+    }
+}
+
+impl<A> Contract<A>
+where
+    A: Account,
+{
+    /// Generates an instance of [`ExecutionV3`] for invoking a function of the contract.
+    pub fn invoke(
+        &self,
+        function_name: &str,
+        calldata: Vec<Felt>,
+    ) -> Result<ExecutionV3<'_, A>, NonAsciiNameError> {
+        Ok(self
+            .account
+            .execute_v3(vec![self.build_call(function_name, calldata)?]))
+    }
+}
+
+impl<A> Contract<A>
+where
+    A: ConnectedAccount,
+{
+    /// Gets a [`ContractReader`] for the same contract using the account's provider and block ID.
+    pub fn reader(&self) -> ContractReader<&A::Provider> {
+        ContractReader::new(self.address, self.account.provider())
+            .with_block_id(self.account.block_id())
+    }
+
+    /// Calls a function of the contract without sending a transaction, returning the raw result.
+    pub async fn call(
+        &self,
+        function_name: &str,
+        calldata: Vec<Felt>,
+    ) -> Result<Vec<Felt>, CallError> {
+        self.reader().call(function_name, calldata).await
+    }
+
+    /// Checks whether the contract supports an interface using SNIP-5 interface detection. IDs of
+    /// common interfaces can be found in [`interface_id`](starknet_core::interface_id).
+    pub async fn supports_interface(&self, interface_id: Felt) -> Result<bool, CallError> {
+        self.reader().supports_interface(interface_id).await
+    }
+
+    /// Creates an [`EventPaginator`] for events emitted by the contract. The `address` field of
+    /// the filter is overridden with the contract address.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn events(&self, filter: EventFilter) -> EventPaginator<&A::Provider> {
+        EventPaginator::new(
+            self.account.provider(),
+            EventFilter {
+                address: Some(self.address),
+                ..filter
+            },
+        )
+    }
+}
+
+impl<P> ContractReader<P> {
+    /// Constructs a new [`ContractReader`] from the contract address and a provider. Calls are made
+    /// against the latest block by default.
+    pub const fn new(address: Felt, provider: P) -> Self {
+        Self {
+            address,
+            provider,
+            block_id: BlockId::Tag(BlockTag::Latest),
+        }
+    }
+
+    /// Sets the block to make calls against.
+    pub fn with_block_id(self, block_id: BlockId) -> Self {
+        Self { block_id, ..self }
+    }
+
+    /// Gets the contract address.
+    pub const fn address(&self) -> Felt {
+        self.address
+    }
+
+    /// Gets a reference to the provider used for queries.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Gets the block calls are made against.
+    pub const fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+
+    /// Creates an [`EventPaginator`] for events emitted by the contract. The `address` field of
+    /// the filter is overridden with the contract address.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn events(&self, filter: EventFilter) -> EventPaginator<&P> {
+        EventPaginator::new(
+            &self.provider,
+            EventFilter {
+                address: Some(self.address),
+                ..filter
+            },
+        )
+    }
+}
+
+impl<P> ContractReader<P>
+where
+    P: Provider,
+{
+    /// Calls a function of the contract without sending a transaction, returning the raw result.
+    pub async fn call(
+        &self,
+        function_name: &str,
+        calldata: Vec<Felt>,
+    ) -> Result<Vec<Felt>, CallError> {
+        self.provider
+            .call(
+                FunctionCall {
+                    contract_address: self.address,
+                    entry_point_selector: get_selector_from_name(function_name)
+                        .map_err(CallError::InvalidFunctionName)?,
+                    calldata,
+                },
+                self.block_id,
+            )
+            .await
+            .map_err(CallError::Provider)
+    }
+
+    /// Checks whether the contract supports an interface using SNIP-5 interface detection. IDs of
+    /// common interfaces can be found in [`interface_id`](starknet_core::interface_id).
+    ///
+    /// Contracts only implementing the legacy `supportsInterface` function are also supported.
+    /// Contracts implementing neither are reported as not supporting any interface.
+    pub async fn supports_interface(&self, interface_id: Felt) -> Result<bool, CallError> {
+        match self
+            .call_selector(
+                SELECTOR_SUPPORTS_INTERFACE,
+                Some(SELECTOR_SUPPORTSINTERFACE),
+                vec![interface_id],
+            )
+            .await
+        {
+            Ok(output) => bool::decode(&output).map_err(CallError::Decode),
+            Err(CallError::Provider(err))
+                if self.is_entrypoint_missing(&err, SELECTOR_SUPPORTSINTERFACE) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Calls the function with `selector`, retrying with `legacy_selector` if the entrypoint
+    /// cannot be found.
+    pub(crate) async fn call_selector(
+        &self,
+        selector: Felt,
+        legacy_selector: Option<Felt>,
+        calldata: Vec<Felt>,
+    ) -> Result<Vec<Felt>, CallError> {
+        let result = self
+            .provider
+            .call(
+                FunctionCall {
+                    contract_address: self.address,
+                    entry_point_selector: selector,
+                    calldata: calldata.clone(),
+                },
+                self.block_id,
+            )
+            .await;
+
+        match (result, legacy_selector) {
+            (Err(err), Some(legacy_selector)) if self.is_entrypoint_missing(&err, selector) => self
+                .provider
+                .call(
+                    FunctionCall {
+                        contract_address: self.address,
+                        entry_point_selector: legacy_selector,
+                        calldata,
+                    },
+                    self.block_id,
+                )
+                .await
+                .map_err(CallError::Provider),
+            (result, _) => result.map_err(CallError::Provider),
+        }
+    }
+
+    /// Whether calling `selector` failed because the contract has no such entrypoint, as opposed
+    /// to the function itself reverting.
+    fn is_entrypoint_missing(&self, err: &ProviderError, selector: Felt) -> bool {
+        match err {
+            ProviderError::StarknetError(StarknetError::EntrypointNotFound) => true,
+            // Calling a missing entrypoint of a Cairo 1 contract results in a contract error
+            // instead of `EntrypointNotFound`. Only the frame of the call itself is checked, so
+            // that missing entrypoints in nested calls are still reported as reverts.
+            ProviderError::StarknetError(StarknetError::ContractError(data)) => {
+                let error = match &data.revert_error {
+                    ContractExecutionError::Nested(frame)
+                        if frame.contract_address == self.address && frame.selector == selector =>
+                    {
+                        &*frame.error
+                    }
+                    error => error,
+                };
+
+                matches!(
+                    error,
+                    ContractExecutionError::Message(message)
+                        if message.contains("ENTRYPOINT_NOT_FOUND")
+                            || message.contains("not found in contract")
+                )
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use serde::{de::DeserializeOwned, Serialize};
+    use starknet_accounts::{ExecutionEncoding, SingleOwnerAccount};
+    use starknet_core::{chain_id, types::requests::CallRequest};
+    use starknet_providers::{
+        jsonrpc::{JsonRpcClient, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport},
+        ProviderRequestData, SequencerGatewayProvider,
+    };
+    use starknet_signers::{LocalWallet, SigningKey};
+
+    use super::*;
+
+    /// Implements `supports_interface` and returns `true`.
+    const SRC5_ADDRESS: u64 = 1;
+    /// Only implements the legacy `supportsInterface` and returns `true`.
+    const LEGACY_ADDRESS: u64 = 2;
+    /// A Cairo 1 contract implementing neither.
+    const NO_SRC5_ADDRESS: u64 = 3;
+    /// A Cairo 0 contract implementing neither.
+    const NO_SRC5_LEGACY_ADDRESS: u64 = 4;
+    /// Reverts in `supports_interface` but returns `true` from `supportsInterface`.
+    const REVERTING_ADDRESS: u64 = 5;
+    /// Calls a missing entrypoint of another contract in `supports_interface`, but returns `true`
+    /// from `supportsInterface`.
+    const NESTED_MISSING_ADDRESS: u64 = 6;
+
+    /// A transport answering `starknet_call` requests for the contracts above.
+    #[derive(Debug)]
+    struct MockTransport;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("unsupported method")]
+    struct MockError;
+
+    fn contract_error(revert_error: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "code": 40,
+            "message": "Contract error",
+            "data": { "revert_error": revert_error },
+        })
+    }
+
+    fn frame(
+        contract_address: Felt,
+        selector: Felt,
+        error: serde_json::Value,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "contract_address": format!("{contract_address:#x}"),
+            "class_hash": "0x1234",
+            "selector": format!("{selector:#x}"),
+            "error": error,
+        })
+    }
+
+    impl MockTransport {
+        fn execute(call: &FunctionCall) -> serde_json::Value {
+            let address = call.contract_address;
+            let selector = call.entry_point_selector;
+            let legacy = selector == SELECTOR_SUPPORTSINTERFACE;
+
+            let error = match u64::try_from(address).unwrap() {
+                SRC5_ADDRESS if !legacy => None,
+                LEGACY_ADDRESS | REVERTING_ADDRESS | NESTED_MISSING_ADDRESS if legacy => None,
+                NO_SRC5_LEGACY_ADDRESS => Some(serde_json::json!({
+                    "code": 21,
+                    "message": "Requested entrypoint does not exist in the contract",
+                })),
+                REVERTING_ADDRESS => Some(contract_error(frame(
+                    address,
+                    selector,
+                    "0x6f6f7073 ('oops')".into(),
+                ))),
+                NESTED_MISSING_ADDRESS => Some(contract_error(frame(
+                    address,
+                    selector,
+                    frame(
+                        Felt::from(0x99),
+                        selector,
+                        "0x454e545259504f494e545f4e4f545f464f554e44 ('ENTRYPOINT_NOT_FOUND')"
+                            .into(),
+                    ),
+                ))),
+                _ => Some(contract_error(frame(
+                    address,
+                    selector,
+                    format!("Entry point EntryPointSelector({selector:#x}) not found in contract.")
+                        .into(),
+                ))),
+            };
+
+            match error {
+                Some(error) => serde_json::json!({ "id": 1, "error": error }),
+                None => serde_json::json!({ "id": 1, "result": ["0x1"] }),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcTransport for MockTransport {
+        type Error = MockError;
+
+        async fn send_request<P, R>(
+            &self,
+            method: JsonRpcMethod,
+            params: P,
+        ) -> Result<JsonRpcResponse<R>, Self::Error>
+        where
+            P: Serialize + Send + Sync,
+            R: DeserializeOwned + Send,
+        {
+            if !matches!(method, JsonRpcMethod::Call) {
+                return Err(MockError);
+            }
+            let request: CallRequest =
+                serde_json::from_value(serde_json::to_value(params).unwrap()).unwrap();
+
+            Ok(serde_json::from_value(Self::execute(&request.request)).unwrap())
+        }
+
+        async fn send_requests<R>(
+            &self,
+            _requests: R,
+        ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+        where
+            R: AsRef<[ProviderRequestData]> + Send + Sync,
+        {
+            Err(MockError)
+        }
+    }
+
+    #[allow(deprecated)]
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_build_call() {
+        let contract = Contract::new(
+            Felt::from_hex("0x1234").unwrap(),
+            SingleOwnerAccount::new(
+                SequencerGatewayProvider::starknet_alpha_sepolia(),
+                LocalWallet::from_signing_key(SigningKey::from_random()),
+                Felt::from_hex("0x5678").unwrap(),
+                chain_id::SEPOLIA,
+                ExecutionEncoding::New,
+            ),
+        );
+
+        let call = contract
+            .build_call("transfer", vec![Felt::ONE, Felt::TWO])
+            .unwrap();
+
+        assert_eq!(call.to, Felt::from_hex("0x1234").unwrap());
+        assert_eq!(
+            call.selector,
+            Felt::from_hex("0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e")
+                .unwrap()
+        );
+        assert_eq!(call.calldata, vec![Felt::ONE, Felt::TWO]);
+
+        assert!(contract.build_call("transfér", vec![]).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_supports_interface_selectors() {
+        assert_eq!(
+            SELECTOR_SUPPORTS_INTERFACE,
+            get_selector_from_name("supports_interface").unwrap()
+        );
+        assert_eq!(
+            SELECTOR_SUPPORTSINTERFACE,
+            get_selector_from_name("supportsInterface").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_supports_interface() {
+        let supports_interface = |address| async move {
+            ContractReader::new(Felt::from(address), JsonRpcClient::new(MockTransport))
+                .supports_interface(Felt::ONE)
+                .await
+        };
+
+        assert!(supports_interface(SRC5_ADDRESS).await.unwrap());
+        assert!(supports_interface(LEGACY_ADDRESS).await.unwrap());
+        assert!(!supports_interface(NO_SRC5_ADDRESS).await.unwrap());
+        assert!(!supports_interface(NO_SRC5_LEGACY_ADDRESS).await.unwrap());
+
+        // Reverts are not mistaken for missing entrypoints
+        assert!(matches!(
+            supports_interface(REVERTING_ADDRESS).await,
+            Err(CallError::Provider(ProviderError::StarknetError(
+                StarknetError::ContractError(_)
+            )))
+        ));
+        assert!(matches!(
+            supports_interface(NESTED_MISSING_ADDRESS).await,
+            Err(CallError::Provider(ProviderError::StarknetError(
+                StarknetError::ContractError(_)
+            )))
+        ));
+    }
+}
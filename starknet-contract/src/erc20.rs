@@ -0,0 +1,351 @@
+use starknet_core::{
+    codec::{Decode, Error as CodecError},
+    types::{BlockId, Call, Felt, U256},
+    utils::parse_cairo_short_string,
+};
+use starknet_providers::Provider;
+
+use crate::{CallError, ContractReader};
+
+/// Selector for entrypoint `balance_of`.
+const SELECTOR_BALANCE_OF: Felt = Felt::from_raw([
+    343731218995559270,
+    5264068538989673709,
+    11305066377149084298,
+    3187814280469066768,
+]);
+
+/// Selector for entrypoint `balanceOf`.
+const SELECTOR_BALANCEOF: Felt = Felt::from_raw([
+    186492163330788704,
+    9799122768618501063,
+    3817639149632004388,
+    8914400797191611589,
+]);
+
+/// Selector for entrypoint `total_supply`.
+const SELECTOR_TOTAL_SUPPLY: Felt = Felt::from_raw([
+    208591859614417130,
+    2722038587670665178,
+    14634243038931256332,
+    12095397922800688737,
+]);
+
+/// Selector for entrypoint `totalSupply`.
+const SELECTOR_TOTALSUPPLY: Felt = Felt::from_raw([
+    69716027446197474,
+    7202072203292561311,
+    16591666299386464833,
+    18112103592448476716,
+]);
+
+/// Selector for entrypoint `allowance`.
+const SELECTOR_ALLOWANCE: Felt = Felt::from_raw([
+    209766809471042785,
+    12509652079980974336,
+    17678706014116587206,
+    10308380584186584568,
+]);
+
+/// Selector for entrypoint `decimals`.
+const SELECTOR_DECIMALS: Felt = Felt::from_raw([
+    451190754876481978,
+    755531479336054762,
+    12219577301920418346,
+    15360137715940544477,
+]);
+
+/// Selector for entrypoint `name`.
+const SELECTOR_NAME: Felt = Felt::from_raw([
+    204437639094763333,
+    16059280649635539212,
+    2380157814635835479,
+    4539611826636167848,
+]);
+
+/// Selector for entrypoint `symbol`.
+const SELECTOR_SYMBOL: Felt = Felt::from_raw([
+    179664498801601103,
+    11796451914517155703,
+    17130963829830369960,
+    9178143007560336762,
+]);
+
+/// Selector for entrypoint `transfer`.
+const SELECTOR_TRANSFER: Felt = Felt::from_raw([
+    437381113334062809,
+    2507318034922653180,
+    16370534037708042650,
+    5927927059297104468,
+]);
+
+/// Selector for entrypoint `transfer_from`.
+const SELECTOR_TRANSFER_FROM: Felt = Felt::from_raw([
+    464342347793828655,
+    13262269167218251377,
+    9348103406587822923,
+    425915925847708578,
+]);
+
+/// Selector for entrypoint `transferFrom`.
+const SELECTOR_TRANSFERFROM: Felt = Felt::from_raw([
+    427849668778881624,
+    11765991293598804043,
+    3869861725982937294,
+    12809927063331595464,
+]);
+
+/// Selector for entrypoint `approve`.
+const SELECTOR_APPROVE: Felt = Felt::from_raw([
+    140600710993877394,
+    13695063774359579960,
+    7107368782042727669,
+    12534173288940467319,
+]);
+
+/// Selector for entrypoint `mint`.
+const SELECTOR_MINT: Felt = Felt::from_raw([
+    325361499047323408,
+    1807990883272191924,
+    6799885222290033850,
+    14659918614400108700,
+]);
+
+/// Typed helpers for a deployed ERC-20 token implementing the SNIP-2 interface.
+///
+/// View functions are looked up by their `snake_case` names first, falling back to the legacy
+/// `camelCase` names for tokens that only implement those. Token names and symbols are decoded
+/// from both legacy short strings and `ByteArray`s.
+///
+/// Functions that change state return a [`Call`] instead of sending a transaction, which can then
+/// be executed with an account, possibly along with other calls. These use the `snake_case` names,
+/// except for [`transfer_from_legacy`](Self::transfer_from_legacy), as `transferFrom` is the only
+/// state-changing function whose legacy name differs.
+#[derive(Debug, Clone)]
+pub struct Erc20<P> {
+    reader: ContractReader<P>,
+}
+
+impl<P> Erc20<P> {
+    /// Constructs a new [`Erc20`] from the token address and a provider. Calls are made against the
+    /// latest block by default.
+    pub const fn new(address: Felt, provider: P) -> Self {
+        Self {
+            reader: ContractReader::new(address, provider),
+        }
+    }
+
+    /// Sets the block to make calls against.
+    pub fn with_block_id(self, block_id: BlockId) -> Self {
+        Self {
+            reader: self.reader.with_block_id(block_id),
+        }
+    }
+
+    /// Gets the token address.
+    pub const fn address(&self) -> Felt {
+        self.reader.address()
+    }
+
+    /// Gets a reference to the provider used for queries.
+    pub const fn provider(&self) -> &P {
+        self.reader.provider()
+    }
+
+    /// Gets the block calls are made against.
+    pub const fn block_id(&self) -> BlockId {
+        self.reader.block_id()
+    }
+
+    /// Builds a [`Call`] transferring `amount` tokens from the caller to `recipient`.
+    pub fn transfer(&self, recipient: Felt, amount: U256) -> Call {
+        self.build_call(SELECTOR_TRANSFER, &[recipient], amount)
+    }
+
+    /// Builds a [`Call`] transferring `amount` tokens from `sender` to `recipient` using the
+    /// allowance of the caller.
+    pub fn transfer_from(&self, sender: Felt, recipient: Felt, amount: U256) -> Call {
+        self.build_call(SELECTOR_TRANSFER_FROM, &[sender, recipient], amount)
+    }
+
+    /// Same as [`transfer_from`](Self::transfer_from), but calling the legacy `transferFrom`
+    /// entrypoint for tokens that don't implement `transfer_from`.
+    pub fn transfer_from_legacy(&self, sender: Felt, recipient: Felt, amount: U256) -> Call {
+        self.build_call(SELECTOR_TRANSFERFROM, &[sender, recipient], amount)
+    }
+
+    /// Builds a [`Call`] allowing `spender` to spend up to `amount` tokens of the caller.
+    pub fn approve(&self, spender: Felt, amount: U256) -> Call {
+        self.build_call(SELECTOR_APPROVE, &[spender], amount)
+    }
+
+    /// Builds a [`Call`] minting `amount` tokens to `recipient`.
+    ///
+    /// `mint` is not part of SNIP-2 and only works with tokens exposing it as
+    /// `mint(recipient, amount)`, such as test tokens.
+    pub fn mint(&self, recipient: Felt, amount: U256) -> Call {
+        self.build_call(SELECTOR_MINT, &[recipient], amount)
+    }
+
+    fn build_call(&self, selector: Felt, addresses: &[Felt], amount: U256) -> Call {
+        let mut calldata = addresses.to_vec();
+        calldata.push(amount.low().into());
+        calldata.push(amount.high().into());
+
+        Call {
+            to: self.address(),
+            selector,
+            calldata,
+        }
+    }
+}
+
+impl<P> Erc20<P>
+where
+    P: Provider,
+{
+    /// Gets the token balance of `account`.
+    pub async fn balance_of(&self, account: Felt) -> Result<U256, CallError> {
+        let output = self
+            .reader
+            .call_selector(SELECTOR_BALANCE_OF, Some(SELECTOR_BALANCEOF), vec![account])
+            .await?;
+        U256::decode(&output).map_err(CallError::Decode)
+    }
+
+    /// Gets the amount of tokens `spender` is allowed to spend on behalf of `owner`.
+    pub async fn allowance(&self, owner: Felt, spender: Felt) -> Result<U256, CallError> {
+        let output = self
+            .reader
+            .call_selector(SELECTOR_ALLOWANCE, None, vec![owner, spender])
+            .await?;
+        U256::decode(&output).map_err(CallError::Decode)
+    }
+
+    /// Gets the total supply of the token.
+    pub async fn total_supply(&self) -> Result<U256, CallError> {
+        let output = self
+            .reader
+            .call_selector(SELECTOR_TOTAL_SUPPLY, Some(SELECTOR_TOTALSUPPLY), vec![])
+            .await?;
+        U256::decode(&output).map_err(CallError::Decode)
+    }
+
+    /// Gets the number of decimals used to display token amounts.
+    pub async fn decimals(&self) -> Result<u8, CallError> {
+        let output = self
+            .reader
+            .call_selector(SELECTOR_DECIMALS, None, vec![])
+            .await?;
+        u8::decode(&output).map_err(CallError::Decode)
+    }
+
+    /// Gets the name of the token.
+    pub async fn name(&self) -> Result<String, CallError> {
+        let output = self
+            .reader
+            .call_selector(SELECTOR_NAME, None, vec![])
+            .await?;
+        decode_string(&output).map_err(CallError::Decode)
+    }
+
+    /// Gets the symbol of the token.
+    pub async fn symbol(&self) -> Result<String, CallError> {
+        let output = self
+            .reader
+            .call_selector(SELECTOR_SYMBOL, None, vec![])
+            .await?;
+        decode_string(&output).map_err(CallError::Decode)
+    }
+}
+
+/// Decodes a string returned as a single short string by legacy tokens, or as a `ByteArray`.
+fn decode_string(output: &[Felt]) -> Result<String, CodecError> {
+    match output {
+        [short_string] => parse_cairo_short_string(short_string).map_err(CodecError::custom),
+        _ => String::decode(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_core::{
+        codec::Encode,
+        types::ByteArray,
+        utils::{cairo_short_string_to_felt, get_selector_from_name},
+    };
+    use starknet_providers::SequencerGatewayProvider;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_erc20_selectors() {
+        for (selector, name) in [
+            (SELECTOR_BALANCE_OF, "balance_of"),
+            (SELECTOR_BALANCEOF, "balanceOf"),
+            (SELECTOR_TOTAL_SUPPLY, "total_supply"),
+            (SELECTOR_TOTALSUPPLY, "totalSupply"),
+            (SELECTOR_ALLOWANCE, "allowance"),
+            (SELECTOR_DECIMALS, "decimals"),
+            (SELECTOR_NAME, "name"),
+            (SELECTOR_SYMBOL, "symbol"),
+            (SELECTOR_TRANSFER, "transfer"),
+            (SELECTOR_TRANSFER_FROM, "transfer_from"),
+            (SELECTOR_TRANSFERFROM, "transferFrom"),
+            (SELECTOR_APPROVE, "approve"),
+            (SELECTOR_MINT, "mint"),
+        ] {
+            assert_eq!(selector, get_selector_from_name(name).unwrap());
+        }
+    }
+
+    #[allow(deprecated)]
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_erc20_calls() {
+        let token = Erc20::new(
+            Felt::from_hex("0x1234").unwrap(),
+            SequencerGatewayProvider::starknet_alpha_sepolia(),
+        );
+
+        let call = token.transfer(Felt::TWO, U256::from_words(3, 4));
+        assert_eq!(call.to, Felt::from_hex("0x1234").unwrap());
+        assert_eq!(call.selector, SELECTOR_TRANSFER);
+        assert_eq!(call.calldata, vec![Felt::TWO, Felt::THREE, Felt::from(4u8)]);
+
+        let call = token.transfer_from(Felt::ONE, Felt::TWO, U256::from(5u8));
+        assert_eq!(call.selector, SELECTOR_TRANSFER_FROM);
+        assert_eq!(
+            call.calldata,
+            vec![Felt::ONE, Felt::TWO, Felt::from(5u8), Felt::ZERO]
+        );
+
+        let call = token.transfer_from_legacy(Felt::ONE, Felt::TWO, U256::from(5u8));
+        assert_eq!(call.selector, SELECTOR_TRANSFERFROM);
+        assert_eq!(
+            call.calldata,
+            vec![Felt::ONE, Felt::TWO, Felt::from(5u8), Felt::ZERO]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_erc20_decode_string() {
+        assert_eq!(
+            decode_string(&[cairo_short_string_to_felt("ETH").unwrap()]).unwrap(),
+            "ETH"
+        );
+
+        let mut output = vec![];
+        ByteArray::from("Starknet Token with a long name")
+            .encode(&mut output)
+            .unwrap();
+        assert_eq!(
+            decode_string(&output).unwrap(),
+            "Starknet Token with a long name"
+        );
+
+        assert!(decode_string(&[]).is_err());
+    }
+}
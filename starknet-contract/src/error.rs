@@ -0,0 +1,31 @@
+use starknet_core::{codec::Error as CodecError, utils::NonAsciiNameError};
+use starknet_providers::ProviderError;
+
+/// Errors calling a contract function without sending a transaction.
+#[derive(Debug, thiserror::Error)]
+pub enum CallError {
+    /// An error is encountered with communicating with the network.
+    #[error(transparent)]
+    Provider(ProviderError),
+    /// The call result cannot be decoded into the expected type.
+    #[error("unable to decode call result: {0}")]
+    Decode(CodecError),
+    /// The function name cannot be turned into an entrypoint selector.
+    #[error("invalid function name: {0}")]
+    InvalidFunctionName(NonAsciiNameError),
+}
+
+/// Errors aggregating read calls with [`Multicall`](crate::Multicall). Failures of individual
+/// calls are not reported with this type.
+#[derive(Debug, thiserror::Error)]
+pub enum MulticallError {
+    /// An error is encountered with communicating with the network.
+    #[error(transparent)]
+    Provider(ProviderError),
+    /// The result of the multicall contract cannot be decoded.
+    #[error("unable to decode multicall result: {0}")]
+    Decode(CodecError),
+    /// The number or type of results does not match the calls sent.
+    #[error("unexpected multicall response")]
+    UnexpectedResponse,
+}
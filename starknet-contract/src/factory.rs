@@ -4,6 +4,8 @@ use starknet_core::{
     utils::{get_udc_deployed_address, UdcUniqueSettings, UdcUniqueness},
 };
 
+use crate::Contract;
+
 /// The Cairo 0 UDC address: `0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf`.
 const LEGACY_UDC_ADDRESS: Felt = Felt::from_raw([
     121672436446604875,
@@ -60,13 +62,14 @@ pub struct DeploymentV3<'f, A> {
 }
 
 /// Specifies the Universal Deployer Contract to be used.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum UdcSelector {
     /// The original, now deprecated, deployer contract deployed at
     /// `0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf`.
     Legacy,
     /// The latest deployer contract deployed at
     /// `0x02ceed65a4bd731034c01113685c831b01c15d7d432f71afb1cf1634b53a2125`.
+    #[default]
     New,
     /// Custom compatible deployer contract instance.
     Custom(Felt),
@@ -243,7 +246,7 @@ where
     }
 }
 
-impl<A> DeploymentV3<'_, A>
+impl<'f, A> DeploymentV3<'f, A>
 where
     A: ConnectedAccount + Sync,
 {
@@ -269,6 +272,19 @@ where
         let execution: ExecutionV3<'_, A> = self.into();
         execution.send().await
     }
+
+    /// Signs and broadcasts the transaction to the network, returning a [`Contract`] handle for the
+    /// deployed contract along with the transaction result. The contract address is calculated
+    /// with [`deployed_address`](Self::deployed_address).
+    pub async fn send_with_contract(
+        &self,
+    ) -> Result<(InvokeTransactionResult, Contract<&'f A>), AccountError<A::SignError>> {
+        let result = self.send().await?;
+        Ok((
+            result,
+            Contract::new(self.deployed_address(), &self.factory.account),
+        ))
+    }
 }
 
 impl<'f, A> From<&DeploymentV3<'f, A>> for ExecutionV3<'f, A> {
@@ -361,12 +377,6 @@ impl From<UdcSelector> for Felt {
     }
 }
 
-impl Default for UdcSelector {
-    fn default() -> Self {
-        Self::New
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use starknet_accounts::{ExecutionEncoding, SingleOwnerAccount};
@@ -1,12 +1,25 @@
 //! Library for deploying and interacting with Starknet contracts.
 //!
-//! Currently, this crate only provides a single type [`ContractFactory`] for deploying contracts
-//! using the Universal Deployer Contract.
-//!
-//! In the future, features like ABI-based contract binding generation will be added to allow type-
-//! safe interaction with Starknet smart contracts.
+//! This crate provides [`ContractFactory`] for deploying contracts using the Universal Deployer
+//! Contract, and [`Contract`] and [`ContractReader`] for interacting with deployed contracts by
+//! function name, [`Erc20`] for interacting with standard fungible tokens, and [`Multicall`] for
+//! aggregating many read calls into few requests. Type-safe contract bindings can be generated
+//! from ABIs with the `abigen!` macro from `starknet-macros`, which report failed calls with
+//! [`CallError`].
 
 #![deny(missing_docs)]
 
 mod factory;
 pub use factory::{ContractFactory, DeploymentV3, UdcSelector};
+
+mod contract;
+pub use contract::{Contract, ContractReader};
+
+mod erc20;
+pub use erc20::Erc20;
+
+mod multicall;
+pub use multicall::Multicall;
+
+mod error;
+pub use error::{CallError, MulticallError};
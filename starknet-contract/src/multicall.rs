@@ -0,0 +1,499 @@
+use std::ops::Range;
+
+use starknet_core::{
+    codec::Decode,
+    types::{requests::CallRequest, BlockId, BlockTag, Felt, FunctionCall, StarknetError},
+};
+use starknet_providers::{Provider, ProviderError, ProviderRequestData, ProviderResponseData};
+
+use crate::{CallError, MulticallError};
+
+/// Selector for entrypoint `aggregate`.
+const SELECTOR_AGGREGATE: Felt = Felt::from_raw([
+    111923219413731522,
+    10170527792622061643,
+    2869016847263578790,
+    10143872062486929310,
+]);
+
+/// The default maximum number of calls sent in a single request.
+const DEFAULT_CHUNK_SIZE: usize = 100;
+
+/// Aggregates many read calls into as few requests as possible.
+///
+/// By default, calls are sent as JSON-RPC batches with [`Provider::batch_requests`]. When a
+/// multicall contract is set with [`with_contract`](Self::with_contract), calls are instead
+/// executed through a single `call` to its `aggregate` function, which must have the signature
+/// `aggregate(calls: Array<Call>) -> (u64, Array<Span<felt252>>)`.
+///
+/// Only a failing call itself (i.e. [`StarknetError::ContractError`],
+/// [`StarknetError::ContractNotFound`] or [`StarknetError::EntrypointNotFound`]) is reported as the
+/// result of that call. With JSON-RPC batches, these errors are read from the response to each
+/// call. A call to the multicall contract instead reverts as a whole with
+/// [`StarknetError::ContractError`] when any call fails, in which case the calls are split and
+/// retried until the failing calls are isolated, so that a failure of one call does not affect the
+/// results of the others. Any other error from the multicall contract, such as it not being
+/// deployed, is returned as [`MulticallError`].
+#[derive(Debug, Clone)]
+pub struct Multicall<P> {
+    provider: P,
+    contract_address: Option<Felt>,
+    block_id: BlockId,
+    chunk_size: usize,
+}
+
+impl<P> Multicall<P> {
+    /// Constructs a new [`Multicall`] sending JSON-RPC batches. Calls are made against the latest
+    /// block by default.
+    pub const fn new(provider: P) -> Self {
+        Self {
+            provider,
+            contract_address: None,
+            block_id: BlockId::Tag(BlockTag::Latest),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Sends calls through the multicall contract deployed at `contract_address` instead of
+    /// JSON-RPC batches.
+    pub fn with_contract(self, contract_address: Felt) -> Self {
+        Self {
+            contract_address: Some(contract_address),
+            ..self
+        }
+    }
+
+    /// Sets the block to make calls against.
+    pub fn with_block_id(self, block_id: BlockId) -> Self {
+        Self { block_id, ..self }
+    }
+
+    /// Sets the maximum number of calls sent in a single request. Defaults to `100`. A value of `0`
+    /// is treated as `1`.
+    pub fn with_chunk_size(self, chunk_size: usize) -> Self {
+        Self {
+            chunk_size: chunk_size.max(1),
+            ..self
+        }
+    }
+
+    /// Gets a reference to the provider used for sending calls.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+}
+
+impl<P> Multicall<P>
+where
+    P: Provider + Sync,
+{
+    /// Sends all calls, returning the raw result or the error of each call in the same order as
+    /// `calls`. An `Err` is returned for any failure other than a failing call, such as network
+    /// errors or a missing block.
+    pub async fn call(
+        &self,
+        calls: &[FunctionCall],
+    ) -> Result<Vec<Result<Vec<Felt>, StarknetError>>, MulticallError> {
+        let Some(contract_address) = self.contract_address else {
+            let mut results = Vec::with_capacity(calls.len());
+            for chunk in calls.chunks(self.chunk_size) {
+                results.extend(self.send_batch(chunk).await?);
+            }
+            return Ok(results);
+        };
+
+        let mut results = vec![None; calls.len()];
+
+        let mut pending: Vec<Range<usize>> = (0..calls.len())
+            .step_by(self.chunk_size)
+            .map(|start| start..(start + self.chunk_size).min(calls.len()))
+            .rev()
+            .collect();
+
+        while let Some(range) = pending.pop() {
+            match self
+                .send_aggregate(contract_address, &calls[range.clone()])
+                .await
+            {
+                Ok(outputs) => {
+                    for (result, output) in results[range].iter_mut().zip(outputs) {
+                        *result = Some(Ok(output));
+                    }
+                }
+                // Failures of aggregated calls revert the `aggregate` call itself. Other errors
+                // concern the multicall contract, and would be the same for any subset of calls.
+                Err(MulticallError::Provider(ProviderError::StarknetError(
+                    err @ StarknetError::ContractError(_),
+                ))) => {
+                    if range.len() == 1 {
+                        results[range.start] = Some(Err(err));
+                    } else {
+                        let middle = range.start + range.len() / 2;
+                        pending.push(middle..range.end);
+                        pending.push(range.start..middle);
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        // All ranges have been resolved at this point.
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Sends all calls and decodes the result of each call into `T`, returning the decoded value
+    /// or the error of each call in the same order as `calls`.
+    pub async fn call_decoded<T>(
+        &self,
+        calls: &[FunctionCall],
+    ) -> Result<Vec<Result<T, CallError>>, MulticallError>
+    where
+        T: for<'a> Decode<'a>,
+    {
+        Ok(self
+            .call(calls)
+            .await?
+            .into_iter()
+            .map(|result| match result {
+                Ok(output) => T::decode(&output).map_err(CallError::Decode),
+                Err(err) => Err(CallError::Provider(ProviderError::StarknetError(err))),
+            })
+            .collect())
+    }
+
+    async fn send_aggregate(
+        &self,
+        contract_address: Felt,
+        calls: &[FunctionCall],
+    ) -> Result<Vec<Vec<Felt>>, MulticallError> {
+        let mut calldata = vec![Felt::from(calls.len())];
+        for call in calls {
+            calldata.push(call.contract_address);
+            calldata.push(call.entry_point_selector);
+            calldata.push(Felt::from(call.calldata.len()));
+            calldata.extend_from_slice(&call.calldata);
+        }
+
+        let output = self
+            .provider
+            .call(
+                FunctionCall {
+                    contract_address,
+                    entry_point_selector: SELECTOR_AGGREGATE,
+                    calldata,
+                },
+                self.block_id,
+            )
+            .await
+            .map_err(MulticallError::Provider)?;
+
+        let (_block_number, outputs) =
+            <(u64, Vec<Vec<Felt>>)>::decode(&output).map_err(MulticallError::Decode)?;
+        if outputs.len() != calls.len() {
+            return Err(MulticallError::UnexpectedResponse);
+        }
+
+        Ok(outputs)
+    }
+
+    async fn send_batch(
+        &self,
+        calls: &[FunctionCall],
+    ) -> Result<Vec<Result<Vec<Felt>, StarknetError>>, MulticallError> {
+        let requests = calls
+            .iter()
+            .map(|call| {
+                ProviderRequestData::Call(CallRequest {
+                    request: call.clone(),
+                    block_id: self.block_id,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let responses = self
+            .provider
+            .try_batch_requests(&requests)
+            .await
+            .map_err(MulticallError::Provider)?;
+        if responses.len() != calls.len() {
+            return Err(MulticallError::UnexpectedResponse);
+        }
+
+        responses
+            .into_iter()
+            .map(|response| match response {
+                Ok(ProviderResponseData::Call(output)) => Ok(Ok(output)),
+                Ok(_) => Err(MulticallError::UnexpectedResponse),
+                Err(ProviderError::StarknetError(err)) if is_call_failure(&err) => Ok(Err(err)),
+                Err(err) => Err(MulticallError::Provider(err)),
+            })
+            .collect()
+    }
+}
+
+/// Whether the error is caused by the call itself, as opposed to e.g. the block being missing,
+/// which affects all calls alike.
+const fn is_call_failure(err: &StarknetError) -> bool {
+    matches!(
+        err,
+        StarknetError::ContractError(_)
+            | StarknetError::ContractNotFound
+            | StarknetError::EntrypointNotFound
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use async_trait::async_trait;
+    use serde::{de::DeserializeOwned, Serialize};
+    use starknet_core::utils::get_selector_from_name;
+    use starknet_providers::jsonrpc::{
+        JsonRpcClient, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport,
+    };
+
+    use super::*;
+
+    const FAILING_ADDRESS: Felt = Felt::from_hex_unchecked("0xdead");
+    const MISSING_BLOCK_ADDRESS: Felt = Felt::from_hex_unchecked("0xb10c");
+    const MULTICALL_ADDRESS: Felt = Felt::from_hex_unchecked("0x1234");
+
+    /// A transport executing calls that echo back their calldata, except for calls to
+    /// `FAILING_ADDRESS`, which fail with `ContractNotFound`, and calls to `MISSING_BLOCK_ADDRESS`,
+    /// which fail with `BlockNotFound`. Calls to the multicall contract revert as a whole if any of
+    /// the aggregated calls fail.
+    #[derive(Debug, Default)]
+    struct MockTransport {
+        requests: AtomicU32,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("unsupported method")]
+    struct MockError;
+
+    impl MockTransport {
+        fn execute(call: &FunctionCall) -> serde_json::Value {
+            if call.contract_address == MULTICALL_ADDRESS {
+                let mut calldata = call.calldata.iter();
+                let count = calldata.next().unwrap();
+
+                let mut output = vec![Felt::from(100u32), *count];
+                for _ in 0..count.to_biguint().try_into().unwrap() {
+                    let to = *calldata.next().unwrap();
+                    let _selector = calldata.next().unwrap();
+                    let len = calldata.next().unwrap();
+                    let data: Vec<Felt> = calldata
+                        .by_ref()
+                        .take(len.to_biguint().try_into().unwrap())
+                        .copied()
+                        .collect();
+
+                    if to == FAILING_ADDRESS {
+                        return serde_json::json!({
+                            "id": 1,
+                            "error": {
+                                "code": 40,
+                                "message": "Contract error",
+                                "data": { "revert_error": "Contract not deployed" }
+                            }
+                        });
+                    }
+                    if to == MISSING_BLOCK_ADDRESS {
+                        return Self::execute(&FunctionCall {
+                            contract_address: to,
+                            entry_point_selector: Felt::ONE,
+                            calldata: data,
+                        });
+                    }
+                    output.push(*len);
+                    output.extend(data);
+                }
+
+                serde_json::json!({ "id": 1, "result": output })
+            } else if call.contract_address == FAILING_ADDRESS {
+                serde_json::json!({
+                    "id": 1,
+                    "error": { "code": 20, "message": "Contract not found" }
+                })
+            } else if call.contract_address == MISSING_BLOCK_ADDRESS {
+                serde_json::json!({
+                    "id": 1,
+                    "error": { "code": 24, "message": "Block not found" }
+                })
+            } else {
+                serde_json::json!({ "id": 1, "result": call.calldata })
+            }
+        }
+    }
+
+    #[async_trait]
+    impl JsonRpcTransport for MockTransport {
+        type Error = MockError;
+
+        async fn send_request<P, R>(
+            &self,
+            method: JsonRpcMethod,
+            params: P,
+        ) -> Result<JsonRpcResponse<R>, Self::Error>
+        where
+            P: Serialize + Send + Sync,
+            R: DeserializeOwned + Send,
+        {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+
+            if !matches!(method, JsonRpcMethod::Call) {
+                return Err(MockError);
+            }
+            let request: CallRequest =
+                serde_json::from_value(serde_json::to_value(params).unwrap()).unwrap();
+
+            Ok(serde_json::from_value(Self::execute(&request.request)).unwrap())
+        }
+
+        async fn send_requests<R>(
+            &self,
+            requests: R,
+        ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+        where
+            R: AsRef<[ProviderRequestData]> + Send + Sync,
+        {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+
+            requests
+                .as_ref()
+                .iter()
+                .map(|request| match request {
+                    ProviderRequestData::Call(request) => {
+                        Ok(serde_json::from_value(Self::execute(&request.request)).unwrap())
+                    }
+                    _ => Err(MockError),
+                })
+                .collect()
+        }
+    }
+
+    fn calls() -> Vec<FunctionCall> {
+        (0..5u32)
+            .map(|ind| FunctionCall {
+                contract_address: if ind == 1 || ind == 4 {
+                    FAILING_ADDRESS
+                } else {
+                    Felt::from(ind + 1)
+                },
+                entry_point_selector: Felt::ONE,
+                calldata: vec![Felt::ONE, Felt::from(ind)],
+            })
+            .collect()
+    }
+
+    fn assert_results(
+        results: &[Result<Vec<Felt>, StarknetError>],
+        is_expected_error: fn(&StarknetError) -> bool,
+    ) {
+        assert_eq!(results.len(), 5);
+        for (ind, result) in results.iter().enumerate() {
+            if ind == 1 || ind == 4 {
+                assert!(is_expected_error(result.as_ref().unwrap_err()));
+            } else {
+                assert_eq!(result.as_ref().unwrap(), &[Felt::ONE, Felt::from(ind)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_aggregate_selector() {
+        assert_eq!(
+            get_selector_from_name("aggregate").unwrap(),
+            SELECTOR_AGGREGATE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_multicall_batch() {
+        let transport = Arc::new(MockTransport::default());
+        let multicall = Multicall::new(JsonRpcClient::new(transport.clone()));
+
+        assert_results(&multicall.call(&calls()).await.unwrap(), |err| {
+            matches!(err, StarknetError::ContractNotFound)
+        });
+        assert!(multicall.call(&[]).await.unwrap().is_empty());
+
+        // Errors are read from the batch response without resending calls
+        assert_eq!(transport.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_multicall_contract() {
+        let transport = Arc::new(MockTransport::default());
+        let multicall = Multicall::new(JsonRpcClient::new(transport.clone()))
+            .with_contract(MULTICALL_ADDRESS)
+            .with_chunk_size(2);
+
+        assert_results(&multicall.call(&calls()).await.unwrap(), |err| {
+            matches!(err, StarknetError::ContractError(_))
+        });
+
+        // 3 chunks, with the first chunk split in 2 after failing
+        assert_eq!(transport.requests.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_multicall_missing_contract() {
+        let transport = Arc::new(MockTransport::default());
+        let multicall =
+            Multicall::new(JsonRpcClient::new(transport.clone())).with_contract(FAILING_ADDRESS);
+
+        assert!(matches!(
+            multicall.call(&calls()).await,
+            Err(MulticallError::Provider(ProviderError::StarknetError(
+                StarknetError::ContractNotFound
+            )))
+        ));
+
+        // Calls are not split for errors of the multicall contract itself
+        assert_eq!(transport.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_multicall_non_call_error() {
+        let mut calls = calls();
+        calls[2].contract_address = MISSING_BLOCK_ADDRESS;
+
+        for multicall in [
+            Multicall::new(JsonRpcClient::new(MockTransport::default())),
+            Multicall::new(JsonRpcClient::new(MockTransport::default()))
+                .with_contract(MULTICALL_ADDRESS),
+        ] {
+            assert!(matches!(
+                multicall.call(&calls).await,
+                Err(MulticallError::Provider(ProviderError::StarknetError(
+                    StarknetError::BlockNotFound
+                )))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multicall_decoded() {
+        let multicall = Multicall::new(JsonRpcClient::new(MockTransport::default()))
+            .with_contract(MULTICALL_ADDRESS);
+
+        let results = multicall
+            .call_decoded::<(bool, u8)>(&calls())
+            .await
+            .unwrap();
+
+        assert_eq!(results[0].as_ref().unwrap(), &(true, 0));
+        assert!(matches!(
+            results[1],
+            Err(CallError::Provider(ProviderError::StarknetError(
+                StarknetError::ContractError(_)
+            )))
+        ));
+        assert_eq!(results[3].as_ref().unwrap(), &(true, 3));
+    }
+}
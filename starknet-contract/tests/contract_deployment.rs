@@ -1,12 +1,20 @@
-use std::time::{Duration, SystemTime};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
 
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
 use starknet_accounts::{ExecutionEncoding, SingleOwnerAccount};
 use starknet_contract::{ContractFactory, UdcSelector};
 use starknet_core::{
     chain_id,
     types::{contract::legacy::LegacyContractClass, BlockId, BlockTag, ExecutionResult, Felt},
 };
-use starknet_providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider};
+use starknet_providers::{
+    jsonrpc::{HttpTransport, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport},
+    JsonRpcClient, Provider, ProviderRequestData,
+};
 use starknet_signers::{LocalWallet, SigningKey};
 use url::Url;
 
@@ -104,6 +112,89 @@ async fn can_deploy_contract_inner(account_address: Felt, udc: UdcSelector, uniq
     assert_eq!(class_hash, class_hash_deployed);
 }
 
+#[tokio::test]
+async fn can_send_deployment_with_contract_handle() {
+    let transport = Arc::new(InvokeTransport::default());
+    let provider = JsonRpcClient::new(transport.clone());
+    let account = SingleOwnerAccount::new(
+        &provider,
+        LocalWallet::from(SigningKey::from_secret_scalar(Felt::ONE)),
+        Felt::from_hex_unchecked("0x1234"),
+        chain_id::SEPOLIA,
+        ExecutionEncoding::New,
+    );
+
+    let factory = ContractFactory::new_with_udc(Felt::from(0x5678), account, UdcSelector::New);
+    let deployment = factory
+        .deploy_v3(vec![Felt::ONE], Felt::TWO, false)
+        .nonce(Felt::ZERO)
+        .l1_gas(0)
+        .l1_gas_price(0)
+        .l2_gas(0)
+        .l2_gas_price(0)
+        .l1_data_gas(0)
+        .l1_data_gas_price(0)
+        .tip(0);
+
+    let (transaction, contract) = deployment.send_with_contract().await.unwrap();
+    assert_eq!(transaction.transaction_hash, Felt::from(0xabcd));
+    assert_eq!(contract.address(), deployment.deployed_address());
+
+    // The deployment is sent from the factory's account through the UDC
+    let invoke = transport.invoke.lock().unwrap().take().unwrap();
+    assert_eq!(invoke["invoke_transaction"]["sender_address"], "0x1234");
+    assert_eq!(
+        invoke["invoke_transaction"]["calldata"][1],
+        format!("{:#x}", UdcSelector::New.address())
+    );
+}
+
+/// A transport that only accepts invoke transactions, recording the last one sent.
+#[derive(Debug, Default)]
+struct InvokeTransport {
+    invoke: Mutex<Option<serde_json::Value>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unsupported method")]
+struct UnsupportedMethod;
+
+#[async_trait]
+impl JsonRpcTransport for InvokeTransport {
+    type Error = UnsupportedMethod;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        if !matches!(method, JsonRpcMethod::AddInvokeTransaction) {
+            return Err(UnsupportedMethod);
+        }
+        *self.invoke.lock().unwrap() = Some(serde_json::to_value(params).unwrap());
+
+        Ok(serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "result": { "transaction_hash": "0xabcd" },
+        }))
+        .unwrap())
+    }
+
+    async fn send_requests<R>(
+        &self,
+        _requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        Err(UnsupportedMethod)
+    }
+}
+
 // Temporary utility for watching tx until built-in tx watcher is implemented.
 async fn watch_tx<P>(provider: P, transaction_hash: Felt, timeout: Duration)
 where
@@ -2,169 +2,376 @@
 
 #![deny(missing_docs)]
 
+use std::collections::BTreeMap;
+
 use proc_macro::TokenStream;
-use proc_macro2::Span;
-use quote::quote;
+use proc_macro2::{Span, TokenTree};
+use quote::{quote, ToTokens};
 use syn::{
+    ext::IdentExt,
     parse::{Error as ParseError, Parse, ParseStream},
-    parse_macro_input, DeriveInput, Fields, LitInt, LitStr, Meta, Token,
+    parse_macro_input,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Data, DeriveInput, Field, Fields, GenericParam, Generics, Ident, Lifetime,
+    LifetimeParam, LitInt, LitStr, Meta, Path, Token,
 };
 
+/// A single argument inside a `#[starknet(...)]` attribute.
+enum Arg {
+    Core(kw::core, LitStr),
+    Transparent(kw::transparent),
+    Skip(kw::skip),
+    With(kw::with, LitStr),
+    Flatten(kw::flatten),
+    Index(kw::index, LitInt),
+}
+
+/// Arguments allowed on the type being derived.
 #[derive(Default)]
-struct Args {
+struct ContainerArgs {
     core: Option<LitStr>,
+    transparent: Option<kw::transparent>,
 }
 
-impl Args {
-    fn merge(&mut self, other: Self) {
-        if let Some(core) = other.core {
-            if self.core.is_some() {
-                panic!("starknet attribute `core` defined more than once");
-            } else {
-                self.core = Some(core);
-            }
+/// Arguments allowed on struct fields and enum variant fields.
+#[derive(Default)]
+struct FieldArgs {
+    skip: bool,
+    with: Option<Path>,
+}
+
+/// Arguments allowed on enum variants.
+#[derive(Default)]
+struct VariantArgs {
+    index: Option<LitInt>,
+}
+
+impl Arg {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Core(..) => "core",
+            Self::Transparent(_) => "transparent",
+            Self::Skip(_) => "skip",
+            Self::With(..) => "with",
+            Self::Flatten(_) => "flatten",
+            Self::Index(..) => "index",
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Self::Core(kw, _) => kw.span,
+            Self::Transparent(kw) => kw.span,
+            Self::Skip(kw) => kw.span,
+            Self::With(kw, _) => kw.span,
+            Self::Flatten(kw) => kw.span,
+            Self::Index(kw, _) => kw.span,
         }
     }
+
+    fn unexpected(&self, position: &str) -> ParseError {
+        ParseError::new(
+            self.span(),
+            format!(
+                "starknet attribute `{}` is not allowed on {}",
+                self.name(),
+                position
+            ),
+        )
+    }
+
+    fn duplicated(&self) -> ParseError {
+        ParseError::new(
+            self.span(),
+            format!(
+                "starknet attribute `{}` defined more than once",
+                self.name()
+            ),
+        )
+    }
 }
 
-impl Parse for Args {
+impl Parse for Arg {
     fn parse(input: ParseStream<'_>) -> Result<Self, ParseError> {
-        let mut core: Option<LitStr> = None;
-
-        while !input.is_empty() {
-            let lookahead = input.lookahead1();
-            if lookahead.peek(kw::core) {
-                let _ = input.parse::<kw::core>()?;
-                let _ = input.parse::<Token![=]>()?;
-                let value = input.parse::<LitStr>()?;
-
-                match core {
-                    Some(_) => {
-                        return Err(ParseError::new(
-                            Span::call_site(),
-                            "starknet attribute `core` defined more than once",
-                        ))
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::core) {
+            let kw = input.parse::<kw::core>()?;
+            let _ = input.parse::<Token![=]>()?;
+            Ok(Self::Core(kw, input.parse()?))
+        } else if lookahead.peek(kw::transparent) {
+            Ok(Self::Transparent(input.parse()?))
+        } else if lookahead.peek(kw::skip) {
+            Ok(Self::Skip(input.parse()?))
+        } else if lookahead.peek(kw::with) {
+            let kw = input.parse::<kw::with>()?;
+            let _ = input.parse::<Token![=]>()?;
+            Ok(Self::With(kw, input.parse()?))
+        } else if lookahead.peek(kw::flatten) {
+            Ok(Self::Flatten(input.parse()?))
+        } else if lookahead.peek(kw::index) {
+            let kw = input.parse::<kw::index>()?;
+            let _ = input.parse::<Token![=]>()?;
+            Ok(Self::Index(kw, input.parse()?))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ContainerArgs {
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self, ParseError> {
+        let mut args = Self::default();
+
+        for arg in parse_args(attrs)? {
+            match arg {
+                Arg::Core(_, ref value) => {
+                    if args.core.is_some() {
+                        return Err(arg.duplicated());
                     }
-                    None => {
-                        core = Some(value);
+                    args.core = Some(value.clone());
+                }
+                Arg::Transparent(kw) => {
+                    if args.transparent.is_some() {
+                        return Err(arg.duplicated());
                     }
+                    args.transparent = Some(kw);
                 }
-            } else {
-                return Err(lookahead.error());
+                _ => return Err(arg.unexpected("types")),
+            }
+        }
+
+        Ok(args)
+    }
+
+    /// Determines the path to the `starknet-core` crate root.
+    fn core_path(&self) -> Result<proc_macro2::TokenStream, ParseError> {
+        match &self.core {
+            Some(core) => core.parse(),
+            None => {
+                #[cfg(not(feature = "import_from_starknet"))]
+                let path = quote! {
+                    ::starknet_core
+                };
+
+                // This feature is enabled by the `starknet` crate. When using `starknet` it's
+                // assumed that users would not have imported `starknet-core` directly.
+                #[cfg(feature = "import_from_starknet")]
+                let path = quote! {
+                    ::starknet::core
+                };
+
+                Ok(path)
+            }
+        }
+    }
+}
+
+impl FieldArgs {
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self, ParseError> {
+        let mut args = Self::default();
+        let mut flatten = false;
+
+        for arg in parse_args(attrs)? {
+            match arg {
+                Arg::Skip(_) => {
+                    if args.skip {
+                        return Err(arg.duplicated());
+                    }
+                    args.skip = true;
+                }
+                Arg::With(_, ref value) => {
+                    if args.with.is_some() {
+                        return Err(arg.duplicated());
+                    }
+                    args.with = Some(value.parse()?);
+                }
+                // Cairo serializes members back to back without any framing, so a flattened field
+                // is encoded exactly like a regular one.
+                Arg::Flatten(_) => {
+                    if flatten {
+                        return Err(arg.duplicated());
+                    }
+                    flatten = true;
+                }
+                _ => return Err(arg.unexpected("fields")),
+            }
+        }
+
+        if args.skip && (args.with.is_some() || flatten) {
+            return Err(ParseError::new(
+                Span::call_site(),
+                "starknet attribute `skip` cannot be combined with other field attributes",
+            ));
+        }
+
+        Ok(args)
+    }
+}
+
+impl VariantArgs {
+    fn from_attrs(attrs: &[Attribute]) -> Result<Self, ParseError> {
+        let mut args = Self::default();
+
+        for arg in parse_args(attrs)? {
+            match arg {
+                Arg::Index(_, ref value) => {
+                    if args.index.is_some() {
+                        return Err(arg.duplicated());
+                    }
+                    args.index = Some(value.clone());
+                }
+                _ => return Err(arg.unexpected("enum variants")),
             }
         }
 
-        Ok(Self { core })
+        Ok(args)
     }
 }
 
 mod kw {
     syn::custom_keyword!(core);
+    syn::custom_keyword!(transparent);
+    syn::custom_keyword!(skip);
+    syn::custom_keyword!(with);
+    syn::custom_keyword!(flatten);
+    syn::custom_keyword!(index);
 }
 
 /// Derives the `Encode` trait.
+///
+/// See the documentation of the `Encode` trait for the supported `#[starknet(...)]` attributes.
 #[proc_macro_derive(Encode, attributes(starknet))]
 pub fn derive_encode(input: TokenStream) -> TokenStream {
     let input: DeriveInput = parse_macro_input!(input);
+
+    expand_encode(input)
+        .unwrap_or_else(ParseError::into_compile_error)
+        .into()
+}
+
+/// Derives the `Decode` trait.
+///
+/// See the documentation of the `Decode` trait for the supported `#[starknet(...)]` attributes.
+#[proc_macro_derive(Decode, attributes(starknet))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+
+    expand_decode(input)
+        .unwrap_or_else(ParseError::into_compile_error)
+        .into()
+}
+
+fn expand_encode(input: DeriveInput) -> Result<proc_macro2::TokenStream, ParseError> {
     let ident = &input.ident;
 
-    let core = derive_core_path(&input);
+    let container_args = ContainerArgs::from_attrs(&input.attrs)?;
+    let core = container_args.core_path()?;
 
-    let impl_block = match input.data {
+    let impl_block = match &input.data {
         syn::Data::Struct(data) => {
-            let field_impls = data.fields.iter().enumerate().map(|(ind_field, field)| {
-                let field_ident = match &field.ident {
-                    Some(field_ident) => quote! { self.#field_ident },
-                    None => {
-                        let ind_field = syn::Index::from(ind_field);
-                        quote! { self.#ind_field }
-                    }
-                };
-                let field_type = &field.ty;
+            check_transparent(&container_args, &data.fields)?;
 
-                quote! {
-                    <#field_type as #core::codec::Encode>::encode(&#field_ident, writer)?;
-                }
-            });
+            let field_impls = data
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(ind_field, field)| {
+                    let field_ident = match &field.ident {
+                        Some(field_ident) => quote! { self.#field_ident },
+                        None => {
+                            let ind_field = syn::Index::from(ind_field);
+                            quote! { self.#ind_field }
+                        }
+                    };
+
+                    encode_field(field, &quote! { &#field_ident }, &core)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
 
             quote! {
                 #(#field_impls)*
             }
         }
         syn::Data::Enum(data) => {
-            let variant_impls =
-                data.variants
-                    .iter()
-                    .enumerate()
-                    .map(|(ind_variant, variant)| {
-                        let variant_ident = &variant.ident;
-                        let ind_variant = int_to_felt(ind_variant, &core);
-
-                        match &variant.fields {
-                            Fields::Named(fields_named) => {
-                                let names = fields_named
-                                    .named
-                                    .iter()
-                                    .map(|field| field.ident.as_ref().unwrap());
-
-                                let field_impls = fields_named.named.iter().map(|field| {
-                                    let field_ident = field.ident.as_ref().unwrap();
-                                    let field_type = &field.ty;
-
-                                    quote! {
-                                        <#field_type as #core::codec::Encode>
-                                            ::encode(#field_ident, writer)?;
-                                    }
-                                });
-
-                                quote! {
-                                    Self::#variant_ident { #(#names),* } => {
-                                        writer.write(#ind_variant);
-                                        #(#field_impls)*
-                                    },
+            check_transparent(&container_args, &Fields::Unit)?;
+
+            let tags = variant_tags(data)?;
+
+            let variant_impls = data
+                .variants
+                .iter()
+                .zip(tags)
+                .map(|(variant, tag)| {
+                    let variant_ident = &variant.ident;
+                    let ind_variant = int_to_felt(tag, &core);
+
+                    let variant_impl = match &variant.fields {
+                        Fields::Named(fields_named) => {
+                            let mut names = vec![];
+                            let mut field_impls = vec![];
+
+                            for field in &fields_named.named {
+                                let field_ident = field.ident.as_ref().unwrap();
+
+                                if !FieldArgs::from_attrs(&field.attrs)?.skip {
+                                    names.push(field_ident);
                                 }
+                                field_impls.push(encode_field(
+                                    field,
+                                    &quote! { #field_ident },
+                                    &core,
+                                )?);
                             }
-                            Fields::Unnamed(fields_unnamed) => {
-                                let names = fields_unnamed.unnamed.iter().enumerate().map(
-                                    |(ind_field, _)| {
-                                        syn::Ident::new(
-                                            &format!("field_{ind_field}"),
-                                            Span::call_site(),
-                                        )
-                                    },
-                                );
 
-                                let field_impls = fields_unnamed.unnamed.iter().enumerate().map(
-                                    |(ind_field, field)| {
-                                        let field_ident = syn::Ident::new(
-                                            &format!("field_{ind_field}"),
-                                            Span::call_site(),
-                                        );
-                                        let field_type = &field.ty;
-
-                                        quote! {
-                                            <#field_type as #core::codec::Encode>
-                                                ::encode(#field_ident, writer)?;
-                                        }
-                                    },
+                            quote! {
+                                Self::#variant_ident { #(#names,)* .. } => {
+                                    writer.write(#ind_variant);
+                                    #(#field_impls)*
+                                },
+                            }
+                        }
+                        Fields::Unnamed(fields_unnamed) => {
+                            let mut names = vec![];
+                            let mut field_impls = vec![];
+
+                            for (ind_field, field) in fields_unnamed.unnamed.iter().enumerate() {
+                                let field_ident = syn::Ident::new(
+                                    &format!("field_{ind_field}"),
+                                    Span::call_site(),
                                 );
 
-                                quote! {
-                                    Self::#variant_ident( #(#names),* ) => {
-                                        writer.write(#ind_variant);
-                                        #(#field_impls)*
-                                    },
+                                if FieldArgs::from_attrs(&field.attrs)?.skip {
+                                    names.push(quote! { _ });
+                                } else {
+                                    names.push(quote! { #field_ident });
                                 }
+                                field_impls.push(encode_field(
+                                    field,
+                                    &quote! { #field_ident },
+                                    &core,
+                                )?);
                             }
-                            Fields::Unit => {
-                                quote! {
-                                    Self::#variant_ident => {
-                                        writer.write(#ind_variant);
-                                    },
-                                }
+
+                            quote! {
+                                Self::#variant_ident( #(#names),* ) => {
+                                    writer.write(#ind_variant);
+                                    #(#field_impls)*
+                                },
                             }
                         }
-                    });
+                        Fields::Unit => {
+                            quote! {
+                                Self::#variant_ident => {
+                                    writer.write(#ind_variant);
+                                },
+                            }
+                        }
+                    };
+
+                    Ok(variant_impl)
+                })
+                .collect::<Result<Vec<_>, ParseError>>()?;
 
             quote! {
                 match self {
@@ -172,123 +379,75 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
                 }
             }
         }
-        syn::Data::Union(_) => panic!("union type not supported"),
+        syn::Data::Union(_) => {
+            return Err(ParseError::new(ident.span(), "union type not supported"))
+        }
     };
 
-    quote! {
+    let mut impl_generics = input.generics.clone();
+    add_field_bounds(
+        &mut impl_generics,
+        &input.data,
+        &quote! { #core::codec::Encode },
+        None,
+    )?;
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    Ok(quote! {
         #[automatically_derived]
-        impl #core::codec::Encode for #ident {
-            fn encode<W: #core::codec::FeltWriter>(&self, writer: &mut W)
+        impl #impl_generics #core::codec::Encode for #ident #ty_generics #where_clause {
+            fn encode<__W: #core::codec::FeltWriter>(&self, writer: &mut __W)
                 -> ::core::result::Result<(), #core::codec::Error> {
                 #impl_block
 
                 Ok(())
             }
         }
-    }
-    .into()
+    })
 }
 
-/// Derives the `Decode` trait.
-#[proc_macro_derive(Decode, attributes(starknet))]
-pub fn derive_decode(input: TokenStream) -> TokenStream {
-    let input: DeriveInput = parse_macro_input!(input);
+fn expand_decode(input: DeriveInput) -> Result<proc_macro2::TokenStream, ParseError> {
     let ident = &input.ident;
 
-    let core = derive_core_path(&input);
-
-    let impl_block = match input.data {
-        syn::Data::Struct(data) => match &data.fields {
-            Fields::Named(fields_named) => {
-                let field_impls = fields_named.named.iter().map(|field| {
-                    let field_ident = &field.ident;
-                    let field_type = &field.ty;
+    let container_args = ContainerArgs::from_attrs(&input.attrs)?;
+    let core = container_args.core_path()?;
 
-                    quote! {
-                        #field_ident: <#field_type as #core::codec::Decode>
-                            ::decode_iter(iter)?,
-                    }
-                });
+    let impl_block = match &input.data {
+        syn::Data::Struct(data) => {
+            check_transparent(&container_args, &data.fields)?;
 
-                quote! {
-                    Ok(Self {
-                        #(#field_impls)*
-                    })
-                }
-            }
-            Fields::Unnamed(fields_unnamed) => {
-                let field_impls = fields_unnamed.unnamed.iter().map(|field| {
-                    let field_type = &field.ty;
-                    quote! {
-                        <#field_type as #core::codec::Decode>::decode_iter(iter)?
-                    }
-                });
+            let fields_impl = decode_fields(&data.fields, &ident.to_string(), &core)?;
 
-                quote! {
-                    Ok(Self (
-                        #(#field_impls),*
-                    ))
-                }
-            }
-            Fields::Unit => {
-                quote! {
-                    Ok(Self)
-                }
+            quote! {
+                Ok(Self #fields_impl)
             }
-        },
+        }
         syn::Data::Enum(data) => {
+            check_transparent(&container_args, &Fields::Unit)?;
+
+            let tags = variant_tags(data)?;
+
             let variant_impls = data
                 .variants
                 .iter()
-                .enumerate()
-                .map(|(ind_variant, variant)| {
+                .zip(tags)
+                .map(|(variant, tag)| {
                     let variant_ident = &variant.ident;
-                    let ind_variant = int_to_felt(ind_variant, &core);
-
-                    let decode_impl = match &variant.fields {
-                        Fields::Named(fields_named) => {
-                            let field_impls = fields_named.named.iter().map(|field| {
-                                let field_ident = field.ident.as_ref().unwrap();
-                                let field_type = &field.ty;
-
-                                quote! {
-                                    #field_ident: <#field_type as #core::codec::Decode>
-                                        ::decode_iter(iter)?,
-                                }
-                            });
-
-                            quote! {
-                                return Ok(Self::#variant_ident {
-                                    #(#field_impls)*
-                                });
-                            }
-                        }
-                        Fields::Unnamed(fields_unnamed) => {
-                            let field_impls = fields_unnamed.unnamed.iter().map(|field| {
-                                let field_type = &field.ty;
-
-                                quote! {
-                                    <#field_type as #core::codec::Decode>::decode_iter(iter)?
-                                }
-                            });
-
-                            quote! {
-                                return Ok(Self::#variant_ident( #(#field_impls),* ));
-                            }
-                        }
-                        Fields::Unit => {
-                            quote! {
-                                return Ok(Self::#variant_ident);
-                            }
-                        }
-                    };
-
-                    quote! {
+                    let ind_variant = int_to_felt(tag, &core);
+                    let fields_impl = decode_fields(
+                        &variant.fields,
+                        &format!("{ident}::{variant_ident}"),
+                        &core,
+                    )?;
+
+                    Ok(quote! {
                         if tag == &#ind_variant {
-                            #decode_impl
+                            return Ok(Self::#variant_ident #fields_impl);
                         }
-                    }
-                });
+                    })
+                })
+                .collect::<Result<Vec<_>, ParseError>>()?;
 
             let ident = ident.to_string();
 
@@ -300,28 +459,269 @@ pub fn derive_decode(input: TokenStream) -> TokenStream {
                 Err(#core::codec::Error::unknown_enum_tag(tag, #ident))
             }
         }
-        syn::Data::Union(_) => panic!("union type not supported"),
+        syn::Data::Union(_) => {
+            return Err(ParseError::new(ident.span(), "union type not supported"))
+        }
     };
 
-    quote! {
+    // A type with a single lifetime parameter is decoded with that lifetime, allowing it to borrow
+    // from the input. Otherwise a new lifetime outliving all lifetime parameters is introduced.
+    let mut impl_generics = input.generics.clone();
+    let mut lifetimes = input.generics.lifetimes();
+    let lifetime = match (lifetimes.next(), lifetimes.next()) {
+        (Some(lifetime), None) => lifetime.lifetime.clone(),
+        _ => {
+            let lifetime = Lifetime::new("'__a", Span::call_site());
+            let mut lifetime_param = LifetimeParam::new(lifetime.clone());
+            lifetime_param.bounds = input
+                .generics
+                .lifetimes()
+                .map(|param| param.lifetime.clone())
+                .collect();
+            impl_generics
+                .params
+                .insert(0, GenericParam::Lifetime(lifetime_param));
+            lifetime
+        }
+    };
+    add_field_bounds(
+        &mut impl_generics,
+        &input.data,
+        &quote! { #core::codec::Decode<#lifetime> },
+        Some(&quote! { ::core::default::Default }),
+    )?;
+    let (impl_generics, _, where_clause) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    Ok(quote! {
         #[automatically_derived]
-        impl<'a> #core::codec::Decode<'a> for #ident {
-            fn decode_iter<T>(iter: &mut T) -> ::core::result::Result<Self, #core::codec::Error>
+        impl #impl_generics #core::codec::Decode<#lifetime> for #ident #ty_generics #where_clause {
+            fn decode_iter<__I>(iter: &mut __I)
+                -> ::core::result::Result<Self, #core::codec::Error>
             where
-                T: core::iter::Iterator<Item = &'a #core::types::Felt>
+                __I: core::iter::Iterator<Item = &#lifetime #core::types::Felt>
             {
                 #impl_block
             }
         }
+    })
+}
+
+/// Bounds type parameters used in encoded fields by `bound`, and those used in skipped fields by
+/// `skip_bound` if any. Fields using custom `with` functions add no bounds.
+fn add_field_bounds(
+    generics: &mut Generics,
+    data: &Data,
+    bound: &proc_macro2::TokenStream,
+    skip_bound: Option<&proc_macro2::TokenStream>,
+) -> Result<(), ParseError> {
+    let fields: Vec<&Field> = match data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .collect(),
+        Data::Union(_) => return Ok(()),
+    };
+
+    let type_params: Vec<Ident> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+
+    let mut predicates = vec![];
+    for param in &type_params {
+        let mut needs_bound = false;
+        let mut needs_skip_bound = false;
+
+        for field in &fields {
+            if !contains_ident(field.ty.to_token_stream(), param) {
+                continue;
+            }
+
+            let args = FieldArgs::from_attrs(&field.attrs)?;
+            if args.skip {
+                needs_skip_bound = true;
+            } else if args.with.is_none() {
+                needs_bound = true;
+            }
+        }
+
+        if needs_bound {
+            predicates.push(quote! { #param: #bound });
+        }
+        if let (true, Some(skip_bound)) = (needs_skip_bound, skip_bound) {
+            predicates.push(quote! { #param: #skip_bound });
+        }
+    }
+
+    let where_clause = generics.make_where_clause();
+    for predicate in predicates {
+        where_clause.predicates.push(syn::parse2(predicate)?);
+    }
+
+    Ok(())
+}
+
+/// Checks whether `ident` appears anywhere in the tokens, including nested groups.
+fn contains_ident(tokens: proc_macro2::TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(token) => &token == ident,
+        TokenTree::Group(group) => contains_ident(group.stream(), ident),
+        _ => false,
+    })
+}
+
+/// Generates the statement encoding a single field, where `value` evaluates to a reference to the
+/// field value.
+fn encode_field(
+    field: &Field,
+    value: &proc_macro2::TokenStream,
+    core: &proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let args = FieldArgs::from_attrs(&field.attrs)?;
+    let field_type = &field.ty;
+
+    Ok(if args.skip {
+        quote! {}
+    } else if let Some(with) = args.with {
+        quote! {
+            #with::encode(#value, writer)?;
+        }
+    } else {
+        quote! {
+            <#field_type as #core::codec::Encode>::encode(#value, writer)?;
+        }
+    })
+}
+
+/// Generates the field list for constructing a struct or an enum variant from the decoder input.
+/// Decoding errors are annotated with the field name and `type_name`.
+fn decode_fields(
+    fields: &Fields,
+    type_name: &str,
+    core: &proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream, ParseError> {
+    let decode_field =
+        |ind_field: usize, field: &Field| -> Result<proc_macro2::TokenStream, ParseError> {
+            let args = FieldArgs::from_attrs(&field.attrs)?;
+            let field_type = &field.ty;
+            let field_name = field
+                .ident
+                .as_ref()
+                .map_or_else(|| ind_field.to_string(), |ident| ident.unraw().to_string());
+
+            let value = if args.skip {
+                return Ok(quote! { ::core::default::Default::default() });
+            } else if let Some(with) = args.with {
+                quote! { #with::decode_iter(iter) }
+            } else {
+                quote! { <#field_type as #core::codec::Decode>::decode_iter(iter) }
+            };
+
+            Ok(quote! {
+                #value.map_err(|err| err.in_field(#field_name, #type_name))?
+            })
+        };
+
+    Ok(match fields {
+        Fields::Named(fields_named) => {
+            let field_impls = fields_named
+                .named
+                .iter()
+                .enumerate()
+                .map(|(ind_field, field)| {
+                    let field_ident = &field.ident;
+                    let value = decode_field(ind_field, field)?;
+
+                    Ok(quote! {
+                        #field_ident: #value,
+                    })
+                })
+                .collect::<Result<Vec<_>, ParseError>>()?;
+
+            quote! {
+                {
+                    #(#field_impls)*
+                }
+            }
+        }
+        Fields::Unnamed(fields_unnamed) => {
+            let field_impls = fields_unnamed
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(ind_field, field)| decode_field(ind_field, field))
+                .collect::<Result<Vec<_>, ParseError>>()?;
+
+            quote! {
+                ( #(#field_impls),* )
+            }
+        }
+        Fields::Unit => quote! {},
+    })
+}
+
+/// Makes sure a `transparent` type is a struct with exactly one field that is not skipped. Since
+/// Cairo serializes a struct as the concatenation of its members, such a struct is encoded exactly
+/// like its only field.
+fn check_transparent(args: &ContainerArgs, fields: &Fields) -> Result<(), ParseError> {
+    let Some(transparent) = &args.transparent else {
+        return Ok(());
+    };
+
+    let mut encoded_fields = 0;
+    for field in fields {
+        if !FieldArgs::from_attrs(&field.attrs)?.skip {
+            encoded_fields += 1;
+        }
+    }
+
+    if encoded_fields == 1 {
+        Ok(())
+    } else {
+        Err(ParseError::new(
+            transparent.span,
+            "starknet attribute `transparent` requires a struct with exactly one non-skipped field",
+        ))
     }
-    .into()
 }
 
-/// Determines the path to the `starknet-core` crate root.
-fn derive_core_path(input: &DeriveInput) -> proc_macro2::TokenStream {
-    let mut attr_args = Args::default();
+/// Assigns a tag to each enum variant. Variants without an explicit `index` take the tag following
+/// the previous variant, the same way Rust assigns enum discriminants.
+fn variant_tags(data: &syn::DataEnum) -> Result<Vec<u64>, ParseError> {
+    let mut tags = Vec::with_capacity(data.variants.len());
+    let mut used_tags = BTreeMap::new();
+    let mut next_tag = 0u64;
+
+    for variant in &data.variants {
+        let tag = match VariantArgs::from_attrs(&variant.attrs)?.index {
+            Some(index) => index.base10_parse::<u64>()?,
+            None => next_tag,
+        };
+
+        if let Some(existing) = used_tags.insert(tag, &variant.ident) {
+            return Err(ParseError::new(
+                variant.span(),
+                format!("enum variant index {tag} is already used by `{existing}`"),
+            ));
+        }
+
+        tags.push(tag);
+        next_tag = tag
+            .checked_add(1)
+            .ok_or_else(|| ParseError::new(variant.span(), "enum variant index overflows `u64`"))?;
+    }
 
-    for attr in &input.attrs {
+    Ok(tags)
+}
+
+/// Collects all arguments from `#[starknet(...)]` attributes.
+fn parse_args(attrs: &[Attribute]) -> Result<Vec<Arg>, ParseError> {
+    let mut args = vec![];
+
+    for attr in attrs {
         if !attr.meta.path().is_ident("starknet") {
             continue;
         }
@@ -329,36 +729,24 @@ fn derive_core_path(input: &DeriveInput) -> proc_macro2::TokenStream {
         match &attr.meta {
             Meta::Path(_) => {}
             Meta::List(meta_list) => {
-                let args: Args = meta_list
-                    .parse_args()
-                    .expect("unable to parse starknet attribute args");
-
-                attr_args.merge(args);
+                args.extend(
+                    meta_list.parse_args_with(Punctuated::<Arg, Token![,]>::parse_terminated)?,
+                );
+            }
+            Meta::NameValue(_) => {
+                return Err(ParseError::new(
+                    attr.span(),
+                    "starknet attribute must not be name-value",
+                ))
             }
-            Meta::NameValue(_) => panic!("starknet attribute must not be name-value"),
         }
     }
 
-    attr_args.core.map_or_else(
-        || {
-            #[cfg(not(feature = "import_from_starknet"))]
-            quote! {
-                ::starknet_core
-            }
-
-            // This feature is enabled by the `starknet` crate. When using `starknet` it's assumed
-            // that users would not have imported `starknet-core` directly.
-            #[cfg(feature = "import_from_starknet")]
-            quote! {
-                ::starknet::core
-            }
-        },
-        |id| id.parse().expect("unable to parse core crate path"),
-    )
+    Ok(args)
 }
 
 /// Turns an integer into an optimal `TokenStream` that constructs a `Felt` with the same value.
-fn int_to_felt(int: usize, core: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+fn int_to_felt(int: u64, core: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
     match int {
         0 => quote! { #core::types::Felt::ZERO },
         1 => quote! { #core::types::Felt::ONE },
@@ -366,7 +754,7 @@ fn int_to_felt(int: usize, core: &proc_macro2::TokenStream) -> proc_macro2::Toke
         3 => quote! { #core::types::Felt::THREE },
         // TODO: turn the number into Montgomery repr and use const ctor instead.
         _ => {
-            let literal = LitInt::new(&int.to_string(), Span::call_site());
+            let literal = LitInt::new(&format!("{int}u64"), Span::call_site());
             quote! { #core::types::Felt::from(#literal) }
         }
     }
@@ -0,0 +1,562 @@
+use alloc::{borrow::ToOwned, boxed::Box, collections::BTreeMap, format, string::*, vec, vec::*};
+
+use serde_json::Value as JsonValue;
+
+use super::{AbiError, AbiType, AbiValue, TypeDefinitions};
+use crate::{
+    codec::FeltWriter,
+    types::{
+        contract::{legacy::RawLegacyAbiEntry, AbiEntry, AbiNamedMember},
+        Felt, LegacyTypedParameter,
+    },
+};
+
+/// Runtime encoder/decoder for contract function calldata and results, driven by a contract ABI.
+///
+/// This is useful when the contract interface is only known at runtime, e.g. in tools taking a
+/// contract, a function name and JSON arguments from users. When the interface is known at compile
+/// time, prefer the [`Encode`](crate::codec::Encode) and [`Decode`](crate::codec::Decode) traits
+/// instead.
+///
+/// # Example
+///
+/// ```rust
+/// use starknet_core::{
+///     abi::{AbiValue, ContractAbi},
+///     types::{contract::AbiEntry, Felt, U256},
+/// };
+///
+/// let abi = serde_json::from_str::<Vec<AbiEntry>>(
+///     r#"[{
+///         "type": "function",
+///         "name": "transfer",
+///         "inputs": [
+///             { "name": "recipient", "type": "core::starknet::contract_address::ContractAddress" },
+///             { "name": "amount", "type": "core::integer::u256" }
+///         ],
+///         "outputs": [{ "type": "core::bool" }],
+///         "state_mutability": "external"
+///     }]"#,
+/// )
+/// .unwrap();
+/// let abi = ContractAbi::from_sierra(&abi).unwrap();
+///
+/// let calldata = abi
+///     .encode_json_inputs(
+///         "transfer",
+///         &serde_json::json!({ "recipient": "0x1234", "amount": "1000" }),
+///     )
+///     .unwrap();
+/// assert_eq!(
+///     calldata,
+///     vec![Felt::from(0x1234), Felt::from(1000), Felt::ZERO]
+/// );
+///
+/// let outputs = abi.decode_outputs("transfer", &[Felt::ONE]).unwrap();
+/// assert_eq!(outputs, vec![AbiValue::Bool(true)]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ContractAbi {
+    types: TypeDefinitions,
+    functions: BTreeMap<String, FunctionSignature>,
+}
+
+/// Inputs and outputs of a function in [`ContractAbi`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSignature {
+    /// Name and type of each input.
+    pub inputs: Vec<(String, AbiType)>,
+    /// Type of each output.
+    ///
+    /// Legacy (Cairo 0) functions can have named outputs, but only their types are kept here.
+    pub outputs: Vec<AbiType>,
+}
+
+impl ContractAbi {
+    /// Constructs [`ContractAbi`] from a Sierra (Cairo 1) contract ABI, including functions
+    /// defined in interfaces, L1 handlers and the constructor.
+    pub fn from_sierra(abi: &[AbiEntry]) -> Result<Self, AbiError> {
+        let mut functions = BTreeMap::new();
+        add_sierra_functions(&mut functions, abi)?;
+
+        Ok(Self {
+            types: TypeDefinitions::from_sierra_abi(abi)?,
+            functions,
+        })
+    }
+
+    /// Constructs [`ContractAbi`] from a legacy (Cairo 0) contract ABI, including L1 handlers and
+    /// the constructor.
+    ///
+    /// Array parameters, represented in the ABI as a pointer following a `felt` parameter with the
+    /// same name suffixed by `_len`, are merged into a single parameter of the array type.
+    pub fn from_legacy(abi: &[RawLegacyAbiEntry]) -> Result<Self, AbiError> {
+        let mut functions = BTreeMap::new();
+
+        for entry in abi {
+            let (name, inputs, outputs) = match entry {
+                RawLegacyAbiEntry::Function(item) => (&item.name, &item.inputs, &item.outputs),
+                RawLegacyAbiEntry::L1Handler(item) => (&item.name, &item.inputs, &item.outputs),
+                RawLegacyAbiEntry::Constructor(item) => (&item.name, &item.inputs, &item.outputs),
+                RawLegacyAbiEntry::Struct(_) | RawLegacyAbiEntry::Event(_) => continue,
+            };
+
+            functions.insert(
+                name.to_owned(),
+                FunctionSignature {
+                    inputs: parse_legacy_parameters(inputs)?,
+                    outputs: parse_legacy_parameters(outputs)?
+                        .into_iter()
+                        .map(|(_, output_type)| output_type)
+                        .collect(),
+                },
+            );
+        }
+
+        Ok(Self {
+            types: TypeDefinitions::from_legacy_abi(abi)?,
+            functions,
+        })
+    }
+
+    /// Gets the signature of a function by name.
+    pub fn function(&self, name: &str) -> Option<&FunctionSignature> {
+        self.functions.get(name)
+    }
+
+    /// Gets an iterator over all functions along with their signatures, sorted by name.
+    pub fn functions(&self) -> impl Iterator<Item = (&str, &FunctionSignature)> {
+        self.functions
+            .iter()
+            .map(|(name, signature)| (name.as_str(), signature))
+    }
+
+    /// Encodes a value of the type into the writer, failing if the value doesn't match the type.
+    pub fn encode<W>(
+        &self,
+        r#type: &AbiType,
+        value: &AbiValue,
+        writer: &mut W,
+    ) -> Result<(), AbiError>
+    where
+        W: FeltWriter,
+    {
+        self.types.encode(r#type, value, writer)
+    }
+
+    /// Decodes a value of the type, failing if not all elements are consumed.
+    pub fn decode(&self, r#type: &AbiType, data: &[Felt]) -> Result<AbiValue, AbiError> {
+        let mut iter = data.iter();
+        let value = self.types.decode(r#type, &mut iter)?;
+
+        match iter.len() {
+            0 => Ok(value),
+            remaining => Err(AbiError::UnconsumedInput(remaining)),
+        }
+    }
+
+    /// Converts a JSON value into a value of the type.
+    ///
+    /// Numbers can be either JSON numbers or strings in decimal or `0x`-prefixed hexadecimal. Byte
+    /// arrays are strings. Structs are objects keyed by field names. Enums are objects with the
+    /// variant name as the only key, or just the variant name as a string for variants without
+    /// data.
+    pub fn value_from_json(
+        &self,
+        r#type: &AbiType,
+        json: &JsonValue,
+    ) -> Result<AbiValue, AbiError> {
+        self.types.value_from_json(r#type, json)
+    }
+
+    /// Encodes the arguments of a function into calldata.
+    pub fn encode_inputs(&self, function: &str, args: &[AbiValue]) -> Result<Vec<Felt>, AbiError> {
+        let signature = self.get_function(function)?;
+        if args.len() != signature.inputs.len() {
+            return Err(AbiError::ArgumentCountMismatch {
+                expected: signature.inputs.len(),
+                actual: args.len(),
+            });
+        }
+
+        let mut calldata = vec![];
+        for ((_, input_type), arg) in signature.inputs.iter().zip(args) {
+            self.types.encode(input_type, arg, &mut calldata)?;
+        }
+
+        Ok(calldata)
+    }
+
+    /// Encodes the arguments of a function from JSON into calldata. Arguments can be supplied as
+    /// either an array in declaration order or an object keyed by input names.
+    pub fn encode_json_inputs(
+        &self,
+        function: &str,
+        args: &JsonValue,
+    ) -> Result<Vec<Felt>, AbiError> {
+        let signature = self.get_function(function)?;
+
+        let args = match args {
+            JsonValue::Array(args) => {
+                if args.len() != signature.inputs.len() {
+                    return Err(AbiError::ArgumentCountMismatch {
+                        expected: signature.inputs.len(),
+                        actual: args.len(),
+                    });
+                }
+
+                signature
+                    .inputs
+                    .iter()
+                    .zip(args)
+                    .map(|((_, input_type), arg)| self.types.value_from_json(input_type, arg))
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+            JsonValue::Object(args) => signature
+                .inputs
+                .iter()
+                .map(|(input_name, input_type)| {
+                    let arg = args
+                        .get(input_name)
+                        .ok_or_else(|| AbiError::FieldNotFound(input_name.to_owned()))?;
+                    self.types.value_from_json(input_type, arg)
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => {
+                return Err(AbiError::UnexpectedValue(format!(
+                    "arguments of `{function}`"
+                )))
+            }
+        };
+
+        self.encode_inputs(function, &args)
+    }
+
+    /// Decodes the result of calling a function, failing if not all elements are consumed.
+    pub fn decode_outputs(&self, function: &str, data: &[Felt]) -> Result<Vec<AbiValue>, AbiError> {
+        let signature = self.get_function(function)?;
+
+        let mut iter = data.iter();
+        let outputs = signature
+            .outputs
+            .iter()
+            .map(|output_type| self.types.decode(output_type, &mut iter))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match iter.len() {
+            0 => Ok(outputs),
+            remaining => Err(AbiError::UnconsumedInput(remaining)),
+        }
+    }
+
+    fn get_function(&self, name: &str) -> Result<&FunctionSignature, AbiError> {
+        self.functions
+            .get(name)
+            .ok_or_else(|| AbiError::FunctionNotFound(name.to_owned()))
+    }
+}
+
+fn add_sierra_functions(
+    functions: &mut BTreeMap<String, FunctionSignature>,
+    entries: &[AbiEntry],
+) -> Result<(), AbiError> {
+    for entry in entries {
+        match entry {
+            AbiEntry::Function(item) | AbiEntry::L1Handler(item) => {
+                functions.insert(
+                    item.name.clone(),
+                    FunctionSignature {
+                        inputs: parse_parameters(&item.inputs)?,
+                        outputs: item
+                            .outputs
+                            .iter()
+                            .map(|output| AbiType::parse(&output.r#type))
+                            .collect::<Result<_, _>>()?,
+                    },
+                );
+            }
+            AbiEntry::Constructor(item) => {
+                functions.insert(
+                    item.name.clone(),
+                    FunctionSignature {
+                        inputs: parse_parameters(&item.inputs)?,
+                        outputs: vec![],
+                    },
+                );
+            }
+            AbiEntry::Interface(item) => add_sierra_functions(functions, &item.items)?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_parameters(parameters: &[AbiNamedMember]) -> Result<Vec<(String, AbiType)>, AbiError> {
+    parameters
+        .iter()
+        .map(|parameter| Ok((parameter.name.clone(), AbiType::parse(&parameter.r#type)?)))
+        .collect()
+}
+
+fn parse_legacy_parameters(
+    parameters: &[LegacyTypedParameter],
+) -> Result<Vec<(String, AbiType)>, AbiError> {
+    let mut parsed: Vec<(String, AbiType)> = vec![];
+
+    for parameter in parameters {
+        match parameter.r#type.strip_suffix('*') {
+            Some(element_type) => {
+                let length_name = format!("{}_len", parameter.name);
+                match parsed.last() {
+                    Some((name, AbiType::Felt)) if name == &length_name => {
+                        parsed.pop();
+                    }
+                    _ => return Err(AbiError::InvalidTypeName(parameter.r#type.clone())),
+                }
+
+                parsed.push((
+                    parameter.name.clone(),
+                    AbiType::Array(Box::new(AbiType::parse_legacy(element_type)?)),
+                ));
+            }
+            None => parsed.push((
+                parameter.name.clone(),
+                AbiType::parse_legacy(&parameter.r#type)?,
+            )),
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{
+        contract::{legacy::LegacyContractClass, SierraClass},
+        ByteArray, U256,
+    };
+
+    use super::*;
+
+    fn load_sierra_abi(artifact: &str) -> ContractAbi {
+        let class = serde_json::from_str::<SierraClass>(artifact).unwrap();
+        ContractAbi::from_sierra(&class.abi).unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_sierra_function_io() {
+        let abi = load_sierra_abi(include_str!(
+            "../../test-data/contracts/cairo2/artifacts/abi_types_sierra.txt"
+        ));
+
+        let calldata = abi
+            .encode_inputs(
+                "example_external_function",
+                &[Felt::from(0x1234).into(), U256::from_words(5, 6).into()],
+            )
+            .unwrap();
+        assert_eq!(
+            calldata,
+            vec![Felt::from(0x1234), Felt::from(5), Felt::from(6)]
+        );
+        assert_eq!(
+            abi.encode_json_inputs(
+                "example_external_function",
+                &serde_json::json!(["0x1234", format!("{:#x}", U256::from_words(5, 6))]),
+            )
+            .unwrap(),
+            calldata
+        );
+
+        let outputs = abi
+            .decode_outputs(
+                "example_external_function",
+                &[
+                    Felt::from(200),
+                    Felt::from(300),
+                    Felt::ONE,
+                    Felt::from(400),
+                    Felt::ZERO,
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&outputs).unwrap(),
+            serde_json::json!([{
+                "field_a": "0xc8",
+                "field_b": "0x12c",
+                "field_c": { "variant_b": "400" },
+                "field_d": [],
+            }])
+        );
+
+        assert!(matches!(
+            abi.encode_inputs("example_external_function", &[]),
+            Err(AbiError::ArgumentCountMismatch {
+                expected: 2,
+                actual: 0
+            })
+        ));
+        assert!(matches!(
+            abi.encode_inputs("missing", &[]),
+            Err(AbiError::FunctionNotFound(_))
+        ));
+        assert!(matches!(
+            abi.decode_outputs("example_external_function", &[Felt::ONE]),
+            Err(AbiError::Codec(_))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_json_round_trip() {
+        let abi = serde_json::from_str::<Vec<AbiEntry>>(
+            r#"[
+                {
+                    "type": "enum",
+                    "name": "core::option::Option::<core::integer::u8>",
+                    "variants": [
+                        { "name": "Some", "type": "core::integer::u8" },
+                        { "name": "None", "type": "()" }
+                    ]
+                },
+                {
+                    "type": "struct",
+                    "name": "demo::Config",
+                    "members": [
+                        { "name": "name", "type": "core::byte_array::ByteArray" },
+                        { "name": "limits", "type": "core::array::Span::<(core::integer::u8, core::integer::i16)>" },
+                        { "name": "fallback", "type": "core::option::Option::<core::integer::u8>" },
+                        { "name": "flags", "type": "[core::bool; 2]" }
+                    ]
+                },
+                {
+                    "type": "interface",
+                    "name": "demo::IDemo",
+                    "items": [
+                        {
+                            "type": "function",
+                            "name": "configure",
+                            "inputs": [{ "name": "config", "type": "demo::Config" }],
+                            "outputs": [{ "type": "demo::Config" }],
+                            "state_mutability": "external"
+                        }
+                    ]
+                }
+            ]"#,
+        )
+        .unwrap();
+        let abi = ContractAbi::from_sierra(&abi).unwrap();
+
+        let config = serde_json::json!({
+            "name": "demo",
+            "limits": [["1", "-2"], [3, 4]],
+            "fallback": "None",
+            "flags": [true, false],
+        });
+        let calldata = abi
+            .encode_json_inputs("configure", &serde_json::json!([config]))
+            .unwrap();
+
+        let mut expected = vec![];
+        crate::codec::Encode::encode(&ByteArray::from("demo"), &mut expected).unwrap();
+        expected.extend([
+            Felt::TWO,
+            Felt::ONE,
+            Felt::from(-2),
+            Felt::THREE,
+            Felt::from(4),
+            Felt::ONE,
+            Felt::ONE,
+            Felt::ZERO,
+        ]);
+        assert_eq!(calldata, expected);
+
+        let outputs = abi.decode_outputs("configure", &calldata).unwrap();
+        assert_eq!(
+            serde_json::to_value(&outputs).unwrap(),
+            serde_json::json!([{
+                "name": "demo",
+                "limits": [["1", "-2"], ["3", "4"]],
+                "fallback": { "None": [] },
+                "flags": [true, false],
+            }])
+        );
+
+        // Decoded values can be encoded back
+        let config_type = &abi.function("configure").unwrap().inputs[0].1;
+        let mut encoded = vec![];
+        abi.encode(config_type, &outputs[0], &mut encoded).unwrap();
+        assert_eq!(encoded, calldata);
+
+        // Values out of range
+        for config in [
+            serde_json::json!({ "name": "", "limits": [[256, 0]], "fallback": "None", "flags": [true, false] }),
+            serde_json::json!({ "name": "", "limits": [[0, -32769]], "fallback": "None", "flags": [true, false] }),
+            serde_json::json!({ "name": "", "limits": [], "fallback": "None", "flags": [true] }),
+        ] {
+            assert!(abi
+                .encode_json_inputs("configure", &serde_json::json!({ "config": config }))
+                .is_err());
+        }
+        assert!(matches!(
+            abi.encode_json_inputs(
+                "configure",
+                &serde_json::json!({ "config": { "name": "", "limits": [], "fallback": "Other", "flags": [true, false] } })
+            ),
+            Err(AbiError::VariantNotFound(_))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_legacy_function_io() {
+        let class = serde_json::from_str::<LegacyContractClass>(include_str!(
+            "../../test-data/contracts/cairo0/artifacts/oz_account.txt"
+        ))
+        .unwrap();
+        let abi = ContractAbi::from_legacy(&class.abi).unwrap();
+
+        let signature = abi.function("__execute__").unwrap();
+        assert_eq!(signature.inputs.len(), 2);
+        assert_eq!(
+            signature.outputs,
+            vec![AbiType::Array(Box::new(AbiType::Felt))]
+        );
+
+        let calldata = abi
+            .encode_json_inputs(
+                "__execute__",
+                &serde_json::json!({
+                    "call_array": [{
+                        "to": "0x1",
+                        "selector": "0x2",
+                        "data_offset": 0,
+                        "data_len": 1,
+                    }],
+                    "calldata": ["0x3"],
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            calldata,
+            vec![
+                Felt::ONE,
+                Felt::ONE,
+                Felt::TWO,
+                Felt::ZERO,
+                Felt::ONE,
+                Felt::ONE,
+                Felt::THREE
+            ]
+        );
+
+        assert_eq!(
+            abi.decode_outputs("__execute__", &[Felt::ONE, Felt::TWO])
+                .unwrap(),
+            vec![AbiValue::Array(vec![AbiValue::Felt(Felt::TWO)])]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use alloc::{borrow::ToOwned, boxed::Box, collections::BTreeMap, string::*, vec::*};
+
+use num_traits::ToPrimitive;
+
+use super::{AbiError, AbiType, AbiValue, EnumValue, StructValue};
+use crate::{
+    codec::{Decode, Encode, Error as CodecError, FeltWriter},
+    types::{
+        contract::{legacy::RawLegacyAbiEntry, AbiEntry, AbiNamedMember},
+        ByteArray, Felt, U256,
+    },
+};
+
+/// Structs and enums defined in an ABI, indexed by name.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TypeDefinitions {
+    definitions: BTreeMap<String, TypeDefinition>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum TypeDefinition {
+    Struct(Vec<(String, AbiType)>),
+    Enum(Vec<(String, AbiType)>),
+}
+
+impl TypeDefinitions {
+    pub fn from_sierra_abi(abi: &[AbiEntry]) -> Result<Self, AbiError> {
+        let mut definitions = Self::default();
+        definitions.add_sierra_entries(abi)?;
+        Ok(definitions)
+    }
+
+    pub fn from_legacy_abi(abi: &[RawLegacyAbiEntry]) -> Result<Self, AbiError> {
+        let mut definitions = Self::default();
+
+        for entry in abi {
+            if let RawLegacyAbiEntry::Struct(item) = entry {
+                definitions.definitions.insert(
+                    item.name.clone(),
+                    TypeDefinition::Struct(
+                        item.members
+                            .iter()
+                            .map(|member| {
+                                Ok((member.name.clone(), AbiType::parse_legacy(&member.r#type)?))
+                            })
+                            .collect::<Result<_, AbiError>>()?,
+                    ),
+                );
+            }
+        }
+
+        Ok(definitions)
+    }
+
+    fn add_sierra_entries(&mut self, entries: &[AbiEntry]) -> Result<(), AbiError> {
+        for entry in entries {
+            match entry {
+                AbiEntry::Struct(item) => {
+                    self.definitions.insert(
+                        item.name.clone(),
+                        TypeDefinition::Struct(parse_members(&item.members)?),
+                    );
+                }
+                AbiEntry::Enum(item) => {
+                    self.definitions.insert(
+                        item.name.clone(),
+                        TypeDefinition::Enum(parse_members(&item.variants)?),
+                    );
+                }
+                AbiEntry::Interface(item) => self.add_sierra_entries(&item.items)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Result<&TypeDefinition, AbiError> {
+        self.definitions
+            .get(name)
+            .ok_or_else(|| AbiError::TypeNotFound(name.to_owned()))
+    }
+
+    /// Encodes a value of the type into the writer, failing if the value doesn't match the type.
+    pub fn encode<W>(
+        &self,
+        r#type: &AbiType,
+        value: &AbiValue,
+        writer: &mut W,
+    ) -> Result<(), AbiError>
+    where
+        W: FeltWriter,
+    {
+        let mismatch = || AbiError::UnexpectedValue(r#type.to_string());
+
+        match (r#type, value) {
+            (AbiType::Felt, AbiValue::Felt(value)) => writer.write(*value),
+            (AbiType::Bool, AbiValue::Bool(value)) => value.encode(writer)?,
+            (AbiType::Unsigned(256), AbiValue::Unsigned(value)) => value.encode(writer)?,
+            (AbiType::Unsigned(bits), AbiValue::Unsigned(value)) => {
+                if value.high() != 0 || (*bits < 128 && value.low() >> bits != 0) {
+                    return Err(CodecError::value_out_of_range(value, &r#type.to_string()).into());
+                }
+                value.low().encode(writer)?;
+            }
+            (AbiType::Signed(bits), AbiValue::Signed(value)) => {
+                if *bits < 128 && (value >> (bits - 1) != 0 && value >> (bits - 1) != -1) {
+                    return Err(CodecError::value_out_of_range(value, &r#type.to_string()).into());
+                }
+                value.encode(writer)?;
+            }
+            (AbiType::ByteArray, AbiValue::ByteArray(value)) => value.encode(writer)?,
+            (AbiType::Array(element), AbiValue::Array(values)) => {
+                writer.write(values.len().into());
+                for value in values {
+                    self.encode(element, value, writer)?;
+                }
+            }
+            (AbiType::FixedArray(element, length), AbiValue::Array(values)) => {
+                if values.len() != *length {
+                    return Err(CodecError::length_mismatch(*length, values.len()).into());
+                }
+                for value in values {
+                    self.encode(element, value, writer)?;
+                }
+            }
+            (AbiType::Tuple(elements), AbiValue::Tuple(values)) => {
+                if values.len() != elements.len() {
+                    return Err(CodecError::length_mismatch(elements.len(), values.len()).into());
+                }
+                for (element, value) in elements.iter().zip(values) {
+                    self.encode(element, value, writer)?;
+                }
+            }
+            (AbiType::Named(name), value) => match (self.get(name)?, value) {
+                (TypeDefinition::Struct(members), AbiValue::Struct(value)) => {
+                    for (member_name, member_type) in members {
+                        let member_value = value
+                            .field(member_name)
+                            .ok_or_else(|| AbiError::FieldNotFound(member_name.to_owned()))?;
+                        self.encode(member_type, member_value, writer)?;
+                    }
+                }
+                (TypeDefinition::Enum(variants), AbiValue::Enum(value)) => {
+                    let (index, (_, variant_type)) = variants
+                        .iter()
+                        .enumerate()
+                        .find(|(_, (variant_name, _))| variant_name == &value.variant)
+                        .ok_or_else(|| AbiError::VariantNotFound(value.variant.clone()))?;
+
+                    writer.write(index.into());
+                    self.encode(variant_type, &value.value, writer)?;
+                }
+                _ => return Err(mismatch()),
+            },
+            _ => return Err(mismatch()),
+        }
+
+        Ok(())
+    }
+
+    /// Decodes a value of the type from the iterator.
+    pub fn decode<'a, I>(&self, r#type: &AbiType, iter: &mut I) -> Result<AbiValue, AbiError>
+    where
+        I: Iterator<Item = &'a Felt>,
+    {
+        Ok(match r#type {
+            AbiType::Felt => AbiValue::Felt(Felt::decode_iter(iter)?),
+            AbiType::Bool => AbiValue::Bool(bool::decode_iter(iter)?),
+            AbiType::Unsigned(256) => AbiValue::Unsigned(U256::decode_iter(iter)?),
+            AbiType::Unsigned(bits) => {
+                let value = u128::decode_iter(iter)?;
+                if *bits < 128 && value >> bits != 0 {
+                    return Err(CodecError::value_out_of_range(value, &r#type.to_string()).into());
+                }
+                AbiValue::Unsigned(value.into())
+            }
+            AbiType::Signed(bits) => {
+                let value = i128::decode_iter(iter)?;
+                if *bits < 128 && (value >> (bits - 1) != 0 && value >> (bits - 1) != -1) {
+                    return Err(CodecError::value_out_of_range(value, &r#type.to_string()).into());
+                }
+                AbiValue::Signed(value)
+            }
+            AbiType::ByteArray => AbiValue::ByteArray(ByteArray::decode_iter(iter)?),
+            AbiType::Array(element) => {
+                let length = iter.next().ok_or_else(CodecError::input_exhausted)?;
+                let length = length
+                    .to_usize()
+                    .ok_or_else(|| CodecError::value_out_of_range(length, "usize"))?;
+
+                AbiValue::Array(
+                    (0..length)
+                        .map(|_| self.decode(element, iter))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            AbiType::FixedArray(element, length) => AbiValue::Array(
+                (0..*length)
+                    .map(|_| self.decode(element, iter))
+                    .collect::<Result<_, _>>()?,
+            ),
+            AbiType::Tuple(elements) => AbiValue::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.decode(element, iter))
+                    .collect::<Result<_, _>>()?,
+            ),
+            AbiType::Named(name) => match self.get(name)? {
+                TypeDefinition::Struct(members) => AbiValue::Struct(StructValue {
+                    name: name.to_owned(),
+                    fields: members
+                        .iter()
+                        .map(|(member_name, member_type)| {
+                            Ok((member_name.to_owned(), self.decode(member_type, iter)?))
+                        })
+                        .collect::<Result<_, AbiError>>()?,
+                }),
+                TypeDefinition::Enum(variants) => {
+                    let tag = iter.next().ok_or_else(CodecError::input_exhausted)?;
+                    let (index, (variant, variant_type)) = tag
+                        .to_usize()
+                        .and_then(|index| Some((index, variants.get(index)?)))
+                        .ok_or_else(|| CodecError::unknown_enum_tag(tag, name))?;
+
+                    AbiValue::Enum(EnumValue {
+                        name: name.to_owned(),
+                        variant: variant.to_owned(),
+                        index,
+                        value: Box::new(self.decode(variant_type, iter)?),
+                    })
+                }
+            },
+        })
+    }
+}
+
+fn parse_members(members: &[AbiNamedMember]) -> Result<Vec<(String, AbiType)>, AbiError> {
+    members
+        .iter()
+        .map(|member| Ok((member.name.clone(), AbiType::parse(&member.r#type)?)))
+        .collect()
+}
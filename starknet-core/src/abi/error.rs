@@ -0,0 +1,101 @@
+use alloc::string::*;
+use core::fmt::Display;
+
+use crate::{codec::Error as CodecError, types::Felt};
+
+/// Possible errors when interpreting data against a contract ABI.
+#[derive(Debug)]
+pub enum AbiError {
+    /// The type name cannot be parsed.
+    InvalidTypeName(
+        /// Type name.
+        String,
+    ),
+    /// A referenced struct or enum is not defined in the ABI.
+    TypeNotFound(
+        /// Type name.
+        String,
+    ),
+    /// The function is not found in the ABI.
+    FunctionNotFound(
+        /// Function name.
+        String,
+    ),
+    /// The number of arguments is different from the number of function inputs.
+    ArgumentCountMismatch {
+        /// The number of function inputs.
+        expected: usize,
+        /// The number of arguments supplied.
+        actual: usize,
+    },
+    /// The value does not match the type it's encoded as.
+    UnexpectedValue(
+        /// Type name.
+        String,
+    ),
+    /// A struct field or named argument is missing from the value.
+    FieldNotFound(
+        /// Field name.
+        String,
+    ),
+    /// The enum variant is not found in the enum definition.
+    VariantNotFound(
+        /// Variant name.
+        String,
+    ),
+    /// The event has no keys, so it cannot be identified.
+    MissingEventSelector,
+    /// No event in the ABI matches the event selector.
+    UnknownEventSelector(
+        /// The unmatched selector.
+        Felt,
+    ),
+    /// A struct or enum contains itself, so it cannot be expanded.
+    RecursiveType(
+        /// Type name.
+        String,
+    ),
+    /// Not all input elements are consumed after decoding.
+    UnconsumedInput(
+        /// Number of elements remaining.
+        usize,
+    ),
+    /// Error decoding the underlying field elements.
+    Codec(CodecError),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AbiError {}
+
+impl Display for AbiError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidTypeName(type_name) => write!(f, "invalid type name: {type_name}"),
+            Self::TypeNotFound(type_name) => write!(f, "type `{type_name}` not defined"),
+            Self::FunctionNotFound(name) => write!(f, "function `{name}` not found"),
+            Self::ArgumentCountMismatch { expected, actual } => {
+                write!(f, "expected {expected} arguments but found {actual}")
+            }
+            Self::UnexpectedValue(type_name) => {
+                write!(f, "value does not match type `{type_name}`")
+            }
+            Self::FieldNotFound(name) => write!(f, "field `{name}` not found in value"),
+            Self::VariantNotFound(name) => write!(f, "enum variant `{name}` not defined"),
+            Self::MissingEventSelector => write!(f, "event has no keys"),
+            Self::UnknownEventSelector(selector) => {
+                write!(f, "no event matching selector {selector:#064x}")
+            }
+            Self::RecursiveType(type_name) => write!(f, "type `{type_name}` is recursive"),
+            Self::UnconsumedInput(remaining) => {
+                write!(f, "{remaining} elements remaining after decoding")
+            }
+            Self::Codec(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<CodecError> for AbiError {
+    fn from(value: CodecError) -> Self {
+        Self::Codec(value)
+    }
+}
@@ -0,0 +1,490 @@
+use alloc::{borrow::ToOwned, collections::BTreeMap, string::*, vec, vec::*};
+use core::slice::Iter;
+
+use super::{AbiError, AbiType, AbiValue, StructValue, TypeDefinitions};
+use crate::{
+    types::{
+        contract::{AbiEntry, AbiEvent, EventField, EventFieldKind, TypedAbiEvent},
+        EmittedEvent, Event, Felt,
+    },
+    utils::starknet_keccak,
+};
+
+/// Decoder for turning raw event keys and data into structured values, as described by a Sierra
+/// (Cairo 1) contract ABI.
+///
+/// For Cairo 2.x contracts, decoding starts from the top-level event enums, i.e. those not
+/// referenced by any other event. `#[nested]` enum variants are identified by the selector of the
+/// variant name, while `#[flat]` variants are looked into without consuming a key, which is how
+/// events from embedded components are usually emitted. The fields of the resulting event struct
+/// are read from either the keys or the data depending on whether they're annotated with `#[key]`
+/// or `#[data]`.
+///
+/// Cairo 1.x events are identified by the selector of the event name, with all fields read from
+/// the data.
+#[derive(Debug, Clone)]
+pub struct EventDecoder {
+    types: TypeDefinitions,
+    events: BTreeMap<String, EventDefinition>,
+    roots: Vec<RootEvent>,
+}
+
+/// An event decoded by [`EventDecoder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedEvent {
+    /// Full name of the event type that's been emitted, e.g.
+    /// `openzeppelin_token::erc20::erc20::ERC20Component::Transfer`.
+    pub name: String,
+    /// Names of the enum variants walked through from the top-level event to the emitted event
+    /// type, e.g. `["ERC20Event", "Transfer"]`. Empty for Cairo 1.x events.
+    pub path: Vec<String>,
+    /// The decoded event, which is usually a [`StructValue`].
+    pub value: AbiValue,
+}
+
+#[derive(Debug, Clone)]
+enum EventDefinition {
+    Struct(Vec<EventMember>),
+    Enum(Vec<EventMember>),
+}
+
+#[derive(Debug, Clone)]
+struct EventMember {
+    name: String,
+    r#type: AbiType,
+    kind: EventFieldKind,
+    selector: Felt,
+}
+
+#[derive(Debug, Clone)]
+enum RootEvent {
+    Typed(String),
+    Untyped {
+        name: String,
+        selector: Felt,
+        inputs: Vec<(String, AbiType)>,
+    },
+}
+
+impl EventDecoder {
+    /// Constructs [`EventDecoder`] from a Sierra (Cairo 1) contract ABI.
+    pub fn new(abi: &[AbiEntry]) -> Result<Self, AbiError> {
+        let types = TypeDefinitions::from_sierra_abi(abi)?;
+        let mut events = BTreeMap::new();
+        let mut roots = vec![];
+
+        for entry in abi {
+            match entry {
+                AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Struct(event))) => {
+                    events.insert(
+                        event.name.clone(),
+                        EventDefinition::Struct(parse_fields(&event.members)?),
+                    );
+                }
+                AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Enum(event))) => {
+                    events.insert(
+                        event.name.clone(),
+                        EventDefinition::Enum(parse_fields(&event.variants)?),
+                    );
+                    roots.push(RootEvent::Typed(event.name.clone()));
+                }
+                AbiEntry::Event(AbiEvent::Untyped(event)) => {
+                    roots.push(RootEvent::Untyped {
+                        name: event.name.clone(),
+                        selector: starknet_keccak(event.name.as_bytes()),
+                        inputs: event
+                            .inputs
+                            .iter()
+                            .map(|input| Ok((input.name.clone(), AbiType::parse(&input.r#type)?)))
+                            .collect::<Result<_, AbiError>>()?,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        // Event enums referenced by other events are not emitted on their own
+        roots.retain(|root| match root {
+            RootEvent::Typed(name) => !events.values().any(|event| {
+                let (EventDefinition::Struct(members) | EventDefinition::Enum(members)) = event;
+                members
+                    .iter()
+                    .any(|member| matches!(&member.r#type, AbiType::Named(type_name) if type_name == name))
+            }),
+            RootEvent::Untyped { .. } => true,
+        });
+
+        Ok(Self {
+            types,
+            events,
+            roots,
+        })
+    }
+
+    /// Decodes an event from its keys and data.
+    pub fn decode(&self, keys: &[Felt], data: &[Felt]) -> Result<DecodedEvent, AbiError> {
+        let selector = keys.first().ok_or(AbiError::MissingEventSelector)?;
+        let mut keys = keys.iter();
+        let mut data = data.iter();
+
+        let decoded = self
+            .roots
+            .iter()
+            .find_map(|root| match root {
+                RootEvent::Typed(name) if self.matches(name, selector) => {
+                    let mut path = vec![];
+                    Some(
+                        self.decode_typed(name, &mut keys, &mut data, &mut path)
+                            .map(|(name, value)| DecodedEvent { name, path, value }),
+                    )
+                }
+                RootEvent::Untyped {
+                    name,
+                    selector: root_selector,
+                    inputs,
+                } if root_selector == selector => {
+                    keys.next();
+                    Some(
+                        inputs
+                            .iter()
+                            .map(|(input_name, input_type)| {
+                                Ok((
+                                    input_name.to_owned(),
+                                    self.types.decode(input_type, &mut data)?,
+                                ))
+                            })
+                            .collect::<Result<_, AbiError>>()
+                            .map(|fields| DecodedEvent {
+                                name: name.to_owned(),
+                                path: vec![],
+                                value: AbiValue::Struct(StructValue {
+                                    name: name.to_owned(),
+                                    fields,
+                                }),
+                            }),
+                    )
+                }
+                _ => None,
+            })
+            .ok_or(AbiError::UnknownEventSelector(*selector))??;
+
+        let remaining = keys.len() + data.len();
+        if remaining != 0 {
+            return Err(AbiError::UnconsumedInput(remaining));
+        }
+
+        Ok(decoded)
+    }
+
+    /// Decodes an [`EmittedEvent`] as returned from `starknet_getEvents`.
+    pub fn decode_emitted_event(&self, event: &EmittedEvent) -> Result<DecodedEvent, AbiError> {
+        self.decode(&event.keys, &event.data)
+    }
+
+    /// Decodes an [`Event`] as found in transaction receipts.
+    pub fn decode_event(&self, event: &Event) -> Result<DecodedEvent, AbiError> {
+        self.decode(&event.keys, &event.data)
+    }
+
+    /// Checks whether an event enum has a variant matching the selector, either directly or
+    /// through flattened variants.
+    fn matches(&self, name: &str, selector: &Felt) -> bool {
+        match self.events.get(name) {
+            Some(EventDefinition::Enum(variants)) => {
+                variants.iter().any(|variant| match variant.kind {
+                    EventFieldKind::Flat => match &variant.r#type {
+                        AbiType::Named(type_name) => self.matches(type_name, selector),
+                        _ => false,
+                    },
+                    _ => &variant.selector == selector,
+                })
+            }
+            _ => false,
+        }
+    }
+
+    /// Decodes an event type, returning the name of the innermost event type along with its value.
+    fn decode_typed(
+        &self,
+        name: &str,
+        keys: &mut Iter<'_, Felt>,
+        data: &mut Iter<'_, Felt>,
+        path: &mut Vec<String>,
+    ) -> Result<(String, AbiValue), AbiError> {
+        match self.events.get(name) {
+            Some(EventDefinition::Enum(variants)) => {
+                let selector = keys
+                    .as_slice()
+                    .first()
+                    .ok_or(AbiError::MissingEventSelector)?;
+
+                let variant = variants
+                    .iter()
+                    .find(|variant| match variant.kind {
+                        EventFieldKind::Flat => match &variant.r#type {
+                            AbiType::Named(type_name) => self.matches(type_name, selector),
+                            _ => false,
+                        },
+                        _ => &variant.selector == selector,
+                    })
+                    .ok_or(AbiError::UnknownEventSelector(*selector))?;
+
+                if !matches!(variant.kind, EventFieldKind::Flat) {
+                    keys.next();
+                }
+                path.push(variant.name.clone());
+
+                match &variant.r#type {
+                    AbiType::Named(type_name) if self.events.contains_key(type_name) => {
+                        self.decode_typed(type_name, keys, data, path)
+                    }
+                    // Variants carrying non-event types are serialized into data
+                    variant_type => Ok((
+                        variant_type.to_string(),
+                        self.types.decode(variant_type, data)?,
+                    )),
+                }
+            }
+            Some(EventDefinition::Struct(members)) => {
+                let mut fields = vec![];
+
+                for member in members {
+                    let value = match (&member.kind, &member.r#type) {
+                        (EventFieldKind::Key, member_type) => {
+                            self.types.decode(member_type, keys)?
+                        }
+                        (
+                            EventFieldKind::Nested | EventFieldKind::Flat,
+                            AbiType::Named(type_name),
+                        ) if self.events.contains_key(type_name) => {
+                            self.decode_typed(type_name, keys, data, &mut vec![])?.1
+                        }
+                        (_, member_type) => self.types.decode(member_type, data)?,
+                    };
+                    fields.push((member.name.clone(), value));
+                }
+
+                Ok((
+                    name.to_owned(),
+                    AbiValue::Struct(StructValue {
+                        name: name.to_owned(),
+                        fields,
+                    }),
+                ))
+            }
+            None => Err(AbiError::TypeNotFound(name.to_owned())),
+        }
+    }
+}
+
+fn parse_fields(fields: &[EventField]) -> Result<Vec<EventMember>, AbiError> {
+    fields
+        .iter()
+        .map(|field| {
+            Ok(EventMember {
+                name: field.name.clone(),
+                r#type: AbiType::parse(&field.r#type)?,
+                kind: field.kind.clone(),
+                selector: starknet_keccak(field.name.as_bytes()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{contract::SierraClass, ByteArray, U256};
+
+    use super::*;
+
+    fn selector(name: &str) -> Felt {
+        starknet_keccak(name.as_bytes())
+    }
+
+    fn load_decoder(artifact: &str) -> EventDecoder {
+        let class = serde_json::from_str::<SierraClass>(artifact).unwrap();
+        EventDecoder::new(&class.abi).unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_nested_event() {
+        let decoder = load_decoder(include_str!(
+            "../../test-data/contracts/cairo2/artifacts/abi_types_sierra.txt"
+        ));
+
+        let decoded = decoder
+            .decode(
+                &[selector("ExampleEvent")],
+                &[
+                    Felt::from(100u32),
+                    Felt::ZERO,
+                    Felt::from(200u32),
+                    Felt::from(300u32),
+                    Felt::ONE,
+                    Felt::from(400u32),
+                    Felt::ZERO,
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(decoded.name, "abi_types::abi_types::AbiTypes::ExampleEvent");
+        assert_eq!(decoded.path, vec!["ExampleEvent"]);
+
+        let event = decoded.value.as_struct().unwrap();
+        assert_eq!(
+            event.field("value_a"),
+            Some(&AbiValue::Unsigned(U256::from(100u32)))
+        );
+
+        let value_b = event.field("value_b").unwrap().as_struct().unwrap();
+        assert_eq!(
+            value_b.field("field_a"),
+            Some(&AbiValue::Felt(200u32.into()))
+        );
+        let field_c = value_b.field("field_c").unwrap().as_enum().unwrap();
+        assert_eq!(field_c.variant, "variant_b");
+        assert_eq!(field_c.index, 1);
+        assert_eq!(*field_c.value, AbiValue::Unsigned(U256::from(400u32)));
+        assert_eq!(value_b.field("field_d"), Some(&AbiValue::Tuple(vec![])));
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_flat_event() {
+        let decoder = load_decoder(include_str!(
+            "../../test-data/contracts/cairo2/artifacts/abi_types_sierra.txt"
+        ));
+
+        let decoded = decoder.decode(&[selector("FlatEvent")], &[]).unwrap();
+
+        assert_eq!(decoded.name, "abi_types::abi_types::AbiTypes::StaticEvent");
+        assert_eq!(decoded.path, vec!["FlatEvent", "FlatEvent"]);
+        assert_eq!(decoded.value.as_struct().unwrap().fields, vec![]);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_component_event_with_keys() {
+        // Component events as emitted by OpenZeppelin contracts, with a `#[key]` field embedded
+        // in the contract event via `#[flat]`
+        let abi = serde_json::from_str::<Vec<AbiEntry>>(
+            r#"[
+                {
+                    "type": "event",
+                    "name": "token::ERC20Component::Transfer",
+                    "kind": "struct",
+                    "members": [
+                        { "name": "from", "type": "core::starknet::contract_address::ContractAddress", "kind": "key" },
+                        { "name": "to", "type": "core::starknet::contract_address::ContractAddress", "kind": "key" },
+                        { "name": "value", "type": "core::integer::u256", "kind": "data" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "token::ERC20Component::Event",
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "Transfer", "type": "token::ERC20Component::Transfer", "kind": "nested" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "token::Token::Upgraded",
+                    "kind": "struct",
+                    "members": [
+                        { "name": "version", "type": "core::byte_array::ByteArray", "kind": "data" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "token::Token::Event",
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "ERC20Event", "type": "token::ERC20Component::Event", "kind": "flat" },
+                        { "name": "Upgraded", "type": "token::Token::Upgraded", "kind": "nested" }
+                    ]
+                }
+            ]"#,
+        )
+        .unwrap();
+        let decoder = EventDecoder::new(&abi).unwrap();
+
+        let decoded = decoder
+            .decode(
+                &[selector("Transfer"), Felt::ONE, Felt::TWO],
+                &[Felt::from(1000u32), Felt::ONE],
+            )
+            .unwrap();
+        assert_eq!(decoded.name, "token::ERC20Component::Transfer");
+        assert_eq!(decoded.path, vec!["ERC20Event", "Transfer"]);
+
+        let event = decoded.value.as_struct().unwrap();
+        assert_eq!(event.field("from"), Some(&AbiValue::Felt(Felt::ONE)));
+        assert_eq!(event.field("to"), Some(&AbiValue::Felt(Felt::TWO)));
+        assert_eq!(
+            event.field("value"),
+            Some(&AbiValue::Unsigned(U256::from_words(1000, 1)))
+        );
+
+        let mut data = vec![];
+        crate::codec::Encode::encode(&ByteArray::from("v2"), &mut data).unwrap();
+        let decoded = decoder.decode(&[selector("Upgraded")], &data).unwrap();
+        assert_eq!(
+            decoded.value.as_struct().unwrap().field("version"),
+            Some(&AbiValue::ByteArray("v2".into()))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_untyped_event() {
+        let decoder = load_decoder(include_str!(
+            "../../test-data/contracts/cairo1/artifacts/erc20_sierra.txt"
+        ));
+
+        let decoded = decoder
+            .decode(
+                &[selector("Approval")],
+                &[Felt::ONE, Felt::TWO, Felt::THREE, Felt::ZERO],
+            )
+            .unwrap();
+        assert_eq!(decoded.name, "Approval");
+        assert!(decoded.path.is_empty());
+        assert_eq!(
+            decoded.value.as_struct().unwrap().field("value"),
+            Some(&AbiValue::Unsigned(U256::from(3u32)))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_event_errors() {
+        let decoder = load_decoder(include_str!(
+            "../../test-data/contracts/cairo2/artifacts/erc20_sierra.txt"
+        ));
+
+        assert!(matches!(
+            decoder.decode(&[], &[]),
+            Err(AbiError::MissingEventSelector)
+        ));
+        assert!(matches!(
+            decoder.decode(&[selector("Mint")], &[]),
+            Err(AbiError::UnknownEventSelector(_))
+        ));
+        assert!(matches!(
+            decoder.decode(
+                &[selector("Transfer")],
+                &[Felt::ONE, Felt::TWO, Felt::THREE]
+            ),
+            Err(AbiError::Codec(_))
+        ));
+        assert!(matches!(
+            decoder.decode(
+                &[selector("Transfer")],
+                &[Felt::ONE, Felt::TWO, Felt::THREE, Felt::ZERO, Felt::ZERO]
+            ),
+            Err(AbiError::UnconsumedInput(1))
+        ));
+    }
+}
@@ -0,0 +1,412 @@
+use alloc::{borrow::ToOwned, collections::BTreeMap, format, string::*, vec, vec::*};
+
+use super::{types::split_top_level, AbiError, AbiType};
+use crate::{
+    types::{
+        contract::{AbiEntry, AbiFunction, AbiInterface, AbiNamedMember, SierraClass},
+        Felt,
+    },
+    utils::starknet_keccak,
+};
+
+/// Structs and enums defined in an ABI, borrowed for rendering SNIP-5 function signatures.
+#[derive(Debug, Default)]
+struct SignatureFormatter<'a> {
+    definitions: BTreeMap<&'a str, Definition<'a>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Definition<'a> {
+    Struct(&'a [AbiNamedMember]),
+    Enum(&'a [AbiNamedMember]),
+}
+
+impl AbiInterface {
+    /// Computes the SNIP-5 interface ID, which is the XOR of the extended function selectors of
+    /// all functions in the interface.
+    ///
+    /// `abi` is the full contract ABI the interface is defined in, used for resolving the structs
+    /// and enums referenced by the functions.
+    pub fn interface_id(&self, abi: &[AbiEntry]) -> Result<Felt, AbiError> {
+        SignatureFormatter::new(abi).interface_id(self)
+    }
+}
+
+impl AbiFunction {
+    /// Computes the SNIP-5 extended function selector, which is the Starknet Keccak of the
+    /// function signature with all types expanded, e.g. `supports_interface(felt252)->E((),())`.
+    ///
+    /// `abi` is the full contract ABI the function is defined in, used for resolving the structs
+    /// and enums referenced by the function.
+    pub fn extended_selector(&self, abi: &[AbiEntry]) -> Result<Felt, AbiError> {
+        let signature = SignatureFormatter::new(abi).signature(self)?;
+        Ok(starknet_keccak(signature.as_bytes()))
+    }
+}
+
+impl SierraClass {
+    /// Computes the SNIP-5 interface IDs of all interfaces defined in the class ABI, along with
+    /// the interface names.
+    pub fn interface_ids(&self) -> Result<Vec<(&str, Felt)>, AbiError> {
+        let formatter = SignatureFormatter::new(&self.abi);
+
+        self.abi
+            .iter()
+            .filter_map(|entry| match entry {
+                AbiEntry::Interface(interface) => Some(interface),
+                _ => None,
+            })
+            .map(|interface| Ok((interface.name.as_str(), formatter.interface_id(interface)?)))
+            .collect()
+    }
+}
+
+impl<'a> SignatureFormatter<'a> {
+    fn new(abi: &'a [AbiEntry]) -> Self {
+        let mut formatter = Self::default();
+        formatter.add_entries(abi);
+        formatter
+    }
+
+    fn add_entries(&mut self, entries: &'a [AbiEntry]) {
+        for entry in entries {
+            match entry {
+                AbiEntry::Struct(item) => {
+                    self.definitions
+                        .insert(&item.name, Definition::Struct(&item.members));
+                }
+                AbiEntry::Enum(item) => {
+                    self.definitions
+                        .insert(&item.name, Definition::Enum(&item.variants));
+                }
+                AbiEntry::Interface(item) => self.add_entries(&item.items),
+                _ => {}
+            }
+        }
+    }
+
+    fn interface_id(&self, interface: &AbiInterface) -> Result<Felt, AbiError> {
+        let mut id = [0u8; 32];
+
+        for item in &interface.items {
+            if let AbiEntry::Function(function) = item {
+                let selector = starknet_keccak(self.signature(function)?.as_bytes()).to_bytes_be();
+                for (byte, selector_byte) in id.iter_mut().zip(selector) {
+                    *byte ^= selector_byte;
+                }
+            }
+        }
+
+        Ok(Felt::from_bytes_be(&id))
+    }
+
+    fn signature(&self, function: &AbiFunction) -> Result<String, AbiError> {
+        let mut stack = vec![];
+
+        let inputs = function
+            .inputs
+            .iter()
+            .map(|input| self.format_type(&input.r#type, &mut stack))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut signature = format!("{}({})", function.name, inputs.join(","));
+
+        // Cairo functions return at most one value
+        if let Some(output) = function.outputs.first() {
+            signature.push_str("->");
+            signature.push_str(&self.format_type(&output.r#type, &mut stack)?);
+        }
+
+        Ok(signature)
+    }
+
+    /// Formats a type according to SNIP-5: structs and tuples as `(T1,T2)`, enums as `E(T1,T2)`,
+    /// and other types by their names without paths, e.g. `Array<felt252>`.
+    ///
+    /// `stack` holds the structs and enums being expanded for detecting recursive types.
+    fn format_type(
+        &self,
+        type_name: &'a str,
+        stack: &mut Vec<&'a str>,
+    ) -> Result<String, AbiError> {
+        let type_name = type_name.trim();
+        let invalid = || AbiError::InvalidTypeName(type_name.to_owned());
+
+        if let Some(inner) = type_name.strip_prefix('@') {
+            return Ok(format!("@{}", self.format_type(inner, stack)?));
+        }
+
+        if let Some(inner) = type_name
+            .strip_prefix('(')
+            .and_then(|inner| inner.strip_suffix(')'))
+        {
+            let items = split_top_level(inner).ok_or_else(invalid)?;
+            return Ok(format!("({})", self.format_list(&items, stack)?));
+        }
+
+        if let Some(inner) = type_name
+            .strip_prefix('[')
+            .and_then(|inner| inner.strip_suffix(']'))
+        {
+            let (element, length) = inner.rsplit_once(';').ok_or_else(invalid)?;
+            return Ok(format!(
+                "[{};{}]",
+                self.format_type(element, stack)?,
+                length.trim()
+            ));
+        }
+
+        if let Some(definition) = self.definitions.get(type_name) {
+            if stack.contains(&type_name) {
+                return Err(AbiError::RecursiveType(type_name.to_owned()));
+            }
+
+            stack.push(type_name);
+            let (prefix, members) = match definition {
+                Definition::Struct(members) => ("", members),
+                Definition::Enum(variants) => ("E", variants),
+            };
+            let members = members
+                .iter()
+                .map(|member| member.r#type.as_str())
+                .collect::<Vec<_>>();
+            let formatted = format!("{prefix}({})", self.format_list(&members, stack)?);
+            stack.pop();
+
+            return Ok(formatted);
+        }
+
+        let (path, args) = AbiType::split_generic_args(type_name)?;
+        Ok(match (path, args.as_slice()) {
+            // Core types that are structs and enums themselves, in case they're not defined in the
+            // ABI.
+            ("core::bool", []) => "E((),())".to_owned(),
+            ("core::integer::u256", []) => "(u128,u128)".to_owned(),
+            ("core::array::Span", [element]) => {
+                format!("(@Array<{}>)", self.format_type(element, stack)?)
+            }
+            _ => {
+                let name = path.rsplit("::").next().unwrap_or(path);
+                if args.is_empty() {
+                    name.to_owned()
+                } else {
+                    format!("{name}<{}>", self.format_list(&args, stack)?)
+                }
+            }
+        })
+    }
+
+    fn format_list(&self, types: &[&'a str], stack: &mut Vec<&'a str>) -> Result<String, AbiError> {
+        Ok(types
+            .iter()
+            .map(|type_name| self.format_type(type_name, stack))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface_id::{SRC5, SRC6};
+
+    fn account_abi() -> Vec<AbiEntry> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "type": "struct",
+                "name": "core::starknet::account::Call",
+                "members": [
+                    { "name": "to", "type": "core::starknet::contract_address::ContractAddress" },
+                    { "name": "selector", "type": "core::felt252" },
+                    { "name": "calldata", "type": "core::array::Array::<core::felt252>" }
+                ]
+            },
+            {
+                "type": "struct",
+                "name": "core::array::Span::<core::felt252>",
+                "members": [
+                    { "name": "snapshot", "type": "@core::array::Array::<core::felt252>" }
+                ]
+            },
+            {
+                "type": "interface",
+                "name": "openzeppelin::account::interface::ISRC6",
+                "items": [
+                    {
+                        "type": "function",
+                        "name": "__execute__",
+                        "inputs": [
+                            {
+                                "name": "calls",
+                                "type": "core::array::Array::<core::starknet::account::Call>"
+                            }
+                        ],
+                        "outputs": [
+                            {
+                                "type": "core::array::Array::<core::array::Span::<core::felt252>>"
+                            }
+                        ],
+                        "state_mutability": "external"
+                    },
+                    {
+                        "type": "function",
+                        "name": "__validate__",
+                        "inputs": [
+                            {
+                                "name": "calls",
+                                "type": "core::array::Array::<core::starknet::account::Call>"
+                            }
+                        ],
+                        "outputs": [{ "type": "core::felt252" }],
+                        "state_mutability": "external"
+                    },
+                    {
+                        "type": "function",
+                        "name": "is_valid_signature",
+                        "inputs": [
+                            { "name": "hash", "type": "core::felt252" },
+                            { "name": "signature", "type": "core::array::Array::<core::felt252>" }
+                        ],
+                        "outputs": [{ "type": "core::felt252" }],
+                        "state_mutability": "view"
+                    }
+                ]
+            },
+            {
+                "type": "enum",
+                "name": "core::bool",
+                "variants": [
+                    { "name": "False", "type": "()" },
+                    { "name": "True", "type": "()" }
+                ]
+            },
+            {
+                "type": "interface",
+                "name": "openzeppelin::introspection::interface::ISRC5",
+                "items": [
+                    {
+                        "type": "function",
+                        "name": "supports_interface",
+                        "inputs": [{ "name": "interface_id", "type": "core::felt252" }],
+                        "outputs": [{ "type": "core::bool" }],
+                        "state_mutability": "view"
+                    }
+                ]
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_interface_id() {
+        let abi = account_abi();
+        let interfaces = abi
+            .iter()
+            .filter_map(|entry| match entry {
+                AbiEntry::Interface(interface) => Some(interface),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(interfaces[0].interface_id(&abi).unwrap(), SRC6);
+        assert_eq!(interfaces[1].interface_id(&abi).unwrap(), SRC5);
+
+        // The built-in fallbacks for core types give the same result
+        assert_eq!(
+            interfaces[0]
+                .interface_id(&[abi[0].clone(), abi[2].clone()])
+                .unwrap(),
+            SRC6
+        );
+        assert_eq!(interfaces[1].interface_id(&abi[4..]).unwrap(), SRC5);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_sierra_class_interface_ids() {
+        let class = serde_json::from_str::<SierraClass>(include_str!(
+            "../../test-data/contracts/cairo2.6/artifacts/erc20_sierra.txt"
+        ))
+        .unwrap();
+
+        let expected_id = [
+            "get_name()->felt252",
+            "get_symbol()->felt252",
+            "get_decimals()->u8",
+            "get_total_supply()->(u128,u128)",
+            "balance_of(ContractAddress)->(u128,u128)",
+            "allowance(ContractAddress,ContractAddress)->(u128,u128)",
+            "transfer(ContractAddress,(u128,u128))",
+            "transfer_from(ContractAddress,ContractAddress,(u128,u128))",
+            "approve(ContractAddress,(u128,u128))",
+            "increase_allowance(ContractAddress,(u128,u128))",
+            "decrease_allowance(ContractAddress,(u128,u128))",
+        ]
+        .into_iter()
+        .fold(Felt::ZERO, |acc, signature| {
+            (acc.to_biguint() ^ starknet_keccak(signature.as_bytes()).to_biguint()).into()
+        });
+
+        let interface_ids = class.interface_ids().unwrap();
+        assert_eq!(interface_ids, [("erc20::erc20::IERC20", expected_id)]);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_extended_selector() {
+        let abi = account_abi();
+        let AbiEntry::Interface(interface) = &abi[2] else {
+            unreachable!()
+        };
+        let AbiEntry::Function(function) = &interface.items[0] else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            SignatureFormatter::new(&abi).signature(function).unwrap(),
+            "__execute__(Array<(ContractAddress,felt252,Array<felt252>)>)->Array<(@Array<felt252>)>"
+        );
+        assert_eq!(
+            function.extended_selector(&abi).unwrap(),
+            starknet_keccak(
+                b"__execute__(Array<(ContractAddress,felt252,Array<felt252>)>)->Array<(@Array<felt252>)>"
+            )
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_interface_ids_recursive_type() {
+        let abi: Vec<AbiEntry> = serde_json::from_value(serde_json::json!([
+            {
+                "type": "struct",
+                "name": "test::Node",
+                "members": [
+                    { "name": "next", "type": "core::box::Box::<test::Node>" }
+                ]
+            },
+            {
+                "type": "interface",
+                "name": "test::INode",
+                "items": [
+                    {
+                        "type": "function",
+                        "name": "get",
+                        "inputs": [],
+                        "outputs": [{ "type": "test::Node" }],
+                        "state_mutability": "view"
+                    }
+                ]
+            }
+        ]))
+        .unwrap();
+        let AbiEntry::Interface(interface) = &abi[1] else {
+            unreachable!()
+        };
+
+        assert!(matches!(
+            interface.interface_id(&abi),
+            Err(AbiError::RecursiveType(_))
+        ));
+    }
+}
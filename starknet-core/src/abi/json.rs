@@ -0,0 +1,201 @@
+use alloc::{borrow::ToOwned, boxed::Box, string::*, vec, vec::*};
+
+use serde::{ser::SerializeMap, Serialize};
+use serde_json::Value as JsonValue;
+
+use super::{
+    definitions::{TypeDefinition, TypeDefinitions},
+    AbiError, AbiType, AbiValue, EnumValue, StructValue,
+};
+use crate::types::{ByteArray, Felt, U256};
+
+impl TypeDefinitions {
+    /// Converts a JSON value into a value of the type.
+    ///
+    /// Numbers can be either JSON numbers or strings in decimal or `0x`-prefixed hexadecimal. Byte
+    /// arrays are strings. Structs are objects keyed by field names. Enums are objects with the
+    /// variant name as the only key, or just the variant name as a string for variants without
+    /// data.
+    pub fn value_from_json(
+        &self,
+        r#type: &AbiType,
+        json: &JsonValue,
+    ) -> Result<AbiValue, AbiError> {
+        let mismatch = || AbiError::UnexpectedValue(r#type.to_string());
+
+        Ok(match (r#type, json) {
+            (AbiType::Felt, _) => AbiValue::Felt(json_to_felt(json).ok_or_else(mismatch)?),
+            (AbiType::Bool, JsonValue::Bool(value)) => AbiValue::Bool(*value),
+            (AbiType::Unsigned(_), _) => {
+                AbiValue::Unsigned(json_to_u256(json).ok_or_else(mismatch)?)
+            }
+            (AbiType::Signed(_), _) => AbiValue::Signed(json_to_i128(json).ok_or_else(mismatch)?),
+            (AbiType::ByteArray, JsonValue::String(value)) => {
+                AbiValue::ByteArray(ByteArray::from(value.as_str()))
+            }
+            (
+                AbiType::Array(element) | AbiType::FixedArray(element, _),
+                JsonValue::Array(values),
+            ) => AbiValue::Array(
+                values
+                    .iter()
+                    .map(|value| self.value_from_json(element, value))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (AbiType::Tuple(elements), JsonValue::Null) if elements.is_empty() => {
+                AbiValue::Tuple(vec![])
+            }
+            (AbiType::Tuple(elements), JsonValue::Array(values)) => {
+                if elements.len() != values.len() {
+                    return Err(mismatch());
+                }
+                AbiValue::Tuple(
+                    elements
+                        .iter()
+                        .zip(values)
+                        .map(|(element, value)| self.value_from_json(element, value))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            (AbiType::Named(name), _) => match (self.get(name)?, json) {
+                (TypeDefinition::Struct(members), JsonValue::Object(fields)) => {
+                    AbiValue::Struct(StructValue {
+                        name: name.to_owned(),
+                        fields: members
+                            .iter()
+                            .map(|(member_name, member_type)| {
+                                let field = fields.get(member_name).ok_or_else(|| {
+                                    AbiError::FieldNotFound(member_name.to_owned())
+                                })?;
+                                Ok((
+                                    member_name.to_owned(),
+                                    self.value_from_json(member_type, field)?,
+                                ))
+                            })
+                            .collect::<Result<_, AbiError>>()?,
+                    })
+                }
+                (TypeDefinition::Enum(variants), JsonValue::String(variant)) => {
+                    self.enum_from_json(name, variants, variant, &JsonValue::Null)?
+                }
+                (TypeDefinition::Enum(variants), JsonValue::Object(fields))
+                    if fields.len() == 1 =>
+                {
+                    let (variant, value) = fields.iter().next().unwrap();
+                    self.enum_from_json(name, variants, variant, value)?
+                }
+                _ => return Err(mismatch()),
+            },
+            _ => return Err(mismatch()),
+        })
+    }
+
+    fn enum_from_json(
+        &self,
+        name: &str,
+        variants: &[(String, AbiType)],
+        variant: &str,
+        value: &JsonValue,
+    ) -> Result<AbiValue, AbiError> {
+        let (index, (_, variant_type)) = variants
+            .iter()
+            .enumerate()
+            .find(|(_, (variant_name, _))| variant_name == variant)
+            .ok_or_else(|| AbiError::VariantNotFound(variant.to_owned()))?;
+
+        Ok(AbiValue::Enum(EnumValue {
+            name: name.to_owned(),
+            variant: variant.to_owned(),
+            index,
+            value: Box::new(self.value_from_json(variant_type, value)?),
+        }))
+    }
+}
+
+/// Serializes the value into a JSON-friendly representation that [`value_from_json`] accepts
+/// back. Integers are represented as decimal strings to avoid losing precision.
+///
+/// [`value_from_json`]: super::ContractAbi::value_from_json
+impl Serialize for AbiValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Felt(value) => serializer.serialize_str(&alloc::format!("{value:#x}")),
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::Unsigned(value) => serializer.serialize_str(&value.to_string()),
+            Self::Signed(value) => serializer.serialize_str(&value.to_string()),
+            Self::ByteArray(value) => {
+                serializer.serialize_str(&String::from_utf8_lossy(&Vec::<u8>::from(value.clone())))
+            }
+            Self::Array(values) | Self::Tuple(values) => values.serialize(serializer),
+            Self::Struct(value) => {
+                let mut map = serializer.serialize_map(Some(value.fields.len()))?;
+                for (name, value) in &value.fields {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            }
+            Self::Enum(value) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(&value.variant, &value.value)?;
+                map.end()
+            }
+        }
+    }
+}
+
+fn json_to_felt(json: &JsonValue) -> Option<Felt> {
+    match json {
+        JsonValue::Number(value) => value.as_u64().map(Felt::from),
+        JsonValue::String(value) => match value.strip_prefix("0x") {
+            Some(_) => Felt::from_hex(value).ok(),
+            None => Felt::from_dec_str(value).ok(),
+        },
+        _ => None,
+    }
+}
+
+fn json_to_u256(json: &JsonValue) -> Option<U256> {
+    match json {
+        JsonValue::Number(value) => value.as_u64().map(U256::from),
+        JsonValue::String(value) => {
+            let (digits, radix) = match value.strip_prefix("0x") {
+                Some(digits) => (digits, 16),
+                None => (value.as_str(), 10),
+            };
+            if digits.is_empty() {
+                return None;
+            }
+
+            // Little-endian 64-bit limbs
+            let mut limbs = [0u64; 4];
+            for char in digits.chars() {
+                let mut carry = char.to_digit(radix)? as u128;
+                for limb in &mut limbs {
+                    let product = (*limb as u128) * (radix as u128) + carry;
+                    *limb = product as u64;
+                    carry = product >> 64;
+                }
+                if carry != 0 {
+                    return None;
+                }
+            }
+
+            Some(U256::from_words(
+                limbs[0] as u128 | ((limbs[1] as u128) << 64),
+                limbs[2] as u128 | ((limbs[3] as u128) << 64),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn json_to_i128(json: &JsonValue) -> Option<i128> {
+    match json {
+        JsonValue::Number(value) => value.as_i64().map(i128::from),
+        JsonValue::String(value) => value.parse().ok(),
+        _ => None,
+    }
+}
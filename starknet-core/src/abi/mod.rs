@@ -0,0 +1,21 @@
+mod error;
+pub use error::AbiError;
+
+mod types;
+pub use types::AbiType;
+
+mod definitions;
+use definitions::TypeDefinitions;
+
+mod value;
+pub use value::{AbiValue, EnumValue, StructValue};
+
+mod json;
+
+mod contract;
+pub use contract::{ContractAbi, FunctionSignature};
+
+mod event;
+pub use event::{DecodedEvent, EventDecoder};
+
+mod interface;
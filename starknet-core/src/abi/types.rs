@@ -0,0 +1,289 @@
+use alloc::{borrow::ToOwned, boxed::Box, string::*, vec, vec::*};
+use core::{fmt::Display, str::FromStr};
+
+use super::AbiError;
+
+/// A Cairo type as referenced by name in contract ABIs, e.g.
+/// `core::array::Span::<core::integer::u64>`.
+///
+/// Types sharing the same serialization are collapsed into one variant. For example, both
+/// `ContractAddress` and `ClassHash` are parsed as [`Felt`](Self::Felt), while `NonZero<T>` is
+/// parsed as `T`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    /// `felt252` or any other type serialized as a single field element without range checks,
+    /// such as `ContractAddress`.
+    Felt,
+    /// `bool`.
+    Bool,
+    /// Unsigned integer with the number of bits, from `u8` to `u256`.
+    Unsigned(u16),
+    /// Signed integer with the number of bits, from `i8` to `i128`.
+    Signed(u16),
+    /// `ByteArray`.
+    ByteArray,
+    /// `Array<T>` or `Span<T>`, serialized with a length prefix.
+    Array(Box<Self>),
+    /// Fixed-size array `[T; N]`, serialized without a length prefix.
+    FixedArray(Box<Self>, usize),
+    /// Tuple type. The unit type `()` is represented as an empty tuple.
+    Tuple(Vec<Self>),
+    /// Struct or enum defined in the ABI, referenced by its full name including generic
+    /// arguments.
+    Named(String),
+}
+
+impl AbiType {
+    /// Parses an ABI type name.
+    pub fn parse(type_name: &str) -> Result<Self, AbiError> {
+        let type_name = type_name.trim();
+        let invalid = || AbiError::InvalidTypeName(type_name.to_owned());
+
+        if let Some(inner) = type_name
+            .strip_prefix('(')
+            .and_then(|inner| inner.strip_suffix(')'))
+        {
+            return Ok(Self::Tuple(
+                split_top_level(inner)
+                    .ok_or_else(invalid)?
+                    .into_iter()
+                    .map(Self::parse)
+                    .collect::<Result<_, _>>()?,
+            ));
+        }
+
+        if let Some(inner) = type_name
+            .strip_prefix('[')
+            .and_then(|inner| inner.strip_suffix(']'))
+        {
+            let (element, length) = inner.rsplit_once(';').ok_or_else(invalid)?;
+            return Ok(Self::FixedArray(
+                Box::new(Self::parse(element)?),
+                length.trim().parse().map_err(|_| invalid())?,
+            ));
+        }
+
+        let (path, args) = Self::split_generic_args(type_name)?;
+
+        Ok(match (path, args.as_slice()) {
+            (
+                "core::felt252"
+                | "core::starknet::contract_address::ContractAddress"
+                | "core::starknet::class_hash::ClassHash"
+                | "core::starknet::storage_access::StorageAddress"
+                | "core::starknet::eth_address::EthAddress"
+                | "core::bytes_31::bytes31",
+                [],
+            ) => Self::Felt,
+            ("core::bool", []) => Self::Bool,
+            ("core::integer::u8", []) => Self::Unsigned(8),
+            ("core::integer::u16", []) => Self::Unsigned(16),
+            ("core::integer::u32", []) => Self::Unsigned(32),
+            ("core::integer::u64", []) => Self::Unsigned(64),
+            ("core::integer::u128", []) => Self::Unsigned(128),
+            ("core::integer::u256", []) => Self::Unsigned(256),
+            ("core::integer::i8", []) => Self::Signed(8),
+            ("core::integer::i16", []) => Self::Signed(16),
+            ("core::integer::i32", []) => Self::Signed(32),
+            ("core::integer::i64", []) => Self::Signed(64),
+            ("core::integer::i128", []) => Self::Signed(128),
+            ("core::byte_array::ByteArray", []) => Self::ByteArray,
+            ("core::array::Array" | "core::array::Span", [element]) => {
+                Self::Array(Box::new(Self::parse(element)?))
+            }
+            ("core::zeroable::NonZero", [inner]) => Self::parse(inner)?,
+            _ => Self::Named(type_name.to_owned()),
+        })
+    }
+
+    /// Splits a path type name into the path itself and its generic arguments, e.g.
+    /// `core::array::Span::<core::felt252>` into `core::array::Span` and `[core::felt252]`.
+    pub fn split_generic_args(type_name: &str) -> Result<(&str, Vec<&str>), AbiError> {
+        let type_name = type_name.trim();
+        let invalid = || AbiError::InvalidTypeName(type_name.to_owned());
+
+        let (path, args) = match type_name.find("::<") {
+            Some(ind) => (
+                &type_name[..ind],
+                split_top_level(
+                    type_name[(ind + 3)..]
+                        .strip_suffix('>')
+                        .ok_or_else(invalid)?,
+                )
+                .ok_or_else(invalid)?,
+            ),
+            None => (type_name, vec![]),
+        };
+        if path.is_empty() || path.contains(['(', ')', '[', ']', '<', '>', ',', ';']) {
+            return Err(invalid());
+        }
+
+        Ok((path, args))
+    }
+
+    /// Parses a type name from a legacy (Cairo 0) ABI. Pointers are not accepted here as they
+    /// only make sense as arrays following a length parameter.
+    pub fn parse_legacy(type_name: &str) -> Result<Self, AbiError> {
+        let type_name = type_name.trim();
+        let invalid = || AbiError::InvalidTypeName(type_name.to_owned());
+
+        if let Some(inner) = type_name
+            .strip_prefix('(')
+            .and_then(|inner| inner.strip_suffix(')'))
+        {
+            // Tuple members can be named as in `(x: felt, y: felt)`
+            return Ok(Self::Tuple(
+                split_top_level(inner)
+                    .ok_or_else(invalid)?
+                    .into_iter()
+                    .map(|member| match member.split_once(':') {
+                        Some((_, member_type)) => Self::parse_legacy(member_type),
+                        None => Self::parse_legacy(member),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ));
+        }
+
+        match type_name {
+            "felt" => Ok(Self::Felt),
+            _ if type_name.is_empty()
+                || !type_name
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '.') =>
+            {
+                Err(invalid())
+            }
+            _ => Ok(Self::Named(type_name.to_owned())),
+        }
+    }
+}
+
+impl FromStr for AbiType {
+    type Err = AbiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for AbiType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Felt => write!(f, "core::felt252"),
+            Self::Bool => write!(f, "core::bool"),
+            Self::Unsigned(bits) => write!(f, "core::integer::u{bits}"),
+            Self::Signed(bits) => write!(f, "core::integer::i{bits}"),
+            Self::ByteArray => write!(f, "core::byte_array::ByteArray"),
+            Self::Array(element) => write!(f, "core::array::Array::<{element}>"),
+            Self::FixedArray(element, length) => write!(f, "[{element}; {length}]"),
+            Self::Tuple(elements) => {
+                write!(f, "(")?;
+                for (ind, element) in elements.iter().enumerate() {
+                    if ind > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                if elements.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Self::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Splits a comma-separated list of types, ignoring commas inside nested types. Returns `None` if
+/// brackets are unbalanced.
+pub(super) fn split_top_level(list: &str) -> Option<Vec<&str>> {
+    let mut items = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (ind, char) in list.char_indices() {
+        match char {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                items.push(list[start..ind].trim());
+                start = ind + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return None;
+    }
+
+    // Allows trailing commas as in `(core::felt252,)`
+    let last = list[start..].trim();
+    if !last.is_empty() {
+        items.push(last);
+    }
+    if items.iter().any(|item| item.is_empty()) {
+        return None;
+    }
+
+    Some(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_parse_abi_type() {
+        for (raw, parsed) in [
+            ("core::felt252", AbiType::Felt),
+            (
+                "core::starknet::contract_address::ContractAddress",
+                AbiType::Felt,
+            ),
+            ("core::integer::u256", AbiType::Unsigned(256)),
+            ("core::integer::i8", AbiType::Signed(8)),
+            ("()", AbiType::Tuple(vec![])),
+            ("(core::felt252,)", AbiType::Tuple(vec![AbiType::Felt])),
+            (
+                "(core::bool, (core::integer::u8, core::felt252))",
+                AbiType::Tuple(vec![
+                    AbiType::Bool,
+                    AbiType::Tuple(vec![AbiType::Unsigned(8), AbiType::Felt]),
+                ]),
+            ),
+            (
+                "core::array::Span::<core::array::Array::<core::felt252>>",
+                AbiType::Array(Box::new(AbiType::Array(Box::new(AbiType::Felt)))),
+            ),
+            (
+                "[core::integer::u32; 3]",
+                AbiType::FixedArray(Box::new(AbiType::Unsigned(32)), 3),
+            ),
+            (
+                "core::zeroable::NonZero::<core::integer::u64>",
+                AbiType::Unsigned(64),
+            ),
+            (
+                "core::option::Option::<(core::felt252, core::integer::u8)>",
+                AbiType::Named("core::option::Option::<(core::felt252, core::integer::u8)>".into()),
+            ),
+        ] {
+            assert_eq!(AbiType::parse(raw).unwrap(), parsed);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_parse_invalid_abi_type() {
+        for raw in [
+            "",
+            "(core::felt252",
+            "core::array::Array::<core::felt252",
+            "(core::felt252,,core::felt252)",
+            "[core::felt252; x]",
+        ] {
+            assert!(AbiType::parse(raw).is_err(), "{raw}");
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::{boxed::Box, string::*, vec::*};
+
+use crate::types::{ByteArray, Felt, U256};
+
+/// A Cairo value whose shape is only known at runtime from a contract ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiValue {
+    /// `felt252` or any other type represented as [`AbiType::Felt`](super::AbiType::Felt).
+    Felt(Felt),
+    /// `bool`.
+    Bool(bool),
+    /// Unsigned integer of any width.
+    Unsigned(U256),
+    /// Signed integer of any width.
+    Signed(i128),
+    /// `ByteArray`.
+    ByteArray(ByteArray),
+    /// `Array<T>`, `Span<T>` or `[T; N]`.
+    Array(Vec<Self>),
+    /// Tuple, including the unit type `()`.
+    Tuple(Vec<Self>),
+    /// Struct defined in the ABI.
+    Struct(StructValue),
+    /// Enum defined in the ABI.
+    Enum(EnumValue),
+}
+
+/// Value of a struct defined in the ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructValue {
+    /// Full name of the struct type.
+    pub name: String,
+    /// Name and value of each field, in declaration order.
+    pub fields: Vec<(String, AbiValue)>,
+}
+
+/// Value of an enum defined in the ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumValue {
+    /// Full name of the enum type.
+    pub name: String,
+    /// Name of the variant.
+    pub variant: String,
+    /// Index of the variant in declaration order, which is also its serialized tag.
+    pub index: usize,
+    /// Value carried by the variant, which is an empty tuple for variants without data.
+    pub value: Box<AbiValue>,
+}
+
+impl AbiValue {
+    /// Gets the value as a [`Felt`], if it is one.
+    pub const fn as_felt(&self) -> Option<Felt> {
+        match self {
+            Self::Felt(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an unsigned integer, if it is one.
+    pub const fn as_unsigned(&self) -> Option<U256> {
+        match self {
+            Self::Unsigned(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as a struct, if it is one.
+    pub const fn as_struct(&self) -> Option<&StructValue> {
+        match self {
+            Self::Struct(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value as an enum, if it is one.
+    pub const fn as_enum(&self) -> Option<&EnumValue> {
+        match self {
+            Self::Enum(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl StructValue {
+    /// Gets the value of a field by name.
+    pub fn field(&self, name: &str) -> Option<&AbiValue> {
+        self.fields
+            .iter()
+            .find(|(field_name, _)| field_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl From<Felt> for AbiValue {
+    fn from(value: Felt) -> Self {
+        Self::Felt(value)
+    }
+}
+
+impl From<bool> for AbiValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<U256> for AbiValue {
+    fn from(value: U256) -> Self {
+        Self::Unsigned(value)
+    }
+}
+
+impl From<ByteArray> for AbiValue {
+    fn from(value: ByteArray) -> Self {
+        Self::ByteArray(value)
+    }
+}
+
+impl From<Vec<Self>> for AbiValue {
+    fn from(value: Vec<Self>) -> Self {
+        Self::Array(value)
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for AbiValue {
+                fn from(value: $ty) -> Self {
+                    Self::Unsigned(value.into())
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for AbiValue {
+                fn from(value: $ty) -> Self {
+                    Self::Signed(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128);
+impl_from_signed!(i8, i16, i32, i64, i128);
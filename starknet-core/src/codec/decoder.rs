@@ -0,0 +1,311 @@
+use core::marker::PhantomData;
+
+use num_traits::ToPrimitive;
+
+use crate::types::Felt;
+
+use super::{CountingIter, Decode, Error};
+
+/// A streaming decoder over a borrowed list of [`Felt`]s.
+///
+/// Unlike [`Decode::decode`], which decodes a single value from the whole input, [`Decoder`] keeps
+/// track of its position in the input, allowing values to be decoded one after another and arrays
+/// to be decoded lazily element by element. Errors carry the index of the [`Felt`] in the whole
+/// input where decoding failed.
+///
+/// # Example
+///
+/// ```rust
+/// use starknet_core::{codec::Decoder, types::Felt};
+///
+/// let input = [Felt::THREE, Felt::TWO, Felt::from(10), Felt::from(20)];
+/// let mut decoder = Decoder::new(&input);
+///
+/// assert_eq!(decoder.decode::<u8>().unwrap(), 3);
+///
+/// let mut span = decoder.decode_span::<u32>().unwrap();
+/// assert_eq!(span.len(), 2);
+/// assert_eq!(span.next().unwrap().unwrap(), 10);
+/// assert_eq!(span.next().unwrap().unwrap(), 20);
+/// assert!(span.next().is_none());
+///
+/// decoder.finish().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    input: &'a [Felt],
+    position: usize,
+    /// Number of elements of the last span not decoded yet.
+    span_remaining: usize,
+}
+
+/// A lazy iterator over the elements of a length-prefixed array, created by
+/// [`Decoder::decode_span`].
+///
+/// Elements must be consumed before using the [`Decoder`] again, as they are decoded in place from
+/// the input. Using the [`Decoder`] with elements left returns an error instead of misreading them
+/// as the following values.
+#[derive(Debug)]
+pub struct SpanDecoder<'d, 'a, T> {
+    decoder: &'d mut Decoder<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a> Decoder<'a> {
+    /// Constructs a new [`Decoder`] starting at the beginning of the input.
+    pub const fn new(input: &'a [Felt]) -> Self {
+        Self {
+            input,
+            position: 0,
+            span_remaining: 0,
+        }
+    }
+
+    /// Gets the index of the next [`Felt`] to be decoded.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Gets the number of [`Felt`]s left in the input.
+    pub const fn remaining(&self) -> usize {
+        self.input.len() - self.position
+    }
+
+    /// Returns `true` if the whole input has been consumed.
+    pub const fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Decodes the next value.
+    pub fn decode<T>(&mut self) -> Result<T, Error>
+    where
+        T: Decode<'a>,
+    {
+        self.check_span_consumed()?;
+        self.decode_next()
+    }
+
+    fn decode_next<T>(&mut self) -> Result<T, Error>
+    where
+        T: Decode<'a>,
+    {
+        let mut iter = CountingIter::new(self.input[self.position..].iter());
+        let result = T::decode_iter(&mut iter);
+
+        match result {
+            Ok(value) => {
+                self.position += iter.consumed;
+                Ok(value)
+            }
+            Err(err) => {
+                let position = self.position + iter.error_position();
+                self.position += iter.consumed;
+                Err(err.at_position(position))
+            }
+        }
+    }
+
+    /// Reads the length prefix of an array and returns an iterator decoding its elements lazily.
+    pub fn decode_span<T>(&mut self) -> Result<SpanDecoder<'_, 'a, T>, Error>
+    where
+        T: Decode<'a>,
+    {
+        let length = self.decode::<Felt>()?;
+        let length = length.to_usize().ok_or_else(|| {
+            Error::value_out_of_range(length, "usize").at_position(self.position - 1)
+        })?;
+
+        self.span_remaining = length;
+        Ok(SpanDecoder {
+            decoder: self,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Borrows the next `count` [`Felt`]s without decoding them.
+    pub fn take(&mut self, count: usize) -> Result<&'a [Felt], Error> {
+        self.check_span_consumed()?;
+
+        if count > self.remaining() {
+            return Err(Error::input_exhausted().at_position(self.input.len()));
+        }
+
+        let felts = &self.input[self.position..(self.position + count)];
+        self.position += count;
+        Ok(felts)
+    }
+
+    /// Makes sure the whole input has been consumed.
+    pub fn finish(self) -> Result<(), Error> {
+        self.check_span_consumed()?;
+
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::custom(format_args!(
+                "{} unexpected trailing felts",
+                self.remaining()
+            ))
+            .at_position(self.position))
+        }
+    }
+
+    fn check_span_consumed(&self) -> Result<(), Error> {
+        if self.span_remaining == 0 {
+            Ok(())
+        } else {
+            Err(Error::custom(format_args!(
+                "{} unconsumed span elements",
+                self.span_remaining
+            ))
+            .at_position(self.position))
+        }
+    }
+}
+
+impl<T> SpanDecoder<'_, '_, T> {
+    /// Gets the number of elements left to be decoded.
+    pub const fn remaining(&self) -> usize {
+        self.decoder.span_remaining
+    }
+}
+
+impl<'a, T> Iterator for SpanDecoder<'_, 'a, T>
+where
+    T: Decode<'a>,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.decoder.span_remaining == 0 {
+            return None;
+        }
+
+        self.decoder.span_remaining -= 1;
+        let result = self.decoder.decode_next();
+
+        // The position of the following elements is unknown after a failure.
+        if result.is_err() {
+            self.decoder.span_remaining = 0;
+        }
+
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining(), Some(self.remaining()))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SpanDecoder<'_, 'a, T> where T: Decode<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::*;
+
+    use super::*;
+
+    #[derive(Debug, Decode)]
+    #[starknet(core = "crate")]
+    #[allow(unused)]
+    struct Inner {
+        a: Felt,
+        b: u8,
+    }
+
+    #[derive(Debug, Decode)]
+    #[starknet(core = "crate")]
+    #[allow(unused)]
+    struct Outer {
+        x: Felt,
+        inner: Inner,
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decoder_values() {
+        let input = [Felt::ONE, Felt::TWO, Felt::THREE];
+        let mut decoder = Decoder::new(&input);
+
+        assert_eq!(decoder.decode::<u8>().unwrap(), 1);
+        assert_eq!(decoder.position(), 1);
+        assert_eq!(decoder.remaining(), 2);
+
+        let felts = decoder.take(2).unwrap();
+        assert!(core::ptr::eq(felts, &input[1..]));
+        assert!(decoder.is_empty());
+
+        assert!(decoder.take(1).is_err());
+        decoder.finish().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decoder_span() {
+        let input = [
+            Felt::THREE,
+            Felt::ONE,
+            Felt::TWO,
+            Felt::from(256),
+            Felt::from(4),
+        ];
+        let mut decoder = Decoder::new(&input);
+
+        let mut span = decoder.decode_span::<u8>().unwrap();
+        assert_eq!(span.len(), 3);
+        assert_eq!(span.next().unwrap().unwrap(), 1);
+        assert_eq!(span.next().unwrap().unwrap(), 2);
+        assert_eq!(span.remaining(), 1);
+
+        let err = span.next().unwrap().unwrap_err();
+        assert_eq!(err.position(), Some(3));
+        assert!(span.next().is_none());
+
+        assert_eq!(decoder.decode::<u8>().unwrap(), 4);
+        decoder.finish().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decoder_span_unconsumed() {
+        let input = [Felt::TWO, Felt::ONE, Felt::TWO, Felt::THREE];
+        let mut decoder = Decoder::new(&input);
+
+        let mut span = decoder.decode_span::<u8>().unwrap();
+        assert_eq!(span.next().unwrap().unwrap(), 1);
+
+        // The remaining element is not misread as the next value
+        assert_eq!(
+            decoder.decode::<u8>().unwrap_err().to_string(),
+            "at felt index 2: 1 unconsumed span elements"
+        );
+        assert!(decoder.take(1).is_err());
+        assert!(decoder.decode_span::<u8>().is_err());
+        assert!(decoder.finish().is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decoder_error_context() {
+        let input = [Felt::ONE, Felt::TWO, Felt::THREE, Felt::from(256)];
+        let mut decoder = Decoder::new(&input);
+
+        assert_eq!(decoder.decode::<Felt>().unwrap(), Felt::ONE);
+        assert_eq!(
+            decoder.decode::<Outer>().unwrap_err().to_string(),
+            "at felt index 3 while decoding field `b` of `Inner` in field `inner` of `Outer`: \
+             value `256` is out of range for type `u8`"
+        );
+
+        assert_eq!(
+            Outer::decode(&input[..2]).unwrap_err().to_string(),
+            "at felt index 2 while decoding field `b` of `Inner` in field `inner` of `Outer`: \
+             unexpected end of input stream"
+        );
+
+        assert_eq!(
+            Decoder::new(&input).finish().unwrap_err().to_string(),
+            "at felt index 0: 4 unexpected trailing felts"
+        );
+    }
+}
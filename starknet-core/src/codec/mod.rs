@@ -4,10 +4,13 @@ use starknet_crypto::{PedersenHasher, PoseidonHasher};
 
 use num_traits::ToPrimitive;
 
-use crate::types::{Felt, U256};
+use crate::types::{ByteArray, Felt, U256};
 
 pub use starknet_core_derive::{Decode, Encode};
 
+mod decoder;
+pub use decoder::{Decoder, SpanDecoder};
+
 const I128_MIN: Felt =
     Felt::from_hex_unchecked("0x0800000000000010ffffffffffffffff80000000000000000000000000000001");
 const I128_MAX: Felt =
@@ -27,7 +30,8 @@ pub trait FeltWriter {
 /// Any type that can be serialized into a series of [Felt]s. This trait corresponds to the
 /// `serialize` function of the Cairo `Serde` trait.
 ///
-/// This trait can be derived as long as all the fields in type implement [`Encode`].
+/// This trait can be derived as long as all the fields in type implement [`Encode`]. Type parameters
+/// used by the fields are required to implement [`Encode`] in the derived implementation.
 ///
 /// # Example
 ///
@@ -54,6 +58,66 @@ pub trait FeltWriter {
 ///
 /// assert_eq!(vec![Felt::THREE, Felt::ZERO, Felt::ONE], serialized);
 /// ```
+///
+/// # Attributes
+///
+/// The derive macros for [`Encode`] and [`Decode`] accept these `#[starknet(...)]` attributes:
+///
+/// - `core = "path"` on the type: sets the path to the `starknet-core` crate root.
+/// - `transparent` on the type: asserts that a struct has exactly one field that is not skipped.
+///   Such a struct is serialized exactly like that field.
+/// - `skip` on a field: leaves the field out of the serialization. The field is set to
+///   [`Default::default()`] when decoding.
+/// - `with = "module"` on a field: serializes the field with the `encode` and `decode_iter`
+///   functions in `module` instead of its own [`Encode`] and [`Decode`] implementations. The
+///   functions have the same signatures as the trait methods, taking the field value as the first
+///   argument of `encode`.
+/// - `flatten` on a field: accepted for compatibility with `serde`. Cairo serializes members back
+///   to back, so the field is already serialized inline.
+/// - `index = N` on an enum variant: sets the tag of the variant. Variants without an explicit
+///   index use the tag following the one of the previous variant.
+///
+/// ```rust
+/// use starknet_core::codec::Encode;
+/// # use starknet_core::types::Felt;
+///
+/// mod as_felt {
+///     # use starknet_core::{codec::{Error, FeltWriter}, types::Felt};
+///     pub fn encode<W: FeltWriter>(value: &u64, writer: &mut W) -> Result<(), Error> {
+///         writer.write(Felt::from(*value * 2));
+///         Ok(())
+///     }
+/// #   pub fn decode_iter<'a, T>(iter: &mut T) -> Result<u64, Error>
+/// #   where
+/// #       T: Iterator<Item = &'a Felt>,
+/// #   {
+/// #       unimplemented!()
+/// #   }
+/// }
+///
+/// #[derive(Encode)]
+/// # #[starknet(core = "starknet_core")]
+/// enum CairoType {
+///     A,
+///     #[starknet(index = 5)]
+///     B {
+///         #[starknet(with = "as_felt")]
+///         a: u64,
+///         #[starknet(skip)]
+///         b: bool,
+///     },
+///     C,
+/// }
+///
+/// let mut serialized = vec![];
+/// CairoType::B { a: 3, b: true }.encode(&mut serialized).unwrap();
+/// CairoType::C.encode(&mut serialized).unwrap();
+///
+/// assert_eq!(
+///     vec![Felt::from(5), Felt::from(6), Felt::from(6)],
+///     serialized
+/// );
+/// ```
 pub trait Encode {
     /// Converts the type into a list of [`Felt`] and append them into the writer.
     fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error>;
@@ -62,7 +126,10 @@ pub trait Encode {
 /// Any type that can be deserialized from a series of [Felt]s. This trait corresponds to the
 /// `deserialize` function of the Cairo `Serde` trait.
 ///
-/// This trait can be derived as long as all the fields in type implement [`Encode`].
+/// This trait can be derived as long as all the fields in type implement [`Decode`]. See [`Encode`]
+/// for the attributes accepted by the derive macro. Type parameters used by the fields are required
+/// to implement [`Decode`] in the derived implementation. A type with exactly one lifetime
+/// parameter is decoded with that lifetime, allowing it to borrow [`Felt`]s from the input.
 ///
 /// # Example
 ///
@@ -89,12 +156,14 @@ pub trait Encode {
 /// );
 /// ```
 pub trait Decode<'a>: Sized {
-    /// Converts into the type from a list of [`Felt`].
+    /// Converts into the type from a list of [`Felt`]. Errors carry the index of the [`Felt`]
+    /// where decoding failed.
     fn decode<T>(reader: T) -> Result<Self, Error>
     where
         T: IntoIterator<Item = &'a Felt>,
     {
-        Self::decode_iter(&mut reader.into_iter())
+        let mut iter = CountingIter::new(reader.into_iter());
+        Self::decode_iter(&mut iter).map_err(|err| err.at_position(iter.error_position()))
     }
 
     /// Converts into the type from an iterator of references to [`Felt`].
@@ -110,12 +179,25 @@ pub trait Decode<'a>: Sized {
 /// attention to fix anyway; even when handling untrusted data, the program is likely to only be
 /// interested in knowing that an error _did_ occur, instead of handling based on cause.
 ///
+/// To help with debugging, decoding errors carry the index of the [`Felt`] where decoding failed
+/// when decoded through [`Decode::decode`] or [`Decoder`], and the fields being decoded when
+/// returned from derived [`Decode`] implementations.
+///
 /// There might be cases where allocations must be avoided. A feature could be added in the future
 /// that turns the `repr` into `()` to address this. Such a feature would be a non-breaking change
 /// so there's no need to add it now.
 #[derive(Debug)]
 pub struct Error {
     repr: Box<str>,
+    position: Option<usize>,
+    fields: Vec<FieldContext>,
+}
+
+/// A field being decoded when an [`Error`] occurred.
+#[derive(Debug)]
+struct FieldContext {
+    field: &'static str,
+    type_name: &'static str,
 }
 
 // This implementation is useful for encoding single-felt types.
@@ -150,6 +232,15 @@ impl Encode for Felt {
     }
 }
 
+impl<T> Encode for &T
+where
+    T: Encode + ?Sized,
+{
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        T::encode(self, writer)
+    }
+}
+
 impl Encode for bool {
     fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
         writer.write(if *self { Felt::ONE } else { Felt::ZERO });
@@ -200,6 +291,34 @@ impl Encode for U256 {
     }
 }
 
+impl Encode for i8 {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write((*self).into());
+        Ok(())
+    }
+}
+
+impl Encode for i16 {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write((*self).into());
+        Ok(())
+    }
+}
+
+impl Encode for i32 {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write((*self).into());
+        Ok(())
+    }
+}
+
+impl Encode for i64 {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write((*self).into());
+        Ok(())
+    }
+}
+
 impl Encode for i128 {
     fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
         writer.write((*self).into());
@@ -271,6 +390,54 @@ where
     }
 }
 
+impl<T> Encode for Box<T>
+where
+    T: Encode + ?Sized,
+{
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        T::encode(self, writer)
+    }
+}
+
+impl<T, E> Encode for Result<T, E>
+where
+    T: Encode,
+    E: Encode,
+{
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        match self {
+            Ok(inner) => {
+                writer.write(Felt::ZERO);
+                inner.encode(writer)?;
+            }
+            Err(inner) => {
+                writer.write(Felt::ONE);
+                inner.encode(writer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Encode for str {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        ByteArray::from(self).encode(writer)
+    }
+}
+
+impl Encode for String {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+        self.as_str().encode(writer)
+    }
+}
+
+impl Encode for () {
+    fn encode<W: FeltWriter>(&self, _writer: &mut W) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
 impl<'a> Decode<'a> for Felt {
     fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
     where
@@ -280,6 +447,15 @@ impl<'a> Decode<'a> for Felt {
     }
 }
 
+impl<'a> Decode<'a> for &'a Felt {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        iter.next().ok_or_else(Error::input_exhausted)
+    }
+}
+
 impl<'a> Decode<'a> for bool {
     fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
     where
@@ -381,19 +557,153 @@ impl<'a> Decode<'a> for i128 {
         T: Iterator<Item = &'a Felt>,
     {
         let input = iter.next().ok_or_else(Error::input_exhausted)?;
+        felt_to_i128(input).ok_or_else(|| Error::value_out_of_range(input, "i128"))
+    }
+}
+
+impl<'a> Decode<'a> for i8 {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let input = iter.next().ok_or_else(Error::input_exhausted)?;
+        felt_to_i128(input)
+            .and_then(|value| value.try_into().ok())
+            .ok_or_else(|| Error::value_out_of_range(input, "i8"))
+    }
+}
+
+impl<'a> Decode<'a> for i16 {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let input = iter.next().ok_or_else(Error::input_exhausted)?;
+        felt_to_i128(input)
+            .and_then(|value| value.try_into().ok())
+            .ok_or_else(|| Error::value_out_of_range(input, "i16"))
+    }
+}
+
+impl<'a> Decode<'a> for i32 {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let input = iter.next().ok_or_else(Error::input_exhausted)?;
+        felt_to_i128(input)
+            .and_then(|value| value.try_into().ok())
+            .ok_or_else(|| Error::value_out_of_range(input, "i32"))
+    }
+}
+
+impl<'a> Decode<'a> for i64 {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let input = iter.next().ok_or_else(Error::input_exhausted)?;
+        felt_to_i128(input)
+            .and_then(|value| value.try_into().ok())
+            .ok_or_else(|| Error::value_out_of_range(input, "i64"))
+    }
+}
 
-        if input <= &I128_MAX {
-            // Range checked. Safe to unwrap.
-            Ok(input.to_i128().unwrap())
-        } else if input >= &I128_MIN {
-            // Range checked. Safe to unwrap.
-            Ok(Self::MIN + (input - I128_MIN).to_i128().unwrap())
+impl<'a, T> Decode<'a> for Box<T>
+where
+    T: Decode<'a>,
+{
+    fn decode_iter<I>(iter: &mut I) -> Result<Self, Error>
+    where
+        I: Iterator<Item = &'a Felt>,
+    {
+        Ok(Self::new(T::decode_iter(iter)?))
+    }
+}
+
+impl<'a, T, E> Decode<'a> for Result<T, E>
+where
+    T: Decode<'a>,
+    E: Decode<'a>,
+{
+    fn decode_iter<I>(iter: &mut I) -> Result<Self, Error>
+    where
+        I: Iterator<Item = &'a Felt>,
+    {
+        let tag = iter.next().ok_or_else(Error::input_exhausted)?;
+
+        if tag == &Felt::ZERO {
+            Ok(Ok(T::decode_iter(iter)?))
+        } else if tag == &Felt::ONE {
+            Ok(Err(E::decode_iter(iter)?))
         } else {
-            Err(Error::value_out_of_range(input, "i128"))
+            Err(Error::unknown_enum_tag(tag, "Result<T, E>"))
         }
     }
 }
 
+impl<'a> Decode<'a> for String {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, Error>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        Self::try_from(ByteArray::decode_iter(iter)?)
+            .map_err(|err| Error::custom(format!("invalid UTF-8 string: {err}")))
+    }
+}
+
+impl<'a> Decode<'a> for () {
+    fn decode_iter<T>(_iter: &mut T) -> Result<Self, Error>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        Ok(())
+    }
+}
+
+/// Implements [`Encode`] and [`Decode`] for tuples, which Cairo serializes as the concatenation of
+/// their elements.
+macro_rules! impl_codec_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name),+> Encode for ($($name,)+)
+        where
+            $($name: Encode,)+
+        {
+            #[allow(non_snake_case)]
+            fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), Error> {
+                let ($($name,)+) = self;
+                $($name.encode(writer)?;)+
+                Ok(())
+            }
+        }
+
+        impl<'a, $($name),+> Decode<'a> for ($($name,)+)
+        where
+            $($name: Decode<'a>,)+
+        {
+            fn decode_iter<I>(iter: &mut I) -> Result<Self, Error>
+            where
+                I: Iterator<Item = &'a Felt>,
+            {
+                Ok(($($name::decode_iter(iter)?,)+))
+            }
+        }
+    };
+}
+
+impl_codec_for_tuple!(T0);
+impl_codec_for_tuple!(T0, T1);
+impl_codec_for_tuple!(T0, T1, T2);
+impl_codec_for_tuple!(T0, T1, T2, T3);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4, T5);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_codec_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
 impl<'a, T> Decode<'a> for Option<T>
 where
     T: Decode<'a>,
@@ -464,22 +774,42 @@ where
     }
 }
 
+/// Interprets a [`Felt`] as a signed integer, where negative values wrap around the field prime as
+/// they do in Cairo.
+fn felt_to_i128(input: &Felt) -> Option<i128> {
+    if input <= &I128_MAX {
+        // Range checked. Safe to unwrap.
+        Some(input.to_i128().unwrap())
+    } else if input >= &I128_MIN {
+        // Range checked. Safe to unwrap.
+        Some(i128::MIN + (input - I128_MIN).to_i128().unwrap())
+    } else {
+        None
+    }
+}
+
 impl Error {
+    const fn new(repr: Box<str>) -> Self {
+        Self {
+            repr,
+            position: None,
+            fields: Vec::new(),
+        }
+    }
+
     /// Creates an [`Error`] which indicates that the input stream has ended prematurely.
     pub fn input_exhausted() -> Self {
-        Self {
-            repr: "unexpected end of input stream"
+        Self::new(
+            "unexpected end of input stream"
                 .to_string()
                 .into_boxed_str(),
-        }
+        )
     }
 
     /// Creates an [`Error`] which indicates that the length (likely prefix) is different from the
     /// expected value.
     pub fn length_mismatch(expected: usize, actual: usize) -> Self {
-        Self {
-            repr: format!("expecting length `{expected}` but got `{actual}`").into_boxed_str(),
-        }
+        Self::new(format!("expecting length `{expected}` but got `{actual}`").into_boxed_str())
     }
 
     /// Creates an [`Error`] which indicates that the input value is out of range.
@@ -487,10 +817,9 @@ impl Error {
     where
         V: Display,
     {
-        Self {
-            repr: format!("value `{value}` is out of range for type `{type_name}`")
-                .into_boxed_str(),
-        }
+        Self::new(
+            format!("value `{value}` is out of range for type `{type_name}`").into_boxed_str(),
+        )
     }
 
     /// Creates an [`Error`] which indicates that the enum tag does not belong to a known variant.
@@ -498,9 +827,7 @@ impl Error {
     where
         V: Display,
     {
-        Self {
-            repr: format!("enum tag `{tag}` is unknown for type `{type_name}`").into_boxed_str(),
-        }
+        Self::new(format!("enum tag `{tag}` is unknown for type `{type_name}`").into_boxed_str())
     }
 
     /// Creates an [`Error`] using a custom error string.
@@ -508,10 +835,28 @@ impl Error {
     where
         T: Display,
     {
+        Self::new(content.to_string().into_boxed_str())
+    }
+
+    /// Attaches the index of the [`Felt`] where decoding failed, replacing any existing one.
+    pub fn at_position(self, position: usize) -> Self {
         Self {
-            repr: content.to_string().into_boxed_str(),
+            position: Some(position),
+            ..self
         }
     }
+
+    /// Records that the error occurred while decoding `field` of `type_name`. This is called by
+    /// derived [`Decode`] implementations, starting from the innermost field.
+    pub fn in_field(mut self, field: &'static str, type_name: &'static str) -> Self {
+        self.fields.push(FieldContext { field, type_name });
+        self
+    }
+
+    /// Gets the index of the [`Felt`] where decoding failed, if known.
+    pub const fn position(&self) -> Option<usize> {
+        self.position
+    }
 }
 
 #[cfg(feature = "std")]
@@ -519,10 +864,78 @@ impl std::error::Error for Error {}
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut has_context = self.position.is_some();
+
+        if let Some(position) = self.position {
+            write!(f, "at felt index {position}")?;
+        }
+
+        for (ind, context) in self.fields.iter().enumerate() {
+            if has_context {
+                write!(f, " ")?;
+            }
+            write!(
+                f,
+                "{} field `{}` of `{}`",
+                if ind == 0 { "while decoding" } else { "in" },
+                context.field,
+                context.type_name
+            )?;
+            has_context = true;
+        }
+
+        if has_context {
+            write!(f, ": ")?;
+        }
+
         write!(f, "{}", self.repr)
     }
 }
 
+/// An iterator adapter that keeps track of how many items have been consumed, so that errors can
+/// point to the offending [`Felt`].
+pub(crate) struct CountingIter<I> {
+    inner: I,
+    consumed: usize,
+    exhausted: bool,
+}
+
+impl<I> CountingIter<I> {
+    pub(crate) const fn new(inner: I) -> Self {
+        Self {
+            inner,
+            consumed: 0,
+            exhausted: false,
+        }
+    }
+
+    /// The index of the last consumed item, which is the one that most likely caused decoding to
+    /// fail, or the total length of the input if it has been exhausted.
+    pub(crate) const fn error_position(&self) -> usize {
+        if self.exhausted || self.consumed == 0 {
+            self.consumed
+        } else {
+            self.consumed - 1
+        }
+    }
+}
+
+impl<I> Iterator for CountingIter<I>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next();
+        match item {
+            Some(_) => self.consumed += 1,
+            None => self.exhausted = true,
+        }
+        item
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::str::FromStr;
@@ -1158,4 +1571,370 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_derive_struct_attributes() {
+        mod doubled {
+            use super::*;
+
+            pub fn encode<W: FeltWriter>(value: &u64, writer: &mut W) -> Result<(), Error> {
+                writer.write(Felt::from(*value * 2));
+                Ok(())
+            }
+
+            pub fn decode_iter<'a, T>(iter: &mut T) -> Result<u64, Error>
+            where
+                T: Iterator<Item = &'a Felt>,
+            {
+                Ok(u64::decode_iter(iter)? / 2)
+            }
+        }
+
+        #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+        #[starknet(core = "crate")]
+        struct CairoType {
+            a: Felt,
+            #[starknet(skip)]
+            b: Vec<u8>,
+            #[starknet(with = "doubled")]
+            c: u64,
+            #[starknet(flatten)]
+            d: Inner,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+        #[starknet(core = "crate", transparent)]
+        struct Inner(bool, #[starknet(skip)] u8);
+
+        let mut serialized = Vec::<Felt>::new();
+        CairoType {
+            a: Felt::from_str("12345").unwrap(),
+            b: vec![1, 2, 3],
+            c: 21,
+            d: Inner(true, 8),
+        }
+        .encode(&mut serialized)
+        .unwrap();
+        assert_eq!(
+            serialized,
+            vec![
+                Felt::from_str("12345").unwrap(),
+                Felt::from_str("42").unwrap(),
+                Felt::from_str("1").unwrap(),
+            ]
+        );
+
+        assert_eq!(
+            CairoType {
+                a: Felt::from_str("12345").unwrap(),
+                b: vec![],
+                c: 21,
+                d: Inner(true, 0),
+            },
+            CairoType::decode(&serialized).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_derive_enum_attributes() {
+        #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+        #[starknet(core = "crate")]
+        enum CairoType {
+            A,
+            #[starknet(index = 5)]
+            B(#[starknet(skip)] bool, u8),
+            C {
+                #[starknet(skip)]
+                a: u64,
+                b: bool,
+            },
+            #[starknet(index = 1)]
+            D,
+        }
+
+        let mut serialized = Vec::<Felt>::new();
+        CairoType::A.encode(&mut serialized).unwrap();
+        CairoType::B(true, 4).encode(&mut serialized).unwrap();
+        CairoType::C { a: 100, b: true }
+            .encode(&mut serialized)
+            .unwrap();
+        CairoType::D.encode(&mut serialized).unwrap();
+        assert_eq!(
+            serialized,
+            vec![
+                Felt::from_str("0").unwrap(),
+                Felt::from_str("5").unwrap(),
+                Felt::from_str("4").unwrap(),
+                Felt::from_str("6").unwrap(),
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("1").unwrap(),
+            ]
+        );
+
+        let mut iter = serialized.iter();
+        assert_eq!(CairoType::A, CairoType::decode_iter(&mut iter).unwrap());
+        assert_eq!(
+            CairoType::B(false, 4),
+            CairoType::decode_iter(&mut iter).unwrap()
+        );
+        assert_eq!(
+            CairoType::C { a: 0, b: true },
+            CairoType::decode_iter(&mut iter).unwrap()
+        );
+        assert_eq!(CairoType::D, CairoType::decode_iter(&mut iter).unwrap());
+
+        assert!(CairoType::decode(&[Felt::from_str("2").unwrap()]).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_derive_generics() {
+        #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+        #[starknet(core = "crate")]
+        struct Pair<T> {
+            a: T,
+            b: T,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+        #[starknet(core = "crate")]
+        enum Either<L, R>
+        where
+            L: Clone,
+        {
+            Left(L),
+            Right(Option<Pair<R>>),
+        }
+
+        #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+        #[starknet(core = "crate")]
+        struct Borrowed<'a, T: Clone> {
+            a: &'a Felt,
+            b: Vec<T>,
+            #[starknet(skip)]
+            c: Option<T>,
+        }
+
+        let mut serialized = Vec::<Felt>::new();
+        Either::<u8, U256>::Right(Some(Pair {
+            a: U256::from_words(1, 2),
+            b: U256::from_words(3, 4),
+        }))
+        .encode(&mut serialized)
+        .unwrap();
+        assert_eq!(
+            serialized,
+            vec![
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("0").unwrap(),
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("2").unwrap(),
+                Felt::from_str("3").unwrap(),
+                Felt::from_str("4").unwrap(),
+            ]
+        );
+        assert_eq!(
+            Either::Right(Some(Pair {
+                a: U256::from_words(1, 2),
+                b: U256::from_words(3, 4),
+            })),
+            Either::<u8, U256>::decode(&serialized).unwrap()
+        );
+
+        let input = vec![
+            Felt::from_str("12345").unwrap(),
+            Felt::from_str("2").unwrap(),
+            Felt::from_str("5").unwrap(),
+            Felt::from_str("6").unwrap(),
+        ];
+        let decoded = Borrowed::<u32>::decode(&input).unwrap();
+        assert!(core::ptr::eq(decoded.a, &input[0]));
+        assert_eq!(decoded.b, vec![5, 6]);
+        assert_eq!(decoded.c, None);
+
+        serialized.clear();
+        decoded.encode(&mut serialized).unwrap();
+        assert_eq!(serialized, input);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_encode_signed() {
+        let mut serialized = Vec::<Felt>::new();
+        (-1i8).encode(&mut serialized).unwrap();
+        i16::MAX.encode(&mut serialized).unwrap();
+        (-100i32).encode(&mut serialized).unwrap();
+        i64::MIN.encode(&mut serialized).unwrap();
+        assert_eq!(
+            serialized,
+            vec![
+                Felt::from_hex_unchecked(
+                    "0x0800000000000011000000000000000000000000000000000000000000000000"
+                ),
+                Felt::from_hex_unchecked("0x7fff"),
+                Felt::from_hex_unchecked(
+                    "0x0800000000000010ffffffffffffffffffffffffffffffffffffffffffffff9d"
+                ),
+                Felt::from_hex_unchecked(
+                    "0x0800000000000010ffffffffffffffffffffffffffffffff8000000000000001"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_signed() {
+        let minus_one = Felt::from_hex_unchecked(
+            "0x0800000000000011000000000000000000000000000000000000000000000000",
+        );
+
+        assert_eq!(i8::decode(&[minus_one]).unwrap(), -1);
+        assert_eq!(i16::decode(&[minus_one]).unwrap(), -1);
+        assert_eq!(i32::decode(&[minus_one]).unwrap(), -1);
+        assert_eq!(i64::decode(&[minus_one]).unwrap(), -1);
+        assert_eq!(i64::decode(&[Felt::from(i64::MIN)]).unwrap(), i64::MIN);
+
+        assert!(i8::decode(&[Felt::from(128u32)]).is_err());
+        assert!(i8::decode(&[Felt::from(-129i32)]).is_err());
+        assert!(i32::decode(&[Felt::from(u64::MAX)]).is_err());
+        assert!(i64::decode(&[Felt::MAX - Felt::from(u128::MAX)]).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_encode_tuple() {
+        let mut serialized = Vec::<Felt>::new();
+        ().encode(&mut serialized).unwrap();
+        (1u8, U256::from_words(2, 3), (true,), vec![4u32])
+            .encode(&mut serialized)
+            .unwrap();
+        assert_eq!(
+            serialized,
+            vec![
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("2").unwrap(),
+                Felt::from_str("3").unwrap(),
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("4").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_tuple() {
+        assert_eq!(<()>::decode(&[]).unwrap(), ());
+        assert_eq!(
+            <(u8, U256, (bool,), Vec<u32>)>::decode(&[
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("2").unwrap(),
+                Felt::from_str("3").unwrap(),
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("4").unwrap(),
+            ])
+            .unwrap(),
+            (1u8, U256::from_words(2, 3), (true,), vec![4u32])
+        );
+        assert_eq!(
+            <(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8)>::decode(&[Felt::ONE; 12]).unwrap(),
+            (1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_encode_result() {
+        let mut serialized = Vec::<Felt>::new();
+        Result::<u8, Felt>::Ok(5).encode(&mut serialized).unwrap();
+        Result::<u8, Felt>::Err(Felt::TWO)
+            .encode(&mut serialized)
+            .unwrap();
+        assert_eq!(
+            serialized,
+            vec![
+                Felt::from_str("0").unwrap(),
+                Felt::from_str("5").unwrap(),
+                Felt::from_str("1").unwrap(),
+                Felt::from_str("2").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_result() {
+        assert_eq!(
+            Result::<u8, Felt>::decode(&[Felt::ZERO, Felt::from(5u8)]).unwrap(),
+            Ok(5)
+        );
+        assert_eq!(
+            Result::<u8, Felt>::decode(&[Felt::ONE, Felt::TWO]).unwrap(),
+            Err(Felt::TWO)
+        );
+        assert!(Result::<u8, Felt>::decode(&[Felt::TWO, Felt::TWO]).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_encode_string() {
+        let mut serialized = Vec::<Felt>::new();
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ12345"
+            .encode(&mut serialized)
+            .unwrap();
+        String::from("hello").encode(&mut serialized).unwrap();
+        assert_eq!(
+            serialized,
+            vec![
+                Felt::from_str("1").unwrap(),
+                Felt::from_hex_unchecked(
+                    "0x004142434445464748494a4b4c4d4e4f505152535455565758595a3132333435"
+                ),
+                Felt::from_str("0").unwrap(),
+                Felt::from_str("0").unwrap(),
+                Felt::from_str("0").unwrap(),
+                Felt::from_hex_unchecked("0x68656c6c6f"),
+                Felt::from_str("5").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_decode_string() {
+        assert_eq!(
+            String::decode(&[
+                Felt::from_str("0").unwrap(),
+                Felt::from_hex_unchecked("0x68656c6c6f"),
+                Felt::from_str("5").unwrap(),
+            ])
+            .unwrap(),
+            "hello"
+        );
+        assert!(String::decode(&[
+            Felt::from_str("0").unwrap(),
+            Felt::from_hex_unchecked("0xff"),
+            Felt::from_str("1").unwrap(),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_encode_decode_box() {
+        let mut serialized = Vec::<Felt>::new();
+        Box::new(U256::from_words(1, 2))
+            .encode(&mut serialized)
+            .unwrap();
+        assert_eq!(serialized, vec![Felt::ONE, Felt::TWO]);
+
+        assert_eq!(
+            Box::<U256>::decode(&serialized).unwrap(),
+            Box::new(U256::from_words(1, 2))
+        );
+    }
 }
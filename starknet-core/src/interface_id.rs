@@ -0,0 +1,64 @@
+use starknet_types_core::felt::Felt;
+
+/// The SNIP-5 interface ID of `ISRC5`, which every contract supporting interface detection must
+/// support itself.
+pub const SRC5: Felt = Felt::from_raw([
+    206762169836899950,
+    12964514102474424790,
+    8655829016678059169,
+    12521094270149008863,
+]);
+
+/// The SNIP-5 interface ID of `ISRC6`, the standard account interface.
+pub const SRC6: Felt = Felt::from_raw([
+    109826926523406676,
+    8911023150441713663,
+    10311367308862490671,
+    5405905756509069804,
+]);
+
+/// The SNIP-5 interface ID of `IERC721`, the standard non-fungible token interface.
+pub const ERC721: Felt = Felt::from_raw([
+    166158514711778764,
+    11497443950084309308,
+    12824668570185063585,
+    14827517048046747608,
+]);
+
+/// The SNIP-5 interface ID of `IERC1155`, the standard multi-token interface.
+pub const ERC1155: Felt = Felt::from_raw([
+    573497912922286795,
+    6161889253696114870,
+    1402312783637795322,
+    18260640375602307176,
+]);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_interface_ids() {
+        for (text, felt) in [
+            (
+                "0x3f918d17e5ee77373b56385708f855659a07f75997f365cf87748628532a055",
+                SRC5,
+            ),
+            (
+                "0x2ceccef7f994940b3962a6c67e0ba4fcd37df7d131417c604f91e03caecc1cd",
+                SRC6,
+            ),
+            (
+                "0x33eb2f84c309543403fd69f0d0f363781ef06ef6faeb0131ff16ea3175bd943",
+                ERC721,
+            ),
+            (
+                "0x6114a8f75559e1b39fcba08ce02961a1aa082d9256a158dd3e64964e4b1b52",
+                ERC1155,
+            ),
+        ] {
+            assert_eq!(Felt::from_hex(text).unwrap(), felt);
+        }
+    }
+}
@@ -19,7 +19,13 @@ pub mod utils;
 /// Chain IDs for commonly used public Starknet networks.
 pub mod chain_id;
 
+/// SNIP-5 interface IDs of commonly used standard interfaces.
+pub mod interface_id;
+
 /// Types for serializing high-level Cairo types into field elements and vice versa.
 pub mod codec;
 
+/// Runtime interpretation of Cairo values based on contract ABIs.
+pub mod abi;
+
 extern crate alloc;
@@ -4,6 +4,8 @@ use core::str::FromStr;
 use serde::{de::Visitor, Deserialize, Serialize};
 use starknet_types_core::felt::Felt;
 
+use crate::codec::{Decode, Encode, Error as CodecError, FeltWriter};
+
 // 0xFFfFfFffFFfffFFfFFfFFFFFffFFFffffFfFFFfF
 const MAX_L1_ADDRESS: Felt = Felt::from_raw([
     461478224317121089,
@@ -205,9 +207,29 @@ impl From<[u8; 20]> for EthAddress {
     }
 }
 
+impl Encode for EthAddress {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), CodecError> {
+        writer.write(Felt::from_bytes_be_slice(&self.inner));
+        Ok(())
+    }
+}
+
+impl<'a> Decode<'a> for EthAddress {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, CodecError>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let input = iter.next().ok_or_else(CodecError::input_exhausted)?;
+        input
+            .try_into()
+            .map_err(|_| CodecError::value_out_of_range(input, "EthAddress"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{EthAddress, Felt};
+    use crate::codec::{Decode, Encode};
 
     use alloc::vec::*;
 
@@ -309,4 +331,18 @@ mod tests {
             panic!("Expected error, but got Ok");
         }
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_eth_address_encode_decode() {
+        let address = EthAddress::from_hex("0xb9b2DEE4C7A29aAFF2D11c8F1FB3A4bfD6EEfE8b").unwrap();
+        let felt = Felt::from_hex_unchecked("0xb9b2dee4c7a29aaff2d11c8f1fb3a4bfd6eefe8b");
+
+        let mut serialized = Vec::<Felt>::new();
+        address.encode(&mut serialized).unwrap();
+        assert_eq!(serialized, vec![felt]);
+
+        assert_eq!(EthAddress::decode(&[felt]).unwrap(), address);
+        assert!(EthAddress::decode(&[Felt::MAX]).is_err());
+    }
 }
@@ -7,6 +7,11 @@ use alloc::{
 use serde::{de::Visitor, Deserialize, Serialize};
 use starknet_types_core::felt::Felt;
 
+use crate::{
+    codec::{Decode, Encode, Error as CodecError, FeltWriter},
+    types::U256,
+};
+
 const HASH_256_BYTE_COUNT: usize = 32;
 
 /// A 256-bit cryptographic hash.
@@ -205,9 +210,42 @@ impl From<[u8; HASH_256_BYTE_COUNT]> for Hash256 {
     }
 }
 
+// Hashes like Keccak and SHA-256 are represented as `u256` in Cairo, with the bytes taken in
+// big-endian order.
+impl Encode for Hash256 {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), CodecError> {
+        let (high, low) = self.inner.split_at(HASH_256_BYTE_COUNT / 2);
+
+        // Safe to unwrap as both halves are exactly 16 bytes.
+        U256::from_words(
+            u128::from_be_bytes(low.try_into().unwrap()),
+            u128::from_be_bytes(high.try_into().unwrap()),
+        )
+        .encode(writer)
+    }
+}
+
+impl<'a> Decode<'a> for Hash256 {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, CodecError>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        let value = U256::decode_iter(iter)?;
+
+        let mut inner = [0u8; HASH_256_BYTE_COUNT];
+        inner[..HASH_256_BYTE_COUNT / 2].copy_from_slice(&value.high().to_be_bytes());
+        inner[HASH_256_BYTE_COUNT / 2..].copy_from_slice(&value.low().to_be_bytes());
+
+        Ok(Self { inner })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Felt, FromHexError, Hash256, HASH_256_BYTE_COUNT};
+    use crate::codec::{Decode, Encode};
+
+    use alloc::vec::*;
 
     use hex_literal::hex;
 
@@ -296,4 +334,22 @@ mod tests {
             hex!("1111111111111111111111111111111111111111111111111111111111111111")
         );
     }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_hash_256_encode_decode() {
+        let hash = Hash256::from_bytes(hex!(
+            "25c5b1592b1743b62d7fabd4373d98219c2ff3750f49ec0608a8355fa3bb060f"
+        ));
+        let encoded = vec![
+            Felt::from_hex_unchecked("0x9c2ff3750f49ec0608a8355fa3bb060f"),
+            Felt::from_hex_unchecked("0x25c5b1592b1743b62d7fabd4373d9821"),
+        ];
+
+        let mut serialized = Vec::<Felt>::new();
+        hash.encode(&mut serialized).unwrap();
+        assert_eq!(serialized, encoded);
+
+        assert_eq!(Hash256::decode(&encoded).unwrap(), hash);
+    }
 }
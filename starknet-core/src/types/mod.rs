@@ -16,6 +16,8 @@ pub mod typed_data;
 pub use typed_data::TypedData;
 
 // TODO: better namespacing of exports?
+// Generated code is not edited by hand. Newer clippy versions flag `Self` repetition in it.
+#[allow(clippy::use_self)]
 mod codegen;
 pub use codegen::{
     BinaryNode, BlockHeader, BlockStatus, BlockTag, BlockWithReceipts, BlockWithTxHashes,
@@ -57,6 +59,10 @@ pub use codegen::{
 pub mod u256;
 pub use u256::U256;
 
+/// Module containing the [`U512`] type.
+pub mod u512;
+pub use u512::U512;
+
 /// Module containing the [`EthAddress`] type.
 pub mod eth_address;
 pub use eth_address::EthAddress;
@@ -83,6 +89,9 @@ pub use call::Call;
 mod byte_array;
 pub use byte_array::ByteArray;
 
+mod non_zero;
+pub use non_zero::NonZero;
+
 // TODO: move generated request code to `starknet-providers`
 /// Module containing JSON-RPC request types.
 pub mod requests;
@@ -0,0 +1,81 @@
+use crate::{
+    codec::{Decode, Encode, Error as CodecError, FeltWriter},
+    types::Felt,
+};
+
+/// The `NonZero<T>` type in Cairo, holding a value that is known to not be zero.
+///
+/// The zero value of `T` is taken to be its [`Default`] value, which holds for [`Felt`], unsigned
+/// integers, [`U256`](super::U256) and [`U512`](super::U512).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NonZero<T>(T);
+
+impl<T> NonZero<T>
+where
+    T: Default + PartialEq,
+{
+    /// Constructs a [`NonZero`] if the value is not zero.
+    pub fn new(value: T) -> Option<Self> {
+        if value == T::default() {
+            None
+        } else {
+            Some(Self(value))
+        }
+    }
+}
+
+impl<T> NonZero<T> {
+    /// Gets a reference to the inner value.
+    pub const fn get(&self) -> &T {
+        &self.0
+    }
+
+    /// Consumes the wrapper and returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Encode for NonZero<T>
+where
+    T: Encode,
+{
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), CodecError> {
+        self.0.encode(writer)
+    }
+}
+
+impl<'a, T> Decode<'a> for NonZero<T>
+where
+    T: Decode<'a> + Default + PartialEq,
+{
+    fn decode_iter<I>(iter: &mut I) -> Result<Self, CodecError>
+    where
+        I: Iterator<Item = &'a Felt>,
+    {
+        Self::new(T::decode_iter(iter)?)
+            .ok_or_else(|| CodecError::custom("unexpected zero value for type `NonZero<T>`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::*;
+
+    use super::*;
+    use crate::types::U256;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_non_zero_encode_decode() {
+        let value = NonZero::new(U256::from_words(12, 34)).unwrap();
+
+        let mut serialized = Vec::<Felt>::new();
+        value.encode(&mut serialized).unwrap();
+        assert_eq!(serialized, vec![Felt::from(12u8), Felt::from(34u8)]);
+
+        assert_eq!(NonZero::<U256>::decode(&serialized).unwrap(), value);
+        assert!(NonZero::<U256>::decode(&[Felt::ZERO, Felt::ZERO]).is_err());
+        assert!(NonZero::new(Felt::ZERO).is_none());
+    }
+}
@@ -7,7 +7,7 @@ use crate::types::Felt;
 /// 256-bit unsiged integer.
 ///
 /// In Cairo, this type is interally represented as two 128-bit words.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct U256(crypto_bigint::U256);
 
 impl U256 {
@@ -0,0 +1,135 @@
+use crypto_bigint::ArrayEncoding;
+use starknet_types_core::felt::Felt;
+
+use crate::{
+    codec::{Decode, Encode, Error as CodecError, FeltWriter},
+    types::U256,
+};
+
+/// 512-bit unsiged integer.
+///
+/// In Cairo, this type is interally represented as four 128-bit limbs, starting from the least
+/// significant one. It's mostly used for holding results of wide multiplications of [`U256`]
+/// values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct U512(crypto_bigint::U512);
+
+impl U512 {
+    /// Constructs a [`U512`] from four 128-bit limbs, starting from the least significant one,
+    /// similar to how they're represented in Cairo.
+    pub fn from_limbs(limbs: [u128; 4]) -> Self {
+        let mut bytes = [0u8; 64];
+        for (chunk, limb) in bytes.chunks_exact_mut(16).rev().zip(limbs) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+
+        Self(crypto_bigint::U512::from_be_byte_array(bytes.into()))
+    }
+
+    /// Gets the four 128-bit limbs, starting from the least significant one.
+    pub fn limbs(&self) -> [u128; 4] {
+        let bytes = self.0.to_be_byte_array();
+
+        let mut limbs = [0u128; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(16).rev()) {
+            // Safe to unwrap as chunks are exactly 16 bytes.
+            *limb = u128::from_be_bytes(chunk.try_into().unwrap());
+        }
+
+        limbs
+    }
+}
+
+impl From<U256> for U512 {
+    fn from(value: U256) -> Self {
+        Self::from_limbs([value.low(), value.high(), 0, 0])
+    }
+}
+
+impl From<crypto_bigint::U512> for U512 {
+    fn from(value: crypto_bigint::U512) -> Self {
+        Self(value)
+    }
+}
+
+impl Encode for U512 {
+    fn encode<W: FeltWriter>(&self, writer: &mut W) -> Result<(), CodecError> {
+        for limb in self.limbs() {
+            limb.encode(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Decode<'a> for U512 {
+    fn decode_iter<T>(iter: &mut T) -> Result<Self, CodecError>
+    where
+        T: Iterator<Item = &'a Felt>,
+    {
+        Ok(Self::from_limbs([
+            u128::decode_iter(iter)?,
+            u128::decode_iter(iter)?,
+            u128::decode_iter(iter)?,
+            u128::decode_iter(iter)?,
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::*;
+
+    use super::*;
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_u512_from_limbs() {
+        let limbs = [
+            0x33445566778899112233445566778899u128,
+            0x00000000001122334455667788991122u128,
+            0xffeeddccbbaa99887766554433221100u128,
+            0x1u128,
+        ];
+
+        let value = U512::from_limbs(limbs);
+        assert_eq!(
+            value,
+            U512::from(crypto_bigint::U512::from_be_hex(
+                "00000000000000000000000000000001ffeeddccbbaa99887766554433221100\
+                 0000000000112233445566778899112233445566778899112233445566778899"
+            ))
+        );
+        assert_eq!(value.limbs(), limbs);
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_u512_from_u256() {
+        assert_eq!(
+            U512::from(U256::from_words(12, 34)),
+            U512::from_limbs([12, 34, 0, 0])
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_u512_encode() {
+        let mut serialized = Vec::<Felt>::new();
+        U512::from_limbs([1, 2, 3, 4])
+            .encode(&mut serialized)
+            .unwrap();
+        assert_eq!(
+            serialized,
+            vec![Felt::ONE, Felt::TWO, Felt::THREE, Felt::from(4u8)]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_u512_decode() {
+        assert_eq!(
+            U512::decode(&[Felt::ONE, Felt::TWO, Felt::THREE, Felt::from(4u8)]).unwrap(),
+            U512::from_limbs([1, 2, 3, 4])
+        );
+    }
+}
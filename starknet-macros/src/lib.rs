@@ -1,14 +1,21 @@
 //! Procedural macros for the `starknet` crate. This crate provides macros that help make defining
-//! certain compile-time constants easier.
+//! certain compile-time constants easier, as well as generating contract bindings from ABIs.
 
 #![deny(missing_docs)]
 
+use std::path::PathBuf;
+
 use proc_macro::TokenStream;
+use quote::quote;
+use starknet_abigen::Abigen;
 use starknet_core::{
     types::Felt,
     utils::{cairo_short_string_to_felt, get_selector_from_name},
 };
-use syn::{parse_macro_input, LitStr};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Ident, LitStr, Token,
+};
 
 /// Defines a compile-time constant for a entrypoint selector of a Starknet contract.
 #[proc_macro]
@@ -126,6 +133,65 @@ pub fn felt_hex(input: TokenStream) -> TokenStream {
     .unwrap()
 }
 
+/// Generates type-safe bindings for a contract from a JSON file containing a Sierra or legacy
+/// contract class, or just its ABI.
+///
+/// The macro takes the name of the contract type to generate and a path to the JSON file, relative
+/// to the root of the crate being compiled:
+///
+/// ```ignore
+/// abigen!(Erc20, "contracts/erc20.contract_class.json");
+/// ```
+///
+/// This generates `Erc20<A>` for sending transactions through an account, `Erc20Reader<P>` for
+/// calling view functions through a provider, and a type for each struct, enum and event defined
+/// in the ABI. See `starknet-abigen` for details, including generating the same bindings from
+/// build scripts instead.
+#[proc_macro]
+pub fn abigen(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as AbigenInput);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = PathBuf::from(manifest_dir).join(input.path.value());
+
+    let bindings = Abigen::from_file(input.name.to_string(), &path)
+        .and_then(|abigen| abigen.generate())
+        .map_err(|err| format!("unable to generate bindings from {}: {err}", path.display()));
+
+    match bindings {
+        Ok(bindings) => {
+            // Makes the compiler rebuild when the file changes.
+            let path = path.display().to_string();
+            quote! {
+                const _: &[u8] = include_bytes!(#path);
+
+                #bindings
+            }
+            .into()
+        }
+        Err(err) => syn::Error::new(input.path.span(), err)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+struct AbigenInput {
+    name: Ident,
+    path: LitStr,
+}
+
+impl Parse for AbigenInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let path = input.parse()?;
+        // Allows a trailing comma
+        let _ = input.parse::<Option<Token![,]>>()?;
+
+        Ok(Self { name, path })
+    }
+}
+
 #[cfg(feature = "use_imported_type")]
 const fn field_element_path() -> &'static str {
     "Felt"
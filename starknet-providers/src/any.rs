@@ -740,4 +740,22 @@ impl Provider for AnyProvider {
             }
         }
     }
+
+    async fn try_batch_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<Result<ProviderResponseData, ProviderError>>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        match self {
+            Self::JsonRpcHttp(inner) => {
+                <JsonRpcClient<HttpTransport> as Provider>::try_batch_requests(inner, requests)
+                    .await
+            }
+            Self::SequencerGateway(inner) => {
+                <SequencerGatewayProvider as Provider>::try_batch_requests(inner, requests).await
+            }
+        }
+    }
 }
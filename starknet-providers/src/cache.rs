@@ -0,0 +1,842 @@
+use std::{num::NonZeroUsize, sync::Mutex};
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use starknet_core::types::{
+    BlockHashAndNumber, BlockId, BlockStatus, BlockTag, BroadcastedDeclareTransaction,
+    BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction, BroadcastedTransaction,
+    ConfirmedBlockId, ContractClass, ContractStorageKeys, DeclareTransactionResult,
+    DeployAccountTransactionResult, EventFilter, EventsPage, FeeEstimate, Felt, FunctionCall,
+    Hash256, InvokeTransactionResult, MaybePreConfirmedBlockWithReceipts,
+    MaybePreConfirmedBlockWithTxHashes, MaybePreConfirmedBlockWithTxs,
+    MaybePreConfirmedStateUpdate, MessageFeeEstimate, MessageStatus, MsgFromL1,
+    SimulatedTransaction, SimulationFlag, SimulationFlagForEstimateFee, StorageProof,
+    SyncStatusType, Transaction, TransactionFinalityStatus, TransactionReceiptWithBlockInfo,
+    TransactionStatus, TransactionTrace, TransactionTraceWithHash,
+};
+
+use crate::{Provider, ProviderError, ProviderRequestData, ProviderResponseData};
+
+/// A [`Provider`] implementation that wraps another provider and memoizes responses which can
+/// never change once observed:
+///
+/// - [`get_class`](Provider::get_class) results, as classes are identified by their hashes. Cached
+///   classes are only served for the `latest` and `pre_confirmed` block tags, since a class might
+///   not be declared yet at an earlier block;
+/// - [`get_block_with_tx_hashes`](Provider::get_block_with_tx_hashes),
+///   [`get_block_with_txs`](Provider::get_block_with_txs) and
+///   [`get_block_with_receipts`](Provider::get_block_with_receipts) results for blocks requested
+///   by hash or number, once the block is final;
+/// - [`get_transaction_receipt`](Provider::get_transaction_receipt) results, once the transaction
+///   is final;
+/// - [`get_storage_at`](Provider::get_storage_at) results at a block specified by hash (or by
+///   number, if blocks accepted on L2 are treated as final).
+///
+/// By default, only blocks and transactions accepted on L1 are considered final. Use
+/// [`with_l2_finality`](CachingProvider::with_l2_finality) to also cache data accepted on L2, at
+/// the risk of serving stale data in the rare case of a chain reorganization.
+///
+/// All other requests are forwarded to the inner provider as is.
+#[derive(Debug)]
+pub struct CachingProvider<P, C = InMemoryCache> {
+    inner: P,
+    cache: C,
+    l2_finality: bool,
+}
+
+/// A storage backend for [`CachingProvider`].
+///
+/// Implementations are free to evict entries at any time. [`InMemoryCache`] is used by default,
+/// but this trait can be implemented for persistent storage, such as on disk. Both [`CacheKey`]
+/// and [`CacheValue`] are serializable to facilitate that.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[auto_impl(&, Box, Arc)]
+pub trait ProviderCache {
+    /// Gets a cached value by key, if present.
+    async fn get(&self, key: &CacheKey) -> Option<CacheValue>;
+
+    /// Stores a value in the cache.
+    async fn insert(&self, key: CacheKey, value: CacheValue);
+}
+
+/// An in-memory [`ProviderCache`] implementation with least-recently-used eviction.
+#[derive(Debug)]
+pub struct InMemoryCache {
+    entries: Mutex<LruCache<CacheKey, CacheValue>>,
+}
+
+/// Keys of entries stored in a [`ProviderCache`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CacheKey {
+    /// Class definition by class hash.
+    Class(Felt),
+    /// Block with transaction hashes.
+    BlockWithTxHashes(CacheBlockId),
+    /// Block with full transactions.
+    BlockWithTxs(CacheBlockId),
+    /// Block with full transactions and receipts.
+    BlockWithReceipts(CacheBlockId),
+    /// Transaction receipt by transaction hash.
+    TransactionReceipt(Felt),
+    /// Storage value at a specific block.
+    StorageAt {
+        /// Address of the contract.
+        contract_address: Felt,
+        /// Storage key.
+        key: Felt,
+        /// The block at which the storage is read.
+        block_id: CacheBlockId,
+    },
+}
+
+/// Block identifiers that can be used as part of a [`CacheKey`]. Block tags are not cacheable as
+/// they point to different blocks over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CacheBlockId {
+    /// Block hash.
+    Hash(Felt),
+    /// Block number (height).
+    Number(u64),
+}
+
+/// Values of entries stored in a [`ProviderCache`].
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CacheValue {
+    /// Class definition.
+    Class(ContractClass),
+    /// Block with transaction hashes.
+    BlockWithTxHashes(MaybePreConfirmedBlockWithTxHashes),
+    /// Block with full transactions.
+    BlockWithTxs(MaybePreConfirmedBlockWithTxs),
+    /// Block with full transactions and receipts.
+    BlockWithReceipts(MaybePreConfirmedBlockWithReceipts),
+    /// Transaction receipt.
+    TransactionReceipt(TransactionReceiptWithBlockInfo),
+    /// Storage value.
+    StorageAt(Felt),
+}
+
+impl<P> CachingProvider<P> {
+    /// Constructs [`CachingProvider`] by wrapping a provider, using an [`InMemoryCache`] with the
+    /// default capacity.
+    pub fn new(inner: P) -> Self {
+        Self::new_with_cache(inner, InMemoryCache::default())
+    }
+}
+
+impl<P, C> CachingProvider<P, C> {
+    /// Constructs [`CachingProvider`] by wrapping a provider with a custom cache backend.
+    pub const fn new_with_cache(inner: P, cache: C) -> Self {
+        Self {
+            inner,
+            cache,
+            l2_finality: false,
+        }
+    }
+
+    /// Consumes the current [`CachingProvider`] instance and returns a new one with blocks and
+    /// transactions accepted on L2 treated as final or not.
+    pub fn with_l2_finality(self, l2_finality: bool) -> Self {
+        Self {
+            l2_finality,
+            ..self
+        }
+    }
+
+    /// Gets a reference to the wrapped provider.
+    pub const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Gets a reference to the cache backend.
+    pub const fn cache(&self) -> &C {
+        &self.cache
+    }
+
+    fn is_block_final(&self, status: &BlockStatus) -> bool {
+        match status {
+            BlockStatus::AcceptedOnL1 => true,
+            BlockStatus::AcceptedOnL2 => self.l2_finality,
+            BlockStatus::PreConfirmed => false,
+        }
+    }
+
+    fn is_transaction_final(&self, status: &TransactionFinalityStatus) -> bool {
+        match status {
+            TransactionFinalityStatus::AcceptedOnL1 => true,
+            TransactionFinalityStatus::AcceptedOnL2 => self.l2_finality,
+            TransactionFinalityStatus::PreConfirmed => false,
+        }
+    }
+
+    /// Block identifier usable as part of a cache key for data that's only immutable when the
+    /// block is.
+    fn storage_block_id(&self, block_id: &BlockId) -> Option<CacheBlockId> {
+        match block_id {
+            BlockId::Hash(hash) => Some(CacheBlockId::Hash(*hash)),
+            BlockId::Number(number) if self.l2_finality => Some(CacheBlockId::Number(*number)),
+            _ => None,
+        }
+    }
+}
+
+impl InMemoryCache {
+    /// Constructs [`InMemoryCache`] holding at most `capacity` entries.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Gets the number of entries currently cached.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Checks whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().unwrap().is_empty()
+    }
+
+    /// Removes all cached entries.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl Default for InMemoryCache {
+    /// Constructs [`InMemoryCache`] with a capacity of 1,024 entries.
+    fn default() -> Self {
+        Self::new(NonZeroUsize::new(1024).unwrap())
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl ProviderCache for InMemoryCache {
+    async fn get(&self, key: &CacheKey) -> Option<CacheValue> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    async fn insert(&self, key: CacheKey, value: CacheValue) {
+        self.entries.lock().unwrap().put(key, value);
+    }
+}
+
+impl CacheBlockId {
+    const fn from_block_id(block_id: &BlockId) -> Option<Self> {
+        match block_id {
+            BlockId::Hash(hash) => Some(Self::Hash(*hash)),
+            BlockId::Number(number) => Some(Self::Number(*number)),
+            BlockId::Tag(_) => None,
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<P, C> Provider for CachingProvider<P, C>
+where
+    P: Provider + Send + Sync,
+    C: ProviderCache + Send + Sync,
+{
+    async fn spec_version(&self) -> Result<String, ProviderError> {
+        self.inner.spec_version().await
+    }
+
+    async fn get_block_with_tx_hashes<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedBlockWithTxHashes, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        let key = CacheBlockId::from_block_id(block_id.as_ref()).map(CacheKey::BlockWithTxHashes);
+        if let Some(key) = &key {
+            if let Some(CacheValue::BlockWithTxHashes(block)) = self.cache.get(key).await {
+                return Ok(block);
+            }
+        }
+
+        let block = self.inner.get_block_with_tx_hashes(block_id).await?;
+        if let (Some(key), MaybePreConfirmedBlockWithTxHashes::Block(inner)) = (key, &block) {
+            if self.is_block_final(&inner.status) {
+                self.cache
+                    .insert(key, CacheValue::BlockWithTxHashes(block.clone()))
+                    .await;
+            }
+        }
+
+        Ok(block)
+    }
+
+    async fn get_block_with_txs<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedBlockWithTxs, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        let key = CacheBlockId::from_block_id(block_id.as_ref()).map(CacheKey::BlockWithTxs);
+        if let Some(key) = &key {
+            if let Some(CacheValue::BlockWithTxs(block)) = self.cache.get(key).await {
+                return Ok(block);
+            }
+        }
+
+        let block = self.inner.get_block_with_txs(block_id).await?;
+        if let (Some(key), MaybePreConfirmedBlockWithTxs::Block(inner)) = (key, &block) {
+            if self.is_block_final(&inner.status) {
+                self.cache
+                    .insert(key, CacheValue::BlockWithTxs(block.clone()))
+                    .await;
+            }
+        }
+
+        Ok(block)
+    }
+
+    async fn get_block_with_receipts<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedBlockWithReceipts, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        let key = CacheBlockId::from_block_id(block_id.as_ref()).map(CacheKey::BlockWithReceipts);
+        if let Some(key) = &key {
+            if let Some(CacheValue::BlockWithReceipts(block)) = self.cache.get(key).await {
+                return Ok(block);
+            }
+        }
+
+        let block = self.inner.get_block_with_receipts(block_id).await?;
+        if let (Some(key), MaybePreConfirmedBlockWithReceipts::Block(inner)) = (key, &block) {
+            if self.is_block_final(&inner.status) {
+                self.cache
+                    .insert(key, CacheValue::BlockWithReceipts(block.clone()))
+                    .await;
+            }
+        }
+
+        Ok(block)
+    }
+
+    async fn get_state_update<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedStateUpdate, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.inner.get_state_update(block_id).await
+    }
+
+    async fn get_storage_at<A, K, B>(
+        &self,
+        contract_address: A,
+        key: K,
+        block_id: B,
+    ) -> Result<Felt, ProviderError>
+    where
+        A: AsRef<Felt> + Send + Sync,
+        K: AsRef<Felt> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        let cache_key =
+            self.storage_block_id(block_id.as_ref())
+                .map(|block_id| CacheKey::StorageAt {
+                    contract_address: *contract_address.as_ref(),
+                    key: *key.as_ref(),
+                    block_id,
+                });
+        if let Some(cache_key) = &cache_key {
+            if let Some(CacheValue::StorageAt(value)) = self.cache.get(cache_key).await {
+                return Ok(value);
+            }
+        }
+
+        let value = self
+            .inner
+            .get_storage_at(contract_address, key, block_id)
+            .await?;
+        if let Some(cache_key) = cache_key {
+            self.cache
+                .insert(cache_key, CacheValue::StorageAt(value))
+                .await;
+        }
+
+        Ok(value)
+    }
+
+    async fn get_messages_status(
+        &self,
+        transaction_hash: Hash256,
+    ) -> Result<Vec<MessageStatus>, ProviderError> {
+        self.inner.get_messages_status(transaction_hash).await
+    }
+
+    async fn get_transaction_status<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<TransactionStatus, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.inner.get_transaction_status(transaction_hash).await
+    }
+
+    async fn get_transaction_by_hash<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<Transaction, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.inner.get_transaction_by_hash(transaction_hash).await
+    }
+
+    async fn get_transaction_by_block_id_and_index<B>(
+        &self,
+        block_id: B,
+        index: u64,
+    ) -> Result<Transaction, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.inner
+            .get_transaction_by_block_id_and_index(block_id, index)
+            .await
+    }
+
+    async fn get_transaction_receipt<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<TransactionReceiptWithBlockInfo, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        let key = CacheKey::TransactionReceipt(*transaction_hash.as_ref());
+        if let Some(CacheValue::TransactionReceipt(receipt)) = self.cache.get(&key).await {
+            return Ok(receipt);
+        }
+
+        let receipt = self.inner.get_transaction_receipt(transaction_hash).await?;
+        if self.is_transaction_final(receipt.receipt.finality_status()) {
+            self.cache
+                .insert(key, CacheValue::TransactionReceipt(receipt.clone()))
+                .await;
+        }
+
+        Ok(receipt)
+    }
+
+    async fn get_class<B, H>(
+        &self,
+        block_id: B,
+        class_hash: H,
+    ) -> Result<ContractClass, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        H: AsRef<Felt> + Send + Sync,
+    {
+        let key = CacheKey::Class(*class_hash.as_ref());
+
+        // A class found at any block stays declared in later blocks. Earlier blocks have to be
+        // checked with the inner provider.
+        if matches!(
+            block_id.as_ref(),
+            BlockId::Tag(BlockTag::Latest | BlockTag::PreConfirmed)
+        ) {
+            if let Some(CacheValue::Class(class)) = self.cache.get(&key).await {
+                return Ok(class);
+            }
+        }
+
+        let class = self.inner.get_class(block_id, class_hash).await?;
+        self.cache
+            .insert(key, CacheValue::Class(class.clone()))
+            .await;
+
+        Ok(class)
+    }
+
+    async fn get_class_hash_at<B, A>(
+        &self,
+        block_id: B,
+        contract_address: A,
+    ) -> Result<Felt, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        A: AsRef<Felt> + Send + Sync,
+    {
+        self.inner
+            .get_class_hash_at(block_id, contract_address)
+            .await
+    }
+
+    async fn get_class_at<B, A>(
+        &self,
+        block_id: B,
+        contract_address: A,
+    ) -> Result<ContractClass, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        A: AsRef<Felt> + Send + Sync,
+    {
+        self.inner.get_class_at(block_id, contract_address).await
+    }
+
+    async fn get_block_transaction_count<B>(&self, block_id: B) -> Result<u64, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.inner.get_block_transaction_count(block_id).await
+    }
+
+    async fn call<R, B>(&self, request: R, block_id: B) -> Result<Vec<Felt>, ProviderError>
+    where
+        R: AsRef<FunctionCall> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.inner.call(request, block_id).await
+    }
+
+    async fn estimate_fee<R, S, B>(
+        &self,
+        request: R,
+        simulation_flags: S,
+        block_id: B,
+    ) -> Result<Vec<FeeEstimate>, ProviderError>
+    where
+        R: AsRef<[BroadcastedTransaction]> + Send + Sync,
+        S: AsRef<[SimulationFlagForEstimateFee]> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.inner
+            .estimate_fee(request, simulation_flags, block_id)
+            .await
+    }
+
+    async fn estimate_message_fee<M, B>(
+        &self,
+        message: M,
+        block_id: B,
+    ) -> Result<MessageFeeEstimate, ProviderError>
+    where
+        M: AsRef<MsgFromL1> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.inner.estimate_message_fee(message, block_id).await
+    }
+
+    async fn block_number(&self) -> Result<u64, ProviderError> {
+        self.inner.block_number().await
+    }
+
+    async fn block_hash_and_number(&self) -> Result<BlockHashAndNumber, ProviderError> {
+        self.inner.block_hash_and_number().await
+    }
+
+    async fn chain_id(&self) -> Result<Felt, ProviderError> {
+        self.inner.chain_id().await
+    }
+
+    async fn syncing(&self) -> Result<SyncStatusType, ProviderError> {
+        self.inner.syncing().await
+    }
+
+    async fn get_events(
+        &self,
+        filter: EventFilter,
+        continuation_token: Option<String>,
+        chunk_size: u64,
+    ) -> Result<EventsPage, ProviderError> {
+        self.inner
+            .get_events(filter, continuation_token, chunk_size)
+            .await
+    }
+
+    async fn get_nonce<B, A>(&self, block_id: B, contract_address: A) -> Result<Felt, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        A: AsRef<Felt> + Send + Sync,
+    {
+        self.inner.get_nonce(block_id, contract_address).await
+    }
+
+    async fn get_storage_proof<B, H, A, K>(
+        &self,
+        block_id: B,
+        class_hashes: H,
+        contract_addresses: A,
+        contracts_storage_keys: K,
+    ) -> Result<StorageProof, ProviderError>
+    where
+        B: AsRef<ConfirmedBlockId> + Send + Sync,
+        H: AsRef<[Felt]> + Send + Sync,
+        A: AsRef<[Felt]> + Send + Sync,
+        K: AsRef<[ContractStorageKeys]> + Send + Sync,
+    {
+        self.inner
+            .get_storage_proof(
+                block_id,
+                class_hashes,
+                contract_addresses,
+                contracts_storage_keys,
+            )
+            .await
+    }
+
+    async fn add_invoke_transaction<I>(
+        &self,
+        invoke_transaction: I,
+    ) -> Result<InvokeTransactionResult, ProviderError>
+    where
+        I: AsRef<BroadcastedInvokeTransaction> + Send + Sync,
+    {
+        self.inner.add_invoke_transaction(invoke_transaction).await
+    }
+
+    async fn add_declare_transaction<D>(
+        &self,
+        declare_transaction: D,
+    ) -> Result<DeclareTransactionResult, ProviderError>
+    where
+        D: AsRef<BroadcastedDeclareTransaction> + Send + Sync,
+    {
+        self.inner
+            .add_declare_transaction(declare_transaction)
+            .await
+    }
+
+    async fn add_deploy_account_transaction<D>(
+        &self,
+        deploy_account_transaction: D,
+    ) -> Result<DeployAccountTransactionResult, ProviderError>
+    where
+        D: AsRef<BroadcastedDeployAccountTransaction> + Send + Sync,
+    {
+        self.inner
+            .add_deploy_account_transaction(deploy_account_transaction)
+            .await
+    }
+
+    async fn trace_transaction<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<TransactionTrace, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.inner.trace_transaction(transaction_hash).await
+    }
+
+    async fn simulate_transactions<B, T, S>(
+        &self,
+        block_id: B,
+        transactions: T,
+        simulation_flags: S,
+    ) -> Result<Vec<SimulatedTransaction>, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        T: AsRef<[BroadcastedTransaction]> + Send + Sync,
+        S: AsRef<[SimulationFlag]> + Send + Sync,
+    {
+        self.inner
+            .simulate_transactions(block_id, transactions, simulation_flags)
+            .await
+    }
+
+    async fn trace_block_transactions<B>(
+        &self,
+        block_id: B,
+    ) -> Result<Vec<TransactionTraceWithHash>, ProviderError>
+    where
+        B: AsRef<ConfirmedBlockId> + Send + Sync,
+    {
+        self.inner.trace_block_transactions(block_id).await
+    }
+
+    async fn batch_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<ProviderResponseData>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        self.inner.batch_requests(requests).await
+    }
+
+    async fn try_batch_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<Result<ProviderResponseData, ProviderError>>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        self.inner.try_batch_requests(requests).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::jsonrpc::{JsonRpcClient, JsonRpcMethod, MockHandler, MockResponse, MockTransport};
+
+    /// A handler returning canned responses for a block with `block_status`.
+    #[derive(Debug)]
+    struct MockChain {
+        block_status: &'static str,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("unsupported method")]
+    struct MockError;
+
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+    #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+    impl MockHandler for MockChain {
+        type Error = MockError;
+
+        async fn respond(
+            &self,
+            method: JsonRpcMethod,
+            _params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            let result = match method {
+                JsonRpcMethod::GetStorageAt => serde_json::json!("0x1234"),
+                JsonRpcMethod::GetClass => serde_json::json!({
+                    "sierra_program": [],
+                    "contract_class_version": "0.1.0",
+                    "entry_points_by_type": {
+                        "CONSTRUCTOR": [],
+                        "EXTERNAL": [],
+                        "L1_HANDLER": []
+                    },
+                    "abi": ""
+                }),
+                JsonRpcMethod::GetBlockWithTxHashes => serde_json::json!({
+                    "status": self.block_status,
+                    "block_hash": "0x1",
+                    "parent_hash": "0x0",
+                    "block_number": 1,
+                    "new_root": "0x0",
+                    "timestamp": 1,
+                    "sequencer_address": "0x0",
+                    "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+                    "l2_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+                    "l1_data_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+                    "l1_da_mode": "BLOB",
+                    "starknet_version": "0.14.0",
+                    "transactions": []
+                }),
+                _ => return Err(MockError),
+            };
+
+            Ok(result.into())
+        }
+    }
+
+    type MockProvider = CachingProvider<JsonRpcClient<Arc<MockTransport<MockChain>>>>;
+
+    fn create_provider(
+        block_status: &'static str,
+    ) -> (Arc<MockTransport<MockChain>>, MockProvider) {
+        let transport = Arc::new(MockTransport::new(MockChain { block_status }));
+        let provider = CachingProvider::new(JsonRpcClient::new(transport.clone()));
+        (transport, provider)
+    }
+
+    #[tokio::test]
+    async fn test_cache_class() {
+        let (transport, provider) = create_provider("ACCEPTED_ON_L1");
+
+        for _ in 0..3 {
+            provider
+                .get_class(BlockId::Tag(BlockTag::Latest), Felt::ONE)
+                .await
+                .unwrap();
+        }
+        assert_eq!(transport.requests(), 1);
+
+        // The class might not be declared yet at a specific block
+        for _ in 0..2 {
+            provider
+                .get_class(BlockId::Number(1), Felt::ONE)
+                .await
+                .unwrap();
+        }
+        assert_eq!(transport.requests(), 3);
+
+        provider
+            .get_class(BlockId::Tag(BlockTag::PreConfirmed), Felt::ONE)
+            .await
+            .unwrap();
+        assert_eq!(transport.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cache_storage_at() {
+        let (transport, provider) = create_provider("ACCEPTED_ON_L1");
+
+        for _ in 0..3 {
+            provider
+                .get_storage_at(Felt::ONE, Felt::TWO, BlockId::Hash(Felt::THREE))
+                .await
+                .unwrap();
+        }
+        assert_eq!(transport.requests(), 1);
+
+        // Dynamic and non-final block references are never cached
+        for _ in 0..3 {
+            provider
+                .get_storage_at(Felt::ONE, Felt::TWO, BlockId::Tag(BlockTag::Latest))
+                .await
+                .unwrap();
+            provider
+                .get_storage_at(Felt::ONE, Felt::TWO, BlockId::Number(1))
+                .await
+                .unwrap();
+        }
+        assert_eq!(transport.requests(), 7);
+        assert_eq!(provider.cache().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_block_finality() {
+        let (transport, provider) = create_provider("ACCEPTED_ON_L1");
+        for _ in 0..3 {
+            provider
+                .get_block_with_tx_hashes(BlockId::Number(1))
+                .await
+                .unwrap();
+        }
+        assert_eq!(transport.requests(), 1);
+
+        let (transport, provider) = create_provider("ACCEPTED_ON_L2");
+        for _ in 0..3 {
+            provider
+                .get_block_with_tx_hashes(BlockId::Number(1))
+                .await
+                .unwrap();
+        }
+        assert_eq!(transport.requests(), 3);
+
+        let (transport, provider) = create_provider("ACCEPTED_ON_L2");
+        let provider = provider.with_l2_finality(true);
+        for _ in 0..3 {
+            provider
+                .get_block_with_tx_hashes(BlockId::Number(1))
+                .await
+                .unwrap();
+        }
+        assert_eq!(transport.requests(), 1);
+    }
+}
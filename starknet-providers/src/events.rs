@@ -0,0 +1,441 @@
+use futures_util::{
+    future::Either,
+    stream::{self, Stream},
+    StreamExt, TryStreamExt,
+};
+use starknet_core::types::{
+    BlockId, BlockTag, EmittedEvent, EventFilter, EventsPage, MaybePreConfirmedBlockWithTxHashes,
+};
+
+use crate::{jsonrpc::RetryPolicy, Provider, ProviderError};
+
+/// A helper for iterating over all events matching an [`EventFilter`] as a [`Stream`], taking care
+/// of walking through pages via continuation tokens.
+///
+/// By default, the filter is sent as is and pages are fetched one after another. For large
+/// backfills, the block range of the filter can instead be split into chunks with
+/// [`with_block_range`](Self::with_block_range), which can then be fetched concurrently with
+/// [`with_concurrency`](Self::with_concurrency). Either way, events are always yielded in the same
+/// order as they'd be returned by paginating over the original filter.
+///
+/// Failed page requests are retried according to a [`RetryPolicy`] without losing the progress made
+/// on the chunk. Errors returned by the node itself (i.e. [`ProviderError::StarknetError`]) are not
+/// retried.
+#[derive(Debug)]
+pub struct EventPaginator<P> {
+    provider: P,
+    filter: EventFilter,
+    page_size: u64,
+    block_range: Option<u64>,
+    concurrency: usize,
+    retry_policy: RetryPolicy,
+}
+
+impl<P> EventPaginator<P> {
+    /// Constructs [`EventPaginator`] for the given filter.
+    ///
+    /// By default, pages of 1000 events are requested, the block range is not chunked, and failed
+    /// requests are retried with the default [`RetryPolicy`].
+    pub fn new(provider: P, filter: EventFilter) -> Self {
+        Self {
+            provider,
+            filter,
+            page_size: 1000,
+            block_range: None,
+            concurrency: 1,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Consumes the current [`EventPaginator`] instance and returns a new one with the number of
+    /// events requested per page set.
+    pub fn with_page_size(self, page_size: u64) -> Self {
+        Self { page_size, ..self }
+    }
+
+    /// Consumes the current [`EventPaginator`] instance and returns a new one that splits the block
+    /// range of the filter into chunks of `block_range` blocks each. A value of `0` is treated as
+    /// `1`.
+    ///
+    /// Block IDs other than numbers in the filter are resolved to block numbers once when the
+    /// stream starts. The last chunk keeps the original `to_block` of the filter, so that e.g.
+    /// events from the pre-confirmed block are still included when requested.
+    pub fn with_block_range(self, block_range: u64) -> Self {
+        Self {
+            block_range: Some(block_range.max(1)),
+            ..self
+        }
+    }
+
+    /// Consumes the current [`EventPaginator`] instance and returns a new one that fetches up to
+    /// `concurrency` chunks at the same time. A value of `0` is treated as `1`.
+    ///
+    /// This only takes effect when the block range is chunked. Note that with a concurrency above
+    /// `1`, events from a whole chunk are buffered in memory before being yielded.
+    pub fn with_concurrency(self, concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            ..self
+        }
+    }
+
+    /// Consumes the current [`EventPaginator`] instance and returns a new one with the policy for
+    /// retrying failed requests set. Use [`RetryPolicy::none`] to disable retries.
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// Gets a reference to the underlying provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Gets a reference to the filter being paginated over.
+    pub const fn filter(&self) -> &EventFilter {
+        &self.filter
+    }
+}
+
+impl<P> EventPaginator<P>
+where
+    P: Provider,
+{
+    /// Returns a stream of all events matching the filter.
+    ///
+    /// The stream ends after yielding the first error.
+    pub fn stream(&self) -> impl Stream<Item = Result<EmittedEvent, ProviderError>> + '_ {
+        stream::once(self.chunks())
+            .map_ok(move |chunks| {
+                if self.concurrency > 1 {
+                    Either::Left(
+                        stream::iter(chunks)
+                            .map(move |chunk| self.fetch_chunk(chunk))
+                            .buffered(self.concurrency),
+                    )
+                } else {
+                    Either::Right(
+                        stream::iter(chunks)
+                            .map(move |chunk| self.pages(chunk))
+                            .flatten(),
+                    )
+                }
+            })
+            .try_flatten()
+            .map_ok(|events| stream::iter(events.into_iter().map(Ok)))
+            .try_flatten()
+            .scan(false, |failed, item| {
+                let item = if *failed { None } else { Some(item) };
+                if matches!(item, Some(Err(_))) {
+                    *failed = true;
+                }
+                async move { item }
+            })
+    }
+
+    /// Splits the filter into chunks according to the configured block range.
+    async fn chunks(&self) -> Result<Vec<EventFilter>, ProviderError> {
+        let Some(block_range) = self.block_range else {
+            return Ok(vec![self.filter.clone()]);
+        };
+
+        let from_block = match self.filter.from_block {
+            Some(block_id) => self.resolve(block_id).await?,
+            None => 0,
+        };
+        let to_block = self
+            .resolve(
+                self.filter
+                    .to_block
+                    .unwrap_or(BlockId::Tag(BlockTag::Latest)),
+            )
+            .await?;
+        if from_block > to_block {
+            return Ok(vec![self.filter.clone()]);
+        }
+
+        let mut chunks = vec![];
+        let mut chunk_start = from_block;
+        loop {
+            let chunk_end = chunk_start.saturating_add(block_range - 1);
+            if chunk_end >= to_block {
+                chunks.push(EventFilter {
+                    from_block: Some(BlockId::Number(chunk_start)),
+                    ..self.filter.clone()
+                });
+                return Ok(chunks);
+            }
+
+            chunks.push(EventFilter {
+                from_block: Some(BlockId::Number(chunk_start)),
+                to_block: Some(BlockId::Number(chunk_end)),
+                ..self.filter.clone()
+            });
+            chunk_start = chunk_end + 1;
+        }
+    }
+
+    async fn resolve(&self, block_id: BlockId) -> Result<u64, ProviderError> {
+        match block_id {
+            BlockId::Number(block_number) => Ok(block_number),
+            BlockId::Tag(BlockTag::Latest | BlockTag::PreConfirmed) => {
+                self.retry_policy
+                    .retry(true, || self.provider.block_number())
+                    .await
+            }
+            BlockId::Hash(_) | BlockId::Tag(BlockTag::L1Accepted) => {
+                match self
+                    .retry_policy
+                    .retry(true, || self.provider.get_block_with_tx_hashes(block_id))
+                    .await?
+                {
+                    MaybePreConfirmedBlockWithTxHashes::Block(block) => Ok(block.block_number),
+                    MaybePreConfirmedBlockWithTxHashes::PreConfirmedBlock(block) => {
+                        Ok(block.block_number)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lazily fetches pages for a chunk one after another.
+    fn pages(
+        &self,
+        filter: EventFilter,
+    ) -> impl Stream<Item = Result<Vec<EmittedEvent>, ProviderError>> + '_ {
+        // `None` marks the end of the chunk
+        stream::unfold(Some(None), move |continuation_token| {
+            let filter = filter.clone();
+            async move {
+                let continuation_token = continuation_token?;
+                match self.fetch_page(filter, continuation_token).await {
+                    Ok(page) => Some((Ok(page.events), page.continuation_token.map(Some))),
+                    Err(err) => Some((Err(err), None)),
+                }
+            }
+        })
+    }
+
+    /// Fetches all pages for a chunk.
+    async fn fetch_chunk(&self, filter: EventFilter) -> Result<Vec<EmittedEvent>, ProviderError> {
+        let mut events = vec![];
+        let mut continuation_token = None;
+
+        loop {
+            let page = self.fetch_page(filter.clone(), continuation_token).await?;
+
+            events.extend(page.events);
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                return Ok(events);
+            }
+        }
+    }
+
+    async fn fetch_page(
+        &self,
+        filter: EventFilter,
+        continuation_token: Option<String>,
+    ) -> Result<EventsPage, ProviderError> {
+        self.retry_policy
+            .retry(true, || {
+                self.provider
+                    .get_events(filter.clone(), continuation_token.clone(), self.page_size)
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use reqwest::StatusCode;
+
+    use super::*;
+    use crate::jsonrpc::{
+        HttpTransportError, JsonRpcClient, JsonRpcMethod, MockHandler, MockResponse, MockTransport,
+    };
+
+    const LATEST_BLOCK: u64 = 9;
+    const EVENTS_PER_BLOCK: u64 = 3;
+
+    /// A handler serving blocks `0` to `LATEST_BLOCK`, each with `EVENTS_PER_BLOCK` events whose
+    /// data is the block number and the index within the block. The first `failures` requests fail
+    /// with a transient error.
+    #[derive(Debug)]
+    struct MockChain {
+        failures: AtomicU32,
+    }
+
+    fn block_number_of(block_id: &serde_json::Value) -> Option<u64> {
+        match block_id {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(_) => Some(LATEST_BLOCK),
+            _ => block_id["block_number"].as_u64(),
+        }
+    }
+
+    #[async_trait]
+    impl MockHandler for MockChain {
+        type Error = HttpTransportError;
+
+        async fn respond(
+            &self,
+            method: JsonRpcMethod,
+            params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            if self
+                .failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |failures| {
+                    failures.checked_sub(1)
+                })
+                .is_ok()
+            {
+                return Err(HttpTransportError::UnexpectedStatus {
+                    status: StatusCode::SERVICE_UNAVAILABLE,
+                    retry_after: None,
+                });
+            }
+
+            let result = match method {
+                JsonRpcMethod::BlockNumber => serde_json::json!(LATEST_BLOCK),
+                JsonRpcMethod::GetEvents => {
+                    let from_block = block_number_of(&params["filter"]["from_block"]).unwrap_or(0);
+                    let to_block =
+                        block_number_of(&params["filter"]["to_block"]).unwrap_or(LATEST_BLOCK);
+                    let offset = params["filter"]["continuation_token"]
+                        .as_str()
+                        .map_or(0, |token| token.parse::<u64>().unwrap());
+                    let chunk_size = params["filter"]["chunk_size"].as_u64().unwrap();
+
+                    // Makes later chunks respond faster to shuffle completion order
+                    tokio::time::sleep(Duration::from_millis(
+                        LATEST_BLOCK.saturating_sub(from_block),
+                    ))
+                    .await;
+
+                    let all_events = (from_block..=to_block)
+                        .flat_map(|block| (0..EVENTS_PER_BLOCK).map(move |index| (block, index)))
+                        .collect::<Vec<_>>();
+                    let end = (offset + chunk_size).min(all_events.len() as u64);
+                    let events = all_events[offset as usize..end as usize]
+                        .iter()
+                        .map(|(block, index)| {
+                            serde_json::json!({
+                                "from_address": "0x1",
+                                "keys": [],
+                                "data": [format!("{block:#x}"), format!("{index:#x}")],
+                                "block_hash": format!("{block:#x}"),
+                                "block_number": block,
+                                "transaction_hash": "0x1",
+                            })
+                        })
+                        .collect::<Vec<_>>();
+
+                    let mut page = serde_json::json!({ "events": events });
+                    if end < all_events.len() as u64 {
+                        page["continuation_token"] = end.to_string().into();
+                    }
+                    page
+                }
+                _ => return Err(HttpTransportError::UnexpectedResponseId(0)),
+            };
+
+            Ok(result.into())
+        }
+    }
+
+    fn create_paginator(failures: u32) -> EventPaginator<JsonRpcClient<MockTransport<MockChain>>> {
+        EventPaginator::new(
+            JsonRpcClient::new(MockTransport::new(MockChain {
+                failures: AtomicU32::new(failures),
+            })),
+            EventFilter {
+                from_block: Some(BlockId::Number(2)),
+                to_block: None,
+                address: None,
+                keys: None,
+            },
+        )
+        .with_page_size(2)
+        .with_retry_policy(RetryPolicy::default().with_initial_backoff(Duration::ZERO))
+    }
+
+    async fn collect_events(
+        paginator: &EventPaginator<JsonRpcClient<MockTransport<MockChain>>>,
+    ) -> Result<Vec<(u64, u64)>, ProviderError> {
+        paginator
+            .stream()
+            .map_ok(|event| {
+                (
+                    event.data[0].try_into().unwrap(),
+                    event.data[1].try_into().unwrap(),
+                )
+            })
+            .try_collect()
+            .await
+    }
+
+    fn expected_events() -> Vec<(u64, u64)> {
+        (2..=LATEST_BLOCK)
+            .flat_map(|block| (0..EVENTS_PER_BLOCK).map(move |index| (block, index)))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_paginate() {
+        let paginator = create_paginator(0);
+        assert_eq!(collect_events(&paginator).await.unwrap(), expected_events());
+    }
+
+    #[tokio::test]
+    async fn test_paginate_chunked() {
+        for concurrency in [1, 4] {
+            let paginator = create_paginator(0)
+                .with_block_range(3)
+                .with_concurrency(concurrency);
+            assert_eq!(collect_events(&paginator).await.unwrap(), expected_events());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paginate_retry() {
+        let paginator = create_paginator(2).with_block_range(3).with_concurrency(4);
+        assert_eq!(collect_events(&paginator).await.unwrap(), expected_events());
+
+        let paginator = create_paginator(2).with_retry_policy(
+            RetryPolicy::default()
+                .with_max_attempts(2)
+                .with_initial_backoff(Duration::ZERO),
+        );
+        assert!(collect_events(&paginator).await.is_err());
+
+        // The stream ends after the first error
+        let paginator = create_paginator(u32::MAX).with_retry_policy(RetryPolicy::none());
+        let items = paginator.stream().collect::<Vec<_>>().await;
+        assert_eq!(items.len(), 1);
+        assert!(items[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_paginate_empty_range() {
+        let paginator = EventPaginator::new(
+            create_paginator(0).provider,
+            EventFilter {
+                from_block: Some(BlockId::Number(LATEST_BLOCK + 1)),
+                to_block: None,
+                address: None,
+                keys: None,
+            },
+        )
+        .with_block_range(3);
+        assert_eq!(collect_events(&paginator).await.unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,948 @@
+use std::{
+    any::Any,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use log::debug;
+use starknet_core::types::{
+    BlockHashAndNumber, BlockId, BroadcastedDeclareTransaction,
+    BroadcastedDeployAccountTransaction, BroadcastedInvokeTransaction, BroadcastedTransaction,
+    ConfirmedBlockId, ContractClass, ContractStorageKeys, DeclareTransactionResult,
+    DeployAccountTransactionResult, EventFilter, EventsPage, FeeEstimate, Felt, FunctionCall,
+    Hash256, InvokeTransactionResult, MaybePreConfirmedBlockWithReceipts,
+    MaybePreConfirmedBlockWithTxHashes, MaybePreConfirmedBlockWithTxs,
+    MaybePreConfirmedStateUpdate, MessageFeeEstimate, MessageStatus, MsgFromL1,
+    SimulatedTransaction, SimulationFlag, SimulationFlagForEstimateFee, StarknetError,
+    StorageProof, SyncStatusType, Transaction, TransactionReceiptWithBlockInfo, TransactionStatus,
+    TransactionTrace, TransactionTraceWithHash,
+};
+
+use crate::{
+    provider::ProviderImplError, Provider, ProviderError, ProviderRequestData, ProviderResponseData,
+};
+
+/// A [`Provider`] implementation that distributes requests across multiple underlying providers,
+/// typically pointing to different nodes or vendors.
+///
+/// How requests are dispatched depends on the configured [`FallbackMode`]. Regardless of the mode,
+/// health is tracked for each endpoint: after a configurable number of consecutive failures, an
+/// endpoint is considered unhealthy and is only used as a last resort until a cooldown period has
+/// passed.
+///
+/// Only errors unrelated to the request itself are considered endpoint failures. A
+/// [`StarknetError`] is a valid answer from the node and is returned to the caller as is.
+///
+/// Event queries are paginated with continuation tokens that are only meaningful to the node that
+/// issued them. Continuation tokens returned by this provider are therefore prefixed with the index
+/// of the issuing endpoint, and requests for subsequent pages are always sent to that endpoint.
+#[derive(Debug)]
+pub struct FallbackProvider<P> {
+    endpoints: Vec<Endpoint<P>>,
+    mode: FallbackMode,
+    failure_threshold: u32,
+    cooldown: Duration,
+    next_index: AtomicUsize,
+}
+
+/// Strategies for dispatching requests across endpoints of a [`FallbackProvider`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackMode {
+    /// Always sends requests to the first healthy endpoint in the list, moving on to the next one
+    /// only on failures.
+    Failover,
+    /// Rotates the starting endpoint for each request to spread the load, while still moving on to
+    /// the next endpoint on failures.
+    RoundRobin,
+    /// Sends read requests to all healthy endpoints concurrently and only returns a response once
+    /// the specified number of endpoints agree on it. Write requests (i.e. transaction submissions),
+    /// batch requests and event queries are dispatched as with [`Failover`](FallbackMode::Failover).
+    ///
+    /// The number must be between `1` and the number of endpoints.
+    Quorum(usize),
+}
+
+/// Snapshot of the health of an endpoint in a [`FallbackProvider`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// Number of failures since the last success.
+    pub consecutive_failures: u32,
+    /// Total number of successful requests.
+    pub total_successes: u64,
+    /// Total number of failed requests.
+    pub total_failures: u64,
+    /// Until when the endpoint is considered unhealthy, if it is.
+    pub unhealthy_until: Option<Instant>,
+}
+
+/// Errors specific to [`FallbackProvider`].
+#[derive(Debug, thiserror::Error)]
+pub enum FallbackProviderError {
+    /// The provider was constructed without any endpoints.
+    #[error("no endpoint available")]
+    NoEndpoints,
+    /// Not enough endpoints returned the same response in [`Quorum`](FallbackMode::Quorum) mode.
+    #[error("quorum not reached: {agreeing} of {required} required endpoints agreed")]
+    QuorumNotReached {
+        /// Number of agreeing responses required.
+        required: usize,
+        /// Largest number of endpoints that agreed on the same response.
+        agreeing: usize,
+    },
+}
+
+#[derive(Debug)]
+struct Endpoint<P> {
+    provider: P,
+    health: Mutex<EndpointHealth>,
+}
+
+impl<P> FallbackProvider<P> {
+    /// Constructs [`FallbackProvider`] from a list of providers, using [`Failover`] mode.
+    ///
+    /// By default, an endpoint is marked unhealthy after 3 consecutive failures, and stays so for
+    /// 30 seconds.
+    ///
+    /// [`Failover`]: FallbackMode::Failover
+    pub fn new(providers: impl IntoIterator<Item = P>) -> Self {
+        Self {
+            endpoints: providers
+                .into_iter()
+                .map(|provider| Endpoint {
+                    provider,
+                    health: Mutex::new(EndpointHealth::default()),
+                })
+                .collect(),
+            mode: FallbackMode::Failover,
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+            next_index: AtomicUsize::new(0),
+        }
+    }
+
+    /// Consumes the current [`FallbackProvider`] instance and returns a new one with the dispatch
+    /// mode set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mode` is [`Quorum`](FallbackMode::Quorum) with a threshold of `0` or one larger
+    /// than the number of endpoints, as such a quorum could never be meaningfully reached.
+    pub fn with_mode(self, mode: FallbackMode) -> Self {
+        if let FallbackMode::Quorum(threshold) = mode {
+            assert!(
+                threshold >= 1 && threshold <= self.endpoints.len(),
+                "invalid quorum threshold {} for {} endpoints",
+                threshold,
+                self.endpoints.len()
+            );
+        }
+
+        Self { mode, ..self }
+    }
+
+    /// Consumes the current [`FallbackProvider`] instance and returns a new one with the number of
+    /// consecutive failures after which an endpoint is considered unhealthy set. A value of `0` is
+    /// treated as `1`.
+    pub fn with_failure_threshold(self, failure_threshold: u32) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            ..self
+        }
+    }
+
+    /// Consumes the current [`FallbackProvider`] instance and returns a new one with the duration
+    /// for which an endpoint stays unhealthy set.
+    pub fn with_cooldown(self, cooldown: Duration) -> Self {
+        Self { cooldown, ..self }
+    }
+
+    /// Gets the dispatch mode in use.
+    pub const fn mode(&self) -> FallbackMode {
+        self.mode
+    }
+
+    /// Gets an iterator over the underlying providers.
+    pub fn providers(&self) -> impl Iterator<Item = &P> {
+        self.endpoints.iter().map(|endpoint| &endpoint.provider)
+    }
+
+    /// Gets a snapshot of the health of all endpoints, in the same order as the providers.
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| endpoint.health.lock().unwrap().clone())
+            .collect()
+    }
+
+    /// Returns endpoint indices in the order they should be tried. Unhealthy endpoints are moved
+    /// to the end instead of being skipped, so that they're still used as a last resort.
+    fn candidates(&self) -> Vec<usize> {
+        let count = self.endpoints.len();
+        let start = match self.mode {
+            FallbackMode::RoundRobin if count > 0 => {
+                self.next_index.fetch_add(1, Ordering::Relaxed) % count
+            }
+            _ => 0,
+        };
+
+        let now = Instant::now();
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = (0..count)
+            .map(|offset| (start + offset) % count)
+            .partition(|index| self.endpoints[*index].is_healthy(now));
+
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    fn record_success(&self, index: usize) {
+        let mut health = self.endpoints[index].health.lock().unwrap();
+        health.consecutive_failures = 0;
+        health.total_successes += 1;
+        health.unhealthy_until = None;
+    }
+
+    fn record_failure(&self, index: usize, error: &ProviderError) {
+        debug!("Request to endpoint #{} failed: {}", index, error);
+
+        let mut health = self.endpoints[index].health.lock().unwrap();
+        health.consecutive_failures += 1;
+        health.total_failures += 1;
+        if health.consecutive_failures >= self.failure_threshold {
+            health.unhealthy_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    /// Sends a request to one endpoint at a time until a response is obtained.
+    async fn failover<'s, T, F, Fut>(&'s self, request: F) -> Result<T, ProviderError>
+    where
+        F: Fn(&'s P) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        self.failover_indexed(request).await.map(|(_, value)| value)
+    }
+
+    /// Same as [`failover`](Self::failover), but also returns the index of the endpoint that
+    /// responded.
+    async fn failover_indexed<'s, T, F, Fut>(
+        &'s self,
+        request: F,
+    ) -> Result<(usize, T), ProviderError>
+    where
+        F: Fn(&'s P) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let mut last_error = None;
+
+        for index in self.candidates() {
+            match self.send_to(index, &request).await {
+                Ok(value) => return Ok((index, value)),
+                Err(ProviderError::StarknetError(error)) => {
+                    return Err(ProviderError::StarknetError(error))
+                }
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| FallbackProviderError::NoEndpoints.into()))
+    }
+
+    /// Sends a request to a specific endpoint, recording the outcome in its health.
+    async fn send_to<'s, T, F, Fut>(&'s self, index: usize, request: F) -> Result<T, ProviderError>
+    where
+        F: Fn(&'s P) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        match request(&self.endpoints[index].provider).await {
+            Err(ProviderError::StarknetError(error)) => {
+                // The endpoint is working fine even though the request failed
+                self.record_success(index);
+                Err(ProviderError::StarknetError(error))
+            }
+            Err(error) => {
+                self.record_failure(index, &error);
+                Err(error)
+            }
+            Ok(value) => {
+                self.record_success(index);
+                Ok(value)
+            }
+        }
+    }
+
+    /// Sends a request to all healthy endpoints concurrently until enough of them agree on the
+    /// same response.
+    async fn quorum<'s, T, F, Fut>(
+        &'s self,
+        threshold: usize,
+        request: F,
+    ) -> Result<T, ProviderError>
+    where
+        T: PartialEq,
+        F: Fn(&'s P) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        let now = Instant::now();
+
+        let mut candidates = self.candidates();
+        let healthy_count = candidates
+            .iter()
+            .filter(|index| self.endpoints[**index].is_healthy(now))
+            .count();
+        // Only fall back to unhealthy endpoints when the quorum is otherwise unreachable
+        candidates.truncate(healthy_count.max(threshold));
+
+        let mut pending = candidates
+            .into_iter()
+            .map(|index| {
+                let response = request(&self.endpoints[index].provider);
+                async move { (index, response.await) }
+            })
+            .collect::<FuturesUnordered<_>>();
+
+        let mut votes: Vec<(Result<T, StarknetError>, usize)> = vec![];
+        let mut last_error = None;
+
+        while let Some((index, response)) = pending.next().await {
+            let vote = match response {
+                Ok(value) => Ok(value),
+                Err(ProviderError::StarknetError(error)) => Err(error),
+                Err(error) => {
+                    self.record_failure(index, &error);
+                    last_error = Some(error);
+                    continue;
+                }
+            };
+            self.record_success(index);
+
+            let position = match votes.iter().position(|(existing, _)| existing == &vote) {
+                Some(position) => {
+                    votes[position].1 += 1;
+                    position
+                }
+                None => {
+                    votes.push((vote, 1));
+                    votes.len() - 1
+                }
+            };
+
+            if votes[position].1 >= threshold {
+                return votes
+                    .swap_remove(position)
+                    .0
+                    .map_err(ProviderError::StarknetError);
+            }
+        }
+
+        match (votes.iter().map(|(_, count)| *count).max(), last_error) {
+            (None, Some(last_error)) => Err(last_error),
+            (agreeing, _) => Err(FallbackProviderError::QuorumNotReached {
+                required: threshold,
+                agreeing: agreeing.unwrap_or_default(),
+            }
+            .into()),
+        }
+    }
+
+    /// Dispatches a read request according to the configured mode.
+    async fn read<'s, T, F, Fut>(&'s self, request: F) -> Result<T, ProviderError>
+    where
+        T: PartialEq,
+        F: Fn(&'s P) -> Fut,
+        Fut: Future<Output = Result<T, ProviderError>>,
+    {
+        match self.mode {
+            FallbackMode::Quorum(threshold) => self.quorum(threshold, request).await,
+            FallbackMode::Failover | FallbackMode::RoundRobin => self.failover(request).await,
+        }
+    }
+
+    /// Splits a continuation token issued by this provider into the index of the endpoint that
+    /// issued the original token and the original token.
+    fn parse_continuation_token<'t>(&self, token: &'t str) -> Option<(usize, &'t str)> {
+        let (index, token) = token.split_once(':')?;
+        let index = index.parse::<usize>().ok()?;
+        (index < self.endpoints.len()).then_some((index, token))
+    }
+}
+
+impl<P> Endpoint<P> {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.health
+            .lock()
+            .unwrap()
+            .unhealthy_until
+            .is_none_or(|until| until <= now)
+    }
+}
+
+#[async_trait]
+impl<P> Provider for FallbackProvider<P>
+where
+    P: Provider + Send + Sync,
+{
+    async fn spec_version(&self) -> Result<String, ProviderError> {
+        self.read(|provider| provider.spec_version()).await
+    }
+
+    async fn get_block_with_tx_hashes<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedBlockWithTxHashes, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.get_block_with_tx_hashes(&block_id))
+            .await
+    }
+
+    async fn get_block_with_txs<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedBlockWithTxs, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.get_block_with_txs(&block_id))
+            .await
+    }
+
+    async fn get_block_with_receipts<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedBlockWithReceipts, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.get_block_with_receipts(&block_id))
+            .await
+    }
+
+    async fn get_state_update<B>(
+        &self,
+        block_id: B,
+    ) -> Result<MaybePreConfirmedStateUpdate, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.get_state_update(&block_id))
+            .await
+    }
+
+    async fn get_storage_at<A, K, B>(
+        &self,
+        contract_address: A,
+        key: K,
+        block_id: B,
+    ) -> Result<Felt, ProviderError>
+    where
+        A: AsRef<Felt> + Send + Sync,
+        K: AsRef<Felt> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.get_storage_at(&contract_address, &key, &block_id))
+            .await
+    }
+
+    async fn get_messages_status(
+        &self,
+        transaction_hash: Hash256,
+    ) -> Result<Vec<MessageStatus>, ProviderError> {
+        self.read(|provider| provider.get_messages_status(transaction_hash))
+            .await
+    }
+
+    async fn get_transaction_status<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<TransactionStatus, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.get_transaction_status(&transaction_hash))
+            .await
+    }
+
+    async fn get_transaction_by_hash<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<Transaction, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.get_transaction_by_hash(&transaction_hash))
+            .await
+    }
+
+    async fn get_transaction_by_block_id_and_index<B>(
+        &self,
+        block_id: B,
+        index: u64,
+    ) -> Result<Transaction, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.get_transaction_by_block_id_and_index(&block_id, index))
+            .await
+    }
+
+    async fn get_transaction_receipt<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<TransactionReceiptWithBlockInfo, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.get_transaction_receipt(&transaction_hash))
+            .await
+    }
+
+    async fn get_class<B, H>(
+        &self,
+        block_id: B,
+        class_hash: H,
+    ) -> Result<ContractClass, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.get_class(&block_id, &class_hash))
+            .await
+    }
+
+    async fn get_class_hash_at<B, A>(
+        &self,
+        block_id: B,
+        contract_address: A,
+    ) -> Result<Felt, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        A: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.get_class_hash_at(&block_id, &contract_address))
+            .await
+    }
+
+    async fn get_class_at<B, A>(
+        &self,
+        block_id: B,
+        contract_address: A,
+    ) -> Result<ContractClass, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        A: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.get_class_at(&block_id, &contract_address))
+            .await
+    }
+
+    async fn get_block_transaction_count<B>(&self, block_id: B) -> Result<u64, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.get_block_transaction_count(&block_id))
+            .await
+    }
+
+    async fn call<R, B>(&self, request: R, block_id: B) -> Result<Vec<Felt>, ProviderError>
+    where
+        R: AsRef<FunctionCall> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.call(&request, &block_id))
+            .await
+    }
+
+    async fn estimate_fee<R, S, B>(
+        &self,
+        request: R,
+        simulation_flags: S,
+        block_id: B,
+    ) -> Result<Vec<FeeEstimate>, ProviderError>
+    where
+        R: AsRef<[BroadcastedTransaction]> + Send + Sync,
+        S: AsRef<[SimulationFlagForEstimateFee]> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.estimate_fee(&request, &simulation_flags, &block_id))
+            .await
+    }
+
+    async fn estimate_message_fee<M, B>(
+        &self,
+        message: M,
+        block_id: B,
+    ) -> Result<MessageFeeEstimate, ProviderError>
+    where
+        M: AsRef<MsgFromL1> + Send + Sync,
+        B: AsRef<BlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.estimate_message_fee(&message, &block_id))
+            .await
+    }
+
+    async fn block_number(&self) -> Result<u64, ProviderError> {
+        self.read(|provider| provider.block_number()).await
+    }
+
+    async fn block_hash_and_number(&self) -> Result<BlockHashAndNumber, ProviderError> {
+        self.read(|provider| provider.block_hash_and_number()).await
+    }
+
+    async fn chain_id(&self) -> Result<Felt, ProviderError> {
+        self.read(|provider| provider.chain_id()).await
+    }
+
+    async fn syncing(&self) -> Result<SyncStatusType, ProviderError> {
+        self.read(|provider| provider.syncing()).await
+    }
+
+    async fn get_events(
+        &self,
+        filter: EventFilter,
+        continuation_token: Option<String>,
+        chunk_size: u64,
+    ) -> Result<EventsPage, ProviderError> {
+        // Subsequent pages must come from the endpoint that issued the continuation token
+        let (index, page) = match continuation_token
+            .as_deref()
+            .and_then(|token| self.parse_continuation_token(token))
+        {
+            Some((index, token)) => {
+                let page = self
+                    .send_to(index, |provider| {
+                        provider.get_events(filter.clone(), Some(token.to_owned()), chunk_size)
+                    })
+                    .await?;
+                (index, page)
+            }
+            None => {
+                self.failover_indexed(|provider| {
+                    provider.get_events(filter.clone(), continuation_token.clone(), chunk_size)
+                })
+                .await?
+            }
+        };
+
+        Ok(EventsPage {
+            continuation_token: page
+                .continuation_token
+                .map(|token| format!("{index}:{token}")),
+            ..page
+        })
+    }
+
+    async fn get_nonce<B, A>(&self, block_id: B, contract_address: A) -> Result<Felt, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        A: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.get_nonce(&block_id, &contract_address))
+            .await
+    }
+
+    async fn get_storage_proof<B, H, A, K>(
+        &self,
+        block_id: B,
+        class_hashes: H,
+        contract_addresses: A,
+        contracts_storage_keys: K,
+    ) -> Result<StorageProof, ProviderError>
+    where
+        B: AsRef<ConfirmedBlockId> + Send + Sync,
+        H: AsRef<[Felt]> + Send + Sync,
+        A: AsRef<[Felt]> + Send + Sync,
+        K: AsRef<[ContractStorageKeys]> + Send + Sync,
+    {
+        self.read(|provider| {
+            provider.get_storage_proof(
+                &block_id,
+                &class_hashes,
+                &contract_addresses,
+                &contracts_storage_keys,
+            )
+        })
+        .await
+    }
+
+    async fn add_invoke_transaction<I>(
+        &self,
+        invoke_transaction: I,
+    ) -> Result<InvokeTransactionResult, ProviderError>
+    where
+        I: AsRef<BroadcastedInvokeTransaction> + Send + Sync,
+    {
+        self.failover(|provider| provider.add_invoke_transaction(&invoke_transaction))
+            .await
+    }
+
+    async fn add_declare_transaction<D>(
+        &self,
+        declare_transaction: D,
+    ) -> Result<DeclareTransactionResult, ProviderError>
+    where
+        D: AsRef<BroadcastedDeclareTransaction> + Send + Sync,
+    {
+        self.failover(|provider| provider.add_declare_transaction(&declare_transaction))
+            .await
+    }
+
+    async fn add_deploy_account_transaction<D>(
+        &self,
+        deploy_account_transaction: D,
+    ) -> Result<DeployAccountTransactionResult, ProviderError>
+    where
+        D: AsRef<BroadcastedDeployAccountTransaction> + Send + Sync,
+    {
+        self.failover(|provider| {
+            provider.add_deploy_account_transaction(&deploy_account_transaction)
+        })
+        .await
+    }
+
+    async fn trace_transaction<H>(
+        &self,
+        transaction_hash: H,
+    ) -> Result<TransactionTrace, ProviderError>
+    where
+        H: AsRef<Felt> + Send + Sync,
+    {
+        self.read(|provider| provider.trace_transaction(&transaction_hash))
+            .await
+    }
+
+    async fn simulate_transactions<B, T, S>(
+        &self,
+        block_id: B,
+        transactions: T,
+        simulation_flags: S,
+    ) -> Result<Vec<SimulatedTransaction>, ProviderError>
+    where
+        B: AsRef<BlockId> + Send + Sync,
+        T: AsRef<[BroadcastedTransaction]> + Send + Sync,
+        S: AsRef<[SimulationFlag]> + Send + Sync,
+    {
+        self.read(|provider| {
+            provider.simulate_transactions(&block_id, &transactions, &simulation_flags)
+        })
+        .await
+    }
+
+    async fn trace_block_transactions<B>(
+        &self,
+        block_id: B,
+    ) -> Result<Vec<TransactionTraceWithHash>, ProviderError>
+    where
+        B: AsRef<ConfirmedBlockId> + Send + Sync,
+    {
+        self.read(|provider| provider.trace_block_transactions(&block_id))
+            .await
+    }
+
+    async fn batch_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<ProviderResponseData>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        self.failover(|provider| provider.batch_requests(&requests))
+            .await
+    }
+
+    async fn try_batch_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<Result<ProviderResponseData, ProviderError>>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        self.failover(|provider| provider.try_batch_requests(&requests))
+            .await
+    }
+}
+
+impl ProviderImplError for FallbackProviderError {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl From<FallbackProviderError> for ProviderError {
+    fn from(value: FallbackProviderError) -> Self {
+        Self::Other(Box::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc::{JsonRpcClient, JsonRpcMethod, MockHandler, MockResponse, MockTransport};
+
+    /// A handler that returns a fixed block number, or fails if none is set. Events are served in
+    /// two pages of a single event carrying the block number.
+    #[derive(Debug)]
+    struct Endpoint(Option<u64>);
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("endpoint down")]
+    struct MockError;
+
+    #[async_trait]
+    impl MockHandler for Endpoint {
+        type Error = MockError;
+
+        async fn respond(
+            &self,
+            method: JsonRpcMethod,
+            params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            let block_number = self.0.ok_or(MockError)?;
+
+            match method {
+                JsonRpcMethod::GetEvents => {
+                    let mut page = serde_json::json!({
+                        "events": [{
+                            "from_address": "0x1",
+                            "keys": [],
+                            "data": [format!("{block_number:#x}")],
+                            "block_hash": "0x1",
+                            "block_number": 1,
+                            "transaction_hash": "0x1",
+                        }],
+                    });
+                    match params["filter"]["continuation_token"].as_str() {
+                        None => page["continuation_token"] = "next".into(),
+                        Some("next") => {}
+                        Some(_) => return Err(MockError),
+                    }
+                    Ok(page.into())
+                }
+                _ => Ok(serde_json::json!(block_number).into()),
+            }
+        }
+    }
+
+    fn create_provider(
+        endpoints: &[Option<u64>],
+    ) -> FallbackProvider<JsonRpcClient<MockTransport<Endpoint>>> {
+        FallbackProvider::new(
+            endpoints
+                .iter()
+                .map(|endpoint| JsonRpcClient::new(MockTransport::new(Endpoint(*endpoint)))),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_failover() {
+        let provider = create_provider(&[None, Some(100), Some(200)]).with_failure_threshold(2);
+
+        assert_eq!(provider.block_number().await.unwrap(), 100);
+        assert_eq!(provider.block_number().await.unwrap(), 100);
+
+        let health = provider.health();
+        assert_eq!(health[0].consecutive_failures, 2);
+        assert!(health[0].unhealthy_until.is_some());
+        assert_eq!(health[1].total_successes, 2);
+        assert_eq!(health[2].total_successes, 0);
+
+        // The unhealthy endpoint is no longer tried first
+        assert_eq!(provider.block_number().await.unwrap(), 100);
+        assert_eq!(provider.health()[0].total_failures, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_all_failing() {
+        let provider = create_provider(&[None, None]);
+
+        assert!(matches!(
+            provider.block_number().await,
+            Err(ProviderError::Other(_))
+        ));
+        assert_eq!(provider.health()[1].total_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_round_robin() {
+        let provider =
+            create_provider(&[Some(100), Some(200), Some(300)]).with_mode(FallbackMode::RoundRobin);
+
+        assert_eq!(provider.block_number().await.unwrap(), 100);
+        assert_eq!(provider.block_number().await.unwrap(), 200);
+        assert_eq!(provider.block_number().await.unwrap(), 300);
+        assert_eq!(provider.block_number().await.unwrap(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_events_pinned_to_endpoint() {
+        let filter = EventFilter {
+            from_block: None,
+            to_block: None,
+            address: None,
+            keys: None,
+        };
+
+        for mode in [FallbackMode::RoundRobin, FallbackMode::Quorum(2)] {
+            let provider = create_provider(&[Some(100), Some(200), Some(200)]).with_mode(mode);
+
+            let page = provider.get_events(filter.clone(), None, 1).await.unwrap();
+            let index = match page.events[0].data[0] {
+                data if data == Felt::from(100u64) => 0,
+                _ => 1,
+            };
+            assert_eq!(page.continuation_token, Some(format!("{index}:next")));
+
+            // Rotates the next endpoint to use in round-robin mode
+            provider.block_number().await.unwrap();
+
+            let page = provider
+                .get_events(filter.clone(), page.continuation_token, 1)
+                .await
+                .unwrap();
+            assert_eq!(page.events[0].data, vec![Felt::from([100u64, 200][index])]);
+            assert_eq!(page.continuation_token, None);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid quorum threshold")]
+    fn test_quorum_zero() {
+        let _ = create_provider(&[Some(100)]).with_mode(FallbackMode::Quorum(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid quorum threshold")]
+    fn test_quorum_above_endpoint_count() {
+        let _ = create_provider(&[Some(100), Some(200)]).with_mode(FallbackMode::Quorum(3));
+    }
+
+    #[tokio::test]
+    async fn test_quorum() {
+        let provider = create_provider(&[Some(100), None, Some(200), Some(200)])
+            .with_mode(FallbackMode::Quorum(2));
+        assert_eq!(provider.block_number().await.unwrap(), 200);
+
+        let provider =
+            create_provider(&[Some(100), None, Some(200)]).with_mode(FallbackMode::Quorum(2));
+        let error = provider.block_number().await.unwrap_err();
+        match error {
+            ProviderError::Other(error) => match error
+                .as_any()
+                .downcast_ref::<FallbackProviderError>()
+                .unwrap()
+            {
+                FallbackProviderError::QuorumNotReached { required, agreeing } => {
+                    assert_eq!(*required, 2);
+                    assert_eq!(*agreeing, 1);
+                }
+                FallbackProviderError::NoEndpoints => panic!("unexpected error"),
+            },
+            _ => panic!("unexpected error"),
+        }
+    }
+}
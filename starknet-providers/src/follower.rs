@@ -0,0 +1,687 @@
+use std::{any::Any, collections::VecDeque, time::Duration};
+
+use starknet_core::types::{
+    BlockHeader, BlockId, BlockWithTxHashes, EmittedEvent, EventFilter, Felt,
+    MaybePreConfirmedBlockWithTxHashes, ReorgData, StarknetError,
+};
+
+use crate::{provider::ProviderImplError, Provider, ProviderError};
+
+/// A follower that turns the chain tip into a linear stream of canonical blocks, along with their
+/// events, while taking care of chain reorganizations.
+///
+/// A window of recent block hashes is kept to detect reorgs. Whenever a block no longer connects
+/// to the followed chain, the follower looks up the latest block in the window that is still
+/// canonical and emits a [`FollowerUpdate::Reverted`] for all blocks after it, before moving on
+/// with the new chain. Consumers can therefore simply roll back what they've processed for the
+/// reverted range, instead of reconciling reorgs themselves.
+///
+/// Blocks are polled from the provider by calling [`next`](Self::next). Alternatively, headers
+/// obtained from a push-based source (e.g. a WebSocket subscription) can be fed via
+/// [`push_header`](Self::push_header) and [`push_reorg`](Self::push_reorg), with the resulting
+/// updates taken out via [`pop_update`](Self::pop_update). The provider is then only used for
+/// filling gaps, fetching events and reconciling reorgs.
+#[derive(Debug)]
+pub struct BlockFollower<P> {
+    provider: P,
+    start_block: Option<u64>,
+    window: VecDeque<(u64, Felt)>,
+    window_size: usize,
+    poll_interval: Duration,
+    events: Option<FollowerEventFilter>,
+    event_chunk_size: u64,
+    updates: VecDeque<FollowerUpdate>,
+}
+
+/// Updates yielded by [`BlockFollower`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum FollowerUpdate {
+    /// A new block extending the canonical chain.
+    Block(FollowedBlock),
+    /// Previously yielded blocks are no longer canonical and should be rolled back. Blocks from
+    /// the new chain are yielded afterwards, starting from `from`.
+    Reverted {
+        /// Number of the first reverted block.
+        from: u64,
+        /// Number of the last reverted block (inclusive).
+        to: u64,
+    },
+}
+
+/// A canonical block yielded by [`BlockFollower`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowedBlock {
+    /// The block header.
+    pub header: BlockHeader,
+    /// Events emitted in the block matching the configured filter, in the order they were
+    /// emitted. Always empty unless events are requested via
+    /// [`with_events`](BlockFollower::with_events).
+    pub events: Vec<EmittedEvent>,
+}
+
+/// Errors specific to [`BlockFollower`].
+#[derive(Debug, thiserror::Error)]
+pub enum FollowerError {
+    /// None of the blocks in the window is canonical anymore, so the fork point cannot be found.
+    #[error("reorg deeper than the block hash window")]
+    ReorgTooDeep,
+}
+
+#[derive(Debug, Clone)]
+struct FollowerEventFilter {
+    address: Option<Felt>,
+    keys: Option<Vec<Vec<Felt>>>,
+}
+
+impl<P> BlockFollower<P> {
+    /// Constructs [`BlockFollower`] which starts following from the latest block.
+    ///
+    /// By default, the hashes of the last 64 blocks are kept, the provider is polled every 2
+    /// seconds, and no events are fetched.
+    pub const fn new(provider: P) -> Self {
+        Self {
+            provider,
+            start_block: None,
+            window: VecDeque::new(),
+            window_size: 64,
+            poll_interval: Duration::from_secs(2),
+            events: None,
+            event_chunk_size: 1000,
+            updates: VecDeque::new(),
+        }
+    }
+
+    /// Consumes the current [`BlockFollower`] instance and returns a new one that starts following
+    /// from the specified block instead of the latest one.
+    pub fn with_start_block(self, start_block: u64) -> Self {
+        Self {
+            start_block: Some(start_block),
+            ..self
+        }
+    }
+
+    /// Consumes the current [`BlockFollower`] instance and returns a new one with the number of
+    /// recent block hashes kept for detecting reorgs set. Reorgs deeper than this cannot be
+    /// reconciled. A value of `0` is treated as `1`.
+    pub fn with_window_size(self, window_size: usize) -> Self {
+        Self {
+            window_size: window_size.max(1),
+            ..self
+        }
+    }
+
+    /// Consumes the current [`BlockFollower`] instance and returns a new one with the interval
+    /// between polls when no new block is available set.
+    pub fn with_poll_interval(self, poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..self
+        }
+    }
+
+    /// Consumes the current [`BlockFollower`] instance and returns a new one that also fetches
+    /// events for each block, optionally filtered by contract address and keys.
+    pub fn with_events(self, address: Option<Felt>, keys: Option<Vec<Vec<Felt>>>) -> Self {
+        Self {
+            events: Some(FollowerEventFilter { address, keys }),
+            ..self
+        }
+    }
+
+    /// Consumes the current [`BlockFollower`] instance and returns a new one with the page size
+    /// used when fetching events set.
+    pub fn with_event_chunk_size(self, event_chunk_size: u64) -> Self {
+        Self {
+            event_chunk_size,
+            ..self
+        }
+    }
+
+    /// Gets a reference to the underlying provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Gets the number and hash of the latest canonical block yielded, if any.
+    pub fn head(&self) -> Option<(u64, Felt)> {
+        self.window.back().copied()
+    }
+
+    /// Gets the number of the block from which a push-based source should start delivering
+    /// headers. This is the current head if any, such that reorgs of the head can be detected
+    /// right away, or otherwise the configured start block.
+    pub fn resume_block(&self) -> Option<u64> {
+        self.head()
+            .map(|(block_number, _)| block_number)
+            .or(self.start_block)
+    }
+
+    /// Takes out the next update resulting from previously pushed headers or reorgs, if any.
+    pub fn pop_update(&mut self) -> Option<FollowerUpdate> {
+        self.updates.pop_front()
+    }
+
+    /// Processes a reorg notification from a push-based source, reverting all followed blocks
+    /// from the reorg starting point onwards.
+    pub fn push_reorg(&mut self, reorg: &ReorgData) {
+        self.revert_from(reorg.starting_block_number);
+    }
+
+    fn hash_at(&self, block_number: u64) -> Option<Felt> {
+        let (first_number, _) = self.window.front()?;
+        let index = block_number.checked_sub(*first_number)?;
+        self.window
+            .get(index as usize)
+            .map(|(_, block_hash)| *block_hash)
+    }
+
+    /// Drops all blocks from `from` onwards from the window, emitting a `Reverted` update if
+    /// there's any.
+    fn revert_from(&mut self, from: u64) {
+        let Some((head_number, _)) = self.head() else {
+            return;
+        };
+        if from > head_number {
+            return;
+        }
+
+        while matches!(self.window.back(), Some((block_number, _)) if *block_number >= from) {
+            self.window.pop_back();
+        }
+        if self.window.is_empty() {
+            // Resumes from the same height on the new chain
+            self.start_block = Some(from);
+        }
+
+        self.updates.push_back(FollowerUpdate::Reverted {
+            from,
+            to: head_number,
+        });
+    }
+}
+
+impl<P> BlockFollower<P>
+where
+    P: Provider,
+{
+    /// Waits for the next update by polling the provider.
+    ///
+    /// Updates resulting from pushed headers that haven't been taken out yet are returned first.
+    pub async fn next(&mut self) -> Result<FollowerUpdate, ProviderError> {
+        loop {
+            if let Some(update) = self.updates.pop_front() {
+                return Ok(update);
+            }
+
+            let latest = self.provider.block_hash_and_number().await?;
+            let head = self.head();
+
+            let new_block = match head {
+                Some((head_number, _)) if latest.block_number <= head_number => {
+                    // Either nothing new, a lagging node, or a reorg onto a chain that is not
+                    // longer than the followed one.
+                    if self
+                        .hash_at(latest.block_number)
+                        .is_some_and(|block_hash| block_hash != latest.block_hash)
+                    {
+                        self.reconcile().await?;
+                    }
+                    None
+                }
+                _ => {
+                    let next_number = head
+                        .map(|(head_number, _)| head_number + 1)
+                        .or(self.start_block)
+                        .unwrap_or(latest.block_number);
+                    self.fetch_header(next_number).await?
+                }
+            };
+
+            match new_block {
+                Some(header) => self.push_header(header).await?,
+                None => {
+                    if self.updates.is_empty() {
+                        tokio::time::sleep(self.poll_interval).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Processes a header from a push-based source.
+    ///
+    /// Duplicate headers are ignored. Missing blocks between the current head and the header are
+    /// fetched from the provider, and headers not connecting to the followed chain trigger reorg
+    /// reconciliation. Resulting updates can be taken out via [`pop_update`](Self::pop_update).
+    pub async fn push_header(&mut self, header: BlockHeader) -> Result<(), ProviderError> {
+        loop {
+            let head = self.head();
+            let Some(next_number) = head
+                .map(|(head_number, _)| head_number + 1)
+                .or(self.start_block)
+            else {
+                // Nothing to connect to yet
+                return self.accept(header).await;
+            };
+
+            if header.block_number < next_number {
+                match self.hash_at(header.block_number) {
+                    // Already followed, or from before the window
+                    Some(block_hash) if block_hash == header.block_hash => return Ok(()),
+                    None => return Ok(()),
+                    Some(_) => {
+                        if !self.reconcile().await? {
+                            // The provider disagrees, so the header is most likely stale
+                            return Ok(());
+                        }
+                    }
+                }
+            } else if header.block_number > next_number {
+                // Fills in the gap first
+                match self.fetch_header(next_number).await? {
+                    Some(missing) => {
+                        if !self.connect(missing).await? {
+                            return Ok(());
+                        }
+                    }
+                    // The provider hasn't caught up yet. Drops the header as later headers will
+                    // fill in the gap anyway.
+                    None => return Ok(()),
+                }
+            } else {
+                let block_hash = header.block_hash;
+                if !self.connect(header.clone()).await?
+                    || self
+                        .head()
+                        .is_some_and(|(_, head_hash)| head_hash == block_hash)
+                {
+                    return Ok(());
+                }
+                // Reverted to the fork point, so the header is retried on top of the new chain
+            }
+        }
+    }
+
+    /// Accepts a header directly following the head if it connects, or reconciles otherwise.
+    /// Returns whether the chain changed either way.
+    async fn connect(&mut self, header: BlockHeader) -> Result<bool, ProviderError> {
+        match self.head() {
+            Some((_, head_hash)) if header.parent_hash != head_hash => self.reconcile().await,
+            _ => {
+                self.accept(header).await?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Adds a block to the followed chain.
+    async fn accept(&mut self, header: BlockHeader) -> Result<(), ProviderError> {
+        let events = match &self.events {
+            Some(filter) => self.fetch_events(filter, header.block_hash).await?,
+            None => vec![],
+        };
+
+        self.window
+            .push_back((header.block_number, header.block_hash));
+        while self.window.len() > self.window_size {
+            self.window.pop_front();
+        }
+
+        self.updates
+            .push_back(FollowerUpdate::Block(FollowedBlock { header, events }));
+        Ok(())
+    }
+
+    /// Finds the latest block in the window that is still canonical, and reverts all blocks after
+    /// it. Returns whether any block has been reverted.
+    async fn reconcile(&mut self) -> Result<bool, ProviderError> {
+        let Some((head_number, _)) = self.head() else {
+            return Ok(false);
+        };
+
+        let window = self.window.iter().rev().copied().collect::<Vec<_>>();
+        for (block_number, block_hash) in window {
+            let canonical = self.fetch_header(block_number).await?;
+            if canonical.is_some_and(|canonical| canonical.block_hash == block_hash) {
+                if block_number == head_number {
+                    return Ok(false);
+                }
+
+                self.revert_from(block_number + 1);
+                return Ok(true);
+            }
+        }
+
+        Err(FollowerError::ReorgTooDeep.into())
+    }
+
+    async fn fetch_header(&self, block_number: u64) -> Result<Option<BlockHeader>, ProviderError> {
+        match self
+            .provider
+            .get_block_with_tx_hashes(BlockId::Number(block_number))
+            .await
+        {
+            Ok(MaybePreConfirmedBlockWithTxHashes::Block(block)) => Ok(Some(header_of(block))),
+            Ok(MaybePreConfirmedBlockWithTxHashes::PreConfirmedBlock(_))
+            | Err(ProviderError::StarknetError(StarknetError::BlockNotFound)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn fetch_events(
+        &self,
+        filter: &FollowerEventFilter,
+        block_hash: Felt,
+    ) -> Result<Vec<EmittedEvent>, ProviderError> {
+        let mut events = vec![];
+        let mut continuation_token = None;
+
+        loop {
+            // Using the block hash ensures the events belong to the exact block being followed
+            let page = self
+                .provider
+                .get_events(
+                    EventFilter {
+                        from_block: Some(BlockId::Hash(block_hash)),
+                        to_block: Some(BlockId::Hash(block_hash)),
+                        address: filter.address,
+                        keys: filter.keys.clone(),
+                    },
+                    continuation_token,
+                    self.event_chunk_size,
+                )
+                .await?;
+
+            events.extend(page.events);
+            continuation_token = page.continuation_token;
+            if continuation_token.is_none() {
+                return Ok(events);
+            }
+        }
+    }
+}
+
+impl ProviderImplError for FollowerError {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl From<FollowerError> for ProviderError {
+    fn from(value: FollowerError) -> Self {
+        Self::Other(Box::new(value))
+    }
+}
+
+fn header_of(block: BlockWithTxHashes) -> BlockHeader {
+    BlockHeader {
+        block_hash: block.block_hash,
+        parent_hash: block.parent_hash,
+        block_number: block.block_number,
+        new_root: block.new_root,
+        timestamp: block.timestamp,
+        sequencer_address: block.sequencer_address,
+        l1_gas_price: block.l1_gas_price,
+        l2_gas_price: block.l2_gas_price,
+        l1_data_gas_price: block.l1_data_gas_price,
+        l1_da_mode: block.l1_da_mode,
+        starknet_version: block.starknet_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use starknet_core::types::{L1DataAvailabilityMode, ResourcePrice};
+
+    use super::*;
+    use crate::jsonrpc::{JsonRpcClient, JsonRpcMethod, MockHandler, MockResponse, MockTransport};
+
+    /// A handler serving a chain of blocks that can be reorganized. Each block has a single
+    /// event carrying the block hash as data.
+    #[derive(Debug, Default)]
+    struct MockChain {
+        /// Block hashes indexed by block number. The parent hash of a block is simply the hash of
+        /// the previous one.
+        blocks: Mutex<Vec<Felt>>,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("mock error")]
+    struct MockError;
+
+    impl MockChain {
+        fn extend(&self, hashes: &[u64]) {
+            self.blocks
+                .lock()
+                .unwrap()
+                .extend(hashes.iter().map(|hash| Felt::from(*hash)));
+        }
+
+        fn reorg(&self, from: usize, hashes: &[u64]) {
+            let mut blocks = self.blocks.lock().unwrap();
+            blocks.truncate(from);
+            blocks.extend(hashes.iter().map(|hash| Felt::from(*hash)));
+        }
+
+        fn header(&self, block_number: u64) -> Option<BlockHeader> {
+            let blocks = self.blocks.lock().unwrap();
+            let block_hash = *blocks.get(block_number as usize)?;
+            let price = ResourcePrice {
+                price_in_fri: Felt::ONE,
+                price_in_wei: Felt::ONE,
+            };
+
+            Some(BlockHeader {
+                block_hash,
+                parent_hash: match block_number {
+                    0 => Felt::ZERO,
+                    _ => blocks[block_number as usize - 1],
+                },
+                block_number,
+                new_root: Felt::ZERO,
+                timestamp: 0,
+                sequencer_address: Felt::ZERO,
+                l1_gas_price: price.clone(),
+                l2_gas_price: price.clone(),
+                l1_data_gas_price: price,
+                l1_da_mode: L1DataAvailabilityMode::Blob,
+                starknet_version: "0.14.0".into(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl MockHandler for MockChain {
+        type Error = MockError;
+
+        async fn respond(
+            &self,
+            method: JsonRpcMethod,
+            params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            match method {
+                JsonRpcMethod::BlockHashAndNumber => {
+                    let blocks = self.blocks.lock().unwrap();
+                    Ok(serde_json::json!({
+                        "block_hash": format!("{:#x}", blocks.last().unwrap()),
+                        "block_number": blocks.len() - 1,
+                    })
+                    .into())
+                }
+                JsonRpcMethod::GetBlockWithTxHashes => {
+                    let block_number = params["block_id"]["block_number"].as_u64().unwrap();
+                    match self.header(block_number) {
+                        Some(header) => {
+                            let mut block = serde_json::to_value(header).unwrap();
+                            block["status"] = "ACCEPTED_ON_L2".into();
+                            block["transactions"] = serde_json::json!([]);
+                            Ok(block.into())
+                        }
+                        None => Ok(MockResponse::Error {
+                            code: 24,
+                            message: "Block not found",
+                        }),
+                    }
+                }
+                JsonRpcMethod::GetEvents => {
+                    let block_hash = params["filter"]["from_block"]["block_hash"].clone();
+                    Ok(serde_json::json!({
+                        "events": [{
+                            "from_address": "0x1",
+                            "keys": [],
+                            "data": [block_hash],
+                            "block_hash": block_hash,
+                            "block_number": 0,
+                            "transaction_hash": "0x1",
+                        }],
+                    })
+                    .into())
+                }
+                _ => Err(MockError),
+            }
+        }
+    }
+
+    type MockFollower = BlockFollower<JsonRpcClient<MockTransport<Arc<MockChain>>>>;
+
+    fn create_follower(hashes: &[u64]) -> (Arc<MockChain>, MockFollower) {
+        let chain = Arc::new(MockChain::default());
+        chain.extend(hashes);
+
+        let follower = BlockFollower::new(JsonRpcClient::new(MockTransport::new(chain.clone())))
+            .with_poll_interval(Duration::from_millis(1));
+        (chain, follower)
+    }
+
+    fn block_hash(update: FollowerUpdate) -> u64 {
+        match update {
+            FollowerUpdate::Block(block) => block.header.block_hash.try_into().unwrap(),
+            FollowerUpdate::Reverted { .. } => panic!("unexpected revert"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_follow_and_revert() {
+        let (chain, follower) = create_follower(&[100, 101, 102]);
+        let mut follower = follower.with_start_block(1).with_events(None, None);
+
+        match follower.next().await.unwrap() {
+            FollowerUpdate::Block(block) => {
+                assert_eq!(block.header.block_number, 1);
+                assert_eq!(block.events[0].data, vec![Felt::from(101u64)]);
+            }
+            FollowerUpdate::Reverted { .. } => panic!("unexpected revert"),
+        }
+        assert_eq!(block_hash(follower.next().await.unwrap()), 102);
+
+        // Blocks #2 and onwards are replaced with a longer chain
+        chain.reorg(2, &[202, 203]);
+        assert_eq!(
+            follower.next().await.unwrap(),
+            FollowerUpdate::Reverted { from: 2, to: 2 }
+        );
+        assert_eq!(block_hash(follower.next().await.unwrap()), 202);
+        assert_eq!(block_hash(follower.next().await.unwrap()), 203);
+
+        // Reorg onto a chain of the same length
+        chain.reorg(3, &[303]);
+        assert_eq!(
+            follower.next().await.unwrap(),
+            FollowerUpdate::Reverted { from: 3, to: 3 }
+        );
+        assert_eq!(block_hash(follower.next().await.unwrap()), 303);
+        assert_eq!(follower.head(), Some((3, Felt::from(303u64))));
+    }
+
+    #[tokio::test]
+    async fn test_push_headers() {
+        let (chain, mut follower) = create_follower(&[100, 101, 102, 103]);
+
+        follower
+            .push_header(chain.header(1).unwrap())
+            .await
+            .unwrap();
+        // Duplicate header
+        follower
+            .push_header(chain.header(1).unwrap())
+            .await
+            .unwrap();
+        // Gap is filled in from the provider
+        follower
+            .push_header(chain.header(3).unwrap())
+            .await
+            .unwrap();
+
+        let mut hashes = vec![];
+        while let Some(update) = follower.pop_update() {
+            hashes.push(block_hash(update));
+        }
+        assert_eq!(hashes, vec![101, 102, 103]);
+
+        // Header not connecting to the head
+        chain.reorg(2, &[202, 203, 204]);
+        follower
+            .push_header(chain.header(4).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            follower.pop_update(),
+            Some(FollowerUpdate::Reverted { from: 2, to: 3 })
+        );
+        assert_eq!(block_hash(follower.pop_update().unwrap()), 202);
+        assert_eq!(block_hash(follower.pop_update().unwrap()), 203);
+        assert_eq!(block_hash(follower.pop_update().unwrap()), 204);
+        assert_eq!(follower.pop_update(), None);
+    }
+
+    #[tokio::test]
+    async fn test_push_reorg() {
+        let (chain, mut follower) = create_follower(&[100, 101, 102]);
+
+        follower
+            .push_header(chain.header(1).unwrap())
+            .await
+            .unwrap();
+        follower
+            .push_header(chain.header(2).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(block_hash(follower.pop_update().unwrap()), 101);
+        assert_eq!(block_hash(follower.pop_update().unwrap()), 102);
+
+        follower.push_reorg(&ReorgData {
+            starting_block_hash: Felt::from(101u64),
+            starting_block_number: 1,
+            ending_block_hash: Felt::from(102u64),
+            ending_block_number: 2,
+        });
+        assert_eq!(
+            follower.pop_update(),
+            Some(FollowerUpdate::Reverted { from: 1, to: 2 })
+        );
+        assert_eq!(follower.resume_block(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_reorg_too_deep() {
+        let (chain, follower) = create_follower(&[100, 101, 102]);
+        let mut follower = follower.with_start_block(1).with_window_size(1);
+
+        assert_eq!(block_hash(follower.next().await.unwrap()), 101);
+        assert_eq!(block_hash(follower.next().await.unwrap()), 102);
+
+        chain.reorg(1, &[201, 202, 203]);
+        let error = follower.next().await.unwrap_err();
+        match error {
+            ProviderError::Other(error) => assert!(matches!(
+                error.as_any().downcast_ref::<FollowerError>(),
+                Some(FollowerError::ReorgTooDeep)
+            )),
+            _ => panic!("unexpected error type"),
+        }
+    }
+}
@@ -27,7 +27,13 @@ use crate::{
 };
 
 mod transports;
+#[cfg(not(target_arch = "wasm32"))]
+pub use transports::{
+    BatchingTransport, BatchingTransportError, RetryPolicy, RetryTransport, RetryableError,
+};
 pub use transports::{HttpTransport, HttpTransportError, JsonRpcTransport};
+#[cfg(test)]
+pub(crate) use transports::{MockHandler, MockResponse, MockTransport};
 #[cfg(feature = "worker")]
 pub use transports::{WorkersTransport, WorkersTransportError};
 
@@ -304,236 +310,250 @@ where
     where
         R: AsRef<[ProviderRequestData]> + Send + Sync,
     {
-        let mut results = vec![];
+        self.try_send_requests(requests)
+            .await?
+            .into_iter()
+            .collect()
+    }
 
+    async fn try_send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<Result<ProviderResponseData, ProviderError>>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
         let responses = self
             .transport
             .send_requests(requests.as_ref().to_vec())
             .await
             .map_err(JsonRpcClientError::TransportError)?;
 
-        for (request, response) in requests.as_ref().iter().zip(responses.into_iter()) {
-            match response {
-                JsonRpcResponse::Success { result, .. } => {
-                    let result = match request {
-                        ProviderRequestData::SpecVersion(_) => ProviderResponseData::SpecVersion(
-                            String::deserialize(result)
+        Ok(requests
+            .as_ref()
+            .iter()
+            .zip(responses)
+            .map(|(request, response)| Self::parse_response(request, response))
+            .collect())
+    }
+
+    fn parse_response(
+        request: &ProviderRequestData,
+        response: JsonRpcResponse<serde_json::Value>,
+    ) -> Result<ProviderResponseData, ProviderError> {
+        match response {
+            JsonRpcResponse::Success { result, .. } => {
+                let result = match request {
+                    ProviderRequestData::SpecVersion(_) => ProviderResponseData::SpecVersion(
+                        String::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::GetBlockWithTxHashes(_) => {
+                        ProviderResponseData::GetBlockWithTxHashes(
+                            MaybePreConfirmedBlockWithTxHashes::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::GetBlockWithTxs(_) => {
+                        ProviderResponseData::GetBlockWithTxs(
+                            MaybePreConfirmedBlockWithTxs::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                        ProviderRequestData::GetBlockWithTxHashes(_) => {
-                            ProviderResponseData::GetBlockWithTxHashes(
-                                MaybePreConfirmedBlockWithTxHashes::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetBlockWithTxs(_) => {
-                            ProviderResponseData::GetBlockWithTxs(
-                                MaybePreConfirmedBlockWithTxs::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetBlockWithReceipts(_) => {
-                            ProviderResponseData::GetBlockWithReceipts(
-                                MaybePreConfirmedBlockWithReceipts::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetStateUpdate(_) => {
-                            ProviderResponseData::GetStateUpdate(
-                                MaybePreConfirmedStateUpdate::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetStorageAt(_) => ProviderResponseData::GetStorageAt(
-                            Felt::deserialize(result)
-                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?
-                                .0,
-                        ),
-                        ProviderRequestData::GetMessagesStatus(_) => {
-                            ProviderResponseData::GetMessagesStatus(
-                                Vec::<MessageStatus>::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetTransactionStatus(_) => {
-                            ProviderResponseData::GetTransactionStatus(
-                                TransactionStatus::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetTransactionByHash(_) => {
-                            ProviderResponseData::GetTransactionByHash(
-                                Transaction::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetTransactionByBlockIdAndIndex(_) => {
-                            ProviderResponseData::GetTransactionByBlockIdAndIndex(
-                                Transaction::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetTransactionReceipt(_) => {
-                            ProviderResponseData::GetTransactionReceipt(
-                                TransactionReceiptWithBlockInfo::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::GetClass(_) => ProviderResponseData::GetClass(
-                            ContractClass::deserialize(result)
+                        )
+                    }
+                    ProviderRequestData::GetBlockWithReceipts(_) => {
+                        ProviderResponseData::GetBlockWithReceipts(
+                            MaybePreConfirmedBlockWithReceipts::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                        ProviderRequestData::GetClassHashAt(_) => {
-                            ProviderResponseData::GetClassHashAt(
-                                Felt::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?
-                                    .0,
-                            )
-                        }
-                        ProviderRequestData::GetClassAt(_) => ProviderResponseData::GetClassAt(
-                            ContractClass::deserialize(result)
+                        )
+                    }
+                    ProviderRequestData::GetStateUpdate(_) => ProviderResponseData::GetStateUpdate(
+                        MaybePreConfirmedStateUpdate::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::GetStorageAt(_) => ProviderResponseData::GetStorageAt(
+                        Felt::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?
+                            .0,
+                    ),
+                    ProviderRequestData::GetMessagesStatus(_) => {
+                        ProviderResponseData::GetMessagesStatus(
+                            Vec::<MessageStatus>::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                        ProviderRequestData::GetBlockTransactionCount(_) => {
-                            ProviderResponseData::GetBlockTransactionCount(
-                                u64::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::Call(_) => ProviderResponseData::Call(
-                            FeltArray::deserialize(result)
-                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?
-                                .0,
-                        ),
-                        ProviderRequestData::EstimateFee(_) => ProviderResponseData::EstimateFee(
-                            Vec::<FeeEstimate>::deserialize(result)
+                        )
+                    }
+                    ProviderRequestData::GetTransactionStatus(_) => {
+                        ProviderResponseData::GetTransactionStatus(
+                            TransactionStatus::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                        ProviderRequestData::EstimateMessageFee(_) => {
-                            ProviderResponseData::EstimateMessageFee(
-                                FeeEstimate::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::BlockNumber(_) => ProviderResponseData::BlockNumber(
+                        )
+                    }
+                    ProviderRequestData::GetTransactionByHash(_) => {
+                        ProviderResponseData::GetTransactionByHash(
+                            Transaction::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::GetTransactionByBlockIdAndIndex(_) => {
+                        ProviderResponseData::GetTransactionByBlockIdAndIndex(
+                            Transaction::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::GetTransactionReceipt(_) => {
+                        ProviderResponseData::GetTransactionReceipt(
+                            TransactionReceiptWithBlockInfo::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::GetClass(_) => ProviderResponseData::GetClass(
+                        ContractClass::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::GetClassHashAt(_) => ProviderResponseData::GetClassHashAt(
+                        Felt::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?
+                            .0,
+                    ),
+                    ProviderRequestData::GetClassAt(_) => ProviderResponseData::GetClassAt(
+                        ContractClass::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::GetBlockTransactionCount(_) => {
+                        ProviderResponseData::GetBlockTransactionCount(
                             u64::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                        ProviderRequestData::BlockHashAndNumber(_) => {
-                            ProviderResponseData::BlockHashAndNumber(
-                                BlockHashAndNumber::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::ChainId(_) => ProviderResponseData::ChainId(
-                            Felt::deserialize(result)
-                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?
-                                .0,
-                        ),
-                        ProviderRequestData::Syncing(_) => ProviderResponseData::Syncing(
-                            SyncStatusType::deserialize(result)
+                        )
+                    }
+                    ProviderRequestData::Call(_) => ProviderResponseData::Call(
+                        FeltArray::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?
+                            .0,
+                    ),
+                    ProviderRequestData::EstimateFee(_) => ProviderResponseData::EstimateFee(
+                        Vec::<FeeEstimate>::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::EstimateMessageFee(_) => {
+                        ProviderResponseData::EstimateMessageFee(
+                            FeeEstimate::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::BlockNumber(_) => ProviderResponseData::BlockNumber(
+                        u64::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::BlockHashAndNumber(_) => {
+                        ProviderResponseData::BlockHashAndNumber(
+                            BlockHashAndNumber::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::ChainId(_) => ProviderResponseData::ChainId(
+                        Felt::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?
+                            .0,
+                    ),
+                    ProviderRequestData::Syncing(_) => ProviderResponseData::Syncing(
+                        SyncStatusType::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::GetEvents(_) => ProviderResponseData::GetEvents(
+                        EventsPage::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                    ProviderRequestData::GetNonce(_) => ProviderResponseData::GetNonce(
+                        Felt::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?
+                            .0,
+                    ),
+                    ProviderRequestData::GetStorageProof(_) => {
+                        ProviderResponseData::GetStorageProof(
+                            StorageProof::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::AddInvokeTransaction(_) => {
+                        ProviderResponseData::AddInvokeTransaction(
+                            InvokeTransactionResult::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::AddDeclareTransaction(_) => {
+                        ProviderResponseData::AddDeclareTransaction(
+                            DeclareTransactionResult::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::AddDeployAccountTransaction(_) => {
+                        ProviderResponseData::AddDeployAccountTransaction(
+                            DeployAccountTransactionResult::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::TraceTransaction(_) => {
+                        ProviderResponseData::TraceTransaction(
+                            TransactionTrace::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::SimulateTransactions(_) => {
+                        ProviderResponseData::SimulateTransactions(
+                            Vec::<SimulatedTransaction>::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::TraceBlockTransactions(_) => {
+                        ProviderResponseData::TraceBlockTransactions(
+                            Vec::<TransactionTraceWithHash>::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                        ProviderRequestData::GetEvents(_) => ProviderResponseData::GetEvents(
-                            EventsPage::deserialize(result)
+                        )
+                    }
+                    ProviderRequestData::SubscribeNewHeads(_) => {
+                        ProviderResponseData::SubscribeNewHeads(
+                            SubscriptionId::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                        ProviderRequestData::GetNonce(_) => ProviderResponseData::GetNonce(
-                            Felt::deserialize(result)
-                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?
-                                .0,
-                        ),
-                        ProviderRequestData::GetStorageProof(_) => {
-                            ProviderResponseData::GetStorageProof(
-                                StorageProof::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::AddInvokeTransaction(_) => {
-                            ProviderResponseData::AddInvokeTransaction(
-                                InvokeTransactionResult::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::AddDeclareTransaction(_) => {
-                            ProviderResponseData::AddDeclareTransaction(
-                                DeclareTransactionResult::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::AddDeployAccountTransaction(_) => {
-                            ProviderResponseData::AddDeployAccountTransaction(
-                                DeployAccountTransactionResult::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::TraceTransaction(_) => {
-                            ProviderResponseData::TraceTransaction(
-                                TransactionTrace::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::SimulateTransactions(_) => {
-                            ProviderResponseData::SimulateTransactions(
-                                Vec::<SimulatedTransaction>::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::TraceBlockTransactions(_) => {
-                            ProviderResponseData::TraceBlockTransactions(
-                                Vec::<TransactionTraceWithHash>::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::SubscribeNewHeads(_) => {
-                            ProviderResponseData::SubscribeNewHeads(
-                                SubscriptionId::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::SubscribeEvents(_) => {
-                            ProviderResponseData::SubscribeEvents(
-                                SubscriptionId::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::SubscribeTransactionStatus(_) => {
-                            ProviderResponseData::SubscribeTransactionStatus(
-                                SubscriptionId::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::SubscribeNewTransactionReceipts(_) => {
-                            ProviderResponseData::SubscribeNewTransactionReceipts(
-                                SubscriptionId::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::SubscribeNewTransactions(_) => {
-                            ProviderResponseData::SubscribeNewTransactions(
-                                SubscriptionId::deserialize(result)
-                                    .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                            )
-                        }
-                        ProviderRequestData::Unsubscribe(_) => ProviderResponseData::Unsubscribe(
-                            bool::deserialize(result)
+                        )
+                    }
+                    ProviderRequestData::SubscribeEvents(_) => {
+                        ProviderResponseData::SubscribeEvents(
+                            SubscriptionId::deserialize(result)
                                 .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
-                        ),
-                    };
-
-                    results.push(result);
-                }
-                // TODO: add context on index of request causing the error
-                JsonRpcResponse::Error { error, .. } => {
-                    return Err(match TryInto::<StarknetError>::try_into(&error) {
-                        Ok(error) => ProviderError::StarknetError(error),
-                        Err(_) => JsonRpcClientError::<T::Error>::JsonRpcError(error).into(),
-                    })
-                }
+                        )
+                    }
+                    ProviderRequestData::SubscribeTransactionStatus(_) => {
+                        ProviderResponseData::SubscribeTransactionStatus(
+                            SubscriptionId::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::SubscribeNewTransactionReceipts(_) => {
+                        ProviderResponseData::SubscribeNewTransactionReceipts(
+                            SubscriptionId::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::SubscribeNewTransactions(_) => {
+                        ProviderResponseData::SubscribeNewTransactions(
+                            SubscriptionId::deserialize(result)
+                                .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                        )
+                    }
+                    ProviderRequestData::Unsubscribe(_) => ProviderResponseData::Unsubscribe(
+                        bool::deserialize(result)
+                            .map_err(JsonRpcClientError::<T::Error>::JsonError)?,
+                    ),
+                };
+
+                Ok(result)
+            }
+            JsonRpcResponse::Error { error, .. } => {
+                Err(match TryInto::<StarknetError>::try_into(&error) {
+                    Ok(error) => ProviderError::StarknetError(error),
+                    Err(_) => JsonRpcClientError::<T::Error>::JsonRpcError(error).into(),
+                })
             }
         }
-
-        Ok(results)
     }
 }
 
@@ -1086,10 +1106,43 @@ where
     {
         self.send_requests(requests).await
     }
+
+    async fn try_batch_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<Result<ProviderResponseData, ProviderError>>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        self.try_send_requests(requests).await
+    }
+}
+
+impl JsonRpcMethod {
+    /// Whether sending a request with this method more than once is guaranteed to have the same
+    /// effect as sending it once.
+    ///
+    /// Transaction submission and subscription management methods are considered non-idempotent,
+    /// as blindly resending them could cause duplicate side effects on the server.
+    pub const fn is_idempotent(&self) -> bool {
+        !matches!(
+            self,
+            Self::AddInvokeTransaction
+                | Self::AddDeclareTransaction
+                | Self::AddDeployAccountTransaction
+                | Self::SubscribeNewHeads
+                | Self::SubscribeEvents
+                | Self::SubscribeTransactionStatus
+                | Self::SubscribeNewTransactionReceipts
+                | Self::SubscribeNewTransactions
+                | Self::Unsubscribe
+        )
+    }
 }
 
 impl ProviderRequestData {
-    const fn jsonrpc_method(&self) -> JsonRpcMethod {
+    /// Gets the JSON-RPC method used for sending this request.
+    pub const fn jsonrpc_method(&self) -> JsonRpcMethod {
         match self {
             Self::SpecVersion(_) => JsonRpcMethod::SpecVersion,
             Self::GetBlockWithTxHashes(_) => JsonRpcMethod::GetBlockWithTxHashes,
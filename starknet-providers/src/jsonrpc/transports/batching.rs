@@ -0,0 +1,415 @@
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use log::trace;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::{
+    jsonrpc::{transports::JsonRpcTransport, JsonRpcMethod, JsonRpcRequest, JsonRpcResponse},
+    paymaster::{PaymasterMethod, PaymasterTransport},
+    ProviderRequestData,
+};
+
+/// A [`JsonRpcTransport`] implementation that wraps another transport and transparently coalesces
+/// individual requests into JSON-RPC batches.
+///
+/// Requests sent via [`send_request`](JsonRpcTransport::send_request) are queued instead of being
+/// sent right away. The queue is flushed as a single batch through the inner transport's
+/// [`send_requests`](JsonRpcTransport::send_requests) once the batching window has elapsed since
+/// the first queued request, or as soon as the maximum batch size is reached, whichever comes
+/// first. Each response is then routed back to the caller that made the corresponding request.
+///
+/// Paymaster requests are not batched, and are sent right away through the inner transport's
+/// [`PaymasterTransport`] implementation, if any.
+///
+/// Flushing happens on a spawned task, so this transport must be used within a `tokio` runtime.
+#[derive(Debug)]
+pub struct BatchingTransport<T>
+where
+    T: JsonRpcTransport,
+{
+    inner: Arc<T>,
+    window: Duration,
+    max_batch_size: usize,
+    state: Arc<Mutex<BatchState<T::Error>>>,
+}
+
+/// Errors using [`BatchingTransport`].
+#[derive(Debug, thiserror::Error)]
+pub enum BatchingTransportError<E> {
+    /// Errors from the inner transport. As the error is shared by all requests in the same batch,
+    /// it's reference-counted.
+    #[error(transparent)]
+    Transport(Arc<E>),
+    /// JSON serialization/deserialization errors.
+    #[error(transparent)]
+    Json(serde_json::Error),
+    /// The inner transport did not return a response for the request.
+    #[error("missing response for batched request")]
+    MissingResponse,
+}
+
+type BatchResult<E> = Result<JsonRpcResponse<serde_json::Value>, BatchingTransportError<E>>;
+
+#[derive(Debug)]
+struct BatchState<E> {
+    /// Incremented whenever the pending batch is taken, such that stale flush timers can tell
+    /// that the batch they were scheduled for has already been sent.
+    generation: u64,
+    pending: Batch<E>,
+}
+
+#[derive(Debug)]
+struct Batch<E> {
+    requests: Vec<ProviderRequestData>,
+    senders: Vec<oneshot::Sender<BatchResult<E>>>,
+}
+
+impl<T> BatchingTransport<T>
+where
+    T: JsonRpcTransport,
+{
+    /// Constructs [`BatchingTransport`] by wrapping a transport, using a batching window of 5
+    /// milliseconds and a maximum batch size of 100.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            window: Duration::from_millis(5),
+            max_batch_size: 100,
+            state: Arc::new(Mutex::new(BatchState {
+                generation: 0,
+                pending: Batch {
+                    requests: vec![],
+                    senders: vec![],
+                },
+            })),
+        }
+    }
+
+    /// Consumes the current [`BatchingTransport`] instance and returns a new one with the batching
+    /// window set.
+    pub fn with_window(self, window: Duration) -> Self {
+        Self { window, ..self }
+    }
+
+    /// Consumes the current [`BatchingTransport`] instance and returns a new one with the maximum
+    /// number of requests in a batch set. A value of `0` is treated as `1`.
+    pub fn with_max_batch_size(self, max_batch_size: usize) -> Self {
+        Self {
+            max_batch_size: max_batch_size.max(1),
+            ..self
+        }
+    }
+
+    /// Gets a reference to the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> BatchingTransport<T>
+where
+    T: 'static + JsonRpcTransport + Send + Sync,
+{
+    /// Adds a request to the pending batch, scheduling a flush as needed.
+    fn enqueue(&self, request: ProviderRequestData) -> oneshot::Receiver<BatchResult<T::Error>> {
+        let (sender, receiver) = oneshot::channel();
+
+        let mut state = self.state.lock().unwrap();
+        state.pending.requests.push(request);
+        state.pending.senders.push(sender);
+
+        if state.pending.requests.len() >= self.max_batch_size {
+            let batch = Self::take_batch(&mut state);
+            drop(state);
+
+            tokio::spawn(Self::flush(self.inner.clone(), batch));
+        } else if state.pending.requests.len() == 1 {
+            let generation = state.generation;
+            drop(state);
+
+            let inner = self.inner.clone();
+            let shared_state = self.state.clone();
+            let window = self.window;
+            tokio::spawn(async move {
+                tokio::time::sleep(window).await;
+
+                let batch = {
+                    let mut state = shared_state.lock().unwrap();
+                    if state.generation != generation {
+                        // Already flushed for reaching the maximum size
+                        return;
+                    }
+                    Self::take_batch(&mut state)
+                };
+
+                Self::flush(inner, batch).await;
+            });
+        }
+
+        receiver
+    }
+
+    fn take_batch(state: &mut BatchState<T::Error>) -> Batch<T::Error> {
+        state.generation += 1;
+        Batch {
+            requests: mem::take(&mut state.pending.requests),
+            senders: mem::take(&mut state.pending.senders),
+        }
+    }
+
+    /// Sends a batch and routes responses back to their senders.
+    async fn flush(inner: Arc<T>, batch: Batch<T::Error>) {
+        let Batch { requests, senders } = batch;
+        trace!("Flushing batch of {} JSON-RPC requests", requests.len());
+
+        if let [request] = requests.as_slice() {
+            // No point in using the batch format for a lone request
+            let result = inner
+                .send_request::<_, serde_json::Value>(request.jsonrpc_method(), request)
+                .await
+                .map_err(|err| BatchingTransportError::Transport(Arc::new(err)));
+            if let Some(sender) = senders.into_iter().next() {
+                // The caller is no longer interested if the receiver is dropped
+                let _ = sender.send(result);
+            }
+            return;
+        }
+
+        match inner.send_requests(&requests).await {
+            Ok(responses) => {
+                let mut senders = senders.into_iter().map(Some).collect::<Vec<_>>();
+
+                // Responses carry the index of the request in the batch as their ID
+                for response in responses {
+                    let id = match &response {
+                        JsonRpcResponse::Success { id, .. } | JsonRpcResponse::Error { id, .. } => {
+                            *id as usize
+                        }
+                    };
+                    if let Some(sender) = senders.get_mut(id).and_then(Option::take) {
+                        let _ = sender.send(Ok(response));
+                    }
+                }
+
+                for sender in senders.into_iter().flatten() {
+                    let _ = sender.send(Err(BatchingTransportError::MissingResponse));
+                }
+            }
+            Err(err) => {
+                let err = Arc::new(err);
+                for sender in senders {
+                    let _ = sender.send(Err(BatchingTransportError::Transport(err.clone())));
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for BatchingTransport<T>
+where
+    T: 'static + JsonRpcTransport + Send + Sync,
+{
+    type Error = BatchingTransportError<T::Error>;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        // Requests are batched in their typed form, which is recovered by re-parsing.
+        let raw_request = serde_json::json!({
+            "id": 0,
+            "method": method,
+            "params": params,
+        });
+        let request = match JsonRpcRequest::deserialize(raw_request) {
+            Ok(request) => request,
+            Err(_) => {
+                // Not a batchable request method. Sends it as is instead.
+                return self
+                    .inner
+                    .send_request(method, params)
+                    .await
+                    .map_err(|err| BatchingTransportError::Transport(Arc::new(err)));
+            }
+        };
+
+        let response = self
+            .enqueue(request.data)
+            .await
+            .map_err(|_| BatchingTransportError::MissingResponse)??;
+
+        Ok(match response {
+            JsonRpcResponse::Success { id, result } => JsonRpcResponse::Success {
+                id,
+                result: R::deserialize(result).map_err(BatchingTransportError::Json)?,
+            },
+            JsonRpcResponse::Error { id, error } => JsonRpcResponse::Error { id, error },
+        })
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        self.inner
+            .send_requests(requests)
+            .await
+            .map_err(|err| BatchingTransportError::Transport(Arc::new(err)))
+    }
+}
+
+#[async_trait]
+impl<T> PaymasterTransport for BatchingTransport<T>
+where
+    T: 'static + JsonRpcTransport + PaymasterTransport + Send + Sync,
+{
+    type Error = BatchingTransportError<<T as PaymasterTransport>::Error>;
+
+    async fn send_paymaster_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.inner
+            .send_paymaster_request(method, params)
+            .await
+            .map_err(|err| BatchingTransportError::Transport(Arc::new(err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::future::join_all;
+    use starknet_core::types::{BlockId, BlockTag, Felt};
+
+    use super::*;
+    use crate::{
+        jsonrpc::{HttpTransportError, JsonRpcClient, MockHandler, MockResponse, MockTransport},
+        Provider,
+    };
+
+    /// A handler that echoes back the contract address of each `starknet_getNonce` request as its
+    /// nonce.
+    #[derive(Debug)]
+    struct EchoNonce;
+
+    #[async_trait]
+    impl MockHandler for EchoNonce {
+        type Error = HttpTransportError;
+
+        async fn respond(
+            &self,
+            method: JsonRpcMethod,
+            params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            match method {
+                JsonRpcMethod::GetNonce => Ok(params["contract_address"].clone().into()),
+                _ => Err(HttpTransportError::UnexpectedResponseId(0)),
+            }
+        }
+
+        async fn respond_paymaster(
+            &self,
+            _method: PaymasterMethod,
+            _params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            Ok(serde_json::json!(true).into())
+        }
+    }
+
+    /// Creates a mock transport that returns batch responses in reverse order, such that they
+    /// must be routed by ID.
+    fn create_transport() -> MockTransport<EchoNonce> {
+        MockTransport::new(EchoNonce).with_reversed_batches()
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_concurrent_requests() {
+        let transport = Arc::new(BatchingTransport::new(create_transport()));
+        let client = JsonRpcClient::new(transport.clone());
+
+        let nonces = join_all(
+            (0..10u64).map(|ind| client.get_nonce(BlockId::Tag(BlockTag::Latest), Felt::from(ind))),
+        )
+        .await;
+
+        for (ind, nonce) in nonces.into_iter().enumerate() {
+            assert_eq!(nonce.unwrap(), Felt::from(ind));
+        }
+        assert_eq!(transport.inner().batch_sizes(), vec![10]);
+    }
+
+    #[tokio::test]
+    async fn test_max_batch_size() {
+        let transport = Arc::new(
+            BatchingTransport::new(create_transport())
+                .with_max_batch_size(4)
+                .with_window(Duration::from_secs(60)),
+        );
+        let client = JsonRpcClient::new(transport.clone());
+
+        let nonces = join_all(
+            (0..8u64).map(|ind| client.get_nonce(BlockId::Tag(BlockTag::Latest), Felt::from(ind))),
+        )
+        .await;
+
+        for (ind, nonce) in nonces.into_iter().enumerate() {
+            assert_eq!(nonce.unwrap(), Felt::from(ind));
+        }
+        assert_eq!(transport.inner().batch_sizes(), vec![4, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_lone_request() {
+        let transport = Arc::new(BatchingTransport::new(create_transport()));
+        let client = JsonRpcClient::new(transport.clone());
+
+        assert_eq!(
+            client
+                .get_nonce(BlockId::Tag(BlockTag::Latest), Felt::THREE)
+                .await
+                .unwrap(),
+            Felt::THREE
+        );
+        assert_eq!(transport.inner().batch_sizes(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_paymaster_requests_not_batched() {
+        let transport =
+            BatchingTransport::new(create_transport()).with_window(Duration::from_secs(60));
+
+        let response = tokio::time::timeout(
+            Duration::from_secs(1),
+            transport.send_paymaster_request::<_, bool>(PaymasterMethod::IsAvailable, ()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(matches!(
+            response,
+            JsonRpcResponse::Success { result: true, .. }
+        ));
+        assert_eq!(transport.inner().batch_sizes(), vec![1]);
+    }
+}
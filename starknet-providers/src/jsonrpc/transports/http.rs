@@ -1,10 +1,13 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 use log::trace;
-use reqwest::{Client, Url};
+use reqwest::{header::RETRY_AFTER, Client, Response, StatusCode, Url};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     jsonrpc::{transports::JsonRpcTransport, JsonRpcMethod, JsonRpcResponse},
+    paymaster::{PaymasterMethod, PaymasterTransport},
     ProviderRequestData,
 };
 
@@ -27,13 +30,24 @@ pub enum HttpTransportError {
     /// Unexpected response ID.
     #[error("unexpected response ID: {0}")]
     UnexpectedResponseId(u64),
+    /// The server responded with a failure HTTP status code without a valid JSON-RPC response
+    /// body. A `429 Too Many Requests` status is always reported as this variant.
+    #[error("unexpected HTTP status: {status}")]
+    UnexpectedStatus {
+        /// The HTTP status code returned by the server.
+        status: StatusCode,
+        /// The delay requested by the server via the `Retry-After` header, if any. Both the
+        /// delay-seconds and HTTP-date forms are supported, except for the latter on `wasm32`
+        /// targets where the system clock is unavailable.
+        retry_after: Option<Duration>,
+    },
 }
 
 #[derive(Debug, Serialize)]
-struct JsonRpcRequest<T> {
+struct JsonRpcRequest<M, T> {
     id: u64,
     jsonrpc: &'static str,
-    method: JsonRpcMethod,
+    method: M,
     params: T,
 }
 
@@ -72,21 +86,16 @@ impl HttpTransport {
     pub fn add_header(&mut self, name: String, value: String) {
         self.headers.push((name, value))
     }
-}
 
-#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
-#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
-impl JsonRpcTransport for HttpTransport {
-    type Error = HttpTransportError;
-
-    async fn send_request<P, R>(
+    async fn send_single_request<M, P, R>(
         &self,
-        method: JsonRpcMethod,
+        method: M,
         params: P,
-    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    ) -> Result<JsonRpcResponse<R>, HttpTransportError>
     where
-        P: Serialize + Send,
-        R: DeserializeOwned + Send,
+        M: Serialize,
+        P: Serialize,
+        R: DeserializeOwned,
     {
         let request_body = JsonRpcRequest {
             id: 1,
@@ -95,7 +104,8 @@ impl JsonRpcTransport for HttpTransport {
             params,
         };
 
-        let request_body = serde_json::to_string(&request_body).map_err(Self::Error::Json)?;
+        let request_body =
+            serde_json::to_string(&request_body).map_err(HttpTransportError::Json)?;
         trace!("Sending request via JSON-RPC: {}", request_body);
 
         let mut request = self
@@ -107,15 +117,30 @@ impl JsonRpcTransport for HttpTransport {
             request = request.header(name, value);
         }
 
-        let response = request.send().await.map_err(Self::Error::Reqwest)?;
-
-        let response_body = response.text().await.map_err(Self::Error::Reqwest)?;
-        trace!("Response from JSON-RPC: {}", response_body);
+        let response = request.send().await.map_err(HttpTransportError::Reqwest)?;
 
-        let parsed_response = serde_json::from_str(&response_body).map_err(Self::Error::Json)?;
+        let parsed_response = parse_response(response).await?;
 
         Ok(parsed_response)
     }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl JsonRpcTransport for HttpTransport {
+    type Error = HttpTransportError;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send,
+        R: DeserializeOwned + Send,
+    {
+        self.send_single_request(method, params).await
+    }
 
     async fn send_requests<R>(
         &self,
@@ -152,11 +177,8 @@ impl JsonRpcTransport for HttpTransport {
 
         let response = request.send().await.map_err(Self::Error::Reqwest)?;
 
-        let response_body = response.text().await.map_err(Self::Error::Reqwest)?;
-        trace!("Response from JSON-RPC: {}", response_body);
-
         let parsed_response: Vec<JsonRpcResponse<serde_json::Value>> =
-            serde_json::from_str(&response_body).map_err(Self::Error::Json)?;
+            parse_response(response).await?;
 
         let mut responses: Vec<Option<JsonRpcResponse<serde_json::Value>>> = vec![];
         responses.resize(request_bodies.len(), None);
@@ -180,3 +202,98 @@ impl JsonRpcTransport for HttpTransport {
         Ok(responses)
     }
 }
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl PaymasterTransport for HttpTransport {
+    type Error = HttpTransportError;
+
+    async fn send_paymaster_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.send_single_request(method, params).await
+    }
+}
+
+/// Reads and parses a JSON-RPC response body, surfacing failure HTTP statuses when the body cannot
+/// be interpreted.
+async fn parse_response<T>(response: Response) -> Result<T, HttpTransportError>
+where
+    T: DeserializeOwned,
+{
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after);
+
+    // Rate limiting responses never carry a useful JSON-RPC body.
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Err(HttpTransportError::UnexpectedStatus {
+            status,
+            retry_after,
+        });
+    }
+
+    let response_body = response.text().await.map_err(HttpTransportError::Reqwest)?;
+    trace!("Response from JSON-RPC: {}", response_body);
+
+    match serde_json::from_str(&response_body) {
+        Ok(parsed_response) => Ok(parsed_response),
+        Err(_) if !status.is_success() => Err(HttpTransportError::UnexpectedStatus {
+            status,
+            retry_after,
+        }),
+        Err(err) => Err(HttpTransportError::Json(err)),
+    }
+}
+
+/// Parses a `Retry-After` header value, which is either a number of seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // A date in the past means retrying right away
+        let date = httpdate::parse_http_date(value).ok()?;
+        Some(
+            date.duration_since(std::time::SystemTime::now())
+                .unwrap_or_default(),
+        )
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after(" 120 "), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+        let delay = parse_retry_after(&date).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    jsonrpc::{JsonRpcMethod, JsonRpcResponse, JsonRpcTransport},
+    paymaster::{PaymasterMethod, PaymasterTransport},
+    ProviderRequestData,
+};
+
+/// A [`JsonRpcTransport`] and [`PaymasterTransport`] for tests that delegates requests to a
+/// [`MockHandler`] and counts them. Batch requests are answered one by one.
+#[derive(Debug)]
+pub(crate) struct MockTransport<H> {
+    handler: H,
+    requests: AtomicU32,
+    batch_sizes: Mutex<Vec<usize>>,
+    reverse_batches: bool,
+}
+
+/// Canned answers of a [`MockTransport`].
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub(crate) trait MockHandler: Send + Sync {
+    /// Transport-level errors, typically returned for methods the handler does not support.
+    type Error: Error + Send + Sync;
+
+    /// Responds to a request with its serialized `params`.
+    async fn respond(
+        &self,
+        method: JsonRpcMethod,
+        params: serde_json::Value,
+    ) -> Result<MockResponse, Self::Error>;
+
+    /// Responds to a paymaster request with its serialized `params`. Panics unless overridden.
+    async fn respond_paymaster(
+        &self,
+        method: PaymasterMethod,
+        _params: serde_json::Value,
+    ) -> Result<MockResponse, Self::Error> {
+        panic!("unexpected paymaster request: {method:?}")
+    }
+}
+
+/// A mocked JSON-RPC response.
+#[derive(Debug)]
+pub(crate) enum MockResponse {
+    /// Successful response with `result`.
+    Result(serde_json::Value),
+    /// JSON-RPC error response.
+    Error {
+        /// Error code.
+        code: i64,
+        /// Error message.
+        message: &'static str,
+    },
+}
+
+impl<H> MockTransport<H> {
+    pub(crate) const fn new(handler: H) -> Self {
+        Self {
+            handler,
+            requests: AtomicU32::new(0),
+            batch_sizes: Mutex::new(vec![]),
+            reverse_batches: false,
+        }
+    }
+
+    /// Consumes the current [`MockTransport`] instance and returns a new one that returns batch
+    /// responses in reverse order.
+    pub(crate) fn with_reversed_batches(self) -> Self {
+        Self {
+            reverse_batches: true,
+            ..self
+        }
+    }
+
+    /// Number of requests received so far, including each request of a batch.
+    pub(crate) fn requests(&self) -> u32 {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// Number of requests in each call received so far, with a single request counting as `1`.
+    pub(crate) fn batch_sizes(&self) -> Vec<usize> {
+        self.batch_sizes.lock().unwrap().clone()
+    }
+}
+
+impl<H> MockTransport<H>
+where
+    H: MockHandler,
+{
+    async fn respond<R>(
+        &self,
+        id: u64,
+        method: JsonRpcMethod,
+        params: serde_json::Value,
+    ) -> Result<JsonRpcResponse<R>, H::Error>
+    where
+        R: DeserializeOwned,
+    {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+
+        Ok(to_response(id, self.handler.respond(method, params).await?))
+    }
+}
+
+fn to_response<R>(id: u64, response: MockResponse) -> JsonRpcResponse<R>
+where
+    R: DeserializeOwned,
+{
+    let response = match response {
+        MockResponse::Result(result) => serde_json::json!({ "id": id, "result": result }),
+        MockResponse::Error { code, message } => serde_json::json!({
+            "id": id,
+            "error": { "code": code, "message": message },
+        }),
+    };
+
+    serde_json::from_value(response).unwrap()
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<H> MockHandler for Arc<H>
+where
+    H: MockHandler,
+{
+    type Error = H::Error;
+
+    async fn respond(
+        &self,
+        method: JsonRpcMethod,
+        params: serde_json::Value,
+    ) -> Result<MockResponse, Self::Error> {
+        self.as_ref().respond(method, params).await
+    }
+
+    async fn respond_paymaster(
+        &self,
+        method: PaymasterMethod,
+        params: serde_json::Value,
+    ) -> Result<MockResponse, Self::Error> {
+        self.as_ref().respond_paymaster(method, params).await
+    }
+}
+
+impl From<serde_json::Value> for MockResponse {
+    fn from(value: serde_json::Value) -> Self {
+        Self::Result(value)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<H> JsonRpcTransport for MockTransport<H>
+where
+    H: MockHandler,
+{
+    type Error = H::Error;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.batch_sizes.lock().unwrap().push(1);
+
+        self.respond(1, method, serde_json::to_value(params).unwrap())
+            .await
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        self.batch_sizes
+            .lock()
+            .unwrap()
+            .push(requests.as_ref().len());
+
+        let mut responses = Vec::with_capacity(requests.as_ref().len());
+
+        for (id, request) in requests.as_ref().iter().enumerate() {
+            responses.push(
+                self.respond(
+                    id as u64,
+                    request.jsonrpc_method(),
+                    serde_json::to_value(request).unwrap(),
+                )
+                .await?,
+            );
+        }
+
+        if self.reverse_batches {
+            responses.reverse();
+        }
+
+        Ok(responses)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<H> PaymasterTransport for MockTransport<H>
+where
+    H: MockHandler,
+{
+    type Error = H::Error;
+
+    async fn send_paymaster_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        self.batch_sizes.lock().unwrap().push(1);
+
+        let response = self
+            .handler
+            .respond_paymaster(method, serde_json::to_value(params).unwrap())
+            .await?;
+
+        Ok(to_response(1, response))
+    }
+}
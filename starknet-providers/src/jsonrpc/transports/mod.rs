@@ -11,6 +11,21 @@ use crate::{
 mod http;
 pub use http::{HttpTransport, HttpTransportError};
 
+#[cfg(not(target_arch = "wasm32"))]
+mod batching;
+#[cfg(not(target_arch = "wasm32"))]
+pub use batching::{BatchingTransport, BatchingTransportError};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod retry;
+#[cfg(not(target_arch = "wasm32"))]
+pub use retry::{RetryPolicy, RetryTransport, RetryableError};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+pub(crate) use mock::{MockHandler, MockResponse, MockTransport};
+
 #[cfg(feature = "worker")]
 mod worker;
 #[cfg(feature = "worker")]
@@ -0,0 +1,768 @@
+use std::{future::Future, time::Duration};
+
+use async_trait::async_trait;
+use log::debug;
+use rand::Rng;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    jsonrpc::{
+        transports::{HttpTransportError, JsonRpcTransport},
+        JsonRpcClientError, JsonRpcError, JsonRpcMethod, JsonRpcResponse,
+    },
+    paymaster::{PaymasterMethod, PaymasterTransport},
+    ProviderError, ProviderRequestData,
+};
+
+/// A [`JsonRpcTransport`] implementation that wraps another transport and retries failed requests
+/// according to a [`RetryPolicy`].
+///
+/// Only errors classified as transient by [`RetryableError`] are retried, along with JSON-RPC error
+/// responses indicating rate limiting (code `429`, or `-32005` for "limit exceeded") that some
+/// providers return with a successful HTTP status. Requests with non-idempotent methods (see
+/// [`JsonRpcMethod::is_idempotent`]) are only resent when the error indicates that the server
+/// never processed the original request, unless explicitly allowed with
+/// [`RetryPolicy::with_retry_non_idempotent`].
+///
+/// Paymaster requests are retried the same way when the inner transport implements
+/// [`PaymasterTransport`], based on [`PaymasterMethod::is_idempotent`].
+#[derive(Debug, Clone)]
+pub struct RetryTransport<T> {
+    inner: T,
+    policy: RetryPolicy,
+}
+
+/// Configuration of retry behavior with exponential backoff.
+///
+/// The delay before the `n`-th retry (0-based) is `initial_backoff * multiplier^n`, capped at
+/// `max_backoff`. A random portion of the delay, up to the `jitter` fraction, is then subtracted
+/// to avoid synchronized retries from multiple clients. When the server specifies a `Retry-After`
+/// delay, it's used instead of the computed backoff, still capped at `max_backoff`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    respect_retry_after: bool,
+    retry_non_idempotent: bool,
+}
+
+/// Errors that can be classified for retrying purposes.
+pub trait RetryableError {
+    /// Whether the error is transient, such that retrying the same request might succeed.
+    fn is_retryable(&self) -> bool;
+
+    /// Whether the error guarantees that the server has not processed the request. Requests with
+    /// non-idempotent methods are only resent on such errors.
+    fn is_unprocessed(&self) -> bool {
+        false
+    }
+
+    /// The delay requested by the server before retrying, if any.
+    fn retry_after(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Outcome of a failed attempt, where a rate limiting JSON-RPC response is retried just like a
+/// transport error but returned as is once attempts are exhausted.
+#[derive(Debug)]
+enum AttemptError<R, E> {
+    Transport(E),
+    RateLimited {
+        response: R,
+        message: String,
+        unprocessed: bool,
+    },
+}
+
+impl<T> RetryTransport<T> {
+    /// Constructs [`RetryTransport`] by wrapping a transport, using the default [`RetryPolicy`].
+    pub fn new(inner: T) -> Self {
+        Self::new_with_policy(inner, RetryPolicy::default())
+    }
+
+    /// Constructs [`RetryTransport`] by wrapping a transport with a custom [`RetryPolicy`].
+    pub const fn new_with_policy(inner: T, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Gets a reference to the wrapped transport.
+    pub const fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a reference to the retry policy in use.
+    pub const fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// Sends a single request with `send`, retrying transport errors and rate limiting responses.
+    async fn send_with_retry<F, Fut, R, E>(
+        &self,
+        idempotent: bool,
+        mut send: F,
+    ) -> Result<JsonRpcResponse<R>, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<JsonRpcResponse<R>, E>>,
+        E: RetryableError + std::fmt::Display,
+    {
+        self.policy
+            .retry(idempotent, || {
+                let response = send();
+                async move {
+                    match response.await {
+                        Ok(JsonRpcResponse::Error { id, error }) if is_rate_limited(&error) => {
+                            Err(AttemptError::RateLimited {
+                                message: error.message.clone(),
+                                response: JsonRpcResponse::Error { id, error },
+                                unprocessed: true,
+                            })
+                        }
+                        Ok(response) => Ok(response),
+                        Err(err) => Err(AttemptError::Transport(err)),
+                    }
+                }
+            })
+            .await
+            .or_else(AttemptError::into_result)
+    }
+}
+
+impl RetryPolicy {
+    /// Constructs a [`RetryPolicy`] that never retries.
+    pub fn none() -> Self {
+        Self::default().with_max_attempts(1)
+    }
+
+    /// Returns a new [`RetryPolicy`] with the maximum number of attempts (including the first one)
+    /// set. A value of `0` is treated as `1`.
+    pub fn with_max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    /// Returns a new [`RetryPolicy`] with the delay before the first retry set.
+    pub fn with_initial_backoff(self, initial_backoff: Duration) -> Self {
+        Self {
+            initial_backoff,
+            ..self
+        }
+    }
+
+    /// Returns a new [`RetryPolicy`] with the maximum delay between attempts set.
+    pub fn with_max_backoff(self, max_backoff: Duration) -> Self {
+        Self {
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// Returns a new [`RetryPolicy`] with the factor by which the delay grows after each retry
+    /// set. Values below `1.0` are treated as `1.0`.
+    pub fn with_multiplier(self, multiplier: f64) -> Self {
+        Self {
+            multiplier: multiplier.max(1.0),
+            ..self
+        }
+    }
+
+    /// Returns a new [`RetryPolicy`] with the jitter fraction set. The value is clamped to the
+    /// range `[0.0, 1.0]`, where `0.0` disables jitter.
+    pub fn with_jitter(self, jitter: f64) -> Self {
+        Self {
+            jitter: jitter.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Returns a new [`RetryPolicy`] with `Retry-After` handling enabled or disabled.
+    pub fn with_respect_retry_after(self, respect_retry_after: bool) -> Self {
+        Self {
+            respect_retry_after,
+            ..self
+        }
+    }
+
+    /// Returns a new [`RetryPolicy`] that resends non-idempotent requests (e.g. transaction
+    /// submissions) on any retryable error. This is disabled by default.
+    pub fn with_retry_non_idempotent(self, retry_non_idempotent: bool) -> Self {
+        Self {
+            retry_non_idempotent,
+            ..self
+        }
+    }
+
+    /// Gets the maximum number of attempts, including the first one.
+    pub const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Runs an operation, retrying on failures according to the policy.
+    ///
+    /// The `idempotent` flag indicates whether the operation is safe to repeat. This can be used
+    /// for retrying any fallible operation with an error type implementing [`RetryableError`],
+    /// including [`Provider`](crate::Provider) calls returning [`ProviderError`].
+    pub async fn retry<F, Fut, T, E>(&self, idempotent: bool, mut operation: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: RetryableError + std::fmt::Display,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(err) => {
+                    attempt += 1;
+                    if attempt >= self.max_attempts || !self.should_retry(idempotent, &err) {
+                        return Err(err);
+                    }
+
+                    let delay = self.delay_for(attempt - 1, err.retry_after());
+                    debug!(
+                        "Retrying failed request in {:?} (attempt {}/{}): {}",
+                        delay,
+                        attempt + 1,
+                        self.max_attempts,
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    fn should_retry<E>(&self, idempotent: bool, err: &E) -> bool
+    where
+        E: RetryableError,
+    {
+        err.is_retryable() && (idempotent || self.retry_non_idempotent || err.is_unprocessed())
+    }
+
+    /// Computes the delay before the retry with the given 0-based index.
+    fn delay_for(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after.filter(|_| self.respect_retry_after) {
+            return retry_after.min(self.max_backoff);
+        }
+
+        let backoff = self.initial_backoff.as_secs_f64()
+            * self.multiplier.powi(retry.try_into().unwrap_or(i32::MAX));
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+
+        let backoff = if self.jitter > 0.0 {
+            backoff * rand::thread_rng().gen_range((1.0 - self.jitter)..=1.0)
+        } else {
+            backoff
+        };
+
+        Duration::from_secs_f64(backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.5,
+            respect_retry_after: true,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for RetryTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync,
+    T::Error: RetryableError,
+{
+    type Error = T::Error;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.send_with_retry(method.is_idempotent(), || {
+            self.inner.send_request(method, &params)
+        })
+        .await
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        let requests = requests.as_ref();
+        let idempotent = requests
+            .iter()
+            .all(|request| request.jsonrpc_method().is_idempotent());
+
+        self.policy
+            .retry(idempotent, || async {
+                let responses = self
+                    .inner
+                    .send_requests(requests)
+                    .await
+                    .map_err(AttemptError::Transport)?;
+
+                match responses.iter().find_map(|response| match response {
+                    JsonRpcResponse::Error { error, .. } if is_rate_limited(error) => {
+                        Some(error.message.clone())
+                    }
+                    _ => None,
+                }) {
+                    // Other requests in the batch might have been processed
+                    Some(message) => Err(AttemptError::RateLimited {
+                        response: responses,
+                        message,
+                        unprocessed: false,
+                    }),
+                    None => Ok(responses),
+                }
+            })
+            .await
+            .or_else(AttemptError::into_result)
+    }
+}
+
+#[async_trait]
+impl<T> PaymasterTransport for RetryTransport<T>
+where
+    T: PaymasterTransport + Send + Sync,
+    T::Error: RetryableError,
+{
+    type Error = T::Error;
+
+    async fn send_paymaster_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.send_with_retry(method.is_idempotent(), || {
+            self.inner.send_paymaster_request(method, &params)
+        })
+        .await
+    }
+}
+
+impl<R, E> AttemptError<R, E> {
+    fn into_result(self) -> Result<R, E> {
+        match self {
+            Self::Transport(err) => Err(err),
+            Self::RateLimited { response, .. } => Ok(response),
+        }
+    }
+}
+
+impl<R, E> std::fmt::Display for AttemptError<R, E>
+where
+    E: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transport(err) => write!(f, "{err}"),
+            Self::RateLimited { message, .. } => write!(f, "rate limited: {message}"),
+        }
+    }
+}
+
+impl<R, E> RetryableError for AttemptError<R, E>
+where
+    E: RetryableError,
+{
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Transport(err) => err.is_retryable(),
+            Self::RateLimited { .. } => true,
+        }
+    }
+
+    fn is_unprocessed(&self) -> bool {
+        match self {
+            Self::Transport(err) => err.is_unprocessed(),
+            Self::RateLimited { unprocessed, .. } => *unprocessed,
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Transport(err) => err.retry_after(),
+            Self::RateLimited { .. } => None,
+        }
+    }
+}
+
+/// Whether a JSON-RPC error response indicates that the request was rejected due to rate limiting.
+const fn is_rate_limited(error: &JsonRpcError) -> bool {
+    matches!(error.code, 429 | -32005)
+}
+
+impl RetryableError for HttpTransportError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Reqwest(err) => {
+                err.is_timeout() || err.is_connect() || err.is_request() || err.is_body()
+            }
+            Self::UnexpectedStatus { status, .. } => {
+                status.as_u16() == 429 || status.is_server_error()
+            }
+            Self::Json(_) | Self::UnexpectedResponseId(_) => false,
+        }
+    }
+
+    fn is_unprocessed(&self) -> bool {
+        match self {
+            Self::Reqwest(err) => err.is_connect(),
+            Self::UnexpectedStatus { status, .. } => status.as_u16() == 429,
+            Self::Json(_) | Self::UnexpectedResponseId(_) => false,
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::UnexpectedStatus { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl RetryableError for ProviderError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited => true,
+            Self::Other(err) => err
+                .as_any()
+                .downcast_ref::<JsonRpcClientError<HttpTransportError>>()
+                .is_some_and(|err| match err {
+                    JsonRpcClientError::TransportError(err) => err.is_retryable(),
+                    JsonRpcClientError::JsonRpcError(err) => is_rate_limited(err),
+                    JsonRpcClientError::JsonError(_) => false,
+                }),
+            Self::StarknetError(_) | Self::ArrayLengthMismatch => false,
+        }
+    }
+
+    fn is_unprocessed(&self) -> bool {
+        match self {
+            Self::RateLimited => true,
+            Self::Other(err) => err
+                .as_any()
+                .downcast_ref::<JsonRpcClientError<HttpTransportError>>()
+                .is_some_and(|err| match err {
+                    JsonRpcClientError::TransportError(err) => err.is_unprocessed(),
+                    JsonRpcClientError::JsonRpcError(err) => is_rate_limited(err),
+                    JsonRpcClientError::JsonError(_) => false,
+                }),
+            Self::StarknetError(_) | Self::ArrayLengthMismatch => false,
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Other(err) => err
+                .as_any()
+                .downcast_ref::<JsonRpcClientError<HttpTransportError>>()
+                .and_then(|err| match err {
+                    JsonRpcClientError::TransportError(err) => err.retry_after(),
+                    _ => None,
+                }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use starknet_core::types::{BlockId, BlockTag};
+
+    use super::*;
+    use crate::{
+        jsonrpc::{JsonRpcClient, MockHandler, MockResponse, MockTransport},
+        Provider, ProviderError,
+    };
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("mock error")]
+    struct MockError {
+        retryable: bool,
+        unprocessed: bool,
+    }
+
+    impl RetryableError for MockError {
+        fn is_retryable(&self) -> bool {
+            self.retryable
+        }
+
+        fn is_unprocessed(&self) -> bool {
+            self.unprocessed
+        }
+    }
+
+    /// A handler that fails a fixed number of times before returning block number `100`.
+    #[derive(Debug)]
+    struct FlakyHandler {
+        failures: u32,
+        retryable: bool,
+        unprocessed: bool,
+        attempts: AtomicU32,
+    }
+
+    fn flaky_transport(
+        failures: u32,
+        retryable: bool,
+        unprocessed: bool,
+    ) -> MockTransport<FlakyHandler> {
+        MockTransport::new(FlakyHandler {
+            failures,
+            retryable,
+            unprocessed,
+            attempts: AtomicU32::new(0),
+        })
+    }
+
+    impl FlakyHandler {
+        fn attempt(&self) -> Result<MockResponse, MockError> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Err(MockError {
+                    retryable: self.retryable,
+                    unprocessed: self.unprocessed,
+                })
+            } else {
+                Ok(serde_json::json!(100).into())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MockHandler for FlakyHandler {
+        type Error = MockError;
+
+        async fn respond(
+            &self,
+            _method: JsonRpcMethod,
+            _params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            self.attempt()
+        }
+
+        async fn respond_paymaster(
+            &self,
+            _method: PaymasterMethod,
+            _params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            self.attempt()
+        }
+    }
+
+    /// A handler that returns a rate limiting JSON-RPC error a fixed number of times before
+    /// returning block number `100`.
+    #[derive(Debug)]
+    struct RateLimitedHandler {
+        failures: u32,
+        attempts: AtomicU32,
+    }
+
+    #[async_trait]
+    impl MockHandler for RateLimitedHandler {
+        type Error = MockError;
+
+        async fn respond(
+            &self,
+            _method: JsonRpcMethod,
+            _params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                Ok(MockResponse::Error {
+                    code: 429,
+                    message: "Too many requests",
+                })
+            } else {
+                Ok(serde_json::json!(100).into())
+            }
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy::default()
+            .with_initial_backoff(Duration::from_millis(1))
+            .with_max_backoff(Duration::from_millis(5))
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        let transport = Arc::new(RetryTransport::new_with_policy(
+            flaky_transport(3, true, false),
+            fast_policy().with_max_attempts(4),
+        ));
+        let client = JsonRpcClient::new(transport.clone());
+
+        assert_eq!(client.block_number().await.unwrap(), 100);
+        assert_eq!(transport.inner().requests(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_attempts() {
+        let transport = Arc::new(RetryTransport::new_with_policy(
+            flaky_transport(10, true, false),
+            fast_policy().with_max_attempts(3),
+        ));
+        let client = JsonRpcClient::new(transport.clone());
+
+        assert!(client.block_number().await.is_err());
+        assert_eq!(transport.inner().requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_permanent_errors() {
+        let transport = Arc::new(RetryTransport::new_with_policy(
+            flaky_transport(1, false, false),
+            fast_policy(),
+        ));
+        let client = JsonRpcClient::new(transport.clone());
+
+        assert!(client
+            .get_block_transaction_count(BlockId::Tag(BlockTag::Latest))
+            .await
+            .is_err());
+        assert_eq!(transport.inner().requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_non_idempotent() {
+        // Not resent when the server might have processed the request
+        let transport =
+            RetryTransport::new_with_policy(flaky_transport(1, true, false), fast_policy());
+        assert!(transport
+            .send_request::<_, serde_json::Value>(JsonRpcMethod::AddInvokeTransaction, ())
+            .await
+            .is_err());
+        assert_eq!(transport.inner().requests(), 1);
+
+        // Resent when the request is known to be unprocessed
+        let transport =
+            RetryTransport::new_with_policy(flaky_transport(1, true, true), fast_policy());
+        assert!(transport
+            .send_request::<_, serde_json::Value>(JsonRpcMethod::AddInvokeTransaction, ())
+            .await
+            .is_ok());
+        assert_eq!(transport.inner().requests(), 2);
+
+        // Resent when explicitly allowed
+        let transport = RetryTransport::new_with_policy(
+            flaky_transport(1, true, false),
+            fast_policy().with_retry_non_idempotent(true),
+        );
+        assert!(transport
+            .send_request::<_, serde_json::Value>(JsonRpcMethod::AddInvokeTransaction, ())
+            .await
+            .is_ok());
+        assert_eq!(transport.inner().requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_paymaster_requests() {
+        let transport =
+            RetryTransport::new_with_policy(flaky_transport(2, true, false), fast_policy());
+        assert!(transport
+            .send_paymaster_request::<_, serde_json::Value>(PaymasterMethod::IsAvailable, ())
+            .await
+            .is_ok());
+        assert_eq!(transport.inner().requests(), 3);
+
+        // Submitting a transaction is not resent when it might have been processed
+        let transport =
+            RetryTransport::new_with_policy(flaky_transport(1, true, false), fast_policy());
+        assert!(transport
+            .send_paymaster_request::<_, serde_json::Value>(PaymasterMethod::ExecuteTransaction, ())
+            .await
+            .is_err());
+        assert_eq!(transport.inner().requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_rate_limited_responses() {
+        let transport = Arc::new(RetryTransport::new_with_policy(
+            MockTransport::new(RateLimitedHandler {
+                failures: 2,
+                attempts: AtomicU32::new(0),
+            }),
+            fast_policy(),
+        ));
+        let client = JsonRpcClient::new(transport.clone());
+
+        assert_eq!(client.block_number().await.unwrap(), 100);
+        assert_eq!(transport.inner().requests(), 3);
+
+        // The rate limiting response is returned once attempts are exhausted
+        let transport = Arc::new(RetryTransport::new_with_policy(
+            MockTransport::new(RateLimitedHandler {
+                failures: 10,
+                attempts: AtomicU32::new(0),
+            }),
+            fast_policy().with_max_attempts(2),
+        ));
+        let client = JsonRpcClient::new(transport.clone());
+
+        match client.block_number().await.unwrap_err() {
+            ProviderError::Other(err) => assert!(err.to_string().contains("Too many requests")),
+            err => panic!("unexpected error: {err:?}"),
+        }
+        assert_eq!(transport.inner().requests(), 2);
+    }
+
+    #[test]
+    fn test_backoff_delays() {
+        let policy = RetryPolicy::default()
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(500))
+            .with_jitter(0.0);
+
+        assert_eq!(policy.delay_for(0, None), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(1, None), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(2, None), Duration::from_millis(400));
+        assert_eq!(policy.delay_for(3, None), Duration::from_millis(500));
+        assert_eq!(
+            policy.delay_for(0, Some(Duration::from_millis(300))),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            policy
+                .clone()
+                .with_respect_retry_after(false)
+                .delay_for(0, Some(Duration::from_millis(300))),
+            Duration::from_millis(100)
+        );
+
+        let policy = policy.with_jitter(0.5);
+        for _ in 0..100 {
+            let delay = policy.delay_for(1, None);
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+}
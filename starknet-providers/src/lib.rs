@@ -24,8 +24,32 @@ pub use sequencer::{
 pub mod jsonrpc;
 pub use jsonrpc::JsonRpcClient;
 
+/// Module containing types related to the SNIP-29 paymaster API.
+pub mod paymaster;
+pub use paymaster::PaymasterClient;
+
 mod any;
 pub use any::AnyProvider;
 
+mod cache;
+pub use cache::{
+    CacheBlockId, CacheKey, CacheValue, CachingProvider, InMemoryCache, ProviderCache,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod fallback;
+#[cfg(not(target_arch = "wasm32"))]
+pub use fallback::{EndpointHealth, FallbackMode, FallbackProvider, FallbackProviderError};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod events;
+#[cfg(not(target_arch = "wasm32"))]
+pub use events::EventPaginator;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod follower;
+#[cfg(not(target_arch = "wasm32"))]
+pub use follower::{BlockFollower, FollowedBlock, FollowerError, FollowerUpdate};
+
 // Re-export
 pub use url::Url;
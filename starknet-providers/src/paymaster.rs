@@ -0,0 +1,669 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use auto_impl::auto_impl;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{serde_as, DeserializeAs, SerializeAs};
+use starknet_core::{
+    serde::unsigned_field_element::UfeHex,
+    types::{typed_data::TypedData, Call, Felt},
+};
+
+use crate::{
+    jsonrpc::{JsonRpcClientError, JsonRpcResponse},
+    ProviderError,
+};
+
+/// A client for the SNIP-29 paymaster JSON-RPC API with any transport.
+///
+/// A paymaster builds transactions executing calls on behalf of an account through SNIP-9 outside
+/// executions and submits them after they are signed, allowing the account to pay transaction fees
+/// in tokens other than `STRK`, or to have them sponsored entirely.
+///
+/// Paymaster services often require an API key, which can be set as a header on the transport
+/// (e.g. with [`HttpTransport::with_header`](crate::jsonrpc::HttpTransport::with_header)).
+#[derive(Debug, Clone)]
+pub struct PaymasterClient<T> {
+    transport: T,
+}
+
+/// All JSON-RPC methods of the SNIP-29 paymaster API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymasterMethod {
+    /// The `paymaster_isAvailable` method.
+    #[serde(rename = "paymaster_isAvailable")]
+    IsAvailable,
+    /// The `paymaster_getSupportedTokens` method.
+    #[serde(rename = "paymaster_getSupportedTokens")]
+    GetSupportedTokens,
+    /// The `paymaster_buildTransaction` method.
+    #[serde(rename = "paymaster_buildTransaction")]
+    BuildTransaction,
+    /// The `paymaster_executeTransaction` method.
+    #[serde(rename = "paymaster_executeTransaction")]
+    ExecuteTransaction,
+}
+
+/// Any type that is capable of producing JSON-RPC responses when given paymaster JSON-RPC
+/// requests.
+///
+/// Paymaster methods are not part of the Starknet JSON-RPC API and are therefore kept out of
+/// [`JsonRpcMethod`](crate::jsonrpc::JsonRpcMethod), such that everything built around it (e.g.
+/// [`ProviderRequestData`](crate::ProviderRequestData) and batching) only deals with requests a
+/// Starknet node can serve. Instead, this trait is implemented alongside
+/// [`JsonRpcTransport`](crate::jsonrpc::JsonRpcTransport) by
+/// [`HttpTransport`](crate::jsonrpc::HttpTransport), and by the wrapping transports
+/// [`RetryTransport`](crate::jsonrpc::RetryTransport) and
+/// [`BatchingTransport`](crate::jsonrpc::BatchingTransport) when the wrapped transport implements
+/// it, so that a paymaster can be used with the same transport stack as a Starknet node. The
+/// WebSocket transport `TungsteniteStream` of `starknet-tokio-tungstenite` implements it too.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[auto_impl(&, Box, Arc)]
+pub trait PaymasterTransport {
+    /// Possible errors processing requests.
+    type Error: Error + Send + Sync;
+
+    /// Sends a paymaster JSON-RPC request to retrieve a response.
+    async fn send_paymaster_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send;
+}
+
+/// A token accepted by a paymaster for paying transaction fees.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct TokenData {
+    /// Address of the token contract.
+    #[serde_as(as = "UfeHex")]
+    pub token_address: Felt,
+    /// Number of decimals of the token.
+    pub decimals: u8,
+    /// Price of one unit of the token in `FRI`.
+    #[serde_as(as = "UfeHex")]
+    pub price_in_strk: Felt,
+}
+
+/// A transaction to be built by a paymaster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UserTransaction {
+    /// Deploys an account contract.
+    Deploy {
+        /// Deployment of the account contract.
+        deployment: DeploymentData,
+    },
+    /// Executes calls from an account contract.
+    Invoke {
+        /// Calls to be executed.
+        invoke: UserInvoke,
+    },
+    /// Deploys an account contract, then executes calls from it.
+    DeployAndInvoke {
+        /// Deployment of the account contract.
+        deployment: DeploymentData,
+        /// Calls to be executed.
+        invoke: UserInvoke,
+    },
+}
+
+/// A transaction built by a paymaster and signed by the user, ready to be executed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutableUserTransaction {
+    /// Deploys an account contract.
+    Deploy {
+        /// Deployment of the account contract.
+        deployment: DeploymentData,
+    },
+    /// Executes calls from an account contract.
+    Invoke {
+        /// Signed outside execution.
+        invoke: ExecutableUserInvoke,
+    },
+    /// Deploys an account contract, then executes calls from it.
+    DeployAndInvoke {
+        /// Deployment of the account contract.
+        deployment: DeploymentData,
+        /// Signed outside execution.
+        invoke: ExecutableUserInvoke,
+    },
+}
+
+/// Calls to be executed from an account contract.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct UserInvoke {
+    /// Address of the account contract.
+    #[serde_as(as = "UfeHex")]
+    pub user_address: Felt,
+    /// Calls to be executed.
+    #[serde_as(as = "Vec<PaymasterCall>")]
+    pub calls: Vec<Call>,
+}
+
+/// A signed outside execution to be submitted by a paymaster.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct ExecutableUserInvoke {
+    /// Address of the account contract.
+    #[serde_as(as = "UfeHex")]
+    pub user_address: Felt,
+    /// Outside execution typed data as returned by `paymaster_buildTransaction`.
+    pub typed_data: TypedData,
+    /// Signature of the typed data by the account.
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<Felt>,
+}
+
+/// Data for deploying an account contract through a paymaster.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct DeploymentData {
+    /// Address of the account contract to be deployed.
+    #[serde_as(as = "UfeHex")]
+    pub address: Felt,
+    /// Class hash of the account contract.
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: Felt,
+    /// Salt used for computing the contract address.
+    #[serde_as(as = "UfeHex")]
+    pub salt: Felt,
+    /// Constructor calldata.
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub calldata: Vec<Felt>,
+    /// Additional data for validating the deployment, if required by the account contract.
+    #[serde_as(as = "Option<Vec<UfeHex>>")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub sigdata: Option<Vec<Felt>>,
+    /// Version of SNIP-9 outside execution supported by the account contract.
+    pub version: u8,
+}
+
+/// Parameters for building and executing a transaction through a paymaster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct ExecutionParameters {
+    /// Version of the execution parameters.
+    pub version: ExecutionParametersVersion,
+    /// How transaction fees are paid.
+    pub fee_mode: FeeMode,
+    /// Time range during which the transaction can be executed.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub time_bounds: Option<TimeBounds>,
+}
+
+/// Version of [`ExecutionParameters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionParametersVersion {
+    /// Version 1.
+    #[serde(rename = "0x1")]
+    V1,
+}
+
+/// How transaction fees are paid when executing through a paymaster.
+#[serde_as]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum FeeMode {
+    /// Fees are paid by the paymaster. This usually requires an API key.
+    Sponsored,
+    /// Fees are paid by the account in the specified token.
+    Default {
+        /// Address of the token used for paying fees.
+        #[serde_as(as = "UfeHex")]
+        gas_token: Felt,
+    },
+}
+
+/// Time range during which a transaction can be executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct TimeBounds {
+    /// Timestamp (in seconds) after which the transaction can be executed.
+    pub execute_after: u64,
+    /// Timestamp (in seconds) before which the transaction can be executed.
+    pub execute_before: u64,
+}
+
+/// Transaction built by a paymaster, along with its fee estimate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BuildTransactionResponse {
+    /// Deploys an account contract.
+    Deploy {
+        /// Deployment of the account contract.
+        deployment: DeploymentData,
+        /// Parameters used for building the transaction.
+        parameters: ExecutionParameters,
+        /// Fee estimate of the transaction.
+        fee: PaymasterFeeEstimate,
+    },
+    /// Executes calls from an account contract.
+    Invoke {
+        /// Outside execution typed data to be signed by the account.
+        typed_data: TypedData,
+        /// Parameters used for building the transaction.
+        parameters: ExecutionParameters,
+        /// Fee estimate of the transaction.
+        fee: PaymasterFeeEstimate,
+    },
+    /// Deploys an account contract, then executes calls from it.
+    DeployAndInvoke {
+        /// Deployment of the account contract.
+        deployment: DeploymentData,
+        /// Outside execution typed data to be signed by the account.
+        typed_data: TypedData,
+        /// Parameters used for building the transaction.
+        parameters: ExecutionParameters,
+        /// Fee estimate of the transaction.
+        fee: PaymasterFeeEstimate,
+    },
+}
+
+/// Fee estimate of a transaction built by a paymaster.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct PaymasterFeeEstimate {
+    /// Price of one unit of the gas token in `FRI`.
+    #[serde_as(as = "UfeHex")]
+    pub gas_token_price_in_strk: Felt,
+    /// Estimated fee in `FRI`.
+    #[serde_as(as = "UfeHex")]
+    pub estimated_fee_in_strk: Felt,
+    /// Estimated fee in the gas token.
+    #[serde_as(as = "UfeHex")]
+    pub estimated_fee_in_gas_token: Felt,
+    /// Suggested maximum fee in `FRI`.
+    #[serde_as(as = "UfeHex")]
+    pub suggested_max_fee_in_strk: Felt,
+    /// Suggested maximum fee in the gas token.
+    #[serde_as(as = "UfeHex")]
+    pub suggested_max_fee_in_gas_token: Felt,
+}
+
+/// Result of submitting a transaction through a paymaster.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+pub struct ExecuteTransactionResponse {
+    /// Identifier for tracking the transaction with the paymaster.
+    #[serde_as(as = "UfeHex")]
+    pub tracking_id: Felt,
+    /// Hash of the submitted transaction.
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: Felt,
+}
+
+/// (De)serializes [`Call`] in the format used by the paymaster API.
+struct PaymasterCall;
+
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "no_unknown_fields", serde(deny_unknown_fields))]
+struct RawCall {
+    #[serde_as(as = "UfeHex")]
+    to: Felt,
+    #[serde_as(as = "UfeHex")]
+    selector: Felt,
+    #[serde_as(as = "Vec<UfeHex>")]
+    calldata: Vec<Felt>,
+}
+
+impl PaymasterMethod {
+    /// Whether sending a request with this method more than once is guaranteed to have the same
+    /// effect as sending it once.
+    ///
+    /// Only `paymaster_executeTransaction` is considered non-idempotent, as it submits a
+    /// transaction.
+    pub const fn is_idempotent(&self) -> bool {
+        !matches!(self, Self::ExecuteTransaction)
+    }
+}
+
+#[derive(Serialize)]
+struct TransactionRequest<'a, T> {
+    transaction: &'a T,
+    parameters: &'a ExecutionParameters,
+}
+
+impl<T> PaymasterClient<T> {
+    /// Constructs a new [`PaymasterClient`] from a transport.
+    pub const fn new(transport: T) -> Self {
+        Self { transport }
+    }
+}
+
+impl<T> PaymasterClient<T>
+where
+    T: 'static + PaymasterTransport + Send + Sync,
+{
+    /// Checks whether the paymaster service is available.
+    pub async fn is_available(&self) -> Result<bool, ProviderError> {
+        self.send_request(PaymasterMethod::IsAvailable, [(); 0])
+            .await
+    }
+
+    /// Gets the list of tokens accepted by the paymaster for paying transaction fees.
+    pub async fn get_supported_tokens(&self) -> Result<Vec<TokenData>, ProviderError> {
+        self.send_request(PaymasterMethod::GetSupportedTokens, [(); 0])
+            .await
+    }
+
+    /// Builds a transaction, returning the typed data to be signed by the account if calls are to be
+    /// executed, along with a fee estimate.
+    pub async fn build_transaction(
+        &self,
+        transaction: &UserTransaction,
+        parameters: &ExecutionParameters,
+    ) -> Result<BuildTransactionResponse, ProviderError> {
+        self.send_request(
+            PaymasterMethod::BuildTransaction,
+            TransactionRequest {
+                transaction,
+                parameters,
+            },
+        )
+        .await
+    }
+
+    /// Submits a transaction previously built with
+    /// [`build_transaction`](Self::build_transaction) and signed by the account.
+    pub async fn execute_transaction(
+        &self,
+        transaction: &ExecutableUserTransaction,
+        parameters: &ExecutionParameters,
+    ) -> Result<ExecuteTransactionResponse, ProviderError> {
+        self.send_request(
+            PaymasterMethod::ExecuteTransaction,
+            TransactionRequest {
+                transaction,
+                parameters,
+            },
+        )
+        .await
+    }
+
+    async fn send_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<R, ProviderError>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        // Paymaster error codes do not overlap with those of `StarknetError` and are therefore
+        // returned as is.
+        match self
+            .transport
+            .send_paymaster_request(method, params)
+            .await
+            .map_err(JsonRpcClientError::TransportError)?
+        {
+            JsonRpcResponse::Success { result, .. } => Ok(result),
+            JsonRpcResponse::Error { error, .. } => {
+                Err(JsonRpcClientError::<T::Error>::JsonRpcError(error).into())
+            }
+        }
+    }
+}
+
+impl SerializeAs<Call> for PaymasterCall {
+    fn serialize_as<S>(value: &Call, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        RawCall {
+            to: value.to,
+            selector: value.selector,
+            calldata: value.calldata.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Call> for PaymasterCall {
+    fn deserialize_as<D>(deserializer: D) -> Result<Call, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = RawCall::deserialize(deserializer)?;
+        Ok(Call {
+            to: raw.to,
+            selector: raw.selector,
+            calldata: raw.calldata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::jsonrpc::{JsonRpcError, JsonRpcMethod, MockHandler, MockResponse, MockTransport};
+
+    /// A paymaster that records requests and returns canned responses.
+    #[derive(Debug, Default)]
+    struct MockPaymaster {
+        requests: Mutex<Vec<(PaymasterMethod, serde_json::Value)>>,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("unsupported method")]
+    struct MockError;
+
+    fn mock_client() -> (
+        Arc<MockPaymaster>,
+        PaymasterClient<MockTransport<Arc<MockPaymaster>>>,
+    ) {
+        let paymaster = Arc::new(MockPaymaster::default());
+        let client = PaymasterClient::new(MockTransport::new(paymaster.clone()));
+        (paymaster, client)
+    }
+
+    #[async_trait]
+    impl MockHandler for MockPaymaster {
+        type Error = MockError;
+
+        async fn respond(
+            &self,
+            _method: JsonRpcMethod,
+            _params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            Err(MockError)
+        }
+
+        async fn respond_paymaster(
+            &self,
+            method: PaymasterMethod,
+            params: serde_json::Value,
+        ) -> Result<MockResponse, Self::Error> {
+            self.requests.lock().unwrap().push((method, params));
+
+            match method {
+                PaymasterMethod::IsAvailable => Ok(serde_json::json!(true).into()),
+                PaymasterMethod::GetSupportedTokens => Ok(serde_json::json!([{
+                    "token_address": "0x1234",
+                    "decimals": 6,
+                    "price_in_strk": "0x5678"
+                }])
+                .into()),
+                PaymasterMethod::BuildTransaction => Ok(MockResponse::Error {
+                    code: 151,
+                    message: "An error occurred (TOKEN_NOT_SUPPORTED)",
+                }),
+                _ => Err(MockError),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_paymaster_method_ser() {
+        assert_eq!(
+            serde_json::to_value([
+                PaymasterMethod::IsAvailable,
+                PaymasterMethod::GetSupportedTokens,
+                PaymasterMethod::BuildTransaction,
+                PaymasterMethod::ExecuteTransaction,
+            ])
+            .unwrap(),
+            serde_json::json!([
+                "paymaster_isAvailable",
+                "paymaster_getSupportedTokens",
+                "paymaster_buildTransaction",
+                "paymaster_executeTransaction"
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paymaster_queries() {
+        let (paymaster, client) = mock_client();
+
+        assert!(client.is_available().await.unwrap());
+        assert_eq!(
+            client.get_supported_tokens().await.unwrap(),
+            vec![TokenData {
+                token_address: Felt::from_hex_unchecked("0x1234"),
+                decimals: 6,
+                price_in_strk: Felt::from_hex_unchecked("0x5678"),
+            }]
+        );
+
+        let requests = paymaster.requests.lock().unwrap();
+        assert_eq!(requests[0].1, serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_paymaster_build_transaction_request() {
+        let (paymaster, client) = mock_client();
+
+        let err = client
+            .build_transaction(
+                &UserTransaction::Invoke {
+                    invoke: UserInvoke {
+                        user_address: Felt::ONE,
+                        calls: vec![Call {
+                            to: Felt::TWO,
+                            selector: Felt::THREE,
+                            calldata: vec![Felt::from(4)],
+                        }],
+                    },
+                },
+                &ExecutionParameters {
+                    version: ExecutionParametersVersion::V1,
+                    fee_mode: FeeMode::Default {
+                        gas_token: Felt::from(5),
+                    },
+                    time_bounds: None,
+                },
+            )
+            .await
+            .unwrap_err();
+
+        match err {
+            ProviderError::Other(err) => {
+                match err
+                    .as_any()
+                    .downcast_ref::<JsonRpcClientError<MockError>>()
+                    .unwrap()
+                {
+                    JsonRpcClientError::JsonRpcError(JsonRpcError { code, .. }) => {
+                        assert_eq!(*code, 151)
+                    }
+                    _ => panic!("unexpected error type"),
+                }
+            }
+            _ => panic!("unexpected error type"),
+        }
+
+        let requests = paymaster.requests.lock().unwrap();
+        assert_eq!(
+            requests[0].1,
+            serde_json::json!({
+                "transaction": {
+                    "type": "invoke",
+                    "invoke": {
+                        "user_address": "0x1",
+                        "calls": [{ "to": "0x2", "selector": "0x3", "calldata": ["0x4"] }]
+                    }
+                },
+                "parameters": {
+                    "version": "0x1",
+                    "fee_mode": { "mode": "default", "gas_token": "0x5" }
+                }
+            })
+        );
+    }
+
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn test_paymaster_build_transaction_response_deser() {
+        let raw = serde_json::json!({
+            "type": "invoke",
+            "typed_data": {
+                "types": {
+                    "StarknetDomain": [
+                        { "name": "name", "type": "shortstring" },
+                        { "name": "version", "type": "shortstring" },
+                        { "name": "chainId", "type": "shortstring" },
+                        { "name": "revision", "type": "shortstring" }
+                    ],
+                    "Example": [{ "name": "value", "type": "felt" }]
+                },
+                "primaryType": "Example",
+                "domain": {
+                    "name": "Account.execute_from_outside",
+                    "version": "2",
+                    "chainId": "SN_SEPOLIA",
+                    "revision": "1"
+                },
+                "message": { "value": "0x1" }
+            },
+            "parameters": {
+                "version": "0x1",
+                "fee_mode": { "mode": "sponsored" },
+                "time_bounds": { "execute_after": 1, "execute_before": 2 }
+            },
+            "fee": {
+                "gas_token_price_in_strk": "0x1",
+                "estimated_fee_in_strk": "0x2",
+                "estimated_fee_in_gas_token": "0x3",
+                "suggested_max_fee_in_strk": "0x4",
+                "suggested_max_fee_in_gas_token": "0x5"
+            }
+        });
+
+        match serde_json::from_value::<BuildTransactionResponse>(raw).unwrap() {
+            BuildTransactionResponse::Invoke {
+                parameters, fee, ..
+            } => {
+                assert_eq!(parameters.fee_mode, FeeMode::Sponsored);
+                assert_eq!(
+                    parameters.time_bounds,
+                    Some(TimeBounds {
+                        execute_after: 1,
+                        execute_before: 2
+                    })
+                );
+                assert_eq!(fee.suggested_max_fee_in_gas_token, Felt::from(5));
+            }
+            _ => panic!("unexpected response type"),
+        }
+    }
+}
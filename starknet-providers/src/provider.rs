@@ -295,6 +295,52 @@ pub trait Provider {
     where
         R: AsRef<[ProviderRequestData]> + Send + Sync;
 
+    /// Sends multiple requests in parallel, returning the result of each request separately in the
+    /// same order as the requests. The function call only fails for errors affecting the batch as a
+    /// whole, such as network errors.
+    ///
+    /// The default implementation sends the requests with
+    /// [`batch_requests`](Self::batch_requests), and resends them one by one to isolate the
+    /// failing requests if the batch fails with a [`StarknetError`]. Requests with non-idempotent
+    /// methods (see
+    /// [`JsonRpcMethod::is_idempotent`](crate::jsonrpc::JsonRpcMethod::is_idempotent)), such as
+    /// transaction submissions, might have been processed already and are never resent. The batch
+    /// error is reported for them instead.
+    async fn try_batch_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<Result<ProviderResponseData, ProviderError>>, ProviderError>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        match self.batch_requests(&requests).await {
+            Ok(responses) => Ok(responses.into_iter().map(Ok).collect()),
+            Err(ProviderError::StarknetError(batch_err)) => {
+                let mut results = vec![];
+                for request in requests.as_ref() {
+                    if !request.jsonrpc_method().is_idempotent() {
+                        results.push(Err(ProviderError::StarknetError(batch_err.clone())));
+                        continue;
+                    }
+
+                    results.push(match self.batch_requests([request.clone()]).await {
+                        Ok(mut responses) if responses.len() == 1 => {
+                            // Unwrapping here is safe because we already checked length
+                            Ok(responses.pop().unwrap())
+                        }
+                        Ok(_) => Err(ProviderError::ArrayLengthMismatch),
+                        Err(ProviderError::StarknetError(err)) => {
+                            Err(ProviderError::StarknetError(err))
+                        }
+                        Err(err) => return Err(err),
+                    });
+                }
+                Ok(results)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Same as [`estimate_fee`](fn.estimate_fee), but only with one estimate.
     async fn estimate_fee_single<R, S, B>(
         &self,
@@ -129,7 +129,7 @@ impl TryFrom<Block> for core::MaybePreConfirmedBlockWithReceipts {
         for (tx, receipt) in value
             .transactions
             .into_iter()
-            .zip(value.transaction_receipts.into_iter())
+            .zip(value.transaction_receipts)
         {
             let core_tx = tx.clone().try_into()?;
 
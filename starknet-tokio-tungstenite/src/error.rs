@@ -1,4 +1,6 @@
-use starknet_providers::jsonrpc::JsonRpcError;
+use std::sync::Arc;
+
+use starknet_providers::{jsonrpc::JsonRpcError, ProviderError};
 use tungstenite::Error as TungsteniteError;
 
 use crate::StreamUpdateType;
@@ -43,6 +45,8 @@ pub enum SubscriptionReceiveError {
         /// The actual update type received.
         actual: StreamUpdateType,
     },
+    /// Re-subscribing after a reconnection failed. No further updates are delivered.
+    Resubscribe(Arc<SubscribeError>),
 }
 
 /// Error type for unsubscription failures.
@@ -60,6 +64,30 @@ pub enum UnsubscribeError {
     Transport(TungsteniteError),
 }
 
+/// Error type for failures sending requests through
+/// [`JsonRpcTransport`](starknet_providers::jsonrpc::JsonRpcTransport).
+#[derive(Debug)]
+pub enum RequestError {
+    /// Failed to re-establish a lost connection.
+    Connect(ConnectError),
+    /// JSON serialization/deserialization error.
+    Json(serde_json::Error),
+    /// Request timed out.
+    Timeout,
+    /// Error in the underlying WebSocket transport.
+    Transport(TungsteniteError),
+}
+
+/// Error type for failures when following blocks with
+/// [`StreamFollower`](crate::StreamFollower).
+#[derive(Debug)]
+pub enum StreamFollowerError {
+    /// Failed to receive an update from the new heads subscription.
+    Receive(SubscriptionReceiveError),
+    /// Failed to fetch missing blocks or events, or to reconcile a reorg, with the provider.
+    Provider(ProviderError),
+}
+
 /// Error type for WebSocket connection closing failures.
 #[derive(Debug)]
 pub enum CloseError {
@@ -128,6 +156,7 @@ impl std::fmt::Display for SubscriptionReceiveError {
 
                 Ok(())
             }
+            Self::Resubscribe(err) => write!(f, "unable to re-subscribe: {err}"),
         }
     }
 }
@@ -154,6 +183,48 @@ impl std::fmt::Display for UnsubscribeError {
 
 impl std::error::Error for UnsubscribeError {}
 
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Connect(err) => write!(f, "unable to reconnect: {err}"),
+            Self::Json(err) => write!(f, "JSON error: {err}"),
+            Self::Timeout => write!(f, "operation timeout"),
+            Self::Transport(err) => write!(f, "tungstenite transport error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+impl From<ConnectError> for RequestError {
+    fn from(value: ConnectError) -> Self {
+        Self::Connect(value)
+    }
+}
+
+impl std::fmt::Display for StreamFollowerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Receive(err) => write!(f, "subscription receive error: {err}"),
+            Self::Provider(err) => write!(f, "provider error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for StreamFollowerError {}
+
+impl From<SubscriptionReceiveError> for StreamFollowerError {
+    fn from(value: SubscriptionReceiveError) -> Self {
+        Self::Receive(value)
+    }
+}
+
+impl From<ProviderError> for StreamFollowerError {
+    fn from(value: ProviderError) -> Self {
+        Self::Provider(value)
+    }
+}
+
 impl std::fmt::Display for CloseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
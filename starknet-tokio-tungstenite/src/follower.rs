@@ -0,0 +1,67 @@
+use starknet_core::types::ConfirmedBlockId;
+use starknet_providers::{BlockFollower, FollowerUpdate, Provider};
+
+use crate::{
+    NewHeadsSubscription, NewHeadsUpdate, StreamFollowerError, SubscribeError, TungsteniteStream,
+};
+
+/// A [`BlockFollower`] driven by a `starknet_subscribeNewHeads` subscription instead of polling.
+///
+/// Headers pushed by the server are fed into the follower as they arrive, while the provider is
+/// only used for filling gaps, fetching events and reconciling reorgs. The stream can be either
+/// the same [`TungsteniteStream`] used as the provider's transport or a separate one.
+#[derive(Debug)]
+pub struct StreamFollower<P> {
+    follower: BlockFollower<P>,
+    subscription: NewHeadsSubscription,
+}
+
+impl<P> StreamFollower<P>
+where
+    P: Provider,
+{
+    /// Subscribes to new heads on `stream`, starting from where `follower` would resume.
+    pub async fn new(
+        stream: &TungsteniteStream,
+        follower: BlockFollower<P>,
+    ) -> Result<Self, SubscribeError> {
+        let block_id = match follower.resume_block() {
+            Some(block_number) => ConfirmedBlockId::Number(block_number),
+            None => ConfirmedBlockId::Latest,
+        };
+        let subscription = stream.subscribe_new_heads(block_id).await?;
+
+        Ok(Self {
+            follower,
+            subscription,
+        })
+    }
+
+    /// Gets a reference to the underlying [`BlockFollower`].
+    pub const fn follower(&self) -> &BlockFollower<P> {
+        &self.follower
+    }
+
+    /// Waits for the next update.
+    pub async fn next(&mut self) -> Result<FollowerUpdate, StreamFollowerError> {
+        loop {
+            if let Some(update) = self.follower.pop_update() {
+                return Ok(update);
+            }
+
+            match self.subscription.recv().await? {
+                NewHeadsUpdate::NewHeader(header) => self.follower.push_header(header).await?,
+                NewHeadsUpdate::Reorg(reorg) => self.follower.push_reorg(&reorg),
+                // The subscription resumes from the last seen block, with duplicates ignored by
+                // the follower
+                NewHeadsUpdate::Reconnected(_) => {}
+            }
+        }
+    }
+
+    /// Unsubscribes and returns the underlying [`BlockFollower`], which can be used to resume
+    /// following later, either by polling or with a new subscription.
+    pub fn into_follower(self) -> BlockFollower<P> {
+        self.follower
+    }
+}
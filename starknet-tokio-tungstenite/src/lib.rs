@@ -9,5 +9,8 @@ pub use error::*;
 mod stream;
 pub use stream::{StreamUpdateType, TungsteniteStream, TungsteniteStreamBuilder};
 
+mod follower;
+pub use follower::StreamFollower;
+
 mod subscription;
 pub use subscription::*;
@@ -1,4 +1,10 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use futures_util::StreamExt;
 use serde::Deserialize;
@@ -10,24 +16,34 @@ use starknet_providers::{
     jsonrpc::{JsonRpcError, JsonRpcResponse, JsonRpcStreamUpdate},
     StreamUpdateData,
 };
-use tokio::{sync::mpsc::UnboundedSender, time::Instant};
+use tokio::{
+    sync::{mpsc::UnboundedSender, oneshot::Receiver as OneshotReceiver, Notify},
+    time::Instant,
+};
 use tokio_tungstenite::connect_async;
 use tokio_util::sync::CancellationToken;
-use tungstenite::{client::IntoClientRequest, Error as TungsteniteError};
+use tungstenite::{
+    client::IntoClientRequest, handshake::client::Request, Error as TungsteniteError,
+};
 
 mod read;
-use read::{ReadAction, StreamReadDriver};
+use read::{ActiveSubscription, ReadAction, StreamReadDriver};
+
+mod reconnect;
+
+mod transport;
 
 mod write;
+pub(crate) use write::SubscribeWriteData;
 pub(crate) use write::WriteAction;
-use write::{StreamWriteDriver, SubscribeWriteData};
+use write::{RequestMethod, RequestWriteData, StreamWriteDriver};
 
 use crate::{
-    error::{CloseError, ConnectError, SubscribeError},
+    error::{CloseError, ConnectError, RequestError, SubscribeError},
     subscription::{
         EventSubscriptionOptions, EventsSubscription, NewHeadsSubscription,
-        NewTransactionReceiptsSubscription, NewTransactionsSubscription, Subscription,
-        TransactionStatusSubscription,
+        NewTransactionReceiptsSubscription, NewTransactionsSubscription, Reconnection,
+        Subscription, TransactionStatusSubscription,
     },
 };
 
@@ -36,6 +52,8 @@ use crate::{
 pub struct TungsteniteStreamBuilder {
     timeout: Duration,
     keepalive_interval: Duration,
+    reconnect: bool,
+    reconnect_interval: Duration,
 }
 
 /// WebSocket stream client powered by `tokio-tungstenite`.
@@ -45,9 +63,64 @@ pub struct TungsteniteStreamBuilder {
 ///
 /// When this instance _and_ all subscription handles are dropped, the underlying WebSocket stream
 /// is closed automatically. Alternatively, the stream can also be closed by calling `.close()`.
+///
+/// This type also implements [`JsonRpcTransport`](starknet_providers::jsonrpc::JsonRpcTransport),
+/// allowing regular requests to share the same connection as subscriptions when used with
+/// [`JsonRpcClient`](starknet_providers::jsonrpc::JsonRpcClient). Requests are multiplexed by their
+/// IDs. Should the connection be lost, it's re-established automatically on the next request.
+/// It implements [`PaymasterTransport`](starknet_providers::paymaster::PaymasterTransport) as well,
+/// for paymaster services served over WebSocket.
+///
+/// By default, subscriptions made on a lost connection are _not_ restored. With reconnect mode
+/// enabled via [`TungsteniteStreamBuilder::reconnect`], the connection is instead re-established in
+/// the background as soon as it's lost, and all live subscriptions are re-subscribed.
 #[derive(Debug)]
 pub struct TungsteniteStream {
-    write_queue: UnboundedSender<WriteAction>,
+    connection: Arc<SharedConnection>,
+    options: ConnectOptions,
+    reconnect_lock: tokio::sync::Mutex<()>,
+}
+
+/// Everything needed for (re-)establishing a connection.
+#[derive(Debug, Clone)]
+struct ConnectOptions {
+    request: Request,
+    timeout: Duration,
+    keepalive_interval: Duration,
+    /// Interval between background reconnection attempts. `None` if reconnect mode is disabled.
+    reconnect_interval: Option<Duration>,
+}
+
+/// Handle to the current connection, shared by the stream and all its subscriptions such that
+/// they all follow when the connection is replaced.
+#[derive(Debug)]
+pub(crate) struct SharedConnection {
+    write_queue: Mutex<UnboundedSender<WriteAction>>,
+    /// Notified whenever the connection is replaced.
+    reconnected: Notify,
+    /// Whether closing the stream has been requested, in which case no reconnection is attempted.
+    closed: AtomicBool,
+}
+
+/// Subscription state that persists across connections, allowing re-subscribing.
+#[derive(Debug)]
+pub(crate) struct SubscriptionState {
+    /// The subscription ID assigned by the server on the current connection.
+    pub id: SubscriptionId,
+    /// The parameters for subscribing again, updated to resume from the last seen block.
+    pub data: SubscribeWriteData,
+}
+
+pub(crate) type SharedSubscription = Arc<Mutex<SubscriptionState>>;
+
+/// Internal type for items delivered to subscription handles.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub(crate) enum SubscriptionItem {
+    Update(StreamUpdateData),
+    Reconnected(Reconnection),
+    /// Re-subscribing on a new connection failed. Always the last item of the stream.
+    ResubscribeFailed(SubscribeError),
 }
 
 /// All possible update types to be streamed from subscriptions.
@@ -70,7 +143,7 @@ pub enum StreamUpdateType {
 /// Internal type for communicating subscribe action results.
 #[derive(Debug)]
 pub(crate) enum SubscriptionResult {
-    Success { id: SubscriptionId },
+    Success { subscription: SharedSubscription },
     JsonRpcError(JsonRpcError),
     TimeoutError,
     TransportError(TungsteniteError),
@@ -85,6 +158,18 @@ pub(crate) enum UnsubscribeResult {
     TransportError(TungsteniteError),
 }
 
+/// Internal type for communicating raw request action results.
+#[derive(Debug)]
+pub(crate) enum RequestResult {
+    Success {
+        responses: Vec<JsonRpcResponse<serde_json::Value>>,
+    },
+    /// The connection was found closed before the requests were sent, making it safe to retry.
+    ConnectionClosed,
+    TimeoutError,
+    TransportError(TungsteniteError),
+}
+
 /// Internal type for communicating close action results.
 #[derive(Debug)]
 pub(crate) enum CloseResult {
@@ -98,7 +183,8 @@ pub(crate) enum CloseResult {
 #[serde(untagged)]
 enum StreamUpdateOrResponse {
     StreamUpdate(JsonRpcStreamUpdate),
-    Response(JsonRpcResponse<SubscriptionIdOrBool>),
+    Response(JsonRpcResponse<serde_json::Value>),
+    Responses(Vec<JsonRpcResponse<serde_json::Value>>),
 }
 
 #[derive(Debug, Deserialize)]
@@ -114,6 +200,8 @@ impl TungsteniteStreamBuilder {
         Self {
             timeout: Duration::from_secs(10),
             keepalive_interval: Duration::from_secs(10),
+            reconnect: false,
+            reconnect_interval: Duration::from_secs(1),
         }
     }
 
@@ -147,12 +235,56 @@ impl TungsteniteStreamBuilder {
         self
     }
 
+    /// Gets whether reconnect mode is enabled.
+    ///
+    /// In reconnect mode, a lost connection is re-established in the background and live
+    /// subscriptions are re-subscribed, each receiving a `Reconnected` update.
+    pub const fn get_reconnect(&self) -> bool {
+        self.reconnect
+    }
+
+    /// Enables or disables reconnect mode.
+    ///
+    /// In reconnect mode, a lost connection is re-established in the background and live
+    /// subscriptions are re-subscribed, each receiving a `Reconnected` update.
+    pub const fn reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Gets the current reconnect interval value.
+    ///
+    /// In reconnect mode, failed reconnection attempts are retried at the rate defined by this
+    /// interval.
+    pub const fn get_reconnect_interval(&self) -> Duration {
+        self.reconnect_interval
+    }
+
+    /// Sets a new reconnect interval value.
+    ///
+    /// In reconnect mode, failed reconnection attempts are retried at the rate defined by this
+    /// interval.
+    pub const fn reconnect_interval(mut self, reconnect_interval: Duration) -> Self {
+        self.reconnect_interval = reconnect_interval;
+        self
+    }
+
     /// Connects to the server to create a [`TungsteniteStream`].
     pub async fn connect<R>(self, request: R) -> Result<TungsteniteStream, ConnectError>
     where
         R: IntoClientRequest,
     {
-        TungsteniteStream::connect_opts(request, self.timeout, self.keepalive_interval).await
+        TungsteniteStream::connect_opts(
+            request,
+            self.timeout,
+            self.keepalive_interval,
+            if self.reconnect {
+                Some(self.reconnect_interval)
+            } else {
+                None
+            },
+        )
+        .await
     }
 }
 
@@ -172,7 +304,7 @@ impl TungsteniteStream {
     where
         R: IntoClientRequest,
     {
-        Self::connect_opts(request, timeout, Duration::from_secs(10)).await
+        Self::connect_opts(request, timeout, Duration::from_secs(10), None).await
     }
 
     /// Subscribes for new chain heads.
@@ -260,8 +392,10 @@ impl TungsteniteStream {
     pub async fn close(self) -> Result<(), CloseError> {
         let (result_tx, result_rx) = tokio::sync::oneshot::channel::<CloseResult>();
 
+        self.connection.closed.store(true, Ordering::Relaxed);
         match self
-            .write_queue
+            .connection
+            .write_queue()
             .send(WriteAction::Close { result: result_tx })
         {
             Ok(_) => {
@@ -287,11 +421,63 @@ impl TungsteniteStream {
         request: R,
         timeout: Duration,
         keepalive_interval: Duration,
+        reconnect_interval: Option<Duration>,
     ) -> Result<Self, ConnectError>
     where
         R: IntoClientRequest,
     {
-        let connect = connect_async(request.into_client_request()?);
+        let options = ConnectOptions {
+            request: request.into_client_request()?,
+            timeout,
+            keepalive_interval,
+            reconnect_interval,
+        };
+        let (write_queue, handover) = Self::establish(&options).await?;
+
+        let connection = Arc::new(SharedConnection {
+            write_queue: Mutex::new(write_queue),
+            reconnected: Notify::new(),
+            closed: AtomicBool::new(false),
+        });
+
+        if let Some(handover) = handover {
+            reconnect::ReconnectDriver {
+                options: options.clone(),
+                connection: Arc::downgrade(&connection),
+                handover: Some(handover),
+            }
+            .drive();
+        }
+
+        Ok(Self {
+            connection,
+            options,
+            reconnect_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// Connects to the server and spawns the stream drivers, returning the handle to the write
+    /// queue.
+    ///
+    /// In reconnect mode, a receiver is also returned for taking over live subscriptions once the
+    /// connection is lost.
+    async fn establish(
+        options: &ConnectOptions,
+    ) -> Result<
+        (
+            UnboundedSender<WriteAction>,
+            Option<OneshotReceiver<Vec<ActiveSubscription>>>,
+        ),
+        ConnectError,
+    > {
+        let ConnectOptions {
+            request,
+            timeout,
+            keepalive_interval,
+            reconnect_interval,
+        } = options.clone();
+
+        let connect = connect_async(request);
         let (stream, _) = tokio::select! {
             result = connect => result?,
             _ = tokio::time::sleep(timeout) => {
@@ -308,6 +494,13 @@ impl TungsteniteStream {
         let (write, read) = stream.split();
         let disconnection_token = CancellationToken::new();
 
+        let (handover_tx, handover_rx) = if reconnect_interval.is_some() {
+            let (handover_tx, handover_rx) = tokio::sync::oneshot::channel();
+            (Some(handover_tx), Some(handover_rx))
+        } else {
+            (None, None)
+        };
+
         StreamWriteDriver {
             timeout,
             keepalive_interval,
@@ -323,44 +516,137 @@ impl TungsteniteStream {
             registry: Default::default(),
             pending_subscriptions: Default::default(),
             pending_unsubscriptions: Default::default(),
+            pending_request_ids: Default::default(),
+            pending_requests: Default::default(),
             stream: read,
             read_queue: registration_rx,
             disconnection: disconnection_token,
+            handover: handover_tx,
         }
         .drive();
 
-        Ok(Self {
-            write_queue: write_queue_tx,
-        })
+        Ok((write_queue_tx, handover_rx))
+    }
+
+    /// Replaces a dead connection with a new one, unless that has already been done by another
+    /// caller since `stale_queue` was obtained.
+    ///
+    /// In reconnect mode, this only waits for the background reconnection to happen instead.
+    async fn reconnect(
+        &self,
+        stale_queue: &UnboundedSender<WriteAction>,
+    ) -> Result<(), ConnectError> {
+        if self.options.reconnect_interval.is_some() {
+            let reconnected = self.connection.reconnected.notified();
+            if !self.connection.write_queue().same_channel(stale_queue) {
+                return Ok(());
+            }
+
+            return tokio::time::timeout(self.options.timeout, reconnected)
+                .await
+                .map_err(|_| ConnectError::Timeout);
+        }
+
+        let _guard = self.reconnect_lock.lock().await;
+        if !self.connection.write_queue().same_channel(stale_queue) {
+            return Ok(());
+        }
+
+        log::debug!("Reconnecting to WebSocket server");
+        let (write_queue, _) = Self::establish(&self.options).await?;
+        self.connection.replace(write_queue);
+
+        Ok(())
+    }
+
+    /// Sends raw requests over the connection, reconnecting first if it's found to be closed.
+    async fn request(
+        &self,
+        data: RequestWriteData,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, RequestError> {
+        let mut reconnected = false;
+
+        loop {
+            let write_queue = self.connection.write_queue();
+            let (result_tx, mut result_rx) =
+                tokio::sync::mpsc::unbounded_channel::<RequestResult>();
+
+            let result = if write_queue
+                .send(WriteAction::Request {
+                    data: data.clone(),
+                    result: result_tx,
+                })
+                .is_ok()
+            {
+                result_rx.recv().await
+            } else {
+                Some(RequestResult::ConnectionClosed)
+            };
+
+            match result {
+                Some(RequestResult::Success { responses }) => return Ok(responses),
+                Some(RequestResult::ConnectionClosed) if !reconnected => {
+                    self.reconnect(&write_queue).await?;
+                    reconnected = true;
+                }
+                Some(RequestResult::ConnectionClosed) | None => {
+                    // Either the new connection is gone too, or the connection was lost while
+                    // waiting for responses, which is not retried as the server might have
+                    // already processed the requests.
+                    return Err(RequestError::Transport(TungsteniteError::ConnectionClosed));
+                }
+                Some(RequestResult::TimeoutError) => return Err(RequestError::Timeout),
+                Some(RequestResult::TransportError(err)) => {
+                    return Err(RequestError::Transport(err))
+                }
+            }
+        }
     }
 
     async fn subscribe(&self, data: SubscribeWriteData) -> Result<Subscription, SubscribeError> {
         let (result_tx, mut result_rx) =
             tokio::sync::mpsc::unbounded_channel::<SubscriptionResult>();
-        let (stream_tx, stream_rx) = tokio::sync::mpsc::unbounded_channel::<StreamUpdateData>();
+        let (stream_tx, stream_rx) = tokio::sync::mpsc::unbounded_channel::<SubscriptionItem>();
 
         if self
-            .write_queue
+            .connection
+            .write_queue()
             .send(WriteAction::Subscribe {
                 data,
                 result: result_tx,
                 stream: stream_tx,
+                state: None,
             })
             .is_err()
         {
             return Err(SubscribeError::Transport(tungstenite::Error::AlreadyClosed));
         }
 
-        // Unwrapping is safe as results are always sent.
-        let sub_result = result_rx.recv().await.unwrap();
-        match sub_result {
-            SubscriptionResult::Success { id } => Ok(Subscription {
-                subscription_id: id,
-                stream: stream_rx,
-                write_queue: self.write_queue.clone(),
-                unsubscribed: false,
-            }),
-            SubscriptionResult::JsonRpcError(err) => match StarknetError::try_from(&err) {
+        // The result channel only ends without a result when the connection is lost after the
+        // request has been sent.
+        let sub_result = result_rx
+            .recv()
+            .await
+            .unwrap_or(SubscriptionResult::TransportError(
+                TungsteniteError::ConnectionClosed,
+            ));
+        let subscription = sub_result.into_result()?;
+        Ok(Subscription {
+            subscription,
+            stream: stream_rx,
+            connection: self.connection.clone(),
+            unsubscribed: false,
+        })
+    }
+}
+
+impl SubscriptionResult {
+    /// Converts the result into the subscription on success, or the matching [`SubscribeError`].
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn into_result(self) -> Result<SharedSubscription, SubscribeError> {
+        match self {
+            Self::Success { subscription } => Ok(subscription),
+            Self::JsonRpcError(err) => match StarknetError::try_from(&err) {
                 Ok(StarknetError::TooManyBlocksBack) => Err(SubscribeError::TooManyBlocksBack),
                 Ok(StarknetError::BlockNotFound) => Err(SubscribeError::BlockNotFound),
                 Ok(StarknetError::TooManyAddressesInFilter) => {
@@ -369,12 +655,24 @@ impl TungsteniteStream {
                 Ok(StarknetError::TooManyKeysInFilter) => Err(SubscribeError::TooManyKeysInFilter),
                 _ => Err(SubscribeError::UnexpectedError(err)),
             },
-            SubscriptionResult::TimeoutError => Err(SubscribeError::Timeout),
-            SubscriptionResult::TransportError(err) => Err(SubscribeError::Transport(err)),
+            Self::TimeoutError => Err(SubscribeError::Timeout),
+            Self::TransportError(err) => Err(SubscribeError::Transport(err)),
         }
     }
 }
 
+impl SharedConnection {
+    /// Gets the write queue of the current connection.
+    pub fn write_queue(&self) -> UnboundedSender<WriteAction> {
+        self.write_queue.lock().unwrap().clone()
+    }
+
+    fn replace(&self, write_queue: UnboundedSender<WriteAction>) {
+        *self.write_queue.lock().unwrap() = write_queue;
+        self.reconnected.notify_waiters();
+    }
+}
+
 impl std::fmt::Display for StreamUpdateType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
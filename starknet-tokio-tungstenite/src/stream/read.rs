@@ -1,8 +1,12 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use futures_util::{stream::SplitStream, StreamExt};
+use serde::Deserialize;
 use starknet_core::types::SubscriptionId;
-use starknet_providers::{jsonrpc::JsonRpcResponse, StreamUpdateData};
+use starknet_providers::jsonrpc::JsonRpcResponse;
 use tokio::{
     net::TcpStream,
     sync::{
@@ -14,24 +18,39 @@ use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use tokio_util::sync::CancellationToken;
 use tungstenite::Message;
 
-use super::{StreamUpdateOrResponse, SubscriptionIdOrBool, SubscriptionResult, UnsubscribeResult};
+use crate::subscription::Reconnection;
+
+use super::{
+    RequestResult, SharedSubscription, StreamUpdateOrResponse, SubscribeWriteData,
+    SubscriptionIdOrBool, SubscriptionItem, SubscriptionResult, SubscriptionState,
+    UnsubscribeResult,
+};
 
 /// An internal type for running the read direction of the WebSocket stream in the background.
 pub(crate) struct StreamReadDriver {
-    pub registry: HashMap<SubscriptionId, UnboundedSender<StreamUpdateData>>,
+    pub registry: HashMap<SubscriptionId, ActiveSubscription>,
     pub pending_subscriptions: HashMap<u64, PendingSubscription>,
     pub pending_unsubscriptions: HashMap<u64, PendingUnsubscription>,
+    /// Maps the ID of each inflight request to the ID of the first request in the same message and
+    /// its position in there.
+    pub pending_request_ids: HashMap<u64, (u64, usize)>,
+    pub pending_requests: HashMap<u64, PendingRequest>,
     pub stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
     pub read_queue: UnboundedReceiver<ReadAction>,
     pub disconnection: CancellationToken,
+    /// Only present in reconnect mode for handing over live subscriptions once the connection is
+    /// lost.
+    pub handover: Option<OneshotSender<Vec<ActiveSubscription>>>,
 }
 
 #[derive(Debug)]
 pub(crate) enum ReadAction {
     Subscribe {
         request_id: u64,
+        data: SubscribeWriteData,
         result: UnboundedSender<SubscriptionResult>,
-        stream: UnboundedSender<StreamUpdateData>,
+        stream: UnboundedSender<SubscriptionItem>,
+        state: Option<SharedSubscription>,
         ack: OneshotSender<ReadAcknowledgement>,
     },
     Unsubscribe {
@@ -40,6 +59,11 @@ pub(crate) enum ReadAction {
         result: Option<UnboundedSender<UnsubscribeResult>>,
         ack: OneshotSender<ReadAcknowledgement>,
     },
+    Request {
+        request_ids: Vec<u64>,
+        result: UnboundedSender<RequestResult>,
+        ack: OneshotSender<ReadAcknowledgement>,
+    },
 }
 
 #[derive(Debug)]
@@ -49,8 +73,17 @@ pub(crate) enum ReadAcknowledgement {
 }
 
 pub(crate) struct PendingSubscription {
+    data: SubscribeWriteData,
     result: UnboundedSender<SubscriptionResult>,
-    stream: UnboundedSender<StreamUpdateData>,
+    stream: UnboundedSender<SubscriptionItem>,
+    state: Option<SharedSubscription>,
+}
+
+/// A subscription confirmed by the server.
+#[derive(Debug)]
+pub(crate) struct ActiveSubscription {
+    pub stream: UnboundedSender<SubscriptionItem>,
+    pub state: SharedSubscription,
 }
 
 pub(crate) struct PendingUnsubscription {
@@ -58,6 +91,12 @@ pub(crate) struct PendingUnsubscription {
     result: Option<UnboundedSender<UnsubscribeResult>>,
 }
 
+pub(crate) struct PendingRequest {
+    responses: Vec<Option<JsonRpcResponse<serde_json::Value>>>,
+    remaining: usize,
+    result: UnboundedSender<RequestResult>,
+}
+
 enum HandleActionResult {
     /// An action was received and processed successfully.
     Success,
@@ -82,6 +121,8 @@ impl StreamReadDriver {
     }
 
     async fn run(mut self) {
+        let mut connection_lost = false;
+
         loop {
             tokio::select! {
                 action = self.read_queue.recv() => {
@@ -96,6 +137,7 @@ impl StreamReadDriver {
                     match self.handle_message(message) {
                         HandleMessageResult::Success | HandleMessageResult::MalformedMessage => {}
                         HandleMessageResult::StreamEnded | HandleMessageResult::StreamAborted => {
+                            connection_lost = true;
                             break
                         }
                     }
@@ -107,12 +149,39 @@ impl StreamReadDriver {
         self.read_queue.close();
         while let Some(action) = self.read_queue.recv().await {
             match action {
-                ReadAction::Subscribe { ack, .. } | ReadAction::Unsubscribe { ack, .. } => {
+                ReadAction::Subscribe { ack, .. }
+                | ReadAction::Unsubscribe { ack, .. }
+                | ReadAction::Request { ack, .. } => {
                     let _ = ack.send(ReadAcknowledgement::StreamClosed);
                 }
             }
         }
 
+        if connection_lost {
+            if let Some(handover) = self.handover.take() {
+                // Subscriptions still waiting for confirmation are only handed over when they're
+                // being re-subscribed. New subscriptions simply fail.
+                let resubscriptions =
+                    self.pending_subscriptions
+                        .drain()
+                        .filter_map(|(_, pending)| {
+                            pending.state.map(|state| ActiveSubscription {
+                                stream: pending.stream,
+                                state,
+                            })
+                        });
+                let subscriptions = self
+                    .registry
+                    .drain()
+                    .map(|(_, subscription)| subscription)
+                    .chain(resubscriptions)
+                    .filter(|subscription| !subscription.stream.is_closed())
+                    .collect();
+
+                let _ = handover.send(subscriptions);
+            }
+        }
+
         self.disconnection.cancel();
     }
 
@@ -120,12 +189,21 @@ impl StreamReadDriver {
         match action {
             Some(ReadAction::Subscribe {
                 request_id,
+                data,
                 result,
                 stream,
+                state,
                 ack,
             }) => {
-                self.pending_subscriptions
-                    .insert(request_id, PendingSubscription { result, stream });
+                self.pending_subscriptions.insert(
+                    request_id,
+                    PendingSubscription {
+                        data,
+                        result,
+                        stream,
+                        state,
+                    },
+                );
 
                 let _ = ack.send(ReadAcknowledgement::Acknowledged);
 
@@ -149,6 +227,30 @@ impl StreamReadDriver {
 
                 HandleActionResult::Success
             }
+            Some(ReadAction::Request {
+                request_ids,
+                result,
+                ack,
+            }) => {
+                if let Some(first_id) = request_ids.first().copied() {
+                    for (index, request_id) in request_ids.iter().enumerate() {
+                        self.pending_request_ids
+                            .insert(*request_id, (first_id, index));
+                    }
+                    self.pending_requests.insert(
+                        first_id,
+                        PendingRequest {
+                            responses: vec![None; request_ids.len()],
+                            remaining: request_ids.len(),
+                            result,
+                        },
+                    );
+                }
+
+                let _ = ack.send(ReadAcknowledgement::Acknowledged);
+
+                HandleActionResult::Success
+            }
             None => HandleActionResult::QueueEnded,
         }
     }
@@ -174,8 +276,19 @@ impl StreamReadDriver {
                 match parsed_message {
                     StreamUpdateOrResponse::StreamUpdate(stream_update) => {
                         match self.registry.get_mut(stream_update.data.subscription_id()) {
-                            Some(sub_stream) => {
-                                if sub_stream.send(stream_update.data).is_err() {
+                            Some(subscription) => {
+                                subscription
+                                    .state
+                                    .lock()
+                                    .unwrap()
+                                    .data
+                                    .advance(&stream_update.data);
+
+                                if subscription
+                                    .stream
+                                    .send(SubscriptionItem::Update(stream_update.data))
+                                    .is_err()
+                                {
                                     // Subscriptions getting dropped should automatically trigger
                                     // unsubscribing. However, there could be a race condition where
                                     // an update arrives before that. This is normal but probably
@@ -190,76 +303,10 @@ impl StreamReadDriver {
                             }
                         }
                     }
-                    StreamUpdateOrResponse::Response(JsonRpcResponse::Success { id, result }) => {
-                        match result {
-                            SubscriptionIdOrBool::SubscriptionId(subscription_id) => {
-                                // Response for subscribe requests
-                                match self.pending_subscriptions.remove(&id) {
-                                    Some(pending) => {
-                                        match pending.result.send(SubscriptionResult::Success {
-                                            id: subscription_id.clone(),
-                                        }) {
-                                            Ok(_) => {
-                                                self.registry
-                                                    .insert(subscription_id, pending.stream);
-                                            }
-                                            Err(_) => {
-                                                // This failing here means the caller gave up on
-                                                // waiting. We now have a dangling subscription.
-                                                //
-                                                // Ideally, here we request to unsubscribe to avoid
-                                                // useless incoming messages.
-                                                //
-                                                // TODO: cancel subscription here
-                                            }
-                                        }
-                                    }
-                                    None => {
-                                        // Unsolicited subscription result. This is probably not
-                                        // worth panicking over.
-                                        log::warn!("WARNING: unsolicited subscription result");
-                                    }
-                                }
-                            }
-                            SubscriptionIdOrBool::Bool(success) => {
-                                // Response for unsubscribe requests
-                                match self.pending_unsubscriptions.remove(&id) {
-                                    Some(pending) => {
-                                        // Remove the subscription from internal registry on a best-
-                                        // effort basis. It's fine if it doesn't exist.
-                                        self.registry.remove(&pending.subscription_id);
-
-                                        if let Some(callback) = pending.result {
-                                            // Callback is also best-effort.
-                                            let _ = callback
-                                                .send(UnsubscribeResult::Success { success });
-                                        }
-                                    }
-                                    None => {
-                                        // Unsolicited unsubscribe result. This is probably not
-                                        // worth panicking over.
-                                        log::warn!("WARNING: unsolicited unsubscribe result");
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    StreamUpdateOrResponse::Response(JsonRpcResponse::Error { id, error }) => {
-                        if let Some(pending_sub) = self.pending_subscriptions.remove(&id) {
-                            // This failing means the caller gave up on waiting. Ignoring failure is
-                            // fine as the subscription didn't succeed anyway.
-                            let _ = pending_sub
-                                .result
-                                .send(SubscriptionResult::JsonRpcError(error));
-                        } else if let Some(pending_unsub) = self.pending_unsubscriptions.remove(&id)
-                        {
-                            // This failing means the caller gave up on waiting. Ignoring failure is
-                            // fine as this usually indicates that the subscription doesn't exist.
-                            if let Some(result) = pending_unsub.result {
-                                let _ = result.send(UnsubscribeResult::JsonRpcError(error));
-                            }
-                        } else {
-                            log::warn!("WARNING: unsolicited error");
+                    StreamUpdateOrResponse::Response(response) => self.handle_response(response),
+                    StreamUpdateOrResponse::Responses(responses) => {
+                        for response in responses {
+                            self.handle_response(response);
                         }
                     }
                 }
@@ -286,4 +333,129 @@ impl StreamReadDriver {
             }
         }
     }
+
+    fn handle_response(&mut self, response: JsonRpcResponse<serde_json::Value>) {
+        let id = match &response {
+            JsonRpcResponse::Success { id, .. } | JsonRpcResponse::Error { id, .. } => *id,
+        };
+
+        if let Some((first_id, index)) = self.pending_request_ids.remove(&id) {
+            // Response for raw requests
+            if let Some(pending) = self.pending_requests.get_mut(&first_id) {
+                pending.responses[index] = Some(response);
+                pending.remaining -= 1;
+
+                if pending.remaining == 0 {
+                    if let Some(pending) = self.pending_requests.remove(&first_id) {
+                        // This failing means the caller gave up on waiting, which is fine.
+                        let _ = pending.result.send(RequestResult::Success {
+                            responses: pending.responses.into_iter().flatten().collect(),
+                        });
+                    }
+                }
+            }
+            return;
+        }
+
+        match response {
+            JsonRpcResponse::Success { id, result } => {
+                match SubscriptionIdOrBool::deserialize(result) {
+                    Ok(SubscriptionIdOrBool::SubscriptionId(subscription_id)) => {
+                        // Response for subscribe requests
+                        match self.pending_subscriptions.remove(&id) {
+                            Some(pending) => {
+                                let state = match pending.state {
+                                    Some(state) => {
+                                        // Re-subscribed on a new connection
+                                        let resumed_from = {
+                                            let mut state = state.lock().unwrap();
+                                            state.id = subscription_id.clone();
+                                            state.data.resume_point()
+                                        };
+                                        let _ = pending.stream.send(SubscriptionItem::Reconnected(
+                                            Reconnection { resumed_from },
+                                        ));
+                                        state
+                                    }
+                                    None => Arc::new(Mutex::new(SubscriptionState {
+                                        id: subscription_id.clone(),
+                                        data: pending.data,
+                                    })),
+                                };
+
+                                match pending.result.send(SubscriptionResult::Success {
+                                    subscription: state.clone(),
+                                }) {
+                                    Ok(_) => {
+                                        self.registry.insert(
+                                            subscription_id,
+                                            ActiveSubscription {
+                                                stream: pending.stream,
+                                                state,
+                                            },
+                                        );
+                                    }
+                                    Err(_) => {
+                                        // This failing here means the caller gave up on
+                                        // waiting. We now have a dangling subscription.
+                                        //
+                                        // Ideally, here we request to unsubscribe to avoid
+                                        // useless incoming messages.
+                                        //
+                                        // TODO: cancel subscription here
+                                    }
+                                }
+                            }
+                            None => {
+                                // Unsolicited subscription result. This is probably not
+                                // worth panicking over.
+                                log::warn!("WARNING: unsolicited subscription result");
+                            }
+                        }
+                    }
+                    Ok(SubscriptionIdOrBool::Bool(success)) => {
+                        // Response for unsubscribe requests
+                        match self.pending_unsubscriptions.remove(&id) {
+                            Some(pending) => {
+                                // Remove the subscription from internal registry on a best-
+                                // effort basis. It's fine if it doesn't exist.
+                                self.registry.remove(&pending.subscription_id);
+
+                                if let Some(callback) = pending.result {
+                                    // Callback is also best-effort.
+                                    let _ = callback.send(UnsubscribeResult::Success { success });
+                                }
+                            }
+                            None => {
+                                // Unsolicited unsubscribe result. This is probably not
+                                // worth panicking over.
+                                log::warn!("WARNING: unsolicited unsubscribe result");
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        // Unsolicited request result. This is probably not worth panicking over.
+                        log::warn!("WARNING: unsolicited response");
+                    }
+                }
+            }
+            JsonRpcResponse::Error { id, error } => {
+                if let Some(pending_sub) = self.pending_subscriptions.remove(&id) {
+                    // This failing means the caller gave up on waiting. Ignoring failure is
+                    // fine as the subscription didn't succeed anyway.
+                    let _ = pending_sub
+                        .result
+                        .send(SubscriptionResult::JsonRpcError(error));
+                } else if let Some(pending_unsub) = self.pending_unsubscriptions.remove(&id) {
+                    // This failing means the caller gave up on waiting. Ignoring failure is
+                    // fine as this usually indicates that the subscription doesn't exist.
+                    if let Some(result) = pending_unsub.result {
+                        let _ = result.send(UnsubscribeResult::JsonRpcError(error));
+                    }
+                } else {
+                    log::warn!("WARNING: unsolicited error");
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,109 @@
+use std::sync::{atomic::Ordering, Weak};
+
+use tokio::sync::{mpsc::error::SendError, oneshot::Receiver as OneshotReceiver};
+
+use super::{
+    read::ActiveSubscription, ConnectOptions, SharedConnection, SubscriptionItem,
+    SubscriptionResult, TungsteniteStream, WriteAction,
+};
+
+/// An internal type for re-establishing lost connections in the background in reconnect mode.
+pub(crate) struct ReconnectDriver {
+    pub options: ConnectOptions,
+    pub connection: Weak<SharedConnection>,
+    pub handover: Option<OneshotReceiver<Vec<ActiveSubscription>>>,
+}
+
+impl ReconnectDriver {
+    pub fn drive(self) {
+        tokio::spawn(self.run());
+    }
+
+    async fn run(mut self) {
+        let reconnect_interval = self.options.reconnect_interval.unwrap_or_default();
+        let mut subscriptions = vec![];
+
+        loop {
+            // The handover is dropped without sending when the connection ends because all handles
+            // have been dropped, in which case there's nothing left to do.
+            let Some(handover) = self.handover.take() else {
+                return;
+            };
+            let Ok(handed_over) = handover.await else {
+                return;
+            };
+            subscriptions.extend(handed_over);
+
+            let write_queue = loop {
+                match self.connection.upgrade() {
+                    Some(connection) if !connection.closed.load(Ordering::Relaxed) => {}
+                    _ => return,
+                }
+
+                log::debug!("Reconnecting to WebSocket server");
+                match TungsteniteStream::establish(&self.options).await {
+                    Ok((write_queue, handover)) => {
+                        self.handover = handover;
+                        break write_queue;
+                    }
+                    Err(err) => {
+                        log::warn!("WARNING: unable to reconnect: {err}");
+                        tokio::time::sleep(reconnect_interval).await;
+                    }
+                }
+            };
+
+            let Some(connection) = self.connection.upgrade() else {
+                return;
+            };
+            connection.replace(write_queue.clone());
+            drop(connection);
+
+            let mut results = vec![];
+            for subscription in std::mem::take(&mut subscriptions) {
+                if subscription.stream.is_closed() {
+                    // Subscription handle dropped while disconnected
+                    continue;
+                }
+
+                let data = subscription.state.lock().unwrap().data.clone();
+                let (result_tx, result_rx) =
+                    tokio::sync::mpsc::unbounded_channel::<SubscriptionResult>();
+
+                let stream = subscription.stream.clone();
+
+                match write_queue.send(WriteAction::Subscribe {
+                    data,
+                    result: result_tx,
+                    stream: subscription.stream,
+                    state: Some(subscription.state),
+                }) {
+                    Ok(_) => results.push((stream, result_rx)),
+                    Err(SendError(WriteAction::Subscribe {
+                        stream,
+                        state: Some(state),
+                        ..
+                    })) => {
+                        // The new connection is already lost. Tries again on the next one.
+                        subscriptions.push(ActiveSubscription { stream, state });
+                    }
+                    Err(_) => {}
+                }
+            }
+
+            // Not holding on to the connection so that it can still be closed by dropping handles
+            drop(write_queue);
+
+            for (stream, mut result) in results {
+                // A result channel ending without a result means the connection got lost again,
+                // in which case the subscription is handed over for the next attempt.
+                if let Some(result) = result.recv().await {
+                    if let Err(err) = result.into_result() {
+                        log::warn!("WARNING: unable to re-subscribe: {err}");
+                        let _ = stream.send(SubscriptionItem::ResubscribeFailed(err));
+                    }
+                }
+            }
+        }
+    }
+}
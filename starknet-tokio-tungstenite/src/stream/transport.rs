@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use starknet_providers::{
+    jsonrpc::{JsonRpcMethod, JsonRpcResponse, JsonRpcTransport},
+    paymaster::{PaymasterMethod, PaymasterTransport},
+    ProviderRequestData,
+};
+
+use super::{RequestMethod, RequestWriteData, TungsteniteStream};
+use crate::error::RequestError;
+
+impl TungsteniteStream {
+    async fn send_single_request<P, R>(
+        &self,
+        method: RequestMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, RequestError>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let params = serde_json::to_value(params).map_err(RequestError::Json)?;
+
+        let response = self
+            .request(RequestWriteData {
+                requests: vec![(method, params)],
+                batch: false,
+            })
+            .await?
+            .into_iter()
+            .next()
+            // Unreachable as results are only sent when all responses are received
+            .ok_or(RequestError::Transport(
+                tungstenite::Error::ConnectionClosed,
+            ))?;
+
+        Ok(match response {
+            JsonRpcResponse::Success { id, result } => JsonRpcResponse::Success {
+                id,
+                result: R::deserialize(result).map_err(RequestError::Json)?,
+            },
+            JsonRpcResponse::Error { id, error } => JsonRpcResponse::Error { id, error },
+        })
+    }
+}
+
+#[async_trait]
+impl JsonRpcTransport for TungsteniteStream {
+    type Error = RequestError;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.send_single_request(RequestMethod::Starknet(method), params)
+            .await
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        if requests.as_ref().is_empty() {
+            // Empty batches are invalid in JSON-RPC
+            return Ok(vec![]);
+        }
+
+        let requests = requests
+            .as_ref()
+            .iter()
+            .map(|request| {
+                serde_json::to_value(request)
+                    .map(|params| (RequestMethod::Starknet(request.jsonrpc_method()), params))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RequestError::Json)?;
+
+        let responses = self
+            .request(RequestWriteData {
+                requests,
+                batch: true,
+            })
+            .await?;
+
+        // Responses are already in request order. IDs are replaced with the index in the batch to
+        // be consistent with other transports.
+        Ok(responses
+            .into_iter()
+            .enumerate()
+            .map(|(ind, response)| match response {
+                JsonRpcResponse::Success { result, .. } => JsonRpcResponse::Success {
+                    id: ind as u64,
+                    result,
+                },
+                JsonRpcResponse::Error { error, .. } => JsonRpcResponse::Error {
+                    id: ind as u64,
+                    error,
+                },
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl PaymasterTransport for TungsteniteStream {
+    type Error = RequestError;
+
+    async fn send_paymaster_request<P, R>(
+        &self,
+        method: PaymasterMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.send_single_request(RequestMethod::Paymaster(method), params)
+            .await
+    }
+}
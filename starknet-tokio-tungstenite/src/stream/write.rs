@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use futures_util::{stream::SplitSink, SinkExt};
 use rand::{thread_rng, RngCore};
+use serde::Serialize;
 use starknet_core::types::{
     requests::{
         SubscribeEventsRequest, SubscribeNewHeadsRequest, SubscribeNewTransactionReceiptsRequest,
@@ -9,7 +10,11 @@ use starknet_core::types::{
     },
     ConfirmedBlockId, Felt, L2TransactionFinalityStatus, L2TransactionStatus, SubscriptionId,
 };
-use starknet_providers::{jsonrpc::JsonRpcRequest, ProviderRequestData, StreamUpdateData};
+use starknet_providers::{
+    jsonrpc::{JsonRpcMethod, JsonRpcRequest},
+    paymaster::PaymasterMethod,
+    ProviderRequestData, StreamUpdateData,
+};
 use tokio::{
     net::TcpStream,
     sync::{
@@ -26,7 +31,8 @@ use crate::subscription::EventSubscriptionOptions;
 
 use super::{
     read::{ReadAcknowledgement, ReadAction},
-    CloseResult, SubscriptionResult, UnsubscribeResult,
+    CloseResult, RequestResult, SharedSubscription, SubscriptionItem, SubscriptionResult,
+    UnsubscribeResult,
 };
 
 /// An internal type for running the write direction of the WebSocket stream in the background.
@@ -45,18 +51,39 @@ pub(crate) enum WriteAction {
     Subscribe {
         data: SubscribeWriteData,
         result: UnboundedSender<SubscriptionResult>,
-        stream: UnboundedSender<StreamUpdateData>,
+        stream: UnboundedSender<SubscriptionItem>,
+        /// Present when re-subscribing an existing subscription on a new connection.
+        state: Option<SharedSubscription>,
     },
     Unsubscribe {
         subscription_id: SubscriptionId,
         result: Option<UnboundedSender<UnsubscribeResult>>,
     },
+    Request {
+        data: RequestWriteData,
+        result: UnboundedSender<RequestResult>,
+    },
     Close {
         result: OneshotSender<CloseResult>,
     },
 }
 
-#[derive(Debug)]
+/// Raw requests to be sent as is, either as a single message or as a batch.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestWriteData {
+    pub requests: Vec<(RequestMethod, serde_json::Value)>,
+    pub batch: bool,
+}
+
+/// Method of a raw request, which is either a Starknet or a paymaster JSON-RPC method.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(untagged)]
+pub(crate) enum RequestMethod {
+    Starknet(JsonRpcMethod),
+    Paymaster(PaymasterMethod),
+}
+
+#[derive(Debug, Clone)]
 pub(crate) enum SubscribeWriteData {
     NewHeads {
         block_id: ConfirmedBlockId,
@@ -77,6 +104,14 @@ pub(crate) enum SubscribeWriteData {
     },
 }
 
+#[derive(Serialize)]
+struct RawRequest<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: RequestMethod,
+    params: &'a serde_json::Value,
+}
+
 enum HandleActionResult {
     /// An action was received and processed successfully.
     Success,
@@ -97,16 +132,24 @@ impl StreamWriteDriver {
 
     async fn run(mut self) {
         let mut close_sent = false;
+        let mut disconnected = false;
 
         loop {
             tokio::select! {
+                _ = self.disconnection.cancelled(), if !disconnected => {
+                    // The read half is gone. Stops accepting new actions so that handles can tell
+                    // that the connection is dead. Queued actions are still drained below.
+                    disconnected = true;
+                    self.write_queue.close();
+                }
                 write_action = self.write_queue.recv() => {
                     if matches!(
                         self.handle_write_action(write_action, &mut close_sent).await,
                         HandleActionResult::QueueEnded
                     ) {
                         // This path is entered only when the stream and all subscriptions have been
-                        // dropped, so it's safe to just drop the writer. No messages are lost.
+                        // dropped, or when the connection is already gone, so it's safe to just drop
+                        // the writer. No messages are lost.
                         break;
                     }
                 }
@@ -131,6 +174,7 @@ impl StreamWriteDriver {
                 data,
                 result,
                 stream,
+                state,
             }) => {
                 let (ack_tx, ack_rx) = tokio::sync::oneshot::channel::<ReadAcknowledgement>();
 
@@ -139,8 +183,10 @@ impl StreamWriteDriver {
                     .read_queue
                     .send(ReadAction::Subscribe {
                         request_id: req_id,
+                        data: data.clone(),
                         result: result.clone(),
                         stream,
+                        state,
                         ack: ack_tx,
                     })
                     .is_err()
@@ -272,6 +318,67 @@ impl StreamWriteDriver {
 
                 HandleActionResult::Success
             }
+            Some(WriteAction::Request { data, result }) => {
+                let (ack_tx, ack_rx) = tokio::sync::oneshot::channel::<ReadAcknowledgement>();
+
+                let req_ids = data
+                    .requests
+                    .iter()
+                    .map(|_| thread_rng().next_u32() as u64)
+                    .collect::<Vec<_>>();
+                if self
+                    .read_queue
+                    .send(ReadAction::Request {
+                        request_ids: req_ids.clone(),
+                        result: result.clone(),
+                        ack: ack_tx,
+                    })
+                    .is_err()
+                {
+                    // This failing means the read handler is dropped. There's no point in
+                    // retrying anymore.
+                    self.write_queue.close();
+                    let _ = result.send(RequestResult::ConnectionClosed);
+                    return HandleActionResult::Success;
+                }
+
+                // Prevent race condition.
+                //
+                // The read thread does not block action processing on IO and never panics. The
+                // awaiting here should almost always resolve very quickly. It's unnecessary to
+                // apply timeout guard here only to add to overhead and code complexity.
+                if !matches!(ack_rx.await, Ok(ReadAcknowledgement::Acknowledged)) {
+                    // This failing means the read handler is dropped. There's no point in
+                    // retrying anymore.
+                    self.write_queue.close();
+                    let _ = result.send(RequestResult::ConnectionClosed);
+                    return HandleActionResult::Success;
+                }
+
+                let raw_requests = data
+                    .requests
+                    .iter()
+                    .zip(req_ids)
+                    .map(|((method, params), id)| RawRequest {
+                        jsonrpc: "2.0",
+                        id,
+                        method: *method,
+                        params,
+                    })
+                    .collect::<Vec<_>>();
+                let text = if data.batch {
+                    serde_json::to_string(&raw_requests)
+                } else {
+                    serde_json::to_string(&raw_requests[0])
+                }
+                .unwrap();
+
+                if let Err(err) = self.send_text(text).await {
+                    let _ = result.send(err.into());
+                }
+
+                HandleActionResult::Success
+            }
             Some(WriteAction::Close { result }) => {
                 if *close_sent {
                     self.disconnection.cancelled().await;
@@ -327,11 +434,12 @@ impl StreamWriteDriver {
         id: u64,
         request: ProviderRequestData,
     ) -> Result<(), SendError> {
-        let send = self.sink.send(Message::Text(
-            serde_json::to_string(&JsonRpcRequest { id, data: request })
-                .unwrap()
-                .into(),
-        ));
+        self.send_text(serde_json::to_string(&JsonRpcRequest { id, data: request }).unwrap())
+            .await
+    }
+
+    async fn send_text(&mut self, text: String) -> Result<(), SendError> {
+        let send = self.sink.send(Message::Text(text.into()));
 
         tokio::select! {
             result = send => result.map_err(SendError::Transport),
@@ -340,6 +448,62 @@ impl StreamWriteDriver {
     }
 }
 
+impl SubscribeWriteData {
+    /// Moves the starting block forward based on an update delivered to the subscription, such
+    /// that re-subscribing with the updated data resumes from the last seen block.
+    ///
+    /// The last seen block itself is always included, as it might not have been fully delivered.
+    pub fn advance(&mut self, update: &StreamUpdateData) {
+        let block_id = match self {
+            Self::NewHeads { block_id } => block_id,
+            Self::Events { options } => &mut options.block_id,
+            Self::TransactionStatus { .. }
+            | Self::NewTransactionReceipts { .. }
+            | Self::NewTransactions { .. } => return,
+        };
+
+        match update {
+            StreamUpdateData::SubscriptionNewHeads(update) => {
+                *block_id = ConfirmedBlockId::Number(update.result.block_number);
+            }
+            StreamUpdateData::SubscriptionEvents(update) => {
+                // Events from pre-confirmed blocks don't have a block number yet
+                if let Some(block_number) = update.result.emitted_event.block_number {
+                    *block_id = ConfirmedBlockId::Number(block_number);
+                }
+            }
+            StreamUpdateData::SubscriptionReorg(update) => {
+                // Blocks from the reorg starting point onwards are no longer valid
+                let last_valid = update.result.starting_block_number.saturating_sub(1);
+                match block_id {
+                    ConfirmedBlockId::Number(block_number) if *block_number <= last_valid => {}
+                    _ => *block_id = ConfirmedBlockId::Number(last_valid),
+                }
+            }
+            StreamUpdateData::SubscriptionTransactionStatus(_)
+            | StreamUpdateData::SubscriptionNewTransactionReceipts(_)
+            | StreamUpdateData::SubscriptionNewTransaction(_) => {}
+        }
+    }
+
+    /// Gets the block from which re-subscribing with this data resumes, if any.
+    pub const fn resume_point(&self) -> Option<ConfirmedBlockId> {
+        let block_id = match self {
+            Self::NewHeads { block_id } => block_id,
+            Self::Events { options } => &options.block_id,
+            Self::TransactionStatus { .. }
+            | Self::NewTransactionReceipts { .. }
+            | Self::NewTransactions { .. } => return None,
+        };
+
+        match block_id {
+            ConfirmedBlockId::Hash(_) | ConfirmedBlockId::Number(_) => Some(*block_id),
+            // Resuming from a moving target means missing updates
+            ConfirmedBlockId::Latest | ConfirmedBlockId::L1Accepted => None,
+        }
+    }
+}
+
 impl From<SendError> for SubscriptionResult {
     fn from(value: SendError) -> Self {
         match value {
@@ -357,3 +521,174 @@ impl From<SendError> for UnsubscribeResult {
         }
     }
 }
+
+impl From<SendError> for RequestResult {
+    fn from(value: SendError) -> Self {
+        match value {
+            SendError::Timeout => Self::TimeoutError,
+            SendError::Transport(error) => Self::TransportError(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet_providers::jsonrpc::JsonRpcStreamUpdate;
+
+    use super::*;
+
+    fn update(method: &str, result: serde_json::Value) -> StreamUpdateData {
+        serde_json::from_value::<JsonRpcStreamUpdate>(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": {
+                "subscription_id": "1",
+                "result": result,
+            },
+        }))
+        .unwrap()
+        .data
+    }
+
+    fn new_heads(block_number: u64) -> StreamUpdateData {
+        let price = serde_json::json!({ "price_in_fri": "0x1", "price_in_wei": "0x1" });
+        update(
+            "starknet_subscriptionNewHeads",
+            serde_json::json!({
+                "block_hash": "0x1",
+                "parent_hash": "0x2",
+                "block_number": block_number,
+                "new_root": "0x3",
+                "timestamp": 1,
+                "sequencer_address": "0x4",
+                "l1_gas_price": price,
+                "l2_gas_price": price,
+                "l1_data_gas_price": price,
+                "l1_da_mode": "BLOB",
+                "starknet_version": "0.14.0",
+            }),
+        )
+    }
+
+    fn event(block_number: Option<u64>) -> StreamUpdateData {
+        let mut event = serde_json::json!({
+            "from_address": "0x1",
+            "keys": [],
+            "data": [],
+            "transaction_hash": "0x2",
+            "finality_status": "ACCEPTED_ON_L2",
+        });
+        if let Some(block_number) = block_number {
+            event["block_hash"] = "0x3".into();
+            event["block_number"] = block_number.into();
+        }
+        update("starknet_subscriptionEvents", event)
+    }
+
+    fn reorg(starting_block_number: u64) -> StreamUpdateData {
+        update(
+            "starknet_subscriptionReorg",
+            serde_json::json!({
+                "starting_block_hash": "0x1",
+                "starting_block_number": starting_block_number,
+                "ending_block_hash": "0x2",
+                "ending_block_number": starting_block_number + 5,
+            }),
+        )
+    }
+
+    fn events_data(block_id: ConfirmedBlockId) -> SubscribeWriteData {
+        SubscribeWriteData::Events {
+            options: EventSubscriptionOptions {
+                from_address: None,
+                keys: None,
+                block_id,
+                finality_status: L2TransactionFinalityStatus::AcceptedOnL2,
+            },
+        }
+    }
+
+    #[test]
+    fn test_advance_new_heads() {
+        let mut data = SubscribeWriteData::NewHeads {
+            block_id: ConfirmedBlockId::Latest,
+        };
+        assert_eq!(data.resume_point(), None);
+
+        data.advance(&new_heads(100));
+        assert_eq!(data.resume_point(), Some(ConfirmedBlockId::Number(100)));
+
+        data.advance(&new_heads(101));
+        assert_eq!(data.resume_point(), Some(ConfirmedBlockId::Number(101)));
+    }
+
+    #[test]
+    fn test_advance_events() {
+        let mut data = events_data(ConfirmedBlockId::Number(10));
+
+        data.advance(&event(Some(12)));
+        assert_eq!(data.resume_point(), Some(ConfirmedBlockId::Number(12)));
+
+        // Pre-confirmed events don't move the resume point
+        data.advance(&event(None));
+        assert_eq!(data.resume_point(), Some(ConfirmedBlockId::Number(12)));
+    }
+
+    #[test]
+    fn test_advance_reorg() {
+        let mut data = SubscribeWriteData::NewHeads {
+            block_id: ConfirmedBlockId::Number(100),
+        };
+
+        // Resumes from the last block before the reorg
+        data.advance(&reorg(95));
+        assert_eq!(data.resume_point(), Some(ConfirmedBlockId::Number(94)));
+
+        // Already before the reorg starting point
+        data.advance(&reorg(98));
+        assert_eq!(data.resume_point(), Some(ConfirmedBlockId::Number(94)));
+
+        let mut data = events_data(ConfirmedBlockId::Latest);
+        data.advance(&reorg(0));
+        assert_eq!(data.resume_point(), Some(ConfirmedBlockId::Number(0)));
+    }
+
+    #[test]
+    fn test_resume_point() {
+        let hash = ConfirmedBlockId::Hash(Felt::ONE);
+        assert_eq!(events_data(hash).resume_point(), Some(hash));
+        assert_eq!(
+            events_data(ConfirmedBlockId::L1Accepted).resume_point(),
+            None
+        );
+
+        let mut data = SubscribeWriteData::TransactionStatus {
+            transaction_hash: Felt::ONE,
+        };
+        data.advance(&new_heads(100));
+        assert_eq!(data.resume_point(), None);
+    }
+
+    #[test]
+    fn test_raw_request_methods() {
+        let params = serde_json::json!([]);
+        let raw_requests = [
+            RequestMethod::Starknet(JsonRpcMethod::BlockNumber),
+            RequestMethod::Paymaster(PaymasterMethod::IsAvailable),
+        ]
+        .map(|method| RawRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params: &params,
+        });
+
+        assert_eq!(
+            serde_json::to_value(raw_requests).unwrap(),
+            serde_json::json!([
+                { "jsonrpc": "2.0", "id": 1, "method": "starknet_blockNumber", "params": [] },
+                { "jsonrpc": "2.0", "id": 1, "method": "paymaster_isAvailable", "params": [] }
+            ])
+        );
+    }
+}
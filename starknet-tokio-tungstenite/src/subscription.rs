@@ -1,14 +1,18 @@
+use std::sync::Arc;
+
 use starknet_core::types::{
     BlockHeader, ConfirmedBlockId, EmittedEventWithFinality, Felt, L2TransactionFinalityStatus,
-    NewTransactionStatus, ReorgData, StarknetError, SubscriptionId,
-    TransactionReceiptWithBlockInfo, TransactionWithL2Status,
+    NewTransactionStatus, ReorgData, StarknetError, TransactionReceiptWithBlockInfo,
+    TransactionWithL2Status,
 };
 use starknet_providers::StreamUpdateData;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::mpsc::UnboundedReceiver;
 
 use crate::{
     error::{SubscriptionReceiveError, UnsubscribeError},
-    stream::{UnsubscribeResult, WriteAction},
+    stream::{
+        SharedConnection, SharedSubscription, SubscriptionItem, UnsubscribeResult, WriteAction,
+    },
     StreamUpdateType,
 };
 
@@ -87,6 +91,8 @@ pub enum NewHeadsUpdate {
     NewHeader(BlockHeader),
     /// A chain reorganization has occurred.
     Reorg(ReorgData),
+    /// The connection was re-established in reconnect mode.
+    Reconnected(Reconnection),
 }
 
 /// Update from an events subscription.
@@ -98,6 +104,8 @@ pub enum EventsUpdate {
     Event(EmittedEventWithFinality),
     /// A chain reorganization has occurred.
     Reorg(ReorgData),
+    /// The connection was re-established in reconnect mode.
+    Reconnected(Reconnection),
 }
 
 /// Update from a transaction status subscription.
@@ -109,6 +117,8 @@ pub enum TransactionStatusUpdate {
     Status(NewTransactionStatus),
     /// A chain reorganization has occurred.
     Reorg(ReorgData),
+    /// The connection was re-established in reconnect mode.
+    Reconnected(Reconnection),
 }
 
 /// Update from a new transaction receipts subscription.
@@ -120,6 +130,8 @@ pub enum NewTransactionReceiptsUpdate {
     Receipt(TransactionReceiptWithBlockInfo),
     /// A chain reorganization has occurred.
     Reorg(ReorgData),
+    /// The connection was re-established in reconnect mode.
+    Reconnected(Reconnection),
 }
 
 /// Update from a new transaction subscription.
@@ -132,6 +144,22 @@ pub enum NewTransactionsUpdate {
     Transaction(TransactionWithL2Status),
     /// A chain reorganization has occurred.
     Reorg(ReorgData),
+    /// The connection was re-established in reconnect mode.
+    Reconnected(Reconnection),
+}
+
+/// Details of a connection re-establishment, delivered to subscriptions when the underlying
+/// [`TungsteniteStream`](crate::TungsteniteStream) is in reconnect mode.
+///
+/// Updates that follow come from the new connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconnection {
+    /// The block from which the subscription was resumed. Updates from this block onwards are
+    /// delivered again, and might therefore be duplicates of ones already received.
+    ///
+    /// `None` if the subscription cannot be resumed from a specific block, in which case updates
+    /// emitted while disconnected are lost.
+    pub resumed_from: Option<ConfirmedBlockId>,
 }
 
 /// Options for subscribing to Starknet events.
@@ -149,9 +177,9 @@ pub struct EventSubscriptionOptions {
 
 #[derive(Debug)]
 pub(crate) struct Subscription {
-    pub subscription_id: SubscriptionId,
-    pub stream: UnboundedReceiver<StreamUpdateData>,
-    pub write_queue: UnboundedSender<WriteAction>,
+    pub subscription: SharedSubscription,
+    pub stream: UnboundedReceiver<SubscriptionItem>,
+    pub connection: Arc<SharedConnection>,
     pub unsubscribed: bool,
 }
 
@@ -186,36 +214,42 @@ impl NewHeadsSubscription {
     /// Returns a new block header or chain reorganization notification.
     pub async fn recv(&mut self) -> Result<NewHeadsUpdate, SubscriptionReceiveError> {
         match self.inner.stream.recv().await {
-            Some(StreamUpdateData::SubscriptionNewHeads(update)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewHeads(update))) => {
                 Ok(NewHeadsUpdate::NewHeader(update.result))
             }
-            Some(StreamUpdateData::SubscriptionReorg(update)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionReorg(update))) => {
                 Ok(NewHeadsUpdate::Reorg(update.result))
             }
-            Some(StreamUpdateData::SubscriptionEvents(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionEvents(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::NewHeads, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::Events,
                 })
             }
-            Some(StreamUpdateData::SubscriptionTransactionStatus(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionTransactionStatus(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::NewHeads, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::TransactionStatus,
                 })
             }
-            Some(StreamUpdateData::SubscriptionNewTransactionReceipts(_)) => {
-                Err(SubscriptionReceiveError::UnexpectedType {
-                    expecting: &[StreamUpdateType::NewHeads, StreamUpdateType::Reorg],
-                    actual: StreamUpdateType::NewTransactionReceipts,
-                })
-            }
-            Some(StreamUpdateData::SubscriptionNewTransaction(_)) => {
+            Some(SubscriptionItem::Update(
+                StreamUpdateData::SubscriptionNewTransactionReceipts(_),
+            )) => Err(SubscriptionReceiveError::UnexpectedType {
+                expecting: &[StreamUpdateType::NewHeads, StreamUpdateType::Reorg],
+                actual: StreamUpdateType::NewTransactionReceipts,
+            }),
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewTransaction(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::NewHeads, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::NewTransaction,
                 })
             }
+            Some(SubscriptionItem::Reconnected(reconnection)) => {
+                Ok(NewHeadsUpdate::Reconnected(reconnection))
+            }
+            Some(SubscriptionItem::ResubscribeFailed(err)) => {
+                Err(SubscriptionReceiveError::Resubscribe(Arc::new(err)))
+            }
             None => Err(SubscriptionReceiveError::StreamClosed),
         }
     }
@@ -234,36 +268,42 @@ impl EventsSubscription {
     /// Returns a new event or chain reorganization notification.
     pub async fn recv(&mut self) -> Result<EventsUpdate, SubscriptionReceiveError> {
         match self.inner.stream.recv().await {
-            Some(StreamUpdateData::SubscriptionEvents(update)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionEvents(update))) => {
                 Ok(EventsUpdate::Event(update.result))
             }
-            Some(StreamUpdateData::SubscriptionReorg(update)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionReorg(update))) => {
                 Ok(EventsUpdate::Reorg(update.result))
             }
-            Some(StreamUpdateData::SubscriptionNewHeads(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewHeads(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::Events, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::NewHeads,
                 })
             }
-            Some(StreamUpdateData::SubscriptionTransactionStatus(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionTransactionStatus(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::Events, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::TransactionStatus,
                 })
             }
-            Some(StreamUpdateData::SubscriptionNewTransactionReceipts(_)) => {
-                Err(SubscriptionReceiveError::UnexpectedType {
-                    expecting: &[StreamUpdateType::Events, StreamUpdateType::Reorg],
-                    actual: StreamUpdateType::NewTransactionReceipts,
-                })
-            }
-            Some(StreamUpdateData::SubscriptionNewTransaction(_)) => {
+            Some(SubscriptionItem::Update(
+                StreamUpdateData::SubscriptionNewTransactionReceipts(_),
+            )) => Err(SubscriptionReceiveError::UnexpectedType {
+                expecting: &[StreamUpdateType::Events, StreamUpdateType::Reorg],
+                actual: StreamUpdateType::NewTransactionReceipts,
+            }),
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewTransaction(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::Events, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::NewTransaction,
                 })
             }
+            Some(SubscriptionItem::Reconnected(reconnection)) => {
+                Ok(EventsUpdate::Reconnected(reconnection))
+            }
+            Some(SubscriptionItem::ResubscribeFailed(err)) => {
+                Err(SubscriptionReceiveError::Resubscribe(Arc::new(err)))
+            }
             None => Err(SubscriptionReceiveError::StreamClosed),
         }
     }
@@ -282,36 +322,42 @@ impl TransactionStatusSubscription {
     /// Returns a transaction status update or chain reorganization notification.
     pub async fn recv(&mut self) -> Result<TransactionStatusUpdate, SubscriptionReceiveError> {
         match self.inner.stream.recv().await {
-            Some(StreamUpdateData::SubscriptionTransactionStatus(update)) => {
-                Ok(TransactionStatusUpdate::Status(update.result))
-            }
-            Some(StreamUpdateData::SubscriptionReorg(update)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionTransactionStatus(
+                update,
+            ))) => Ok(TransactionStatusUpdate::Status(update.result)),
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionReorg(update))) => {
                 Ok(TransactionStatusUpdate::Reorg(update.result))
             }
-            Some(StreamUpdateData::SubscriptionNewHeads(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewHeads(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::TransactionStatus, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::NewHeads,
                 })
             }
-            Some(StreamUpdateData::SubscriptionEvents(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionEvents(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::TransactionStatus, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::Events,
                 })
             }
-            Some(StreamUpdateData::SubscriptionNewTransactionReceipts(_)) => {
-                Err(SubscriptionReceiveError::UnexpectedType {
-                    expecting: &[StreamUpdateType::TransactionStatus, StreamUpdateType::Reorg],
-                    actual: StreamUpdateType::NewTransactionReceipts,
-                })
-            }
-            Some(StreamUpdateData::SubscriptionNewTransaction(_)) => {
+            Some(SubscriptionItem::Update(
+                StreamUpdateData::SubscriptionNewTransactionReceipts(_),
+            )) => Err(SubscriptionReceiveError::UnexpectedType {
+                expecting: &[StreamUpdateType::TransactionStatus, StreamUpdateType::Reorg],
+                actual: StreamUpdateType::NewTransactionReceipts,
+            }),
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewTransaction(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::TransactionStatus, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::NewTransaction,
                 })
             }
+            Some(SubscriptionItem::Reconnected(reconnection)) => {
+                Ok(TransactionStatusUpdate::Reconnected(reconnection))
+            }
+            Some(SubscriptionItem::ResubscribeFailed(err)) => {
+                Err(SubscriptionReceiveError::Resubscribe(Arc::new(err)))
+            }
             None => Err(SubscriptionReceiveError::StreamClosed),
         }
     }
@@ -330,13 +376,13 @@ impl NewTransactionReceiptsSubscription {
     /// Returns the transaction receipt.
     pub async fn recv(&mut self) -> Result<NewTransactionReceiptsUpdate, SubscriptionReceiveError> {
         match self.inner.stream.recv().await {
-            Some(StreamUpdateData::SubscriptionNewTransactionReceipts(update)) => {
-                Ok(NewTransactionReceiptsUpdate::Receipt(update.result))
-            }
-            Some(StreamUpdateData::SubscriptionReorg(update)) => {
+            Some(SubscriptionItem::Update(
+                StreamUpdateData::SubscriptionNewTransactionReceipts(update),
+            )) => Ok(NewTransactionReceiptsUpdate::Receipt(update.result)),
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionReorg(update))) => {
                 Ok(NewTransactionReceiptsUpdate::Reorg(update.result))
             }
-            Some(StreamUpdateData::SubscriptionNewHeads(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewHeads(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[
                         StreamUpdateType::NewTransactionReceipts,
@@ -345,7 +391,7 @@ impl NewTransactionReceiptsSubscription {
                     actual: StreamUpdateType::NewHeads,
                 })
             }
-            Some(StreamUpdateData::SubscriptionEvents(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionEvents(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[
                         StreamUpdateType::NewTransactionReceipts,
@@ -354,7 +400,7 @@ impl NewTransactionReceiptsSubscription {
                     actual: StreamUpdateType::Events,
                 })
             }
-            Some(StreamUpdateData::SubscriptionTransactionStatus(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionTransactionStatus(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[
                         StreamUpdateType::NewTransactionReceipts,
@@ -363,7 +409,7 @@ impl NewTransactionReceiptsSubscription {
                     actual: StreamUpdateType::TransactionStatus,
                 })
             }
-            Some(StreamUpdateData::SubscriptionNewTransaction(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewTransaction(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[
                         StreamUpdateType::NewTransactionReceipts,
@@ -372,6 +418,12 @@ impl NewTransactionReceiptsSubscription {
                     actual: StreamUpdateType::NewTransaction,
                 })
             }
+            Some(SubscriptionItem::Reconnected(reconnection)) => {
+                Ok(NewTransactionReceiptsUpdate::Reconnected(reconnection))
+            }
+            Some(SubscriptionItem::ResubscribeFailed(err)) => {
+                Err(SubscriptionReceiveError::Resubscribe(Arc::new(err)))
+            }
             None => Err(SubscriptionReceiveError::StreamClosed),
         }
     }
@@ -390,35 +442,41 @@ impl NewTransactionsSubscription {
     /// Returns the transaction alongside its layer-2 status.
     pub async fn recv(&mut self) -> Result<NewTransactionsUpdate, SubscriptionReceiveError> {
         match self.inner.stream.recv().await {
-            Some(StreamUpdateData::SubscriptionNewTransaction(update)) => {
-                Ok(NewTransactionsUpdate::Transaction(update.result))
-            }
-            Some(StreamUpdateData::SubscriptionReorg(update)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewTransaction(
+                update,
+            ))) => Ok(NewTransactionsUpdate::Transaction(update.result)),
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionReorg(update))) => {
                 Ok(NewTransactionsUpdate::Reorg(update.result))
             }
-            Some(StreamUpdateData::SubscriptionNewHeads(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionNewHeads(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::NewTransaction, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::NewHeads,
                 })
             }
-            Some(StreamUpdateData::SubscriptionEvents(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionEvents(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::NewTransaction, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::Events,
                 })
             }
-            Some(StreamUpdateData::SubscriptionTransactionStatus(_)) => {
+            Some(SubscriptionItem::Update(StreamUpdateData::SubscriptionTransactionStatus(_))) => {
                 Err(SubscriptionReceiveError::UnexpectedType {
                     expecting: &[StreamUpdateType::NewTransaction, StreamUpdateType::Reorg],
                     actual: StreamUpdateType::TransactionStatus,
                 })
             }
-            Some(StreamUpdateData::SubscriptionNewTransactionReceipts(_)) => {
-                Err(SubscriptionReceiveError::UnexpectedType {
-                    expecting: &[StreamUpdateType::NewTransaction, StreamUpdateType::Reorg],
-                    actual: StreamUpdateType::NewTransactionReceipts,
-                })
+            Some(SubscriptionItem::Update(
+                StreamUpdateData::SubscriptionNewTransactionReceipts(_),
+            )) => Err(SubscriptionReceiveError::UnexpectedType {
+                expecting: &[StreamUpdateType::NewTransaction, StreamUpdateType::Reorg],
+                actual: StreamUpdateType::NewTransactionReceipts,
+            }),
+            Some(SubscriptionItem::Reconnected(reconnection)) => {
+                Ok(NewTransactionsUpdate::Reconnected(reconnection))
+            }
+            Some(SubscriptionItem::ResubscribeFailed(err)) => {
+                Err(SubscriptionReceiveError::Resubscribe(Arc::new(err)))
             }
             None => Err(SubscriptionReceiveError::StreamClosed),
         }
@@ -440,10 +498,12 @@ impl Subscription {
         let (result_tx, mut result_rx) =
             tokio::sync::mpsc::unbounded_channel::<UnsubscribeResult>();
 
+        let subscription_id = self.subscription.lock().unwrap().id.clone();
         if self
-            .write_queue
+            .connection
+            .write_queue()
             .send(WriteAction::Unsubscribe {
-                subscription_id: self.subscription_id.clone(),
+                subscription_id,
                 result: Some(result_tx),
             })
             .is_err()
@@ -496,10 +556,14 @@ impl Drop for Subscription {
     fn drop(&mut self) {
         if !self.unsubscribed {
             // This failing means the connection is already broken anyway.
-            let _ = self.write_queue.send(WriteAction::Unsubscribe {
-                subscription_id: self.subscription_id.clone(),
-                result: None,
-            });
+            let subscription_id = self.subscription.lock().unwrap().id.clone();
+            let _ = self
+                .connection
+                .write_queue()
+                .send(WriteAction::Unsubscribe {
+                    subscription_id,
+                    result: None,
+                });
         }
     }
 }
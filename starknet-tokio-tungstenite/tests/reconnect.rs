@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use starknet_core::types::ConfirmedBlockId;
+use starknet_tokio_tungstenite::{
+    NewHeadsSubscription, NewHeadsUpdate, SubscribeError, SubscriptionReceiveError,
+    TungsteniteStream, TungsteniteStreamBuilder,
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::WebSocketStream;
+use tungstenite::Message;
+
+/// How a mocked server answers the subscribe request on the second connection.
+#[derive(Clone, Copy)]
+enum Resubscribe {
+    Succeed,
+    Fail,
+}
+
+/// Starts a WebSocket server that accepts a new heads subscription, sends one header, drops the
+/// connection, and then answers the re-subscribe request on the next connection.
+async fn start_server(resubscribe: Resubscribe) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let mut socket = accept(&listener).await;
+        let request = next_request(&mut socket).await;
+        assert_eq!(request["method"], "starknet_subscribeNewHeads");
+        send(
+            &mut socket,
+            serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": "1" }),
+        )
+        .await;
+        send(&mut socket, new_heads("1", 100)).await;
+        drop(socket);
+
+        let mut socket = accept(&listener).await;
+        let request = next_request(&mut socket).await;
+        assert_eq!(request["method"], "starknet_subscribeNewHeads");
+        assert_eq!(
+            request["params"]["block_id"],
+            serde_json::json!({ "block_number": 100 })
+        );
+        match resubscribe {
+            Resubscribe::Succeed => {
+                send(
+                    &mut socket,
+                    serde_json::json!({ "jsonrpc": "2.0", "id": request["id"], "result": "2" }),
+                )
+                .await;
+                send(&mut socket, new_heads("2", 101)).await;
+            }
+            Resubscribe::Fail => {
+                send(
+                    &mut socket,
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "error": { "code": 24, "message": "Block not found" },
+                    }),
+                )
+                .await;
+            }
+        }
+
+        // Keeps the connection open until the client goes away
+        while socket.next().await.is_some() {}
+    });
+
+    format!("ws://{address}")
+}
+
+async fn accept(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+    let (stream, _) = listener.accept().await.unwrap();
+    tokio_tungstenite::accept_async(stream).await.unwrap()
+}
+
+async fn next_request(socket: &mut WebSocketStream<TcpStream>) -> serde_json::Value {
+    loop {
+        if let Message::Text(text) = socket.next().await.unwrap().unwrap() {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocketStream<TcpStream>, message: serde_json::Value) {
+    socket
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .unwrap();
+}
+
+fn new_heads(subscription_id: &str, block_number: u64) -> serde_json::Value {
+    let price = serde_json::json!({ "price_in_fri": "0x1", "price_in_wei": "0x1" });
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "starknet_subscriptionNewHeads",
+        "params": {
+            "subscription_id": subscription_id,
+            "result": {
+                "block_hash": "0x1",
+                "parent_hash": "0x2",
+                "block_number": block_number,
+                "new_root": "0x3",
+                "timestamp": 1,
+                "sequencer_address": "0x4",
+                "l1_gas_price": price,
+                "l2_gas_price": price,
+                "l1_data_gas_price": price,
+                "l1_da_mode": "BLOB",
+                "starknet_version": "0.14.0",
+            },
+        },
+    })
+}
+
+async fn connect(url: &str) -> TungsteniteStream {
+    TungsteniteStreamBuilder::new()
+        .timeout(Duration::from_secs(5))
+        .reconnect(true)
+        .reconnect_interval(Duration::from_millis(10))
+        .connect(url)
+        .await
+        .unwrap()
+}
+
+async fn recv(
+    subscription: &mut NewHeadsSubscription,
+) -> Result<NewHeadsUpdate, SubscriptionReceiveError> {
+    tokio::time::timeout(Duration::from_secs(5), subscription.recv())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn reconnect_resumes_subscription() {
+    let stream = connect(&start_server(Resubscribe::Succeed).await).await;
+    let mut subscription = stream
+        .subscribe_new_heads(ConfirmedBlockId::Latest)
+        .await
+        .unwrap();
+
+    let NewHeadsUpdate::NewHeader(header) = recv(&mut subscription).await.unwrap() else {
+        panic!("Unexpected update type");
+    };
+    assert_eq!(header.block_number, 100);
+
+    let NewHeadsUpdate::Reconnected(reconnection) = recv(&mut subscription).await.unwrap() else {
+        panic!("Unexpected update type");
+    };
+    assert_eq!(
+        reconnection.resumed_from,
+        Some(ConfirmedBlockId::Number(100))
+    );
+
+    let NewHeadsUpdate::NewHeader(header) = recv(&mut subscription).await.unwrap() else {
+        panic!("Unexpected update type");
+    };
+    assert_eq!(header.block_number, 101);
+}
+
+#[tokio::test]
+async fn reconnect_surfaces_resubscribe_failure() {
+    let stream = connect(&start_server(Resubscribe::Fail).await).await;
+    let mut subscription = stream
+        .subscribe_new_heads(ConfirmedBlockId::Latest)
+        .await
+        .unwrap();
+
+    let NewHeadsUpdate::NewHeader(header) = recv(&mut subscription).await.unwrap() else {
+        panic!("Unexpected update type");
+    };
+    assert_eq!(header.block_number, 100);
+
+    match recv(&mut subscription).await {
+        Err(SubscriptionReceiveError::Resubscribe(err)) => {
+            assert!(matches!(*err, SubscribeError::BlockNotFound));
+        }
+        _ => panic!("Unexpected result"),
+    }
+    assert!(matches!(
+        recv(&mut subscription).await,
+        Err(SubscriptionReceiveError::StreamClosed)
+    ));
+}
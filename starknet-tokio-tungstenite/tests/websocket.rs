@@ -1,6 +1,12 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use starknet_core::types::{ConfirmedBlockId, Felt};
+use starknet_core::types::{
+    requests::{BlockNumberRequest, ChainIdRequest},
+    ConfirmedBlockId, Felt,
+};
+use starknet_providers::{
+    jsonrpc::JsonRpcClient, Provider, ProviderRequestData, ProviderResponseData,
+};
 use starknet_tokio_tungstenite::{
     EventSubscriptionOptions, EventsUpdate, NewHeadsUpdate, NewTransactionReceiptsUpdate,
     NewTransactionsUpdate, TransactionStatusUpdate, TungsteniteStream,
@@ -139,3 +145,41 @@ async fn websocket_new_transactions_subscription() {
 
     assert_ne!(tx.txn.transaction_hash(), &Felt::ZERO);
 }
+
+#[tokio::test]
+async fn websocket_jsonrpc_transport() {
+    let stream = Arc::new(create_stream().await);
+    let client = JsonRpcClient::new(stream.clone());
+
+    let block_number = client.block_number().await.unwrap();
+    assert!(block_number > 0);
+
+    let responses = client
+        .batch_requests([
+            ProviderRequestData::BlockNumber(BlockNumberRequest),
+            ProviderRequestData::ChainId(ChainIdRequest),
+        ])
+        .await
+        .unwrap();
+    assert!(matches!(
+        responses.as_slice(),
+        [
+            ProviderResponseData::BlockNumber(_),
+            ProviderResponseData::ChainId(_)
+        ]
+    ));
+
+    // Subscriptions share the same connection
+    let mut subscription = stream
+        .subscribe_new_heads(ConfirmedBlockId::Latest)
+        .await
+        .unwrap();
+    let NewHeadsUpdate::NewHeader(_) =
+        tokio::time::timeout(Duration::from_secs(5), subscription.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    else {
+        panic!("Unexpected update type");
+    };
+}
@@ -0,0 +1,191 @@
+use starknet::{
+    core::{
+        abi::{AbiValue, ContractAbi},
+        codec::{Decode, Encode},
+        types::{
+            contract::{legacy::LegacyContractClass, SierraClass},
+            Felt, U256,
+        },
+        utils::get_selector_from_name,
+    },
+    macros::{abigen, felt},
+};
+
+abigen!(
+    Erc20,
+    "starknet-core/test-data/contracts/cairo2/artifacts/erc20_sierra.txt"
+);
+
+mod abi_types {
+    starknet::macros::abigen!(
+        AbiTypes,
+        "starknet-core/test-data/contracts/cairo2/artifacts/abi_types_sierra.txt"
+    );
+}
+
+mod legacy {
+    starknet::macros::abigen!(
+        OzAccount,
+        "starknet-core/test-data/contracts/cairo0/artifacts/oz_account.txt"
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn abigen_builds_calls() {
+    let token = Erc20::new(felt!("0x1234"), ());
+
+    let call = token.transfer_call(&felt!("0x5678"), &U256::from(1000u32));
+
+    assert_eq!(call.to, felt!("0x1234"));
+    assert_eq!(call.selector, get_selector_from_name("transfer").unwrap());
+    assert_eq!(
+        call.calldata,
+        vec![felt!("0x5678"), Felt::from(1000u32), Felt::ZERO]
+    );
+
+    let call = token.get_total_supply_call();
+    assert_eq!(
+        call.selector,
+        get_selector_from_name("get_total_supply").unwrap()
+    );
+    assert!(call.calldata.is_empty());
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn abigen_generates_event_types() {
+    let event = Event::Transfer(Transfer {
+        from: felt!("0x1"),
+        to: felt!("0x2"),
+        value: U256::from(3u32),
+    });
+
+    let mut encoded = vec![];
+    event.encode(&mut encoded).unwrap();
+    assert_eq!(
+        encoded,
+        vec![
+            Felt::ZERO,
+            felt!("0x1"),
+            felt!("0x2"),
+            Felt::THREE,
+            Felt::ZERO
+        ]
+    );
+
+    assert_eq!(Event::decode(&encoded).unwrap(), event);
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn abigen_decodes_emitted_events() {
+    let keys = [get_selector_from_name("Transfer").unwrap()];
+    let data = [felt!("0x1"), felt!("0x2"), Felt::THREE, Felt::ZERO];
+
+    assert_eq!(
+        Event::decode_event(&keys, &data).unwrap(),
+        Event::Transfer(Transfer {
+            from: felt!("0x1"),
+            to: felt!("0x2"),
+            value: U256::from(3u32),
+        })
+    );
+    assert!(Event::decode_event(&keys, &data[..3]).is_err());
+    assert!(Event::decode_event(&keys, &[data.as_slice(), &[Felt::ONE]].concat()).is_err());
+    assert!(Event::decode_event(&[get_selector_from_name("Mint").unwrap()], &data).is_err());
+
+    // Flattened event enums don't consume a selector key.
+    assert_eq!(
+        abi_types::Event::decode_event(&[get_selector_from_name("FlatEvent").unwrap()], &[])
+            .unwrap(),
+        abi_types::Event::FlatEvent(abi_types::FlatEvent::FlatEvent(abi_types::StaticEvent {}))
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn abigen_matches_runtime_abi_encoding() {
+    let class = serde_json::from_str::<SierraClass>(include_str!(
+        "../starknet-core/test-data/contracts/cairo2/artifacts/erc20_sierra.txt"
+    ))
+    .unwrap();
+    let abi = ContractAbi::from_sierra(&class.abi).unwrap();
+
+    let call = Erc20::new(Felt::ZERO, ()).transfer_call(&felt!("0x5678"), &U256::from(1000u32));
+
+    assert_eq!(
+        call.calldata,
+        abi.encode_inputs(
+            "transfer",
+            &[
+                AbiValue::from(felt!("0x5678")),
+                AbiValue::from(U256::from(1000u32))
+            ]
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn abigen_legacy_matches_runtime_abi_encoding() {
+    let class = serde_json::from_str::<LegacyContractClass>(include_str!(
+        "../starknet-core/test-data/contracts/cairo0/artifacts/oz_account.txt"
+    ))
+    .unwrap();
+    let abi = ContractAbi::from_legacy(&class.abi).unwrap();
+
+    let call_array = vec![legacy::AccountCallArray {
+        to: felt!("0x1"),
+        selector: felt!("0x2"),
+        data_offset: Felt::ZERO,
+        data_len: Felt::ONE,
+    }];
+    let call =
+        legacy::OzAccount::new(Felt::ZERO, ()).__execute___call(&call_array, &vec![felt!("0x3")]);
+
+    assert_eq!(
+        call.selector,
+        get_selector_from_name("__execute__").unwrap()
+    );
+    assert_eq!(
+        call.calldata,
+        abi.encode_json_inputs(
+            "__execute__",
+            &serde_json::json!({
+                "call_array": [{ "to": "0x1", "selector": "0x2", "data_offset": 0, "data_len": 1 }],
+                "calldata": ["0x3"],
+            })
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+fn abigen_supports_unit_and_enum_members() {
+    use abi_types::{ExampleEnum, ExampleStruct};
+
+    let value = ExampleStruct {
+        field_a: felt!("0x1"),
+        field_b: felt!("0x2"),
+        field_c: ExampleEnum::VariantB(U256::from(3u32)),
+        field_d: (),
+    };
+
+    let mut encoded = vec![];
+    value.encode(&mut encoded).unwrap();
+    assert_eq!(
+        encoded,
+        vec![
+            felt!("0x1"),
+            felt!("0x2"),
+            Felt::ONE,
+            Felt::THREE,
+            Felt::ZERO
+        ]
+    );
+
+    assert_eq!(ExampleStruct::decode(&encoded).unwrap(), value);
+}